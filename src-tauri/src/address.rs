@@ -0,0 +1,95 @@
+// 地址/数值字符串解析
+// PDSC 属性、命令行参数和前端输入共用同一套规则：0x 前缀为十六进制，否则为十进制，允许 _ 分隔
+
+/// 解析用户输入的地址，返回数值或说明原因的错误
+pub fn parse_address(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("地址不能为空".to_string());
+    }
+
+    let (digits, radix) = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (trimmed, 10),
+    };
+    if digits.is_empty() {
+        return Err(format!("地址 '{}' 缺少数字", trimmed));
+    }
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err(format!("地址 '{}' 的下划线位置无效", trimmed));
+    }
+
+    let digits: String = digits.chars().filter(|&c| c != '_').collect();
+    if let Some(bad) = digits.chars().find(|c| !c.is_digit(radix)) {
+        return Err(if radix == 10 && bad.is_ascii_hexdigit() {
+            format!("地址 '{}' 包含十六进制字符，十六进制地址需带 0x 前缀", trimmed)
+        } else {
+            format!("地址 '{}' 包含无效字符 '{}'", trimmed, bad)
+        });
+    }
+
+    u64::from_str_radix(&digits, radix).map_err(|_| format!("地址 '{}' 超出 64 位范围", trimmed))
+}
+
+/// JavaScript number 能精确表示的最大整数 (2^53 - 1)
+pub const MAX_JS_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// 解析前端输入的地址：结果以 JS number 返回，超过 2^53 - 1 会丢失精度，直接拒绝
+pub fn parse_frontend_address(input: &str) -> Result<u64, String> {
+    let value = parse_address(input)?;
+    if value > MAX_JS_SAFE_INTEGER {
+        return Err(format!("地址 '{}' 超出前端可精确表示的范围 (最大 0x{:X})", input.trim(), MAX_JS_SAFE_INTEGER));
+    }
+    Ok(value)
+}
+
+/// 解析十六进制或十进制数字，失败时返回 None
+pub fn parse_hex_or_dec(s: &str) -> Option<u64> {
+    parse_address(s).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepted_forms() {
+        assert_eq!(parse_address("0x08000000"), Ok(0x0800_0000));
+        assert_eq!(parse_address("0X0800_0000"), Ok(0x0800_0000));
+        assert_eq!(parse_address("134217728"), Ok(0x0800_0000));
+        assert_eq!(parse_address("  1_000  "), Ok(1000));
+        // 命令行与 PDSC 使用完整的 64 位范围
+        assert_eq!(parse_address("0xFFFFFFFFFFFFFFFF"), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_frontend_addresses_fit_js_number() {
+        assert_eq!(parse_frontend_address("0x1F_FFFF_FFFF_FFFF"), Ok(MAX_JS_SAFE_INTEGER));
+        assert!(parse_frontend_address("0x20_0000_0000_0000").is_err());
+        assert!(parse_frontend_address("0xFFFFFFFFFFFFFFFF").is_err());
+    }
+
+    #[test]
+    fn test_rejected_forms() {
+        assert!(parse_address("").is_err());
+        assert!(parse_address("0x").is_err());
+        assert!(parse_address("0x_0800").is_err());
+        assert!(parse_address("0x0800__0000").is_err());
+        assert!(parse_address("0x08g0").is_err());
+        assert!(parse_address("0x1_0000_0000_0000_0000").is_err());
+        assert!(parse_address("-1").is_err());
+    }
+
+    #[test]
+    fn test_hex_without_prefix_hints() {
+        let err = parse_address("08000ABC").unwrap_err();
+        assert!(err.contains("0x"), "{}", err);
+    }
+
+    #[test]
+    fn test_pdsc_attribute_values() {
+        assert_eq!(parse_hex_or_dec("0x20000000"), Some(0x2000_0000));
+        assert_eq!(parse_hex_or_dec("0"), Some(0));
+        assert_eq!(parse_hex_or_dec("abc"), None);
+    }
+}
@@ -3,6 +3,7 @@
 use crate::error::{AppError, AppResult};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,12 +12,168 @@ use std::path::PathBuf;
 pub struct AppConfig {
     /// 自定义Pack目录路径
     pub custom_packs_dir: Option<String>,
+    /// 是否以 gzip 压缩保存扫描报告
+    #[serde(default)]
+    pub compress_scan_reports: bool,
+    /// 厂商（芯片名前缀）默认 Flash 基地址，覆盖内置表
+    #[serde(default)]
+    pub vendor_flash_bases: HashMap<String, u64>,
+    /// 目标定义覆盖（芯片/家族名称 -> YAML 片段）
+    #[serde(default)]
+    pub target_overrides: HashMap<String, String>,
+    /// 兼容芯片回退映射（数据库中找不到芯片时按前缀使用兼容型号）
+    #[serde(default = "default_fallback_mappings")]
+    pub fallback_mappings: Vec<FallbackMapping>,
+    /// Flash 算法头部预留大小覆盖（Pack 名称或设备名前缀 -> 字节数）
+    #[serde(default)]
+    pub header_reserves: HashMap<String, u64>,
+    /// Flash 算法栈大小与页缓冲数量覆盖（Pack 名称或设备名前缀 -> 尺寸）
+    #[serde(default)]
+    pub algorithm_sizing: HashMap<String, AlgorithmSizing>,
+    /// RTT 查看器通道显示与过滤设置
+    #[serde(default)]
+    pub rtt_view_config: RttViewConfig,
+    /// 打开探针遇到暂时占用（设备忙）时的重试设置
+    #[serde(default)]
+    pub probe_open_retry: ProbeOpenRetry,
+    /// 最近一次成功连接的参数（快速连接）
+    #[serde(default)]
+    pub last_connection: Option<LastConnection>,
+    /// 本地脚本控制服务设置
+    #[serde(default)]
+    pub control_server: ControlServerConfig,
+    /// 内核操作统一超时覆盖（毫秒），未设置时各操作使用各自的默认值
+    #[serde(default)]
+    pub core_operation_timeout_ms: Option<u64>,
+}
+
+/// 最近一次成功连接的参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastConnection {
+    pub options: crate::commands::probe::ConnectOptions,
+    /// 探针序列号，用于在标识变化后重新定位探针
+    pub probe_serial: Option<String>,
+    pub vendor_id: u16,
+    pub product_id: u16,
+}
+
+/// 打开探针的重试设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeOpenRetry {
+    /// 总尝试次数（含首次）
+    pub attempts: u32,
+    /// 每次重试前等待的毫秒数
+    pub delay_ms: u64,
+}
+
+impl Default for ProbeOpenRetry {
+    fn default() -> Self {
+        Self { attempts: 3, delay_ms: 200 }
+    }
+}
+
+/// 本地控制服务（JSON-RPC over HTTP，仅绑定 127.0.0.1）设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlServerConfig {
+    /// 是否在启动时开启控制服务（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口
+    #[serde(default = "default_control_port")]
+    pub port: u16,
+    /// 访问令牌，请求需携带 `Authorization: Bearer <token>`；为空时开启服务会自动生成
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+fn default_control_port() -> u16 {
+    7801
+}
+
+impl Default for ControlServerConfig {
+    fn default() -> Self {
+        Self { enabled: false, port: default_control_port(), token: None }
+    }
+}
+
+/// Flash 算法 RAM 尺寸覆盖
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlgorithmSizing {
+    /// 栈大小（字节），未指定时按页大小推算
+    #[serde(default)]
+    pub stack_size: Option<u64>,
+    /// 预留的页数据缓冲数量（用于 RAM 占用校验），默认 1
+    #[serde(default)]
+    pub page_buffers: Option<u32>,
+}
+
+/// RTT 查看器配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RttViewConfig {
+    /// 各通道显示设置
+    #[serde(default)]
+    pub channels: Vec<RttChannelView>,
+    /// 由后端按过滤规则预先过滤数据（减少高频通道的 IPC）
+    #[serde(default)]
+    pub backend_filter: bool,
+}
+
+/// RTT 单通道显示设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RttChannelView {
+    pub channel: usize,
+    /// 是否显示该通道
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    /// 自定义显示名称
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// 显示颜色（前端使用）
+    #[serde(default)]
+    pub color: Option<String>,
+    /// 文本过滤正则（匹配任意一条的行保留，为空时不过滤）
+    #[serde(default)]
+    pub filters: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 兼容芯片回退映射
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FallbackMapping {
+    /// 芯片名前缀（不区分大小写）
+    pub prefix: String,
+    /// 使用的兼容芯片名称
+    pub fallback: String,
+}
+
+/// 内置的回退映射
+fn default_fallback_mappings() -> Vec<FallbackMapping> {
+    vec![
+        // GD32F470 系列 -> GD32F407 (相似的 Cortex-M4 架构)
+        FallbackMapping { prefix: "GD32F470".to_string(), fallback: "GD32F407".to_string() },
+        // GD32F450 系列 -> GD32F407
+        FallbackMapping { prefix: "GD32F450".to_string(), fallback: "GD32F407".to_string() },
+    ]
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             custom_packs_dir: None,
+            compress_scan_reports: false,
+            vendor_flash_bases: HashMap::new(),
+            target_overrides: HashMap::new(),
+            fallback_mappings: default_fallback_mappings(),
+            header_reserves: HashMap::new(),
+            algorithm_sizing: HashMap::new(),
+            rtt_view_config: RttViewConfig::default(),
+            probe_open_retry: ProbeOpenRetry::default(),
+            last_connection: None,
+            control_server: ControlServerConfig::default(),
+            core_operation_timeout_ms: None,
         }
     }
 }
@@ -0,0 +1,413 @@
+// 命令行（无界面）模式
+// 带任一命令行参数（--flash / --list-probes / --chip 等）启动时不打开窗口，直接复用连接与烧录逻辑，结束后以退出码返回结果
+
+use crate::address::parse_address;
+use crate::commands::config::init_packs;
+use crate::commands::flash::{flash_firmware_with, EraseMode, EventSink, FlashOptions};
+use crate::commands::probe::{connect_target_with, ConnectOptions};
+use crate::error::AppError;
+use crate::state::{AppState, ConnectMode, InterfaceType};
+use probe_rs::probe::list::Lister;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// 退出码：成功
+pub const EXIT_OK: i32 = 0;
+/// 退出码：连接或烧录失败
+pub const EXIT_FAILURE: i32 = 1;
+/// 退出码：参数错误
+pub const EXIT_USAGE: i32 = 2;
+
+pub const USAGE: &str = "\
+用法:
+  ek-omniprobe --flash <固件> --chip <芯片> [选项]
+  ek-omniprobe --list-probes
+
+选项:
+  --probe <序列号|标识>   选择探针（只连接了一个探针时可省略）
+  --protocol <swd|jtag>   调试接口（默认 swd）
+  --speed <kHz>           调试时钟
+  --under-reset           以 UnderReset 模式连接
+  --base <地址>           BIN 固件烧录地址（十六进制需带 0x）
+  --chip-erase            全片擦除（默认扇区擦除）
+  --verify                烧录后校验
+  --no-reset              烧录后不复位
+  --yes                   确认需要二次确认的操作（如覆盖受保护区域）
+  --help                  显示本帮助
+
+不带参数启动时打开图形界面。";
+
+/// 命令行要执行的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Help,
+    ListProbes,
+    Flash(CliFlashArgs),
+}
+
+/// --flash 的参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliFlashArgs {
+    pub file: String,
+    pub chip: String,
+    pub probe: Option<String>,
+    pub interface_type: InterfaceType,
+    pub speed_khz: Option<u32>,
+    pub under_reset: bool,
+    pub base_address: Option<u64>,
+    pub chip_erase: bool,
+    pub verify: bool,
+    pub reset_after: bool,
+    pub confirmed: bool,
+}
+
+/// 命令行模式识别的全部参数；出现任一参数即进入命令行模式，缺少必需参数时报告用法错误而不是打开界面
+const CLI_FLAGS: &[&str] = &[
+    "--flash",
+    "--list-probes",
+    "--help",
+    "-h",
+    "--chip",
+    "--probe",
+    "--protocol",
+    "--speed",
+    "--base",
+    "--under-reset",
+    "--chip-erase",
+    "--verify",
+    "--no-reset",
+    "--yes",
+];
+
+/// 是否应进入命令行模式（包含任一命令行模式参数）
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.iter().any(|a| CLI_FLAGS.contains(&a.as_str()))
+}
+
+/// Windows 发布版使用 GUI 子系统，没有控制台；从终端启动时附加到父进程控制台，使输出可见
+#[cfg(windows)]
+fn attach_parent_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    /// ATTACH_PARENT_PROCESS ((DWORD)-1)
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    // 已有控制台（调试版）或不是从终端启动时调用失败，忽略即可
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// 解析命令行参数（不含程序名）
+pub fn parse_args(args: &[String]) -> Result<CliCommand, String> {
+    let mut file = None;
+    let mut chip = None;
+    let mut probe = None;
+    let mut interface_type = InterfaceType::Swd;
+    let mut speed_khz = None;
+    let mut under_reset = false;
+    let mut base_address = None;
+    let mut chip_erase = false;
+    let mut verify = false;
+    let mut reset_after = true;
+    let mut confirmed = false;
+    let mut list_probes = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{} 缺少参数值", name))
+        };
+        match arg.as_str() {
+            "--help" | "-h" => return Ok(CliCommand::Help),
+            "--list-probes" => list_probes = true,
+            "--flash" => file = Some(value(arg.as_str())?),
+            "--chip" => chip = Some(value(arg.as_str())?),
+            "--probe" => probe = Some(value(arg.as_str())?),
+            "--protocol" => {
+                interface_type = match value(arg.as_str())?.to_lowercase().as_str() {
+                    "swd" => InterfaceType::Swd,
+                    "jtag" => InterfaceType::Jtag,
+                    other => return Err(format!("不支持的调试接口: {}", other)),
+                }
+            }
+            "--speed" => {
+                let v = value(arg.as_str())?;
+                let khz: u32 = v.parse().map_err(|_| format!("无效的时钟速度: {}", v))?;
+                // 连接时换算为 Hz，需保证不溢出
+                khz.checked_mul(1000)
+                    .ok_or_else(|| format!("时钟速度超出范围: {} kHz", v))?;
+                speed_khz = Some(khz);
+            }
+            "--base" => {
+                let v = value(arg.as_str())?;
+                base_address = Some(parse_address(&v)?);
+            }
+            "--under-reset" => under_reset = true,
+            "--chip-erase" => chip_erase = true,
+            "--verify" => verify = true,
+            "--no-reset" => reset_after = false,
+            "--yes" => confirmed = true,
+            other => return Err(format!("未知参数: {}", other)),
+        }
+    }
+
+    if list_probes && file.is_none() {
+        return Ok(CliCommand::ListProbes);
+    }
+    let file = file.ok_or("缺少 --flash <固件>")?;
+    let chip = chip.ok_or("缺少 --chip <芯片>")?;
+
+    Ok(CliCommand::Flash(CliFlashArgs {
+        file,
+        chip,
+        probe,
+        interface_type,
+        speed_khz,
+        under_reset,
+        base_address,
+        chip_erase,
+        verify,
+        reset_after,
+        confirmed,
+    }))
+}
+
+/// 按序列号或标识选择探针，返回 (标识, 同名探针中的序号)
+/// probes 为 (标识, 序列号) 列表；未指定时要求只连接了一个探针
+pub fn select_cli_probe(probes: &[(String, Option<String>)], wanted: Option<&str>) -> Result<(String, usize), String> {
+    let position = match wanted {
+        Some(wanted) => probes
+            .iter()
+            .position(|(_, serial)| serial.as_deref() == Some(wanted))
+            .or_else(|| probes.iter().position(|(identifier, _)| identifier == wanted))
+            .ok_or_else(|| format!("未找到探针: {}", wanted))?,
+        None => match probes.len() {
+            0 => return Err("未检测到探针".to_string()),
+            1 => 0,
+            n => return Err(format!("检测到 {} 个探针，请用 --probe <序列号> 指定", n)),
+        },
+    };
+    let identifier = probes[position].0.clone();
+    let index = probes[..position].iter().filter(|(id, _)| *id == identifier).count();
+    Ok((identifier, index))
+}
+
+/// 将连接/烧录事件打印到终端
+#[derive(Clone, Default)]
+struct ConsoleEvents {
+    last_phase: Arc<Mutex<String>>,
+}
+
+impl EventSink for ConsoleEvents {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let Ok(payload) = serde_json::to_value(payload) else {
+            return;
+        };
+        let text = |key: &str| payload.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        match event {
+            "flash-log" => println!("[{}] {}", text("level"), text("message")),
+            "flash-warning" | "clock-warning" => println!("[warn] {}", text("message")),
+            "flash-progress" => {
+                // 只在阶段切换时输出，避免逐块刷屏
+                let phase = text("phase");
+                let mut last = self.last_phase.lock().unwrap();
+                if *last != phase {
+                    let progress = payload.get("progress").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                    println!("[{:>3.0}%] {}", progress * 100.0, text("message"));
+                    *last = phase;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn list_probes() -> i32 {
+    let probes = Lister::new().list_all();
+    if probes.is_empty() {
+        eprintln!("未检测到探针");
+        return EXIT_FAILURE;
+    }
+    for probe in probes {
+        println!(
+            "{}  {:04x}:{:04x}  序列号: {}",
+            probe.identifier,
+            probe.vendor_id,
+            probe.product_id,
+            probe.serial_number.as_deref().unwrap_or("-")
+        );
+    }
+    EXIT_OK
+}
+
+fn flash(args: CliFlashArgs) -> Result<(), AppError> {
+    if let Err(e) = tauri::async_runtime::block_on(init_packs()) {
+        log::warn!("加载已导入的 Pack 失败: {}", e);
+    }
+
+    let probes: Vec<(String, Option<String>)> = Lister::new()
+        .list_all()
+        .into_iter()
+        .map(|p| (p.identifier, p.serial_number))
+        .collect();
+    let (probe_identifier, probe_index) =
+        select_cli_probe(&probes, args.probe.as_deref()).map_err(AppError::ProbeError)?;
+
+    let state = AppState::from_config(&crate::app_config::load_config());
+    let events = ConsoleEvents::default();
+    let connect_options = ConnectOptions {
+        probe_identifier,
+        probe_index: Some(probe_index),
+        usb_location: None,
+        target: args.chip,
+        interface_type: args.interface_type,
+        clock_speed: args.speed_khz.map(|khz| khz * 1000),
+        connect_mode: if args.under_reset { ConnectMode::UnderReset } else { ConnectMode::Normal },
+        ap_index: None,
+        debug_base: None,
+        algorithm_ram_region: None,
+        flash_algorithm: None,
+        force_dap_version: None,
+        read_reset_reason: false,
+    };
+    let target = connect_target_with(connect_options, &state, &events)?;
+    println!("已连接: {} ({})", target.name, target.core_type);
+
+    let options = FlashOptions {
+        file_path: args.file,
+        verify: args.verify,
+        skip_erase: false,
+        reset_after: args.reset_after,
+        erase_mode: if args.chip_erase { EraseMode::ChipErase } else { EraseMode::SectorErase },
+        use_custom_address: args.base_address.map(|_| true),
+        custom_flash_address: args.base_address,
+        custom_flash_size: None,
+        flash_algorithm: None,
+        preverify: false,
+        core_index: None,
+        algorithm_ram_region: None,
+        auto_algorithm_ram_region: false,
+        halt_after: false,
+        preserve_regions: Vec::new(),
+        verify_per_sector: false,
+        confirmed: args.confirmed,
+        flash_retry_count: 0,
+        reset_before: None,
+    };
+    let result = flash_firmware_with(options, &state, &events)?;
+    println!(
+        "烧录成功: 编程 {} 字节，擦除 {} 字节，耗时 {} ms{}",
+        result.bytes_programmed,
+        result.bytes_erased,
+        result.elapsed_ms,
+        if result.verified { "，已校验" } else { "" }
+    );
+    Ok(())
+}
+
+/// 命令行参数表明是无界面调用时执行并返回退出码，否则返回 None（启动图形界面）
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    if !is_cli_invocation(args) {
+        return None;
+    }
+    #[cfg(windows)]
+    attach_parent_console();
+
+    let code = match parse_args(args) {
+        Ok(CliCommand::Help) => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        Ok(CliCommand::ListProbes) => list_probes(),
+        Ok(CliCommand::Flash(flash_args)) => match flash(flash_args) {
+            Ok(()) => EXIT_OK,
+            Err(AppError::RequiresConfirmation(reason)) => {
+                eprintln!("需要确认: {}（确认后加 --yes 重新执行）", reason);
+                EXIT_FAILURE
+            }
+            Err(e) => {
+                eprintln!("失败: {}", e);
+                EXIT_FAILURE
+            }
+        },
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            EXIT_USAGE
+        }
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::InterfaceType;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_gui_when_no_cli_flags() {
+        assert!(!is_cli_invocation(&[]));
+        assert!(!is_cli_invocation(&args(&["-psn_0_12345"])));
+        assert!(is_cli_invocation(&args(&["--chip", "STM32F103C8", "--flash", "fw.hex"])));
+    }
+
+    #[test]
+    fn test_chip_without_flash_is_usage_error() {
+        let raw = args(&["--chip", "STM32F103C8"]);
+        assert!(is_cli_invocation(&raw));
+        assert!(parse_args(&raw).is_err());
+    }
+
+    #[test]
+    fn test_parse_flash_args() {
+        let cmd = parse_args(&args(&[
+            "--flash", "fw.bin", "--chip", "STM32F103C8", "--probe", "ABC123",
+            "--protocol", "JTAG", "--speed", "4000", "--base", "0x08004000", "--verify", "--no-reset",
+        ]))
+        .unwrap();
+        let CliCommand::Flash(flash) = cmd else { panic!("应解析为烧录命令") };
+        assert_eq!(flash.file, "fw.bin");
+        assert_eq!(flash.chip, "STM32F103C8");
+        assert_eq!(flash.probe.as_deref(), Some("ABC123"));
+        assert_eq!(flash.interface_type, InterfaceType::Jtag);
+        assert_eq!(flash.speed_khz, Some(4000));
+        assert_eq!(flash.base_address, Some(0x0800_4000));
+        assert!(flash.verify);
+        assert!(!flash.reset_after);
+        assert!(!flash.chip_erase);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_args(&args(&["--flash", "fw.hex"])).is_err());
+        assert!(parse_args(&args(&["--flash"])).is_err());
+        assert!(parse_args(&args(&["--flash", "fw.hex", "--chip", "X", "--bogus"])).is_err());
+        assert!(parse_args(&args(&["--flash", "fw.hex", "--chip", "X", "--base", "zz"])).is_err());
+        assert!(parse_args(&args(&["--flash", "fw.hex", "--chip", "X", "--speed", "5000000"])).is_err());
+        assert_eq!(parse_args(&args(&["--list-probes"])).unwrap(), CliCommand::ListProbes);
+        assert_eq!(parse_args(&args(&["--flash", "a", "-h"])).unwrap(), CliCommand::Help);
+    }
+
+    #[test]
+    fn test_select_probe() {
+        let probes = vec![
+            ("CMSIS-DAP".to_string(), Some("AAA".to_string())),
+            ("ST-Link V2".to_string(), None),
+            ("CMSIS-DAP".to_string(), Some("BBB".to_string())),
+        ];
+        assert_eq!(select_cli_probe(&probes, Some("BBB")).unwrap(), ("CMSIS-DAP".to_string(), 1));
+        assert_eq!(select_cli_probe(&probes, Some("ST-Link V2")).unwrap(), ("ST-Link V2".to_string(), 0));
+        assert!(select_cli_probe(&probes, Some("CCC")).is_err());
+        assert!(select_cli_probe(&probes, None).is_err());
+        assert_eq!(select_cli_probe(&probes[1..2], None).unwrap(), ("ST-Link V2".to_string(), 0));
+        assert!(select_cli_probe(&[], None).is_err());
+    }
+}
@@ -1,15 +1,46 @@
 use crate::error::{AppError, AppResult};
+use crate::app_config::{AlgorithmSizing, FallbackMapping};
 use crate::pack::manager::{PackManager, PackInfo};
 use crate::pack::target_gen;
+use crate::pack::target_override;
 use probe_rs::config::Registry;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::Emitter;
 
 // Global target registry - probe-rs 0.31 uses instance-based Registry
 lazy_static::lazy_static! {
     pub static ref TARGET_REGISTRY: Mutex<Registry> = Mutex::new(Registry::from_builtin_families());
+    /// 从已导入 Pack 注册的家族名 -> Pack 名
+    /// 内置目标同样可能带 generated_from_pack 标记，来源判断只认这里登记的家族
+    pub static ref PACK_FAMILIES: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// 注册 Pack 生成的目标家族并登记其来源 Pack，返回家族名
+pub fn register_pack_family(registry: &mut Registry, yaml_content: &str, pack_name: &str) -> AppResult<String> {
+    let family = serde_yaml::from_str::<serde_yaml::Value>(yaml_content)
+        .ok()
+        .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_string))
+        .ok_or_else(|| AppError::PackError("targets.yaml 缺少家族名称".to_string()))?;
+
+    registry
+        .add_target_family_from_yaml(yaml_content)
+        .map_err(|e| AppError::PackError(format!("注册设备到 probe-rs 失败: {}", e)))?;
+
+    PACK_FAMILIES.lock().unwrap().insert(family.clone(), pack_name.to_string());
+    Ok(family)
+}
+
+/// 提供该芯片的已导入 Pack 名称，内置目标返回 None
+pub fn pack_for_chip(registry: &Registry, chip_name: &str) -> Option<String> {
+    let pack_families = PACK_FAMILIES.lock().unwrap();
+    registry
+        .families()
+        .iter()
+        .find(|family| family.variants().iter().any(|v| v.name.eq_ignore_ascii_case(chip_name)))
+        .and_then(|family| pack_families.get(&family.name).cloned())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +51,9 @@ pub struct ChipInfo {
     pub cores: Vec<CoreInfo>,
     pub memory_regions: Vec<MemoryRegionInfo>,
     pub flash_algorithms: Vec<FlashAlgorithmInfo>,
+    /// 使用的兼容芯片回退映射（直接匹配时为 None）
+    #[serde(default)]
+    pub fallback: Option<FallbackMapping>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,8 +183,10 @@ pub async fn get_supported_chips() -> AppResult<Vec<String>> {
     Ok(BUILTIN_CHIPS.iter().map(|s| s.to_string()).collect())
 }
 
-#[tauri::command]
-pub async fn search_chips(query: String) -> AppResult<Vec<String>> {
+/// 在内置列表和 Registry 中搜索芯片名称
+/// Registry 包含 probe-rs 内置目标和从 Pack 导入的设备，
+/// 因此仅存在于 Pack 中（不在 BUILTIN_CHIPS 中）的芯片同样可以被搜索和连接
+pub fn search_chip_names(registry: &Registry, query: &str) -> Vec<String> {
     let query_lower = query.to_lowercase();
     let mut all_chips = Vec::new();
 
@@ -163,7 +199,6 @@ pub async fn search_chips(query: String) -> AppResult<Vec<String>> {
     all_chips.extend(builtin_matched);
 
     // 2. 从 probe-rs 注册的所有目标中搜索（包含从 Pack 导入的）
-    let registry = TARGET_REGISTRY.lock().unwrap();
     for family in registry.families() {
         for variant in family.variants() {
             let chip_name = variant.name.clone();
@@ -175,11 +210,39 @@ pub async fn search_chips(query: String) -> AppResult<Vec<String>> {
             }
         }
     }
-    drop(registry);
 
     // 限制返回数量并排序
     all_chips.sort();
     all_chips.truncate(50);
+    all_chips
+}
+
+/// 解析到的芯片定义
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedChip {
+    /// Registry 中的芯片名称
+    pub name: String,
+    /// 定义来源: "builtin" | "probe_rs" | "pack"
+    pub source: String,
+}
+
+/// 通过 Registry 解析芯片名称（任何 probe-rs 可解析的名称均可，不要求在内置列表中）
+pub fn resolve_chip(registry: &Registry, name: &str) -> AppResult<ResolvedChip> {
+    let target = registry
+        .get_target_by_name(name)
+        .map_err(|e| AppError::ConfigError(format!("未找到芯片 '{}': {}", name, e)))?;
+
+    Ok(ResolvedChip {
+        source: chip_source(registry, name, &target.name).to_string(),
+        name: target.name,
+    })
+}
+
+#[tauri::command]
+pub async fn search_chips(query: String) -> AppResult<Vec<String>> {
+    let registry = TARGET_REGISTRY.lock().unwrap();
+    let all_chips = search_chip_names(&registry, &query);
+    drop(registry);
 
     #[cfg(debug_assertions)]
     if !all_chips.is_empty() {
@@ -226,6 +289,12 @@ pub async fn init_packs() -> AppResult<usize> {
         }
     }
 
+    // 重新应用用户的目标定义覆盖
+    let overrides = target_override::apply_saved_overrides(&mut TARGET_REGISTRY.lock().unwrap());
+    if overrides > 0 {
+        log::info!("已应用 {} 个目标定义覆盖", overrides);
+    }
+
     #[cfg(debug_assertions)]
     println!("\n✅ Pack 初始化完成，共注册 {} 个设备\n", total_devices);
 
@@ -234,25 +303,243 @@ pub async fn init_packs() -> AppResult<usize> {
     Ok(total_devices)
 }
 
+/// 重新加载目标定义的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadTargetsResult {
+    /// 成功注册的家族数
+    pub families: usize,
+    /// 成功注册的芯片型号数
+    pub variants: usize,
+    /// 解析或注册失败的 Pack
+    pub failed: Vec<ReloadTargetsFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadTargetsFailure {
+    pub pack: String,
+    pub error: String,
+}
+
+/// 读取 Pack 目录中的 targets.yaml 并注册到 Registry，返回 (家族名, 型号数)
+fn reload_pack_yaml(registry: &mut Registry, pack_dir: &Path, pack_name: &str) -> AppResult<(String, usize)> {
+    let yaml_path = pack_dir.join("targets.yaml");
+    let yaml_content = std::fs::read_to_string(&yaml_path)
+        .map_err(|e| AppError::PackError(format!("读取 {:?} 失败: {}", yaml_path, e)))?;
+
+    let family_value: serde_yaml::Value = serde_yaml::from_str(&yaml_content)
+        .map_err(|e| AppError::PackError(format!("解析 targets.yaml 失败: {}", e)))?;
+    let variants = family_value
+        .get("variants")
+        .and_then(|v| v.as_sequence())
+        .map_or(0, |v| v.len());
+
+    let family = register_pack_family(registry, &yaml_content, pack_name)?;
+
+    Ok((family, variants))
+}
+
+/// 重新加载所有 Pack 的 targets.yaml（手动编辑后无需重启即可生效）
+/// 直接注册已生成的 YAML，不重新解析 PDSC
+#[tauri::command]
+pub async fn reload_targets() -> AppResult<ReloadTargetsResult> {
+    let manager = PackManager::new()?;
+    let packs = manager.list_packs()?;
+
+    let mut result = ReloadTargetsResult {
+        families: 0,
+        variants: 0,
+        failed: Vec::new(),
+    };
+
+    let mut registry = TARGET_REGISTRY.lock().unwrap();
+    for pack in packs {
+        let pack_dir = manager.get_pack_dir(&pack.name);
+        match reload_pack_yaml(&mut registry, &pack_dir, &pack.name) {
+            Ok((family, variants)) => {
+                log::info!("已重新加载 Pack {} (家族 {}, {} 个型号)", pack.name, family, variants);
+                result.families += 1;
+                result.variants += variants;
+            }
+            Err(e) => {
+                log::warn!("重新加载 Pack {} 失败: {}", pack.name, e);
+                result.failed.push(ReloadTargetsFailure {
+                    pack: pack.name,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    // 重新注册会覆盖用户的目标定义覆盖，需要重新应用
+    target_override::apply_saved_overrides(&mut registry);
+
+    Ok(result)
+}
+
+/// 导出目标定义的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportTargetsResult {
+    pub output_dir: String,
+    /// 每个家族一个文件
+    pub files: Vec<String>,
+    pub families: Vec<String>,
+    pub variants: usize,
+    /// 同名而被跳过的家族
+    pub duplicates: Vec<String>,
+    /// 重新生成或解析失败的 Pack
+    pub failed: Vec<ReloadTargetsFailure>,
+}
+
+/// 将所有已导入 Pack 的目标定义导出到目录，每个家族一个 probe-rs 目标文件
+/// 缺少 targets.yaml 的 Pack 先重新生成
+#[tauri::command]
+pub async fn export_all_targets_yaml(output_dir: String) -> AppResult<ExportTargetsResult> {
+    let manager = PackManager::new()?;
+    let mut documents = Vec::new();
+    let mut failed = Vec::new();
+
+    for pack in manager.list_packs()? {
+        let pack_dir = manager.get_pack_dir(&pack.name);
+        let yaml_path = pack_dir.join("targets.yaml");
+        if !yaml_path.exists() {
+            log::info!("Pack {} 缺少 targets.yaml，重新生成", pack.name);
+            if let Err(e) = register_pack_devices(&pack_dir, &pack.name, None) {
+                failed.push(ReloadTargetsFailure { pack: pack.name, error: e.to_string() });
+                continue;
+            }
+        }
+        match std::fs::read_to_string(&yaml_path) {
+            Ok(content) => documents.push((pack.name, content)),
+            Err(e) => failed.push(ReloadTargetsFailure {
+                pack: pack.name,
+                error: format!("读取 {:?} 失败: {}", yaml_path, e),
+            }),
+        }
+    }
+
+    let (bundle, errors) = target_gen::bundle_target_families(&documents);
+    failed.extend(errors.into_iter().map(|(pack, error)| ReloadTargetsFailure { pack, error }));
+    if bundle.families.is_empty() {
+        return Err(AppError::PackError("没有可导出的目标定义".to_string()));
+    }
+    for name in &bundle.duplicates {
+        log::warn!("家族 {} 重复，只导出第一个", name);
+    }
+
+    let files = target_gen::write_target_bundle(&bundle, std::path::Path::new(&output_dir))?;
+    log::info!(
+        "已导出 {} 个家族 ({} 个型号) 到 {}",
+        bundle.families.len(),
+        bundle.variants,
+        output_dir
+    );
+
+    Ok(ExportTargetsResult {
+        output_dir,
+        files: files.iter().map(|f| f.to_string_lossy().to_string()).collect(),
+        families: bundle.families,
+        variants: bundle.variants,
+        duplicates: bundle.duplicates,
+        failed,
+    })
+}
+
 /// 获取芯片的回退兼容型号
 /// 当 probe-rs 不支持某个芯片时，尝试使用相似架构的芯片
-fn get_fallback_chip(chip_name: &str) -> Option<String> {
+fn get_fallback_chip(chip_name: &str) -> Option<FallbackMapping> {
     let chip_upper = chip_name.to_uppercase();
 
-    // GD32F470 系列 -> GD32F407 (相似的 Cortex-M4 架构)
-    if chip_upper.starts_with("GD32F470") {
-        return Some("GD32F407".to_string());
+    // 取最长匹配的前缀，便于用户添加更具体的规则
+    crate::app_config::load_config()
+        .fallback_mappings
+        .into_iter()
+        .filter(|m| !m.prefix.is_empty() && chip_upper.starts_with(&m.prefix.to_uppercase()))
+        .max_by_key(|m| m.prefix.len())
+}
+
+/// 添加（或替换同前缀的）兼容芯片回退映射
+#[tauri::command]
+pub async fn add_fallback_mapping(prefix: String, fallback: String) -> AppResult<Vec<FallbackMapping>> {
+    let prefix = prefix.trim().to_string();
+    if prefix.is_empty() {
+        return Err(AppError::ConfigError("回退映射前缀不能为空".to_string()));
+    }
+
+    {
+        let registry = TARGET_REGISTRY.lock().unwrap();
+        registry.get_target_by_name(&fallback)
+            .map_err(|e| AppError::ConfigError(format!("兼容芯片 {} 不存在: {}", fallback, e)))?;
+    }
+
+    let mut config = crate::app_config::load_config();
+    config.fallback_mappings.retain(|m| !m.prefix.eq_ignore_ascii_case(&prefix));
+    config.fallback_mappings.push(FallbackMapping { prefix, fallback });
+    crate::app_config::save_config(&config)?;
+
+    Ok(config.fallback_mappings)
+}
+
+/// 列出当前的兼容芯片回退映射
+#[tauri::command]
+pub async fn list_fallback_mappings() -> AppResult<Vec<FallbackMapping>> {
+    Ok(crate::app_config::load_config().fallback_mappings)
+}
+
+/// 芯片支持查询结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ChipSupport {
+    pub supported: bool,
+    /// 定义来源: "builtin" | "probe_rs" | "pack" | "none"
+    pub source: String,
+    /// 实际解析到的芯片名称
+    pub resolved_name: Option<String>,
+    /// 是否使用了回退兼容型号（近似目标）
+    pub fallback: bool,
+}
+
+/// 判断芯片定义来源
+fn chip_source(registry: &Registry, requested: &str, resolved: &str) -> &'static str {
+    if pack_for_chip(registry, resolved).is_some() {
+        "pack"
+    } else if BUILTIN_CHIPS.iter().any(|c| c.eq_ignore_ascii_case(requested)) {
+        "builtin"
+    } else {
+        "probe_rs"
     }
+}
 
-    // GD32F450 系列 -> GD32F407
-    if chip_upper.starts_with("GD32F450") {
-        return Some("GD32F407".to_string());
+/// 查询芯片是否受支持及其定义来源（保存项目配置前校验）
+#[tauri::command]
+pub async fn is_chip_supported(name: String) -> AppResult<ChipSupport> {
+    let registry = TARGET_REGISTRY.lock().unwrap();
+
+    if let Ok(target) = registry.get_target_by_name(&name) {
+        return Ok(ChipSupport {
+            supported: true,
+            source: chip_source(&registry, &name, &target.name).to_string(),
+            resolved_name: Some(target.name),
+            fallback: false,
+        });
     }
 
-    // 可以添加更多回退规则
-    // 例如：GD32F3xx -> STM32F3xx
+    if let Some(FallbackMapping { fallback, .. }) = get_fallback_chip(&name) {
+        if let Ok(target) = registry.get_target_by_name(&fallback) {
+            log::warn!("芯片 {} 将使用兼容芯片 {}", name, target.name);
+            return Ok(ChipSupport {
+                supported: true,
+                source: chip_source(&registry, &fallback, &target.name).to_string(),
+                resolved_name: Some(target.name),
+                fallback: true,
+            });
+        }
+    }
 
-    None
+    Ok(ChipSupport {
+        supported: false,
+        source: "none".to_string(),
+        resolved_name: None,
+        fallback: false,
+    })
 }
 
 /// 从 Pack 目录注册设备到 probe-rs
@@ -285,7 +572,9 @@ fn register_pack_devices(
     log::info!("从 Pack {} 解析到 {} 个设备", pack_name, devices.len());
 
     // 生成 probe-rs YAML 格式（包含 Flash 算法）
-    let yaml_content = target_gen::generate_probe_rs_yaml_with_algo(&devices, pack_name, pack_dir, progress_callback)?;
+    let overrides = target_gen::TargetGenOverrides::from_config(&crate::app_config::load_config());
+    let yaml_content =
+        target_gen::generate_probe_rs_yaml_with_algo(&devices, pack_name, pack_dir, &overrides, progress_callback)?;
 
     // 保存 YAML 文件到 Pack 目录
     let yaml_path = pack_dir.join("targets.yaml");
@@ -305,16 +594,20 @@ fn register_pack_devices(
     }
 
     let mut registry = TARGET_REGISTRY.lock().unwrap();
-    match registry.add_target_family_from_yaml(&yaml_content) {
+    match register_pack_family(&mut registry, &yaml_content, pack_name) {
         Ok(_) => {
             log::info!("成功注册 {} 个设备到 probe-rs（包含 Flash 算法）", devices.len());
+
+            // 重新扫描会覆盖生成的定义，需要重新应用用户覆盖
+            target_override::apply_saved_overrides(&mut registry);
             #[cfg(debug_assertions)]
             println!("  ✅ 成功注册到 probe-rs");
 
             // 生成并保存扫描报告
-            match target_gen::generate_scan_report(&devices, pack_name, pack_dir) {
+            match target_gen::generate_scan_report(&devices, pack_name, pack_dir, &overrides) {
                 Ok(report) => {
-                    if let Err(e) = target_gen::save_scan_report(&report, pack_dir) {
+                    let compress = crate::app_config::load_config().compress_scan_reports;
+                    if let Err(e) = target_gen::save_scan_report(&report, pack_dir, compress) {
                         log::warn!("保存扫描报告失败: {}", e);
                     } else {
                         log::info!("扫描报告已生成: {} 个设备，{} 个有算法，{} 个无算法",
@@ -335,28 +628,57 @@ fn register_pack_devices(
                 println!("  💡 提示: 请检查 targets.yaml 文件格式");
                 println!("  💡 错误详情: {:?}", e);
             }
-            Err(AppError::PackError(format!("注册设备到 probe-rs 失败: {}", e)))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
 pub async fn get_chip_info(chip_name: String) -> AppResult<ChipInfo> {
-    // 尝试从probe-rs获取目标信息
     let registry = TARGET_REGISTRY.lock().unwrap();
-    let target = match registry.get_target_by_name(&chip_name) {
+    chip_info(&registry, &chip_name)
+}
+
+/// 批量查询中单个芯片的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ChipInfoLookup {
+    /// 请求的芯片名称
+    pub name: String,
+    pub info: Option<ChipInfo>,
+    pub error: Option<String>,
+}
+
+/// 批量查询芯片信息（供对比表使用），单个芯片失败不影响其他芯片
+#[tauri::command]
+pub async fn get_chip_info_batch(names: Vec<String>) -> AppResult<Vec<ChipInfoLookup>> {
+    let registry = TARGET_REGISTRY.lock().unwrap();
+    Ok(names
+        .into_iter()
+        .map(|name| match chip_info(&registry, &name) {
+            Ok(info) => ChipInfoLookup { name, info: Some(info), error: None },
+            Err(e) => ChipInfoLookup { name, info: None, error: Some(e.to_string()) },
+        })
+        .collect())
+}
+
+/// 从 Registry 获取芯片信息，找不到时尝试兼容芯片回退映射
+fn chip_info(registry: &Registry, chip_name: &str) -> AppResult<ChipInfo> {
+    let mut applied_fallback = None;
+    let target = match registry.get_target_by_name(chip_name) {
         Ok(t) => t,
         Err(e) => {
             // 如果找不到精确匹配，尝试使用家族名称作为回退
             // 例如：GD32F470ZGT6 -> GD32F407 (相似架构)
-            let fallback_chip = get_fallback_chip(&chip_name);
-            if let Some(fallback) = fallback_chip {
-                log::warn!("芯片 {} 不在 probe-rs 数据库中，尝试使用兼容芯片: {}", chip_name, fallback);
-                registry.get_target_by_name(&fallback)
+            let fallback_chip = get_fallback_chip(chip_name);
+            if let Some(mapping) = fallback_chip {
+                log::warn!("芯片 {} 不在 probe-rs 数据库中，尝试使用兼容芯片: {}", chip_name, mapping.fallback);
+                let target = registry.get_target_by_name(&mapping.fallback)
                     .map_err(|e2| AppError::ConfigError(format!(
                         "未找到芯片 {} 及其兼容芯片 {}: 原始错误: {}, 回退错误: {}",
-                        chip_name, fallback, e, e2
-                    )))?
+                        chip_name, mapping.fallback, e, e2
+                    )))?;
+                applied_fallback = Some(mapping);
+                target
             } else {
                 return Err(AppError::ConfigError(format!("未找到芯片 {}: {}", chip_name, e)));
             }
@@ -415,41 +737,165 @@ pub async fn get_chip_info(chip_name: String) -> AppResult<ChipInfo> {
                 data_section_offset: a.data_section_offset,
             })
             .collect(),
+        fallback: applied_fallback,
     };
 
     Ok(chip_info)
 }
 
-#[tauri::command]
-pub async fn import_pack(app: tauri::AppHandle, pack_path: String) -> AppResult<PackInfo> {
-    let path = PathBuf::from(&pack_path);
-
-    if !path.exists() {
-        return Err(AppError::FileError("Pack文件不存在".to_string()));
-    }
-
+/// 导入单个 Pack 文件并注册其中的设备
+/// 注册失败不影响导入本身，错误信息随 Pack 信息一并返回，由调用方决定如何报告
+fn import_pack_file(app: &tauri::AppHandle, path: &Path) -> AppResult<(PackInfo, Option<String>)> {
+    use crate::pack::progress::{ImportProgressCallback, PackImportProgress};
     let manager = PackManager::new()?;
-    let pack_info = manager.import_pack(&path)?;
+
+    // 解压阶段单独发送 pack-import-progress，与设备扫描的 pack-scan-progress 区分
+    let import_app = app.clone();
+    let import_callback: ImportProgressCallback = Box::new(move |progress: PackImportProgress| {
+        let _ = import_app.emit("pack-import-progress", &progress);
+    });
+    let pack_info = manager.import_pack_with_progress(path, Some(&import_callback))?;
 
     // 导入后，尝试从 Pack 中提取设备定义并注册到 probe-rs
     let pack_dir = manager.get_pack_dir(&pack_info.name);
 
     // 创建进度回调，通过Tauri事件发送进度
     use crate::pack::progress::{PackScanProgress, ProgressCallback};
+    let app = app.clone();
     let callback: ProgressCallback = Box::new(move |progress: PackScanProgress| {
         let _ = app.emit("pack-scan-progress", &progress);
     });
 
-    match register_pack_devices(&pack_dir, &pack_info.name, Some(&callback)) {
+    let register_error = match register_pack_devices(&pack_dir, &pack_info.name, Some(&callback)) {
         Ok(count) => {
             log::info!("成功从 Pack {} 注册了 {} 个设备到 probe-rs", pack_info.name, count);
+            None
         }
         Err(e) => {
             log::warn!("从 Pack {} 注册设备失败: {}，Pack 已导入但设备可能无法使用", pack_info.name, e);
+            Some(format!("Pack 已导入，但注册设备失败: {}", e))
         }
+    };
+
+    Ok((pack_info, register_error))
+}
+
+#[tauri::command]
+pub async fn import_pack(app: tauri::AppHandle, pack_path: String) -> AppResult<PackInfo> {
+    let path = PathBuf::from(&pack_path);
+
+    if !path.exists() {
+        return Err(AppError::FileError("Pack文件不存在".to_string()));
     }
 
-    Ok(pack_info)
+    import_pack_file(&app, &path).map(|(pack_info, _)| pack_info)
+}
+
+/// 列出目录下（不递归）的 .pack/.zip 文件，按文件名排序
+pub fn find_pack_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(AppError::FileError(format!("目录不存在: {}", dir.display())));
+    }
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("pack") || e.eq_ignore_ascii_case("zip"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// 目录批量导入中单个文件的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PackImportResult {
+    pub file: String,
+    /// 导入且设备注册均成功；Pack 已导入但注册失败时为 false，pack_name 仍有值
+    pub success: bool,
+    pub pack_name: Option<String>,
+    pub device_count: Option<usize>,
+    pub error: Option<String>,
+}
+
+/// 目录批量导入的整体进度
+#[derive(Debug, Clone, Serialize)]
+pub struct PackBatchProgress {
+    /// 已处理的文件数
+    pub current: usize,
+    pub total: usize,
+    /// 正在导入的文件名
+    pub file: String,
+    pub progress: f64,
+}
+
+/// 导入目录下所有 .pack/.zip 文件，单个文件失败不影响其余文件
+#[tauri::command]
+pub async fn import_packs_from_directory(
+    app: tauri::AppHandle,
+    directory: String,
+) -> AppResult<Vec<PackImportResult>> {
+    let files = find_pack_files(Path::new(&directory))?;
+    let total = files.len();
+    log::info!("从目录 {} 批量导入 {} 个 Pack", directory, total);
+
+    let mut results = Vec::with_capacity(total);
+    for (index, path) in files.iter().enumerate() {
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let _ = app.emit(
+            "pack-import-batch-progress",
+            PackBatchProgress {
+                current: index,
+                total,
+                file: file.clone(),
+                progress: index as f64 / total as f64,
+            },
+        );
+
+        let result = match import_pack_file(&app, path) {
+            Ok((info, register_error)) => PackImportResult {
+                file,
+                success: register_error.is_none(),
+                pack_name: Some(info.name),
+                device_count: Some(info.device_count),
+                error: register_error,
+            },
+            Err(e) => {
+                log::warn!("导入 {} 失败: {}", file, e);
+                PackImportResult {
+                    file,
+                    success: false,
+                    pack_name: None,
+                    device_count: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    let _ = app.emit(
+        "pack-import-batch-progress",
+        PackBatchProgress {
+            current: total,
+            total,
+            file: String::new(),
+            progress: 1.0,
+        },
+    );
+    log::info!(
+        "批量导入完成: {}/{} 个 Pack 成功",
+        results.iter().filter(|r| r.success).count(),
+        total
+    );
+
+    Ok(results)
 }
 
 #[tauri::command]
@@ -486,6 +932,125 @@ pub async fn get_flash_algorithms(chip_name: String) -> AppResult<Vec<FlashAlgor
     Ok(algorithms)
 }
 
+/// 列出能容纳固件所有数据段的已注册芯片
+/// 带地址的固件 (ELF/HEX/S-record) 要求每个数据段都落在芯片的 NVM 区域内，
+/// BIN 固件只要求大小不超过芯片主 Flash 容量
+#[tauri::command]
+pub async fn suggest_targets_for_firmware(file_path: String) -> AppResult<Vec<String>> {
+    use crate::firmware::{self, FirmwareImage};
+    use probe_rs::config::MemoryRegion;
+
+    let image = firmware::load_image(std::path::Path::new(&file_path))?;
+
+    let registry = TARGET_REGISTRY.lock().unwrap();
+    let mut matches = Vec::new();
+
+    for family in registry.families() {
+        for variant in family.variants() {
+            let nvm_ranges: Vec<_> = variant
+                .memory_map
+                .iter()
+                .filter_map(|region| match region {
+                    MemoryRegion::Nvm(nvm) => Some(nvm),
+                    _ => None,
+                })
+                .collect();
+            if nvm_ranges.is_empty() {
+                continue;
+            }
+
+            let fits = match &image {
+                FirmwareImage::Segments(segments) => segments.iter().all(|segment| {
+                    let end = segment.address + segment.data.len() as u64;
+                    nvm_ranges
+                        .iter()
+                        .any(|nvm| nvm.range.start <= segment.address && end <= nvm.range.end)
+                }),
+                FirmwareImage::Binary(data) => nvm_ranges
+                    .iter()
+                    .find(|nvm| !nvm.is_alias)
+                    .is_some_and(|nvm| data.len() as u64 <= nvm.range.end - nvm.range.start),
+            };
+
+            if fits {
+                matches.push(variant.name.clone());
+            }
+        }
+    }
+
+    matches.sort();
+    matches.dedup();
+    log::info!("固件 {} 可烧录到 {} 个已注册芯片", file_path, matches.len());
+
+    Ok(matches)
+}
+
+/// 将 YAML 片段覆盖到已注册的芯片/家族定义上，并持久化以便启动时重新应用
+#[tauri::command]
+pub async fn override_target_yaml(name: String, yaml_fragment: String) -> AppResult<String> {
+    let family_name = {
+        let mut registry = TARGET_REGISTRY.lock().unwrap();
+        target_override::apply_override(&mut registry, &name, &yaml_fragment)?
+    };
+
+    let mut config = crate::app_config::load_config();
+    config.target_overrides.insert(name, yaml_fragment);
+    crate::app_config::save_config(&config)?;
+
+    Ok(family_name)
+}
+
+/// 删除已持久化的目标定义覆盖（重新扫描 Pack 或重启后恢复原始定义）
+#[tauri::command]
+pub async fn remove_target_override(name: String) -> AppResult<()> {
+    let mut config = crate::app_config::load_config();
+    if config.target_overrides.remove(&name).is_none() {
+        return Err(AppError::ConfigError(format!("{} 没有覆盖配置", name)));
+    }
+    crate::app_config::save_config(&config)
+}
+
+/// 设置（或清除）Pack/设备的 Flash 算法 header 预留大小，重新扫描 Pack 后生效
+#[tauri::command]
+pub async fn set_header_reserve(key: String, reserve: Option<u64>) -> AppResult<()> {
+    let mut config = crate::app_config::load_config();
+    match reserve {
+        Some(reserve) => {
+            if reserve % 4 != 0 {
+                return Err(AppError::ConfigError(format!("预留大小 0x{:x} 必须 4 字节对齐", reserve)));
+            }
+            config.header_reserves.insert(key, reserve);
+        }
+        None => {
+            config.header_reserves.remove(&key);
+        }
+    }
+    crate::app_config::save_config(&config)
+}
+
+/// 设置（或清除）Pack/设备的 Flash 算法栈大小与页缓冲数量，重新扫描 Pack 后生效
+#[tauri::command]
+pub async fn set_algorithm_sizing(key: String, sizing: Option<AlgorithmSizing>) -> AppResult<()> {
+    let mut config = crate::app_config::load_config();
+    match sizing {
+        Some(sizing) => {
+            if let Some(stack_size) = sizing.stack_size {
+                if stack_size == 0 || stack_size % 8 != 0 {
+                    return Err(AppError::ConfigError(format!("栈大小 0x{:x} 必须为非零且 8 字节对齐", stack_size)));
+                }
+            }
+            if sizing.page_buffers == Some(0) {
+                return Err(AppError::ConfigError("页缓冲数量至少为 1".to_string()));
+            }
+            config.algorithm_sizing.insert(key, sizing);
+        }
+        None => {
+            config.algorithm_sizing.remove(&key);
+        }
+    }
+    crate::app_config::save_config(&config)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectConfig {
     pub name: String,
@@ -530,9 +1095,89 @@ pub async fn get_devices_without_algorithm(pack_name: String) -> AppResult<Vec<S
     Ok(report.get_devices_without_algorithm())
 }
 
-/// 检查所有Pack的扫描器版本,返回需要重新扫描的Pack列表
+/// 芯片定义的提供者
+#[derive(Debug, Clone, Serialize)]
+pub struct ChipProvider {
+    pub chip_name: String,
+    /// 定义来源: "pack" | "builtin" | "probe_rs" | "none"
+    pub source: String,
+    /// 提供该芯片的 Pack（source 为 "pack" 时）
+    pub pack_name: Option<String>,
+    pub has_algorithm: bool,
+    /// Pack 扫描报告中的设备状态
+    pub status: Option<crate::pack::scan_report::DeviceStatus>,
+    pub status_reason: Option<String>,
+}
+
+/// 查询芯片由哪个已导入的 Pack 提供，以及扫描时是否找到可用的 Flash 算法
+/// 多个 Pack 提供同一芯片时优先返回状态正常的那个
+#[tauri::command]
+pub async fn find_chip_provider(chip_name: String) -> AppResult<ChipProvider> {
+    use crate::pack::scan_report::DeviceStatus;
+
+    let manager = PackManager::new()?;
+    let mut found: Option<ChipProvider> = None;
+    for pack in manager.list_packs()? {
+        let report = match target_gen::load_scan_report(&manager.get_pack_dir(&pack.name)) {
+            Ok(report) => report,
+            Err(e) => {
+                log::debug!("Pack {} 没有可用的扫描报告: {}", pack.name, e);
+                continue;
+            }
+        };
+        let Some(device) = report.find_device(&chip_name) else {
+            continue;
+        };
+
+        let provider = ChipProvider {
+            chip_name: device.name.clone(),
+            source: "pack".to_string(),
+            pack_name: Some(pack.name.clone()),
+            has_algorithm: device.algorithm.is_some(),
+            status: Some(device.status.clone()),
+            status_reason: device.status_reason(),
+        };
+        if device.status == DeviceStatus::Ok {
+            return Ok(provider);
+        }
+        found.get_or_insert(provider);
+    }
+    if let Some(provider) = found {
+        return Ok(provider);
+    }
+
+    let registry = TARGET_REGISTRY.lock().unwrap();
+    Ok(match registry.get_target_by_name(&chip_name) {
+        Ok(target) => ChipProvider {
+            source: chip_source(&registry, &chip_name, &target.name).to_string(),
+            has_algorithm: !target.flash_algorithms.is_empty(),
+            pack_name: pack_for_chip(&registry, &target.name),
+            chip_name: target.name,
+            status: None,
+            status_reason: None,
+        },
+        Err(_) => ChipProvider {
+            chip_name,
+            source: "none".to_string(),
+            pack_name: None,
+            has_algorithm: false,
+            status: None,
+            status_reason: Some("未在已导入的 Pack 或 probe-rs 内置目标中找到".to_string()),
+        },
+    })
+}
+
+/// 需要重新扫描的 Pack 及原因
+#[derive(Debug, Clone, Serialize)]
+pub struct OutdatedPack {
+    #[serde(flatten)]
+    pub pack: PackInfo,
+    pub reason: target_gen::RescanReason,
+}
+
+/// 检查所有Pack的扫描器版本与 PDSC 修改时间,返回需要重新扫描的Pack列表
 #[tauri::command]
-pub async fn check_outdated_packs() -> AppResult<Vec<PackInfo>> {
+pub async fn check_outdated_packs() -> AppResult<Vec<OutdatedPack>> {
     let manager = PackManager::new()?;
     let packs = manager.list_packs()?;
 
@@ -540,8 +1185,8 @@ pub async fn check_outdated_packs() -> AppResult<Vec<PackInfo>> {
 
     for pack in packs {
         let pack_dir = manager.get_pack_dir(&pack.name);
-        if target_gen::needs_rescan(&pack_dir) {
-            outdated_packs.push(pack);
+        if let Some(reason) = target_gen::rescan_reason(&pack_dir) {
+            outdated_packs.push(OutdatedPack { pack, reason });
         }
     }
 
@@ -583,7 +1228,7 @@ pub async fn rescan_all_outdated_packs(app: tauri::AppHandle) -> AppResult<Vec<S
     let outdated_packs = check_outdated_packs().await?;
     let mut rescanned = Vec::new();
 
-    for pack in outdated_packs {
+    for OutdatedPack { pack, .. } in outdated_packs {
         match rescan_pack(app.clone(), pack.name.clone()).await {
             Ok(_) => {
                 rescanned.push(pack.name);
@@ -604,10 +1249,32 @@ pub async fn get_packs_directory() -> AppResult<String> {
     Ok(packs_dir.to_string_lossy().to_string())
 }
 
+/// 检查目录可写（写入并删除一个临时文件）
+pub fn check_dir_writable(dir: &Path) -> AppResult<()> {
+    let probe = dir.join(".write_test");
+    std::fs::write(&probe, b"ok")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| AppError::PackError(format!("目录 {:?} 不可写: {}", dir, e)))
+}
+
+/// 更换 Pack 目录的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PacksDirectoryChange {
+    pub old_dir: String,
+    pub new_dir: String,
+    /// 未要求迁移或新旧目录相同时为 None
+    pub migration: Option<crate::pack::manager::PackMigrationReport>,
+}
+
 /// 设置自定义Pack目录路径
+/// 先验证新目录可写，可选将旧目录中的 Pack 复制或移动到新目录，迁移成功后再保存配置
 #[tauri::command]
-pub async fn set_custom_packs_directory(path: Option<String>) -> AppResult<()> {
+pub async fn set_custom_packs_directory(
+    path: Option<String>,
+    migrate: Option<crate::pack::manager::PackMigrationMode>,
+) -> AppResult<PacksDirectoryChange> {
     log::info!("设置自定义Pack目录: {:?}", path);
+    let old_dir = crate::pack::paths::get_packs_dir();
 
     // 验证路径是否有效
     if let Some(ref p) = path {
@@ -623,11 +1290,36 @@ pub async fn set_custom_packs_directory(path: Option<String>) -> AppResult<()> {
                 "指定的路径不是一个目录".to_string()
             ));
         }
+        check_dir_writable(&path_buf)?;
     }
 
-    crate::app_config::set_custom_packs_dir(path)?;
+    let new_dir = crate::pack::paths::packs_dir_for(path.as_ref().map(std::path::PathBuf::from));
 
+    let same_dir = match (old_dir.canonicalize(), new_dir.canonicalize()) {
+        (Ok(old), Ok(new)) => old == new,
+        _ => old_dir == new_dir,
+    };
+    let migration = match migrate {
+        Some(mode) if !same_dir => {
+            let report = PackManager::migrate_packs(&old_dir, &new_dir, mode)?;
+            log::info!(
+                "Pack 迁移完成: {} 个已迁移，{} 个已存在跳过，{} 个失败",
+                report.migrated.len(),
+                report.skipped.len(),
+                report.failed.len()
+            );
+            Some(report)
+        }
+        _ => None,
+    };
+
+    // 迁移完成后再保存配置，迁移失败时仍使用旧目录
+    crate::app_config::set_custom_packs_dir(path)?;
     log::info!("Pack目录配置已更新");
 
-    Ok(())
+    Ok(PacksDirectoryChange {
+        old_dir: old_dir.to_string_lossy().to_string(),
+        new_dir: new_dir.to_string_lossy().to_string(),
+        migration,
+    })
 }
@@ -0,0 +1,475 @@
+use crate::app_config::{self, ControlServerConfig};
+use crate::commands::flash::{flash_firmware_with, FlashOptions};
+use crate::commands::memory::{read_memory, ReadMemoryOptions};
+use crate::commands::probe::{self, ConnectOptions};
+use crate::error::{AppError, AppResult};
+use crate::state::{AppState, ControlServerHandle};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+/// 单个请求体的最大字节数
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+/// 请求头的最大行数
+const MAX_HEADER_LINES: usize = 64;
+/// 请求头的最大字节数（认证前读取，需要限制）
+const MAX_HEADER_SIZE: u64 = 16 * 1024;
+/// 读取请求头/请求体的超时
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// JSON-RPC 错误码
+pub const RPC_PARSE_ERROR: i32 = -32700;
+pub const RPC_INVALID_REQUEST: i32 = -32600;
+pub const RPC_METHOD_NOT_FOUND: i32 = -32601;
+pub const RPC_INVALID_PARAMS: i32 = -32602;
+/// 命令执行失败（AppError）
+pub const RPC_COMMAND_ERROR: i32 = -32000;
+/// 令牌缺失或错误
+pub const RPC_UNAUTHORIZED: i32 = -32001;
+
+/// 控制服务支持的方法
+pub const RPC_METHODS: &[&str] = &[
+    "list_probes",
+    "connect",
+    "disconnect",
+    "status",
+    "flash",
+    "read_memory",
+    "reset",
+];
+
+/// 控制服务状态
+#[derive(Debug, Clone, Serialize)]
+pub struct ControlServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    /// 访问令牌（未生成时为 None）
+    pub token: Option<String>,
+    /// 请求地址，如 http://127.0.0.1:7801/rpc
+    pub url: String,
+    pub methods: Vec<String>,
+}
+
+/// 解析后的 HTTP 请求头
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestHead {
+    pub method: String,
+    pub path: String,
+    pub content_length: usize,
+    pub authorization: Option<String>,
+}
+
+/// JSON-RPC 请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// JSON-RPC 错误
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl From<AppError> for RpcError {
+    fn from(e: AppError) -> Self {
+        Self::new(RPC_COMMAND_ERROR, e.to_string())
+    }
+}
+
+/// 解析请求行与请求头（不含空行与请求体）
+pub fn parse_request_head(lines: &[String]) -> Option<RequestHead> {
+    let mut parts = lines.first()?.split_whitespace();
+    let method = parts.next()?.to_uppercase();
+    let path = parts.next()?.to_string();
+
+    let mut head = RequestHead { method, path, content_length: 0, authorization: None };
+    for line in &lines[1..] {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => head.content_length = value.parse().ok()?,
+            "authorization" => head.authorization = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(head)
+}
+
+/// 校验 `Authorization: Bearer <token>`
+pub fn check_token(authorization: Option<&str>, token: &str) -> bool {
+    let Some(provided) = authorization.and_then(|v| v.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // 逐字节比较完整长度，避免按前缀提前返回
+    provided.len() == token.len()
+        && provided.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// 解析 JSON-RPC 请求体
+pub fn parse_rpc_request(body: &[u8]) -> Result<RpcRequest, RpcError> {
+    let value: Value = serde_json::from_slice(body)
+        .map_err(|e| RpcError::new(RPC_PARSE_ERROR, format!("JSON 解析失败: {}", e)))?;
+    serde_json::from_value(value)
+        .map_err(|e| RpcError::new(RPC_INVALID_REQUEST, format!("无效的请求: {}", e)))
+}
+
+/// 构造 JSON-RPC 响应
+pub fn rpc_response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+/// 生成随机访问令牌（系统随机数，32 位十六进制）
+pub fn generate_token() -> AppResult<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::ConfigError(format!("生成控制服务令牌失败: {}", e)))?;
+    Ok(hex::encode(bytes))
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(RPC_INVALID_PARAMS, format!("参数错误: {}", e)))
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| AppError::from(e).into())
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DisconnectParams {
+    #[serde(default)]
+    leave_halted: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResetParams {
+    #[serde(default)]
+    halt: Option<bool>,
+    #[serde(default)]
+    core_index: Option<usize>,
+}
+
+/// 分发到现有命令实现
+async fn dispatch(app: &AppHandle, method: &str, raw: Value) -> Result<Value, RpcError> {
+    let state: State<'_, AppState> = app.state();
+    match method {
+        "list_probes" => to_value(probe::list_probes().await?),
+        "connect" => {
+            let options: ConnectOptions = params(raw)?;
+            to_value(probe::connect_target(options, state, app.clone()).await?)
+        }
+        "disconnect" => {
+            let p: DisconnectParams = params(raw)?;
+            to_value(probe::disconnect(p.leave_halted, state).await?)
+        }
+        "status" => to_value(probe::get_connection_status(state).await?),
+        "flash" => {
+            let options: FlashOptions = params(raw)?;
+            // 烧录日志与进度仍发往前端，GUI 中可同步看到脚本触发的烧录
+            to_value(flash_firmware_with(options, &state, app)?)
+        }
+        "read_memory" => {
+            let options: ReadMemoryOptions = params(raw)?;
+            let address = options.address;
+            let data = read_memory(options, state).await?;
+            Ok(json!({ "address": address, "size": data.len(), "hex": hex::encode(&data) }))
+        }
+        "reset" => {
+            let p: ResetParams = params(raw)?;
+            to_value(probe::reset_target(p.halt, p.core_index, state).await?)
+        }
+        _ => Err(RpcError::new(RPC_METHOD_NOT_FOUND, format!("未知方法: {}", method))),
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &Value) -> std::io::Result<()> {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// 读取请求头行（直到空行、连接关闭或超过行数限制）
+async fn read_head_lines<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> std::io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            break;
+        }
+        let line = line.trim_end().to_string();
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+        if lines.len() > MAX_HEADER_LINES {
+            break;
+        }
+    }
+    Ok(lines)
+}
+
+fn timed_out() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::TimedOut, "读取请求超时")
+}
+
+/// 处理单个连接（每个连接一个请求）
+/// 认证前的读取限制字节数和时间，避免本地未认证客户端占用内存或任务
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, token: Arc<String>) -> std::io::Result<()> {
+    let mut reader = BufReader::new((&mut stream).take(MAX_HEADER_SIZE));
+    let lines = tokio::time::timeout(READ_TIMEOUT, read_head_lines(&mut reader))
+        .await
+        .map_err(|_| timed_out())??;
+
+    let Some(head) = parse_request_head(&lines) else {
+        let body = rpc_response(Value::Null, Err(RpcError::new(RPC_INVALID_REQUEST, "无效的 HTTP 请求")));
+        return write_response(&mut stream, "400 Bad Request", &body).await;
+    };
+    if head.method != "POST" || head.path != "/rpc" {
+        let body = rpc_response(Value::Null, Err(RpcError::new(RPC_INVALID_REQUEST, "仅支持 POST /rpc")));
+        return write_response(&mut stream, "404 Not Found", &body).await;
+    }
+    if !check_token(head.authorization.as_deref(), &token) {
+        log::warn!("控制服务拒绝了令牌无效的请求");
+        let body = rpc_response(Value::Null, Err(RpcError::new(RPC_UNAUTHORIZED, "令牌无效")));
+        return write_response(&mut stream, "401 Unauthorized", &body).await;
+    }
+    if head.content_length > MAX_BODY_SIZE {
+        let body = rpc_response(Value::Null, Err(RpcError::new(RPC_INVALID_REQUEST, "请求体过大")));
+        return write_response(&mut stream, "413 Payload Too Large", &body).await;
+    }
+
+    // 请求头已缓冲的部分计入请求体，剩余部分按 Content-Length 放开读取限制
+    let buffered = reader.buffer().len();
+    reader
+        .get_mut()
+        .set_limit(head.content_length.saturating_sub(buffered) as u64);
+    let mut body = vec![0u8; head.content_length];
+    tokio::time::timeout(READ_TIMEOUT, reader.read_exact(&mut body))
+        .await
+        .map_err(|_| timed_out())??;
+    drop(reader);
+
+    let response = match parse_rpc_request(&body) {
+        Ok(request) => {
+            log::info!("控制服务请求: {}", request.method);
+            let result = dispatch(&app, &request.method, request.params).await;
+            if let Err(e) = &result {
+                log::warn!("控制服务请求 {} 失败: {}", request.method, e.message);
+            }
+            rpc_response(request.id, result)
+        }
+        Err(e) => rpc_response(Value::Null, Err(e)),
+    };
+    write_response(&mut stream, "200 OK", &response).await
+}
+
+/// 启动控制服务（已运行时先停止并等待旧监听释放端口）
+pub async fn start_control_server(app: &AppHandle, port: u16, token: String) -> AppResult<()> {
+    let state: State<'_, AppState> = app.state();
+    stop_control_server_inner(&state).await;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| AppError::ConfigError(format!("控制服务无法监听 127.0.0.1:{}: {}", port, e)))?;
+    let shutdown = Arc::new(Notify::new());
+    log::info!("控制服务已启动: http://127.0.0.1:{}/rpc", port);
+
+    let app_handle = app.clone();
+    let token = Arc::new(token);
+    let task_shutdown = Arc::clone(&shutdown);
+    let task = tauri::async_runtime::spawn(async move {
+        let app = app_handle;
+        let shutdown = task_shutdown;
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let app = app.clone();
+                        let token = Arc::clone(&token);
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = handle_connection(app, stream, token).await {
+                                log::warn!("控制服务连接错误: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => log::warn!("控制服务接受连接失败: {}", e),
+                },
+            }
+        }
+        log::info!("控制服务已停止");
+    });
+    *state.control_server.lock() = Some(ControlServerHandle { port, shutdown, task });
+    Ok(())
+}
+
+/// 停止控制服务并等待监听任务退出（监听套接字随任务释放）
+async fn stop_control_server_inner(state: &AppState) {
+    let handle = state.control_server.lock().take();
+    if let Some(handle) = handle {
+        handle.shutdown.notify_one();
+        if let Err(e) = handle.task.await {
+            log::warn!("等待控制服务退出失败: {}", e);
+        }
+    }
+}
+
+/// 应用启动时按配置开启控制服务
+pub async fn start_from_config(app: AppHandle) {
+    let config = app_config::load_config().control_server;
+    if !config.enabled {
+        return;
+    }
+    let Some(token) = config.token else {
+        log::warn!("控制服务已启用但未配置令牌，跳过启动");
+        return;
+    };
+    if let Err(e) = start_control_server(&app, config.port, token).await {
+        log::error!("{}", e);
+    }
+}
+
+fn status(config: &ControlServerConfig, state: &AppState) -> ControlServerStatus {
+    let running = state.control_server.lock().as_ref().map(|h| h.port);
+    let port = running.unwrap_or(config.port);
+    ControlServerStatus {
+        enabled: config.enabled,
+        running: running.is_some(),
+        port,
+        token: config.token.clone(),
+        url: format!("http://127.0.0.1:{}/rpc", port),
+        methods: RPC_METHODS.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+/// 获取控制服务状态
+#[tauri::command]
+pub async fn get_control_server_status(state: State<'_, AppState>) -> AppResult<ControlServerStatus> {
+    Ok(status(&app_config::load_config().control_server, &state))
+}
+
+/// 修改控制服务设置并立即生效
+/// 开启时若没有令牌（或 regenerate_token 为 true）会生成新令牌并保存
+#[tauri::command]
+pub async fn set_control_server_config(
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> AppResult<ControlServerStatus> {
+    let mut config = app_config::load_config();
+    let server = &mut config.control_server;
+    server.enabled = enabled;
+    if let Some(port) = port {
+        server.port = port;
+    }
+    if enabled && (server.token.is_none() || regenerate_token.unwrap_or(false)) {
+        server.token = Some(generate_token()?);
+        log::info!("已生成新的控制服务令牌");
+    }
+
+    if enabled {
+        let token = server.token.clone().unwrap_or_default();
+        start_control_server(&app, server.port, token).await?;
+    } else {
+        stop_control_server_inner(&state).await;
+    }
+
+    app_config::save_config(&config)?;
+    Ok(status(&config.control_server, &state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_request_head() {
+        let head = parse_request_head(&lines(&[
+            "post /rpc HTTP/1.1",
+            "Host: 127.0.0.1:7801",
+            "content-length: 42",
+            "Authorization: Bearer abc",
+        ]))
+        .unwrap();
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/rpc");
+        assert_eq!(head.content_length, 42);
+        assert_eq!(head.authorization.as_deref(), Some("Bearer abc"));
+
+        assert!(parse_request_head(&[]).is_none());
+        assert!(parse_request_head(&lines(&["POST /rpc HTTP/1.1", "Content-Length: x"])).is_none());
+    }
+
+    #[test]
+    fn test_check_token() {
+        assert!(check_token(Some("Bearer secret"), "secret"));
+        assert!(!check_token(Some("Bearer secre"), "secret"));
+        assert!(!check_token(Some("secret"), "secret"));
+        assert!(!check_token(None, "secret"));
+    }
+
+    #[test]
+    fn test_parse_rpc_request() {
+        let req = parse_rpc_request(br#"{"jsonrpc":"2.0","id":7,"method":"reset","params":{"halt":true}}"#).unwrap();
+        assert_eq!(req.method, "reset");
+        assert_eq!(req.id, json!(7));
+        assert_eq!(req.params, json!({ "halt": true }));
+
+        assert_eq!(parse_rpc_request(b"{not json").unwrap_err().code, RPC_PARSE_ERROR);
+        assert_eq!(parse_rpc_request(br#"{"id":1}"#).unwrap_err().code, RPC_INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_rpc_response_shape() {
+        let ok = rpc_response(json!(1), Ok(json!({ "size": 4 })));
+        assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": { "size": 4 } }));
+
+        let err = rpc_response(json!("a"), parse_rpc_request(b"").map(|_| json!(null)));
+        assert_eq!(err["error"]["code"], json!(RPC_PARSE_ERROR));
+        assert!(err.get("result").is_none());
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let a = generate_token().unwrap();
+        assert_eq!(a.len(), 32);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, generate_token().unwrap());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::error::{AppError, AppResult};
-use crate::state::AppState;
-use probe_rs::flashing::{download_file_with_options, erase_all, FlashProgress, ProgressEvent, ProgressOperation, Format, DownloadOptions, BinOptions, ElfOptions};
-use probe_rs::MemoryInterface;
+use crate::firmware::{self, FirmwareFormat, FirmwareMetadata};
+use crate::state::{AppState, ConnectMode, CoreOperation, SessionGuard};
+use probe_rs::flashing::{download_file_with_options, erase, erase_all, FlashProgress, ProgressEvent, ProgressOperation, Format, DownloadOptions, BinOptions, ElfOptions};
+use probe_rs::{MemoryInterface, Session};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
@@ -14,6 +15,8 @@ struct ProgressState {
     erase_current: u64,
     program_total: u64,
     program_current: u64,
+    /// 擦除进度事件按扇区上报，用于统计实际擦除的扇区数
+    erase_sectors: usize,
 }
 
 impl ProgressState {
@@ -23,6 +26,7 @@ impl ProgressState {
             erase_current: 0,
             program_total: 0,
             program_current: 0,
+            erase_sectors: 0,
         }
     }
 
@@ -42,6 +46,126 @@ impl ProgressState {
 }
 
 
+/// 内置厂商（芯片名前缀）默认 Flash 基地址
+const VENDOR_FLASH_BASES: &[(&str, u64)] = &[
+    ("STM32", 0x0800_0000),
+    ("GD32", 0x0800_0000),
+    ("CW32", 0x0000_0000),
+    ("NRF", 0x0000_0000),
+    ("RP2040", 0x1000_0000), // XIP Flash
+    ("ESP32", 0x4200_0000),
+];
+
+/// 按芯片名查询厂商默认 Flash 基地址
+/// 用户配置优先，其次内置表（均按最长前缀匹配），未知厂商返回 0
+pub fn vendor_default_flash_base(chip_name: &str) -> u64 {
+    let chip_upper = chip_name.to_uppercase();
+    let config = crate::app_config::load_config();
+
+    let user_match = config
+        .vendor_flash_bases
+        .iter()
+        .filter(|(prefix, _)| chip_upper.starts_with(&prefix.to_uppercase()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, addr)| *addr);
+
+    user_match
+        .or_else(|| {
+            VENDOR_FLASH_BASES
+                .iter()
+                .filter(|(prefix, _)| chip_upper.starts_with(prefix))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, addr)| *addr)
+        })
+        .unwrap_or(0)
+}
+
+/// 获取目标的 Flash 起始地址：优先使用内存映射中的第一个片内 NVM 区域，否则按厂商默认值
+/// 只有外部 Flash (QSPI/OSPI) 时使用第一个外部区域
+pub fn target_flash_base(target: &probe_rs::Target) -> u64 {
+    let nvm_regions: Vec<&probe_rs::config::NvmRegion> = target
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Nvm(r) => Some(r),
+            _ => None,
+        })
+        .collect();
+
+    nvm_regions
+        .iter()
+        .find(|r| !is_external_nvm(r))
+        .or_else(|| nvm_regions.first())
+        .map(|r| r.range.start)
+        .unwrap_or_else(|| vendor_default_flash_base(&target.name))
+}
+
+/// 判断 NVM 区域是否为外部存储器映射的 Flash (QSPI/OSPI XIP)
+/// 按区域名称或 Cortex-M 外部存储器地址空间 (0x60000000-0x9FFFFFFF) 判断
+pub fn is_external_nvm(region: &probe_rs::config::NvmRegion) -> bool {
+    const EXTERNAL_NAMES: [&str; 5] = ["qspi", "ospi", "xspi", "octo", "ext"];
+
+    let named_external = region.name.as_deref().is_some_and(|name| {
+        let name = name.to_lowercase();
+        EXTERNAL_NAMES.iter().any(|pattern| name.contains(pattern))
+    });
+    named_external || (0x6000_0000..0xA000_0000).contains(&region.range.start)
+}
+
+/// 校验自定义烧录地址位于某个 NVM 区域内，地址位于外部 Flash 时返回负责该区域的算法名称
+/// 指定 size 时 [address, address + size) 必须完整落在同一区域内
+fn resolve_custom_flash_address(
+    target: &probe_rs::Target,
+    address: u64,
+    size: Option<u64>,
+) -> AppResult<Option<String>> {
+    let region = target
+        .memory_map
+        .iter()
+        .find_map(|region| match region {
+            probe_rs::config::MemoryRegion::Nvm(r) if r.range.contains(&address) => Some(r),
+            _ => None,
+        })
+        .ok_or_else(|| AppError::FlashError(format!("地址 0x{:08X} 不在目标的任何 Flash 区域内", address)))?;
+
+    if let Some(size) = size {
+        let fits = size > 0 && address.checked_add(size).is_some_and(|end| end <= region.range.end);
+        if !fits {
+            return Err(AppError::FlashError(format!(
+                "自定义烧录范围 0x{:08X} + 0x{:X} 超出 Flash 区域 0x{:08X}-0x{:08X}",
+                address, size, region.range.start, region.range.end
+            )));
+        }
+    }
+
+    if !is_external_nvm(region) {
+        return Ok(None);
+    }
+
+    target
+        .flash_algorithms
+        .iter()
+        .filter(|a| a.flash_properties.address_range.contains(&address))
+        .max_by_key(|a| a.default)
+        .map(|a| Some(a.name.clone()))
+        .ok_or_else(|| {
+            AppError::FlashError(format!(
+                "外部 Flash 区域 0x{:08X}-0x{:08X} 没有可用的 Flash 算法，请使用外部加载算法 (.FLM) 烧录",
+                region.range.start, region.range.end
+            ))
+        })
+}
+
+/// 获取芯片默认 Flash 基地址（未连接时使用，优先读取目标定义）
+#[tauri::command]
+pub async fn get_default_flash_base(chip_name: String) -> AppResult<u64> {
+    let registry = crate::commands::config::TARGET_REGISTRY.lock().unwrap();
+    match registry.get_target_by_name(&chip_name) {
+        Ok(target) => Ok(target_flash_base(&target)),
+        Err(_) => Ok(vendor_default_flash_base(&chip_name)),
+    }
+}
+
 /// 擦除模式
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum EraseMode {
@@ -67,6 +191,209 @@ pub struct FlashOptions {
     // 预校验：烧录前检查，跳过已正确的块（加速重复烧录）
     #[serde(default)]
     pub preverify: bool,
+    /// 复位使用的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
+    /// Flash 算法加载使用的 RAM 区域 (起始地址, 大小)
+    #[serde(default)]
+    pub algorithm_ram_region: Option<(u64, u64)>,
+    /// 未指定算法 RAM 区域且默认加载地址处 RAM 不可写时自动探测可用区域
+    /// 探测会暂停内核并写入测试数据，结果只用于本次烧录，不保存到连接设置
+    #[serde(default)]
+    pub auto_algorithm_ram_region: bool,
+    /// 烧录后复位并暂停在复位向量（供调试器接入），与 reset_after 同时设置时以此为准
+    #[serde(default)]
+    pub halt_after: bool,
+    /// 擦除后需要恢复的 Flash 区域（如校准数据、DFU 配置），不能与固件数据重叠
+    #[serde(default)]
+    pub preserve_regions: Vec<PreserveRegion>,
+    /// 逐扇区烧录并立即读回校验，在第一个出错的扇区处停止
+    #[serde(default)]
+    pub verify_per_sector: bool,
+    /// 用户已确认烧录前检查给出的风险（擦除范围过大、超出 Flash 范围）
+    #[serde(default)]
+    pub confirmed: bool,
+    /// 通信类暂时性错误时整体重新烧录的次数（默认 0，不重试）
+    #[serde(default)]
+    pub flash_retry_count: u32,
+    /// 烧录前复位并暂停内核，使 Flash 控制器、DMA 等外设从干净状态开始（与 reset_after 无关）
+    /// 未指定时按连接模式决定：UnderReset 连接默认开启，Normal 连接默认关闭
+    #[serde(default)]
+    pub reset_before: Option<bool>,
+}
+
+/// 判断烧录错误是否为暂时性通信错误：沿错误链查找 SWD/JTAG 传输错误（DAP 应答异常、超时）
+/// 或探针 USB 通信错误；校验失败、文件解析、算法缺失、地址越界等错误链中不含这些类型，不重试
+pub fn is_transient_flash_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    use probe_rs::architecture::arm::ArmError;
+    use probe_rs::probe::DebugProbeError;
+
+    let mut current = Some(error);
+    while let Some(e) = current {
+        if let Some(arm) = e.downcast_ref::<ArmError>() {
+            if matches!(arm, ArmError::Timeout | ArmError::Dap(_)) {
+                return true;
+            }
+        }
+        if let Some(probe) = e.downcast_ref::<DebugProbeError>() {
+            return matches!(
+                probe,
+                DebugProbeError::Usb(_) | DebugProbeError::ProbeSpecific(_) | DebugProbeError::Timeout
+            );
+        }
+        if let Some(probe_rs::Error::Timeout) = e.downcast_ref::<probe_rs::Error>() {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+/// 将 probe-rs 烧录错误转换为 AppError，暂时性通信错误标记为 TransientFlashError 供重试判断
+fn flash_failure(error: &(dyn std::error::Error + 'static), message: String) -> AppError {
+    if is_transient_flash_error(error) {
+        AppError::TransientFlashError(message)
+    } else {
+        AppError::FlashError(message)
+    }
+}
+
+/// 烧录结果统计
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashResult {
+    pub bytes_programmed: u64,
+    pub bytes_erased: u64,
+    /// 烧录过程中已完成校验（verify 或 verify_per_sector）
+    pub verified: bool,
+    pub elapsed_ms: u64,
+    /// 内容未变化而跳过的扇区数（preverify）
+    pub sectors_skipped: usize,
+    /// 本次自动探测使用的算法 RAM 区域，用户确认后可作为 algorithm_ram_region 传入
+    pub detected_algorithm_ram_region: Option<(u64, u64)>,
+}
+
+fn sector_in_image(sector: &FlashSector, image: &[std::ops::Range<u64>]) -> bool {
+    image.iter().any(|r| sector.address < r.end && r.start < sector.address + sector.size)
+}
+
+/// 固件数据涉及的扇区数
+pub fn image_sector_count(image: &[std::ops::Range<u64>], sectors: &[FlashSector]) -> usize {
+    sectors.iter().filter(|s| sector_in_image(s, image)).count()
+}
+
+/// 去掉完整落在 RAM 中的固件段（如 RAM 函数、noinit 段），只保留需要写入 Flash 的数据
+pub fn exclude_ram_ranges(
+    image: &[std::ops::Range<u64>],
+    ram: &[std::ops::Range<u64>],
+) -> Vec<std::ops::Range<u64>> {
+    image
+        .iter()
+        .filter(|r| !ram.iter().any(|ram| ram.start <= r.start && r.end <= ram.end))
+        .cloned()
+        .collect()
+}
+
+/// 按 probe-rs 的算法选择规则展开固件涉及的扇区：
+/// 与固件数据重叠的算法中，被重叠的默认算法覆盖的非默认算法不会被使用
+fn selected_algorithm_sectors(target: &probe_rs::Target, image: &[std::ops::Range<u64>]) -> Vec<FlashSector> {
+    let overlaps = |a: &std::ops::Range<u64>, b: &std::ops::Range<u64>| a.start < b.end && b.start < a.end;
+
+    let mut sectors: Vec<FlashSector> = target
+        .flash_algorithms
+        .iter()
+        .filter(|algo| {
+            let range = &algo.flash_properties.address_range;
+            image.iter().any(|r| overlaps(r, range))
+                && (algo.default
+                    || !target
+                        .flash_algorithms
+                        .iter()
+                        .any(|other| other.default && overlaps(&other.flash_properties.address_range, range)))
+        })
+        .flat_map(|algo| {
+            let range = &algo.flash_properties.address_range;
+            let descriptions: Vec<(u64, u64)> =
+                algo.flash_properties.sectors.iter().map(|d| (d.address, d.size)).collect();
+            expand_sectors(range, &descriptions, range)
+        })
+        .collect();
+    sectors.sort_by_key(|s| s.address);
+    sectors.dedup_by_key(|s| s.address);
+    sectors
+}
+
+/// 需要保留的 Flash 区域
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreserveRegion {
+    pub address: u64,
+    pub size: u64,
+}
+
+/// 计算固件将写入的地址范围
+fn image_ranges(path: &Path, bin_base: u64) -> AppResult<Vec<std::ops::Range<u64>>> {
+    Ok(match firmware::load_image(path)? {
+        firmware::FirmwareImage::Segments(segments) => segments
+            .iter()
+            .map(|s| s.address..s.address + s.data.len() as u64)
+            .collect(),
+        firmware::FirmwareImage::Binary(data) => std::iter::once(bin_base..bin_base + data.len() as u64).collect(),
+    })
+}
+
+/// 单个保留区域的最大字节数（保留数据暂存在内存中）
+pub const MAX_PRESERVE_REGION_SIZE: u64 = 1024 * 1024;
+
+/// 读取需要保留的区域内容，区域与固件数据重叠时报错（固件数据不会被静默覆盖）
+fn read_preserved_regions(
+    session: &mut Session,
+    core_index: usize,
+    regions: &[PreserveRegion],
+    image: &[std::ops::Range<u64>],
+) -> AppResult<Vec<(u64, Vec<u8>)>> {
+    for region in regions {
+        if region.size == 0 || region.size > MAX_PRESERVE_REGION_SIZE {
+            return Err(AppError::FlashError(format!(
+                "保留区域 0x{:08X} 大小 0x{:X} 无效（1 ~ 0x{:X} 字节）",
+                region.address, region.size, MAX_PRESERVE_REGION_SIZE
+            )));
+        }
+        let end = region.address.checked_add(region.size).ok_or_else(|| {
+            AppError::FlashError(format!("保留区域 0x{:08X} + 0x{:X} 超出地址范围", region.address, region.size))
+        })?;
+        if let Some(overlap) = image.iter().find(|r| r.start < end && region.address < r.end) {
+            return Err(AppError::FlashError(format!(
+                "保留区域 0x{:08X}-0x{:08X} 与固件数据 0x{:08X}-0x{:08X} 重叠",
+                region.address, end, overlap.start, overlap.end
+            )));
+        }
+    }
+
+    let mut core = session.core(core_index).map_err(|e| AppError::FlashError(e.to_string()))?;
+    regions
+        .iter()
+        .map(|region| {
+            let mut data = vec![0u8; region.size as usize];
+            core.read_8(region.address, &mut data)
+                .map_err(|e| AppError::FlashError(format!("读取保留区域 0x{:08X} 失败: {}", region.address, e)))?;
+            Ok((region.address, data))
+        })
+        .collect()
+}
+
+/// 将保留的数据写回 Flash（保留同扇区内其他字节）
+fn restore_preserved_regions(session: &mut Session, preserved: &[(u64, Vec<u8>)]) -> AppResult<()> {
+    let mut loader = session.target().flash_loader();
+    for (address, data) in preserved {
+        loader
+            .add_data(*address, data)
+            .map_err(|e| AppError::FlashError(format!("{:#}", e)))?;
+    }
+
+    let mut download_options = DownloadOptions::default();
+    download_options.keep_unwritten_bytes = true;
+    loader
+        .commit(session, download_options)
+        .map_err(|e| AppError::FlashError(format!("恢复保留区域失败: {:#}", e)))
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -76,200 +403,953 @@ pub struct FlashProgressEvent {
     pub message: String,
 }
 
+/// 烧录 S-record 固件：解析为数据段后通过 FlashLoader 写入
+fn download_srec(session: &mut Session, path: &Path, download_options: DownloadOptions) -> AppResult<()> {
+    let image = firmware::srec::parse_file(path)?;
+    log::info!("S-record 解析完成: {} 个数据段", image.segments.len());
+
+    let mut loader = session.target().flash_loader();
+    for segment in &image.segments {
+        loader
+            .add_data(segment.address, &segment.data)
+            .map_err(|e| AppError::FlashError(format!("{:#}", e)))?;
+    }
+
+    loader.commit(session, download_options).map_err(|e| {
+        log::error!("Flash 错误详情: {:?}", e);
+        flash_failure(&e, format!("{:#}", e))
+    })
+}
+
+/// 将固件数据按扇区切分，返回每个扇区及落在其中的数据段（按扇区地址排序）
+/// 有数据不在任何扇区内时返回其地址
+pub fn split_into_sectors(
+    segments: &[firmware::FirmwareSegment],
+    sectors: &[FlashSector],
+) -> Result<Vec<(FlashSector, Vec<firmware::FirmwareSegment>)>, u64> {
+    let mut sorted: Vec<&FlashSector> = sectors.iter().collect();
+    sorted.sort_by_key(|s| s.address);
+
+    // 每个数据段必须完整落在扇区内
+    for segment in segments.iter().filter(|s| !s.data.is_empty()) {
+        let end = segment.address + segment.data.len() as u64;
+        let mut address = segment.address;
+        while address < end {
+            match sorted.iter().find(|s| s.address <= address && address < s.address + s.size) {
+                Some(sector) => address = sector.address + sector.size,
+                None => return Err(address),
+            }
+        }
+    }
+
+    Ok(sorted
+        .into_iter()
+        .filter_map(|sector| {
+            let sector_end = sector.address + sector.size;
+            let chunks: Vec<firmware::FirmwareSegment> = segments
+                .iter()
+                .filter_map(|segment| {
+                    let start = segment.address.max(sector.address);
+                    let end = (segment.address + segment.data.len() as u64).min(sector_end);
+                    (start < end).then(|| firmware::FirmwareSegment {
+                        address: start,
+                        data: segment.data[(start - segment.address) as usize..(end - segment.address) as usize].to_vec(),
+                    })
+                })
+                .collect();
+            (!chunks.is_empty()).then(|| (sector.clone(), chunks))
+        })
+        .collect())
+}
+
+/// 烧录前检查结果（需要确认时作为 requires_confirmation 错误的详情返回）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlashPlanReport {
+    /// 固件数据总字节数
+    pub image_bytes: u64,
+    /// 将被擦除的字节数
+    pub erase_bytes: u64,
+    /// 固件实际需要擦除的字节数（覆盖固件数据的扇区）
+    pub required_erase_bytes: u64,
+    /// 目标 Flash 总容量
+    pub flash_bytes: u64,
+    /// 第一个不在 Flash 范围内的固件地址
+    pub out_of_bounds_address: Option<u64>,
+    /// 需要确认的原因
+    pub reasons: Vec<String>,
+}
+
+/// 擦除字节数超过固件大小的该倍数时需要确认
+pub const ERASE_CONFIRM_RATIO: u64 = 2;
+
+/// 比较固件地址范围、计划擦除范围与目标 Flash 扇区，给出需要确认的风险
+pub fn evaluate_flash_plan(
+    image: &[std::ops::Range<u64>],
+    sectors: &[FlashSector],
+    erase_range: Option<std::ops::Range<u64>>,
+    chip_erase: bool,
+    skip_erase: bool,
+) -> FlashPlanReport {
+    let overlaps = |sector: &FlashSector, range: &std::ops::Range<u64>| {
+        sector.address < range.end && range.start < sector.address + sector.size
+    };
+    let sector_bytes = |ranges: &[std::ops::Range<u64>]| -> u64 {
+        sectors
+            .iter()
+            .filter(|s| ranges.iter().any(|r| overlaps(s, r)))
+            .map(|s| s.size)
+            .sum()
+    };
+
+    let image_bytes: u64 = image.iter().map(|r| r.end - r.start).sum();
+    let flash_bytes: u64 = sectors.iter().map(|s| s.size).sum();
+    let required_erase_bytes = sector_bytes(image);
+    let erase_bytes = if skip_erase {
+        0
+    } else if chip_erase {
+        flash_bytes
+    } else {
+        let mut ranges = image.to_vec();
+        ranges.extend(erase_range);
+        sector_bytes(&ranges)
+    };
+
+    // 逐段检查固件数据是否完整落在扇区内
+    let out_of_bounds_address = image.iter().find_map(|range| {
+        let mut address = range.start;
+        while address < range.end {
+            match sectors.iter().find(|s| s.address <= address && address < s.address + s.size) {
+                Some(sector) => address = sector.address + sector.size,
+                None => return Some(address),
+            }
+        }
+        None
+    });
+
+    let mut reasons = Vec::new();
+    if let Some(address) = out_of_bounds_address {
+        reasons.push(format!("固件数据地址 0x{:08X} 超出目标 Flash 范围", address));
+    }
+    if image_bytes > flash_bytes {
+        reasons.push(format!("固件大小 {} 字节超过目标 Flash 容量 {} 字节", image_bytes, flash_bytes));
+    }
+    // 整片擦除与自定义擦除范围同样检查，避免小固件误触发整片擦除
+    if erase_bytes > required_erase_bytes && erase_bytes > image_bytes * ERASE_CONFIRM_RATIO {
+        reasons.push(format!(
+            "将擦除 {} 字节，超过固件大小 {} 字节的 {} 倍",
+            erase_bytes, image_bytes, ERASE_CONFIRM_RATIO
+        ));
+    }
+
+    FlashPlanReport {
+        image_bytes,
+        erase_bytes,
+        required_erase_bytes,
+        flash_bytes,
+        out_of_bounds_address,
+        reasons,
+    }
+}
+
+/// 逐扇区烧录：每个扇区烧录后立即读回比对，第一个出错的扇区处停止并报告地址
+/// 返回烧录的字节数
+fn flash_per_sector<E: EventSink>(
+    session: &mut Session,
+    image: firmware::FirmwareImage,
+    bin_base: u64,
+    skip_erase: bool,
+    chip_erase: bool,
+    core_index: usize,
+    events: &E,
+) -> AppResult<u64> {
+    let segments = match image {
+        firmware::FirmwareImage::Segments(segments) => segments,
+        firmware::FirmwareImage::Binary(data) => vec![firmware::FirmwareSegment { address: bin_base, data }],
+    };
+    let sectors: Vec<FlashSector> = flash_layout(session.target())
+        .into_iter()
+        .flat_map(|region| region.sectors)
+        .collect();
+    let plan = split_into_sectors(&segments, &sectors).map_err(|address| {
+        AppError::FlashError(format!("地址 0x{:08X} 不在任何 Flash 扇区内", address))
+    })?;
+
+    if chip_erase && !skip_erase {
+        flash_log(events, log::Level::Info, "全片擦除...".to_string());
+        erase_all(session, &mut FlashProgress::new(|_| {}), false)
+            .map_err(|e| flash_failure(&e, format!("全片擦除失败: {:#}", e)))?;
+    }
+
+    let total = plan.len();
+    let mut programmed = 0u64;
+    for (i, (sector, chunks)) in plan.iter().enumerate() {
+        let mut loader = session.target().flash_loader();
+        for chunk in chunks {
+            loader
+                .add_data(chunk.address, &chunk.data)
+                .map_err(|e| AppError::FlashError(format!("{:#}", e)))?;
+        }
+        let mut download_options = DownloadOptions::default();
+        download_options.skip_erase = skip_erase || chip_erase;
+        loader.commit(session, download_options).map_err(|e| {
+            flash_log(events, log::Level::Error, format!("扇区 0x{:08X} 烧录失败", sector.address));
+            flash_failure(&e, format!("扇区 0x{:08X} 烧录失败: {:#}", sector.address, e))
+        })?;
+
+        let mut core = session.core(core_index).map_err(|e| AppError::FlashError(e.to_string()))?;
+        for chunk in chunks {
+            let mut readback = vec![0u8; chunk.data.len()];
+            core.read_8(chunk.address, &mut readback)
+                .map_err(|e| flash_failure(&e, format!("读回扇区 0x{:08X} 失败: {}", sector.address, e)))?;
+            if let Some(offset) = readback.iter().zip(&chunk.data).position(|(a, b)| a != b) {
+                let address = chunk.address + offset as u64;
+                flash_log(
+                    events,
+                    log::Level::Error,
+                    format!("扇区 0x{:08X} 校验失败，首个差异位于 0x{:08X}", sector.address, address),
+                );
+                return Err(AppError::FlashError(format!(
+                    "扇区 0x{:08X} 校验失败: 地址 0x{:08X} 期望 0x{:02X}，读回 0x{:02X}",
+                    sector.address, address, chunk.data[offset], readback[offset]
+                )));
+            }
+            programmed += chunk.data.len() as u64;
+        }
+
+        events.emit_event(
+            "flash-progress",
+            FlashProgressEvent {
+                phase: "program".to_string(),
+                progress: 0.9 * (i + 1) as f32 / total as f32,
+                message: format!("扇区 0x{:08X} 已烧录并校验 ({}/{})", sector.address, i + 1, total),
+            },
+        );
+    }
+
+    Ok(programmed)
+}
+
+/// 烧录失败后恢复会话：Flash 算法可能仍驻留在 RAM 中且内核处于暂停状态，
+/// 复位并暂停内核使后续操作从干净的状态开始，复位失败时再尝试普通复位
+fn recover_after_flash_error<E: EventSink>(session: &mut Session, core_index: usize, timeout: std::time::Duration, events: &E) {
+    let mut core = match session.core(core_index) {
+        Ok(core) => core,
+        Err(e) => {
+            flash_log(events, log::Level::Warn, format!("烧录失败后无法访问内核，建议重新连接: {}", e));
+            return;
+        }
+    };
+
+    match core.reset_and_halt(timeout) {
+        Ok(info) => {
+            flash_log(
+                events,
+                log::Level::Info,
+                format!("烧录失败后已复位并暂停内核 (PC = 0x{:08X})，会话可继续使用", info.pc),
+            );
+        }
+        Err(e) => {
+            log::warn!("复位并暂停失败: {}", e);
+            match core.reset() {
+                Ok(()) => flash_log(events, log::Level::Info, "烧录失败后已复位内核，会话可继续使用".to_string()),
+                Err(e) => flash_log(
+                    events,
+                    log::Level::Warn,
+                    format!("烧录失败后复位内核失败，建议重新连接: {}", e),
+                ),
+            }
+        }
+    }
+}
+
+/// 单次操作的探针传输统计
+/// probe-rs 未公开底层 SWD 传输/重试计数器，这里只统计整次操作的重试
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashStatsEvent {
+    pub operation: String,
+    /// 因暂时性通信错误整体重试的次数
+    pub retries: u32,
+    pub bytes: u64,
+    pub elapsed_ms: u64,
+}
+
+fn emit_flash_stats<E: EventSink>(events: &E, operation: &str, bytes: u64, retries: u32, started: std::time::Instant) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    log::info!(
+        "{} 统计: {} 字节, 重试 {} 次, 耗时 {} ms ({:.1} KB/s)",
+        operation,
+        bytes,
+        retries,
+        elapsed_ms,
+        if elapsed_ms > 0 { bytes as f64 / elapsed_ms as f64 * 1000.0 / 1024.0 } else { 0.0 }
+    );
+    events.emit_event(
+        "flash-stats",
+        FlashStatsEvent {
+            operation: operation.to_string(),
+            retries,
+            bytes,
+            elapsed_ms,
+        },
+    );
+}
+
+/// 结构化烧录日志事件（推送到前端烧录控制台）
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashLogEvent {
+    /// "error" | "warn" | "info" | "debug"
+    pub level: String,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+/// 连接/烧录过程的事件输出：GUI 下发往前端，命令行模式下打印到终端
+pub trait EventSink: Clone + Send + Sync + 'static {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S);
+}
+
+impl<R: tauri::Runtime> EventSink for Window<R> {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.emit(event, payload);
+    }
+}
+
+impl<R: tauri::Runtime> EventSink for tauri::AppHandle<R> {
+    fn emit_event<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        let _ = self.emit(event, payload);
+    }
+}
+
+/// 写入日志并以 flash-log 事件发送到前端
+pub(crate) fn flash_log<E: EventSink>(emitter: &E, level: log::Level, message: String) {
+    log::log!(level, "{}", message);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    emitter.emit_event(
+        "flash-log",
+        FlashLogEvent {
+            level: level.as_str().to_lowercase(),
+            message,
+            timestamp,
+        },
+    );
+}
+
+/// 烧录前警告事件（不阻止烧录）
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashWarningEvent {
+    pub message: String,
+}
+
+/// 检查 ELF 固件的架构/浮点 ABI 与目标内核是否匹配，不匹配时仅发出警告
+fn check_firmware_arch<E: EventSink>(session: &Session, path: &Path, events: &E) {
+    let core_type = match session.target().cores.first() {
+        Some(core) => format!("{:?}", core.core_type).to_lowercase(),
+        None => return,
+    };
+
+    let info = match std::fs::read(path)
+        .map_err(AppError::from)
+        .and_then(|data| firmware::arch::parse_elf_arch(&data))
+    {
+        Ok(info) => info,
+        Err(e) => {
+            log::debug!("跳过固件架构检查: {}", e);
+            return;
+        }
+    };
+
+    for message in firmware::arch::arch_mismatch_warnings(&info, &core_type) {
+        log::warn!("{}", message);
+        events.emit_event("flash-warning", FlashWarningEvent { message });
+    }
+}
+
+/// 检查 Flash 算法的加载地址是否为可写 RAM；不可写时（如未贴装的 RAM bank）
+/// 返回第一个首尾均可写的 RAM 区域 (起始地址, 大小)，加载地址正常时返回 None
+/// 写测试数据前先暂停内核，避免与运行中的固件争用 RAM，探测后恢复原运行状态
+fn auto_algorithm_ram_region(
+    session: &mut Session,
+    core_index: usize,
+    halt_timeout: std::time::Duration,
+) -> AppResult<Option<(u64, u64)>> {
+    let load_address = match session
+        .target()
+        .flash_algorithms
+        .iter()
+        .find(|a| a.default)
+        .or_else(|| session.target().flash_algorithms.first())
+        .and_then(|a| a.load_address)
+    {
+        Some(addr) => addr,
+        None => return Ok(None),
+    };
+
+    let ram_regions: Vec<_> = session
+        .target()
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Ram(ram) => Some(ram.range.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut core = session.core(core_index).map_err(|e| AppError::FlashError(e.to_string()))?;
+    let was_halted = core.core_halted().map_err(|e| AppError::FlashError(e.to_string()))?;
+    if !was_halted {
+        core.halt(halt_timeout)
+            .map_err(|e| AppError::FlashError(format!("探测算法 RAM 前暂停内核失败: {}", e)))?;
+    }
+
+    let detected = if crate::commands::memory::ram_word_writable(&mut core, load_address & !0x3) {
+        None
+    } else {
+        log::warn!("Flash 算法加载地址 0x{:08X} 处 RAM 不可写，尝试自动选择其他 RAM 区域", load_address);
+        let found = ram_regions.into_iter().find(|range| {
+            !range.contains(&load_address)
+                && range.end - range.start >= 0x1000
+                && crate::commands::memory::ram_word_writable(&mut core, range.start)
+                && crate::commands::memory::ram_word_writable(&mut core, (range.end - 4) & !0x3)
+        });
+        match &found {
+            Some(range) => log::info!("自动选择算法 RAM 区域: 0x{:08X}-0x{:08X}", range.start, range.end),
+            None => log::warn!("未找到可写的 RAM 区域，继续使用默认加载地址"),
+        }
+        found.map(|range| (range.start, range.end - range.start))
+    };
+
+    if !was_halted {
+        if let Err(e) = core.run() {
+            log::warn!("探测算法 RAM 后恢复内核运行失败: {}", e);
+        }
+    }
+    Ok(detected)
+}
+
+/// 根据文件扩展名确定格式
+/// 支持的格式: ELF, HEX, BIN, AXF (ARM ELF), OUT, S-record
+/// S-record 由本地解析后烧录，返回 None；BIN 的基地址由 bin_base 给出
+fn detect_flash_format<E: EventSink>(
+    path: &Path,
+    bin_base: impl FnOnce() -> AppResult<u64>,
+    events: &E,
+) -> AppResult<Option<Format>> {
+    let ext = firmware::file_extension(path);
+    let format = match ext.as_deref().and_then(FirmwareFormat::from_extension) {
+        // Motorola S-record 格式
+        Some(FirmwareFormat::Srec) => {
+            flash_log(events, log::Level::Info, "检测到 S-record 格式固件".to_string());
+            None
+        }
+        // Intel HEX 格式
+        Some(FirmwareFormat::Hex) => {
+            flash_log(events, log::Level::Info, "检测到 HEX 格式固件".to_string());
+            Some(Format::Hex)
+        }
+        // 纯二进制格式 - 需要指定基地址
+        Some(FirmwareFormat::Bin) => {
+            flash_log(events, log::Level::Info, "检测到 BIN 格式固件".to_string());
+            let base_address = bin_base()?;
+            flash_log(events, log::Level::Info, format!("BIN 基地址: 0x{:08X}", base_address));
+            Some(Format::Bin(BinOptions { base_address: Some(base_address), skip: 0 }))
+        }
+        // ELF 格式 (包括 AXF - ARM eXecutable Format)
+        Some(FirmwareFormat::Elf) => {
+            flash_log(events, log::Level::Info, format!("检测到 ELF 格式固件 (扩展名: {})", ext.as_deref().unwrap_or("unknown")));
+            Some(Format::Elf(ElfOptions::default()))
+        }
+        // 未知扩展名 - 尝试作为 ELF 解析
+        None => {
+            flash_log(events, log::Level::Info, format!("未知扩展名 {:?}，尝试作为 ELF 格式解析", ext));
+            Some(Format::Elf(ElfOptions::default()))
+        }
+    };
+    Ok(format)
+}
+
 #[tauri::command]
 pub async fn flash_firmware(
     options: FlashOptions,
     state: State<'_, AppState>,
     window: Window,
-) -> AppResult<()> {
-    let mut session_guard = state.session.lock();
-    let session = session_guard
-        .as_mut()
-        .ok_or(AppError::NotConnected)?;
+) -> AppResult<FlashResult> {
+    flash_firmware_with(options, &state, &window)
+}
+
+/// 烧录主流程，日志与进度经 events 输出（GUI 为窗口，命令行模式为终端）
+pub fn flash_firmware_with<E: EventSink>(
+    options: FlashOptions,
+    state: &AppState,
+    events: &E,
+) -> AppResult<FlashResult> {
+    let mut session_guard = state.lock_session_for_flash();
+    if session_guard.is_none() {
+        return Err(AppError::NotConnected);
+    }
 
     let path = Path::new(&options.file_path);
     if !path.exists() {
         return Err(AppError::FileError("文件不存在".to_string()));
     }
 
-    // 记录选中的Flash算法（如果指定）
-    if let Some(ref algo_name) = options.flash_algorithm {
-        log::info!("用户选择的Flash算法: {}", algo_name);
-        // 注意：probe-rs 0.27 会自动使用目标配置中的算法
-        // 这里只是记录用户的选择，实际算法由probe-rs根据地址范围自动选择
+    // 用户要求自动探测且未指定算法 RAM 区域时，若默认加载地址处的 RAM 不可写则选择可用区域
+    let detected_algorithm_ram_region = match options.algorithm_ram_region {
+        None if options.auto_algorithm_ram_region => auto_algorithm_ram_region(
+            session_guard.as_mut().ok_or(AppError::NotConnected)?,
+            state.core_index(options.core_index),
+            state.core_timeout(CoreOperation::Reset),
+        )?,
+        _ => None,
+    };
+    let algorithm_ram_region = options.algorithm_ram_region.or(detected_algorithm_ram_region);
+
+    // BIN 使用自定义地址时先校验范围；目标位于外部 Flash 时必须使用负责该区域的算法，按强制算法流程重新连接
+    let is_bin = matches!(
+        firmware::file_extension(path).as_deref().and_then(FirmwareFormat::from_extension),
+        Some(FirmwareFormat::Bin)
+    );
+    let mut flash_algorithm = options.flash_algorithm.clone();
+    let custom_address = match options.use_custom_address {
+        Some(true) if is_bin => {
+            let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+            let address = options.custom_flash_address.unwrap_or_else(|| target_flash_base(session.target()));
+            if let Some(size) = options.custom_flash_size {
+                let file_size = std::fs::metadata(path)?.len();
+                if file_size > size {
+                    return Err(AppError::FlashError(format!(
+                        "固件大小 {} 字节超过指定的烧录大小 {} 字节",
+                        file_size, size
+                    )));
+                }
+            }
+            if let Some(external) = resolve_custom_flash_address(session.target(), address, options.custom_flash_size)? {
+                match &flash_algorithm {
+                    Some(name) if !name.eq_ignore_ascii_case(&external) => {
+                        return Err(AppError::FlashError(format!(
+                            "地址 0x{:08X} 位于外部 Flash，需要使用算法 {}，与指定的算法 {} 冲突",
+                            address, external, name
+                        )));
+                    }
+                    _ => {
+                        flash_log(events, log::Level::Info, format!("目标地址位于外部 Flash，使用算法: {}", external));
+                        flash_algorithm = Some(external);
+                    }
+                }
+            }
+            Some(address)
+        }
+        _ => None,
+    };
+
+    // 指定的 Flash 算法必须存在于目标定义中
+    if let Some(ref algo_name) = flash_algorithm {
+        let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+        let available: Vec<String> = session.target().flash_algorithms.iter().map(|a| a.name.clone()).collect();
+        if !available.iter().any(|name| name.eq_ignore_ascii_case(algo_name)) {
+            flash_log(events, log::Level::Error, format!("Flash 算法 {} 不存在", algo_name));
+            return Err(AppError::FlashError(format!(
+                "目标没有名为 '{}' 的 Flash 算法，可用算法: {}",
+                algo_name,
+                available.join(", ")
+            )));
+        }
     }
 
-    // 根据文件扩展名确定格式
-    // 支持的格式: ELF, HEX, BIN, AXF (ARM ELF), OUT
-    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
-    let format = match ext.as_deref() {
-        // Intel HEX 格式
-        Some("hex") | Some("ihex") => {
-            log::info!("检测到 HEX 格式固件");
-            Format::Hex
+    // 当前连接使用的算法选择与本次不同时需要重新连接
+    let algorithm_changed = state
+        .connect_options
+        .lock()
+        .as_ref()
+        .is_some_and(|o| o.flash_algorithm != flash_algorithm);
+
+    // 指定了算法 RAM 区域或算法选择变化时，以覆盖后的目标定义重新连接
+    if algorithm_ram_region.is_some() || algorithm_changed {
+        let previous_options = state
+            .connect_options
+            .lock()
+            .clone()
+            .ok_or(AppError::NotConnected)?;
+        let mut connect_options = previous_options.clone();
+        if algorithm_ram_region.is_some() {
+            connect_options.algorithm_ram_region = algorithm_ram_region;
         }
-        // 纯二进制格式 - 需要指定基地址
-        Some("bin") => {
-            log::info!("检测到 BIN 格式固件");
-            let base_address = if options.use_custom_address.unwrap_or(false) {
-                options.custom_flash_address.unwrap_or(0x08000000)
-            } else {
-                // 自动从目标内存映射获取Flash起始地址
-                session.target().memory_map.iter()
-                    .find_map(|region| {
-                        if let probe_rs::config::MemoryRegion::Nvm(r) = region {
-                            Some(r.range.start)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(0x08000000)
-            };
-            log::info!("BIN 基地址: 0x{:08X}", base_address);
-            Format::Bin(BinOptions { base_address: Some(base_address), skip: 0 })
+        connect_options.flash_algorithm = flash_algorithm.clone();
+
+        let registry = crate::commands::config::TARGET_REGISTRY.lock().unwrap();
+        *session_guard = None;
+        match crate::commands::probe::reattach_session(&connect_options, &registry) {
+            Ok(session) => *session_guard = Some(session),
+            Err(e) => {
+                // 覆盖后的目标定义无法连接时回到原会话，不丢失连接
+                let _ = crate::commands::probe::restore_session(state, &mut session_guard, &previous_options, &registry, events);
+                return Err(e);
+            }
         }
-        // ELF 格式 (包括 AXF - ARM eXecutable Format)
-        Some("elf") | Some("axf") | Some("out") => {
-            log::info!("检测到 ELF 格式固件 (扩展名: {})", ext.as_deref().unwrap_or("unknown"));
-            Format::Elf(ElfOptions::default())
+        drop(registry);
+        // 自动探测的区域只用于本次烧录，不写入连接设置
+        if detected_algorithm_ram_region.is_some() {
+            connect_options.algorithm_ram_region = previous_options.algorithm_ram_region;
         }
-        // 未知扩展名 - 尝试作为 ELF 解析
-        _ => {
-            log::info!("未知扩展名 {:?}，尝试作为 ELF 格式解析", ext);
-            Format::Elf(ElfOptions::default())
+        *state.connect_options.lock() = Some(connect_options);
+    }
+
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    // 没有 Flash 算法时 probe-rs 会在深处报错，提前给出可操作的提示
+    if session.target().flash_algorithms.is_empty() {
+        let has_nvm = session
+            .target()
+            .memory_map
+            .iter()
+            .any(|region| matches!(region, probe_rs::config::MemoryRegion::Nvm(_)));
+        let hint = if has_nvm {
+            "请重新扫描对应的 Pack 以生成 Flash 算法，或在扫描报告中检查该芯片的算法匹配情况"
+        } else {
+            "该目标为纯 RAM 器件，请通过内存写入将程序加载到 RAM 中运行"
+        };
+        flash_log(events, log::Level::Error, format!("目标 {} 没有 Flash 算法", session.target().name));
+        return Err(AppError::FlashError(format!("该目标没有可用的Flash算法，无法烧录。{}", hint)));
+    }
+
+    // 记录实际使用的Flash算法
+    match flash_algorithm {
+        Some(ref algo_name) => {
+            flash_log(events, log::Level::Info, format!("强制使用Flash算法: {}", algo_name));
         }
-    };
+        None => {
+            let names: Vec<&str> = session.target().flash_algorithms.iter().map(|a| a.name.as_str()).collect();
+            flash_log(events, log::Level::Info, format!("按地址范围自动选择Flash算法: {}", names.join(", ")));
+        }
+    }
 
-    // 根据擦除模式配置下载选项
-    let mut download_options = DownloadOptions::default();
-    if options.skip_erase {
-        download_options.skip_erase = true;
-    } else {
-        match options.erase_mode {
-            EraseMode::ChipErase => {
-                download_options.do_chip_erase = true;
-            }
-            EraseMode::SectorErase => {
-                download_options.do_chip_erase = false;
-                // probe-rs 默认使用扇区擦除
+    let format = detect_flash_format(
+        path,
+        // 未指定自定义地址时自动从目标内存映射获取Flash起始地址
+        || Ok(custom_address.unwrap_or_else(|| target_flash_base(session.target()))),
+        events,
+    )?;
+
+    if matches!(format, Some(Format::Elf(_))) {
+        check_firmware_arch(session, path, events);
+    }
+
+    let bin_base = match &format {
+        Some(Format::Bin(bin)) => bin.base_address.unwrap_or(0),
+        _ => 0,
+    };
+    // 固件地址范围只用于烧录前检查、保留区域和统计，预解析失败时交给 probe-rs 处理，不阻止烧录
+    let image = match image_ranges(path, bin_base) {
+        Ok(ranges) => {
+            let ram: Vec<_> = session
+                .target()
+                .memory_map
+                .iter()
+                .filter_map(|region| match region {
+                    probe_rs::config::MemoryRegion::Ram(ram) => Some(ram.range.clone()),
+                    _ => None,
+                })
+                .collect();
+            Some((exclude_ram_ranges(&ranges, &ram), ranges))
+        }
+        Err(e) => {
+            log::warn!("预解析固件地址范围失败，跳过烧录前检查: {}", e);
+            None
+        }
+    };
+    let (flash_image, full_image) = match &image {
+        Some((flash_image, full_image)) => (flash_image.as_slice(), Some(full_image.as_slice())),
+        None => (&[][..], None),
+    };
+    let sectors = selected_algorithm_sectors(session.target(), flash_image);
+    let image_sectors = image_sector_count(flash_image, &sectors);
+
+    // 烧录前检查：固件超出 Flash 范围或擦除范围远大于固件时需要用户确认（擦除范围按实际选用算法的扇区计算）
+    // 自定义地址同时指定了大小时，该范围计入擦除范围
+    if !options.confirmed && image.is_some() {
+        let chip_erase = !options.skip_erase && options.erase_mode == EraseMode::ChipErase;
+        let erase_range = custom_address
+            .zip(options.custom_flash_size)
+            .map(|(address, size)| address..address.saturating_add(size));
+        let report = evaluate_flash_plan(flash_image, &sectors, erase_range, chip_erase, options.skip_erase);
+        if !report.reasons.is_empty() {
+            for reason in &report.reasons {
+                flash_log(events, log::Level::Warn, reason.clone());
             }
+            return Err(AppError::RequiresConfirmation(serde_json::to_string(&report)?));
         }
     }
-    download_options.verify = options.verify;
-    download_options.preverify = options.preverify;  // 预校验：跳过已正确的块
 
-    // 创建并设置进度回调
-    let progress_state = Arc::new(Mutex::new(ProgressState::new()));
-    let window_clone = window.clone();
-    let progress_state_clone = Arc::clone(&progress_state);
+    // 烧录前复位并暂停，避免运行中的固件留下的外设状态干扰 Flash 算法
+    let reset_before = options.reset_before.unwrap_or_else(|| {
+        state
+            .connect_options
+            .lock()
+            .as_ref()
+            .is_some_and(|o| o.connect_mode == ConnectMode::UnderReset)
+    });
+    if reset_before {
+        let mut core = session
+            .core(state.core_index(options.core_index))
+            .map_err(|e| AppError::FlashError(e.to_string()))?;
+        let info = core
+            .reset_and_halt(state.core_timeout(CoreOperation::Reset))
+            .map_err(|e| AppError::FlashError(format!("烧录前复位并暂停失败: {}", e)))?;
+        flash_log(events, log::Level::Info, format!("烧录前已复位并暂停内核 (PC = 0x{:08X})", info.pc));
+    }
 
-    let progress_callback = FlashProgress::new(move |event| {
-        let mut state = progress_state_clone.lock().unwrap();
+    // 擦除前读取需要保留的区域
+    let preserved = if options.preserve_regions.is_empty() {
+        Vec::new()
+    } else if options.skip_erase {
+        log::info!("跳过擦除，无需保留区域");
+        Vec::new()
+    } else {
+        let image = full_image.ok_or_else(|| {
+            AppError::FlashError("无法解析固件地址范围，不能确认保留区域与固件数据不重叠".to_string())
+        })?;
+        let preserved = read_preserved_regions(session, state.core_index(options.core_index), &options.preserve_regions, image)?;
+        flash_log(events, log::Level::Info, format!("已读取 {} 个保留区域", preserved.len()));
+        preserved
+    };
 
-        let (phase, message) = match event {
-            ProgressEvent::FlashLayoutReady { .. } => {
-                ("init".to_string(), "Flash布局准备完成".to_string())
-            }
-            ProgressEvent::AddProgressBar { operation, total } => {
-                match operation {
-                    ProgressOperation::Erase => {
-                        state.erase_total = total.unwrap_or(0);
-                        ("init".to_string(), format!("准备擦除 {} 字节", state.erase_total))
-                    }
-                    ProgressOperation::Program => {
-                        state.program_total = total.unwrap_or(0);
-                        ("init".to_string(), format!("准备编程 {} 字节", state.program_total))
-                    }
-                    ProgressOperation::Fill => {
-                        ("init".to_string(), "准备填充数据".to_string())
-                    }
-                    ProgressOperation::Verify => {
-                        ("init".to_string(), "准备校验".to_string())
-                    }
+    // 根据擦除模式配置下载选项（每次尝试重新构造，进度回调不可复用）
+    let progress_state = Arc::new(Mutex::new(ProgressState::new()));
+    let build_download_options = || {
+        let mut download_options = DownloadOptions::default();
+        if options.skip_erase {
+            download_options.skip_erase = true;
+        } else {
+            match options.erase_mode {
+                EraseMode::ChipErase => {
+                    download_options.do_chip_erase = true;
                 }
-            }
-            ProgressEvent::Started(operation) => {
-                match operation {
-                    ProgressOperation::Fill => ("fill".to_string(), "开始填充数据".to_string()),
-                    ProgressOperation::Erase => {
-                        state.erase_current = 0;
-                        ("erase".to_string(), "开始擦除".to_string())
-                    }
-                    ProgressOperation::Program => {
-                        state.program_current = 0;
-                        ("program".to_string(), "开始编程".to_string())
-                    }
-                    ProgressOperation::Verify => ("verify".to_string(), "开始校验".to_string()),
+                EraseMode::SectorErase => {
+                    download_options.do_chip_erase = false;
+                    // probe-rs 默认使用扇区擦除
                 }
             }
-            ProgressEvent::Progress { operation, size, .. } => {
-                match operation {
-                    ProgressOperation::Fill => {
-                        ("fill".to_string(), format!("已填充 {} 字节", size))
+        }
+        download_options.verify = options.verify;
+        download_options.preverify = options.preverify;  // 预校验：跳过已正确的块
+
+        // 创建并设置进度回调
+        let events_clone = events.clone();
+        let progress_state_clone = Arc::clone(&progress_state);
+
+        let progress_callback = FlashProgress::new(move |event| {
+            let mut state = progress_state_clone.lock().unwrap();
+
+            // 阶段切换和诊断信息同时写入烧录日志
+            let log_level = match &event {
+                ProgressEvent::Started(_) | ProgressEvent::Finished(_) | ProgressEvent::DiagnosticMessage { .. } => {
+                    Some(log::Level::Info)
+                }
+                ProgressEvent::Failed(_) => Some(log::Level::Error),
+                _ => None,
+            };
+
+            let (phase, message) = match event {
+                ProgressEvent::FlashLayoutReady { .. } => {
+                    ("init".to_string(), "Flash布局准备完成".to_string())
+                }
+                ProgressEvent::AddProgressBar { operation, total } => {
+                    match operation {
+                        ProgressOperation::Erase => {
+                            state.erase_total = total.unwrap_or(0);
+                            ("init".to_string(), format!("准备擦除 {} 字节", state.erase_total))
+                        }
+                        ProgressOperation::Program => {
+                            state.program_total = total.unwrap_or(0);
+                            ("init".to_string(), format!("准备编程 {} 字节", state.program_total))
+                        }
+                        ProgressOperation::Fill => {
+                            ("init".to_string(), "准备填充数据".to_string())
+                        }
+                        ProgressOperation::Verify => {
+                            ("init".to_string(), "准备校验".to_string())
+                        }
                     }
-                    ProgressOperation::Erase => {
-                        state.erase_current += size;
-                        state.erase_total = state.erase_current.max(state.erase_total);
-                        ("erase".to_string(), format!("已擦除 {} 字节", state.erase_current))
+                }
+                ProgressEvent::Started(operation) => {
+                    match operation {
+                        ProgressOperation::Fill => ("fill".to_string(), "开始填充数据".to_string()),
+                        ProgressOperation::Erase => {
+                            state.erase_current = 0;
+                            ("erase".to_string(), "开始擦除".to_string())
+                        }
+                        ProgressOperation::Program => {
+                            state.program_current = 0;
+                            ("program".to_string(), "开始编程".to_string())
+                        }
+                        ProgressOperation::Verify => ("verify".to_string(), "开始校验".to_string()),
                     }
-                    ProgressOperation::Program => {
-                        state.program_current += size;
-                        ("program".to_string(), format!("已编程 {}/{} 字节", state.program_current, state.program_total))
+                }
+                ProgressEvent::Progress { operation, size, .. } => {
+                    match operation {
+                        ProgressOperation::Fill => {
+                            ("fill".to_string(), format!("已填充 {} 字节", size))
+                        }
+                        ProgressOperation::Erase => {
+                            state.erase_current += size;
+                            state.erase_sectors += 1;
+                            state.erase_total = state.erase_current.max(state.erase_total);
+                            ("erase".to_string(), format!("已擦除 {} 字节", state.erase_current))
+                        }
+                        ProgressOperation::Program => {
+                            state.program_current += size;
+                            ("program".to_string(), format!("已编程 {}/{} 字节", state.program_current, state.program_total))
+                        }
+                        ProgressOperation::Verify => {
+                            ("verify".to_string(), format!("已校验 {} 字节", size))
+                        }
                     }
-                    ProgressOperation::Verify => {
-                        ("verify".to_string(), format!("已校验 {} 字节", size))
+                }
+                ProgressEvent::Failed(operation) => {
+                    match operation {
+                        ProgressOperation::Fill => ("fill".to_string(), "填充失败".to_string()),
+                        ProgressOperation::Erase => ("erase".to_string(), "擦除失败".to_string()),
+                        ProgressOperation::Program => ("program".to_string(), "编程失败".to_string()),
+                        ProgressOperation::Verify => ("verify".to_string(), "校验失败".to_string()),
                     }
                 }
-            }
-            ProgressEvent::Failed(operation) => {
-                match operation {
-                    ProgressOperation::Fill => ("fill".to_string(), "填充失败".to_string()),
-                    ProgressOperation::Erase => ("erase".to_string(), "擦除失败".to_string()),
-                    ProgressOperation::Program => ("program".to_string(), "编程失败".to_string()),
-                    ProgressOperation::Verify => ("verify".to_string(), "校验失败".to_string()),
+                ProgressEvent::Finished(operation) => {
+                    match operation {
+                        ProgressOperation::Fill => ("fill".to_string(), "填充完成".to_string()),
+                        ProgressOperation::Erase => ("erase".to_string(), "擦除完成".to_string()),
+                        ProgressOperation::Program => ("program".to_string(), "编程完成".to_string()),
+                        ProgressOperation::Verify => ("verify".to_string(), "校验完成".to_string()),
+                    }
                 }
-            }
-            ProgressEvent::Finished(operation) => {
-                match operation {
-                    ProgressOperation::Fill => ("fill".to_string(), "填充完成".to_string()),
-                    ProgressOperation::Erase => ("erase".to_string(), "擦除完成".to_string()),
-                    ProgressOperation::Program => ("program".to_string(), "编程完成".to_string()),
-                    ProgressOperation::Verify => ("verify".to_string(), "校验完成".to_string()),
+                ProgressEvent::DiagnosticMessage { message } => {
+                    ("info".to_string(), message)
                 }
+            };
+
+            if let Some(level) = log_level {
+                flash_log(&events_clone, level, message.clone());
             }
-            ProgressEvent::DiagnosticMessage { message } => {
-                ("info".to_string(), message)
-            }
-        };
 
-        let progress = state.calculate_progress();
+            let progress = state.calculate_progress();
 
-        let _ = window_clone.emit(
-            "flash-progress",
-            FlashProgressEvent {
-                phase,
-                progress,
-                message,
-            },
-        );
-    });
+            events_clone.emit_event(
+                "flash-progress",
+                FlashProgressEvent {
+                    phase,
+                    progress,
+                    message,
+                },
+            );
+        });
 
-    download_options.progress = progress_callback;
+        download_options.progress = progress_callback;
+        download_options
+    };
 
     // 执行下载
-    download_file_with_options(session, path, format, download_options)
-        .map_err(|e| {
-            // 输出详细的错误信息用于调试
-            log::error!("Flash 错误详情: {:?}", e);
-            log::error!("Flash 错误类型: {}", std::any::type_name_of_val(&e));
-
-            // 构建更详细的错误消息
-            let error_msg = format!("{:#}", e);
-            AppError::FlashError(error_msg)
-        })?;
+    flash_log(
+        events,
+        log::Level::Info,
+        format!("开始烧录 {} (擦除模式: {:?}, 校验: {})", options.file_path, options.erase_mode, options.verify),
+    );
+    let started = std::time::Instant::now();
+    let mut per_sector_programmed = None;
+    let core_index = state.core_index(options.core_index);
+    let mut retries = 0;
+    let result = loop {
+        let result = if options.verify_per_sector {
+            flash_log(events, log::Level::Info, "逐扇区烧录并校验".to_string());
+            let chip_erase = matches!(options.erase_mode, EraseMode::ChipErase);
+            firmware::load_image(path)
+                .and_then(|image| flash_per_sector(session, image, bin_base, options.skip_erase, chip_erase, core_index, events))
+                .map(|programmed| per_sector_programmed = Some(programmed))
+        } else {
+            match &format {
+                Some(format) => download_file_with_options(session, path, format.clone(), build_download_options())
+                    .map_err(|e| {
+                        // 输出详细的错误信息用于调试
+                        log::error!("Flash 错误详情: {:?}", e);
+                        log::error!("Flash 错误类型: {}", std::any::type_name_of_val(&e));
+
+                        // 构建更详细的错误消息
+                        let error_msg = format!("{:#}", e);
+                        flash_failure(&e, error_msg)
+                    }),
+                None => download_srec(session, path, build_download_options()),
+            }
+        };
+
+        // 通信类的暂时性错误整体重试，校验失败、文件解析等错误直接返回
+        match &result {
+            Err(e @ AppError::TransientFlashError(_)) if retries < options.flash_retry_count => {
+                retries += 1;
+                flash_log(
+                    events,
+                    log::Level::Warn,
+                    format!("烧录失败，第 {}/{} 次重试: {}", retries, options.flash_retry_count, e),
+                );
+                recover_after_flash_error(session, core_index, state.core_timeout(CoreOperation::Reset), events);
+                *progress_state.lock().unwrap() = ProgressState::new();
+            }
+            _ => break result,
+        }
+    };
+    if let Err(e) = &result {
+        flash_log(events, log::Level::Error, format!("烧录失败: {}", e));
+        recover_after_flash_error(session, core_index, state.core_timeout(CoreOperation::Reset), events);
+
+        // 擦除后烧录失败时保留区域已被擦掉，先写回再返回错误
+        if !preserved.is_empty() {
+            if let Err(restore_err) = restore_preserved_regions(session, &preserved) {
+                flash_log(events, log::Level::Error, format!("烧录失败后恢复保留区域失败: {}", restore_err));
+                return Err(AppError::FlashError(format!("{}；恢复保留区域也失败: {}", e, restore_err)));
+            }
+            flash_log(events, log::Level::Info, format!("烧录失败，已恢复 {} 个保留区域", preserved.len()));
+        }
+    }
+    result?;
+
+    if !preserved.is_empty() {
+        restore_preserved_regions(session, &preserved)?;
+        flash_log(events, log::Level::Info, format!("已恢复 {} 个保留区域", preserved.len()));
+    }
+
+    let chip_erase = options.erase_mode == EraseMode::ChipErase;
+    let (programmed, bytes_erased, sectors_skipped) = match per_sector_programmed {
+        // 逐扇区烧录没有进度事件，擦除范围按扇区计算
+        Some(programmed) => {
+            let erased = if options.skip_erase {
+                0
+            } else if chip_erase {
+                sectors.iter().map(|s| s.size).sum()
+            } else {
+                sectors
+                    .iter()
+                    .filter(|s| sector_in_image(s, flash_image))
+                    .map(|s| s.size)
+                    .sum()
+            };
+            (programmed, erased, 0)
+        }
+        None => {
+            let progress = progress_state.lock().unwrap();
+            let skipped = if options.skip_erase || chip_erase {
+                0
+            } else {
+                image_sectors.saturating_sub(progress.erase_sectors)
+            };
+            (progress.program_total, progress.erase_current, skipped)
+        }
+    };
+    emit_flash_stats(events, "flash", programmed, retries, started);
 
     // 烧录完成，发送 95% 进度
-    let _ = window.emit(
+    events.emit_event(
         "flash-progress",
         FlashProgressEvent {
             phase: "finishing".to_string(),
@@ -278,9 +1358,38 @@ pub async fn flash_firmware(
         },
     );
 
-    // 重置芯片
-    if options.reset_after {
-        let _ = window.emit(
+    // 复位并暂停在入口处
+    if options.halt_after {
+        if options.reset_after {
+            log::warn!("reset_after 与 halt_after 同时设置，烧录后将复位并暂停");
+        }
+        events.emit_event(
+            "flash-progress",
+            FlashProgressEvent {
+                phase: "reset".to_string(),
+                progress: 0.98,
+                message: "正在复位并暂停芯片...".to_string(),
+            },
+        );
+        let mut core = session
+            .core(state.core_index(options.core_index))
+            .map_err(|e| AppError::FlashError(e.to_string()))?;
+        let info = core
+            .reset_and_halt(state.core_timeout(CoreOperation::Reset))
+            .map_err(|e| AppError::FlashError(format!("复位并暂停失败: {}", e)))?;
+        let halted = core.core_halted().unwrap_or(false);
+        flash_log(
+            events,
+            if halted { log::Level::Info } else { log::Level::Warn },
+            format!(
+                "内核{}，PC = 0x{:08X}",
+                if halted { "已暂停于复位向量" } else { "未能保持暂停" },
+                info.pc
+            ),
+        );
+    } else if options.reset_after {
+        // 重置芯片
+        events.emit_event(
             "flash-progress",
             FlashProgressEvent {
                 phase: "reset".to_string(),
@@ -288,11 +1397,30 @@ pub async fn flash_firmware(
                 message: "正在复位芯片...".to_string(),
             },
         );
-        let mut core = session.core(0).map_err(|e| AppError::FlashError(e.to_string()))?;
+        let mut core = session
+            .core(state.core_index(options.core_index))
+            .map_err(|e| AppError::FlashError(e.to_string()))?;
         core.reset().map_err(|e| AppError::FlashError(e.to_string()))?;
+        flash_log(events, log::Level::Info, "芯片已复位".to_string());
     }
 
-    let _ = window.emit(
+    let result = FlashResult {
+        bytes_programmed: programmed,
+        bytes_erased,
+        verified: options.verify || options.verify_per_sector,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        sectors_skipped,
+        detected_algorithm_ram_region,
+    };
+    flash_log(
+        events,
+        log::Level::Info,
+        format!(
+            "烧录完成，编程 {} 字节，擦除 {} 字节，跳过 {} 个扇区，耗时 {} ms",
+            result.bytes_programmed, result.bytes_erased, result.sectors_skipped, result.elapsed_ms
+        ),
+    );
+    events.emit_event(
         "flash-progress",
         FlashProgressEvent {
             phase: "complete".to_string(),
@@ -301,6 +1429,154 @@ pub async fn flash_firmware(
         },
     );
 
+    Ok(result)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExternalLoaderFlashOptions {
+    pub file_path: String,
+    /// 外部 Flash 加载算法 (.FLM)
+    pub flm_path: String,
+    /// 算法加载到的 RAM 地址
+    pub load_address: u64,
+    /// 外部 Flash 映射基地址
+    pub flash_base: u64,
+    /// 外部 Flash 容量
+    pub flash_size: u64,
+    #[serde(default)]
+    pub verify: bool,
+}
+
+/// 使用用户指定的外部加载算法 (.FLM) 烧录外部 Flash (QSPI/SPI)
+/// 基于当前芯片注册临时变体后重新连接烧录，完成后恢复原连接
+#[tauri::command]
+pub async fn flash_with_external_loader(
+    options: ExternalLoaderFlashOptions,
+    state: State<'_, AppState>,
+    window: Window,
+) -> AppResult<()> {
+    flash_with_external_loader_with(options, &state, &window)
+}
+
+/// 外部 Flash 烧录主流程，日志与进度经 events 输出
+pub fn flash_with_external_loader_with<E: EventSink>(
+    options: ExternalLoaderFlashOptions,
+    state: &AppState,
+    events: &E,
+) -> AppResult<()> {
+    let path = Path::new(&options.file_path);
+    if !path.exists() {
+        return Err(AppError::FileError("文件不存在".to_string()));
+    }
+
+    let connect_options = state
+        .connect_options
+        .lock()
+        .clone()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut session_guard = state.lock_session_for_flash();
+    let target_name = session_guard
+        .as_ref()
+        .map(|session| session.target().name.clone())
+        .ok_or(AppError::NotConnected)?;
+
+    let mut algo = crate::pack::flash_algo::extract_flash_algorithm_from_flm(
+        Path::new(&options.flm_path),
+        options.flash_base,
+        options.flash_size,
+    )?;
+    algo.name = format!("ext_{}", algo.name);
+    algo.description = "External flash loader".to_string();
+
+    let mut registry = crate::commands::config::TARGET_REGISTRY.lock().unwrap();
+
+    // header 预留大小与 Pack 生成目标定义时使用同一配置
+    let pack_name = crate::commands::config::pack_for_chip(&registry, &target_name).unwrap_or_default();
+    let header_reserve = crate::pack::target_gen::resolve_header_reserve(
+        &crate::app_config::load_config().header_reserves,
+        &pack_name,
+        &target_name,
+    );
+    let variant_name = crate::pack::target_override::register_external_loader_variant(
+        &mut registry,
+        &connect_options.target,
+        &algo,
+        options.load_address + header_reserve,
+    )?;
+
+    flash_log(events, log::Level::Info, format!("使用外部加载算法 {} 重新连接...", algo.name));
+    events.emit_event(
+        "flash-progress",
+        FlashProgressEvent {
+            phase: "init".to_string(),
+            progress: 0.0,
+            message: format!("使用外部加载算法 {} 重新连接...", algo.name),
+        },
+    );
+
+    // 释放原连接后以临时变体重新连接，失败时恢复原连接
+    *session_guard = None;
+    let mut ext_session = match crate::commands::probe::attach_session(
+        &connect_options,
+        probe_rs::config::TargetSelector::from(variant_name.as_str()),
+        &registry,
+    ) {
+        Ok(session) => session,
+        Err(e) => {
+            flash_log(events, log::Level::Error, format!("以外部加载算法重新连接失败: {}", e));
+            let _ = crate::commands::probe::restore_session(state, &mut session_guard, &connect_options, &registry, events);
+            return Err(e);
+        }
+    };
+
+    // BIN 文件烧录到外部 Flash 映射基地址
+    let format = detect_flash_format(path, || Ok(options.flash_base), events);
+
+    let result = format.and_then(|format| {
+        let mut download_options = DownloadOptions::default();
+        download_options.verify = options.verify;
+
+        flash_log(events, log::Level::Info, format!("正在烧录外部 Flash (0x{:08X})...", options.flash_base));
+        events.emit_event(
+            "flash-progress",
+            FlashProgressEvent {
+                phase: "program".to_string(),
+                progress: 0.1,
+                message: format!("正在烧录外部 Flash (0x{:08X})...", options.flash_base),
+            },
+        );
+
+        match format {
+            Some(format) => download_file_with_options(&mut ext_session, path, format, download_options)
+                .map_err(|e| {
+                    log::error!("外部 Flash 烧录错误详情: {:?}", e);
+                    AppError::FlashError(format!("{:#}", e))
+                }),
+            None => download_srec(&mut ext_session, path, download_options),
+        }
+    });
+    drop(ext_session);
+
+    // 恢复原连接
+    let restored = crate::commands::probe::restore_session(state, &mut session_guard, &connect_options, &registry, events);
+
+    if let Err(e) = &result {
+        flash_log(events, log::Level::Error, format!("外部 Flash 烧录失败: {}", e));
+    }
+    result?;
+    restored?;
+
+    flash_log(events, log::Level::Info, "外部 Flash 烧录完成".to_string());
+    events.emit_event(
+        "flash-progress",
+        FlashProgressEvent {
+            phase: "complete".to_string(),
+            progress: 1.0,
+            message: "外部 Flash 烧录完成".to_string(),
+        },
+    );
+
     Ok(())
 }
 
@@ -310,18 +1586,88 @@ pub struct EraseChipOptions {
     pub erase_mode: EraseMode,
 }
 
+/// 擦除的连续区域
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErasedRegion {
+    pub address: u64,
+    pub size: u64,
+}
+
+/// 擦除结果，供前端确认擦除范围（耗时异常短通常说明擦除被保护拦截）
+#[derive(Debug, Clone, Serialize)]
+pub struct EraseResult {
+    pub regions_erased: Vec<ErasedRegion>,
+    pub total_bytes: u64,
+    pub elapsed_ms: u64,
+}
+
+impl EraseResult {
+    fn new(regions_erased: Vec<ErasedRegion>, started: std::time::Instant) -> Self {
+        let total_bytes = regions_erased.iter().map(|r| r.size).sum();
+        let result = Self {
+            regions_erased,
+            total_bytes,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        log::info!(
+            "擦除 {} 字节，共 {} 个区域，耗时 {} ms",
+            result.total_bytes,
+            result.regions_erased.len(),
+            result.elapsed_ms
+        );
+        result
+    }
+}
+
+/// 与 [start, end) 重叠的扇区，相邻扇区合并为连续区域
+pub fn sector_erase_scope(sectors: &[FlashSector], range: std::ops::Range<u64>) -> Vec<ErasedRegion> {
+    let mut regions: Vec<ErasedRegion> = Vec::new();
+    for sector in sectors
+        .iter()
+        .filter(|s| s.address < range.end && range.start < s.address + s.size)
+    {
+        match regions.last_mut() {
+            Some(last) if last.address + last.size == sector.address => last.size += sector.size,
+            _ => regions.push(ErasedRegion {
+                address: sector.address,
+                size: sector.size,
+            }),
+        }
+    }
+    regions
+}
+
 #[tauri::command]
 pub async fn erase_chip(
     options: Option<EraseChipOptions>,
     state: State<'_, AppState>,
     window: Window,
-) -> AppResult<()> {
-    let mut session_guard = state.session.lock();
+) -> AppResult<EraseResult> {
+    let mut session_guard = state.lock_session_for_flash();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
     let erase_mode = options.map(|o| o.erase_mode).unwrap_or(EraseMode::ChipErase);
+    let started = std::time::Instant::now();
+
+    // 所有 Flash 区域（跳过别名区域，避免重复擦除同一块 Flash）
+    let flash_regions: Vec<_> = session.target().memory_map.iter()
+        .filter_map(|region| {
+            if let probe_rs::config::MemoryRegion::Nvm(r) = region {
+                (!r.is_alias).then(|| r.range.clone())
+            } else {
+                None
+            }
+        })
+        .collect();
+    let regions_erased = flash_regions
+        .iter()
+        .map(|r| ErasedRegion {
+            address: r.start,
+            size: r.end - r.start,
+        })
+        .collect();
 
     match erase_mode {
         EraseMode::ChipErase => {
@@ -346,74 +1692,490 @@ pub async fn erase_chip(
                 },
             );
         }
-        EraseMode::SectorErase => {
-            let _ = window.emit(
-                "flash-progress",
-                FlashProgressEvent {
-                    phase: "erase".to_string(),
-                    progress: 0.0,
-                    message: "开始扇区擦除".to_string(),
-                },
-            );
+        EraseMode::SectorErase => {
+            let _ = window.emit(
+                "flash-progress",
+                FlashProgressEvent {
+                    phase: "erase".to_string(),
+                    progress: 0.0,
+                    message: "开始扇区擦除".to_string(),
+                },
+            );
+
+            // 所有区域共用一个进度回调，汇总为统一的 0-100%
+            let total: u64 = flash_regions.iter().map(|r| r.end - r.start).sum();
+            let erased = Arc::new(Mutex::new(0u64));
+            let erased_clone = Arc::clone(&erased);
+            let window_clone = window.clone();
+            let mut progress = FlashProgress::new(move |event| {
+                if let ProgressEvent::Progress { operation: ProgressOperation::Erase, size, .. } = event {
+                    let mut erased = erased_clone.lock().unwrap();
+                    *erased += size;
+                    let progress = if total > 0 { (*erased as f32 / total as f32).min(1.0) } else { 1.0 };
+                    let _ = window_clone.emit(
+                        "flash-progress",
+                        FlashProgressEvent {
+                            phase: "erase".to_string(),
+                            progress,
+                            message: format!("已擦除 {}/{} 字节", *erased, total),
+                        },
+                    );
+                }
+            });
+
+            for range in flash_regions {
+                log::info!("扇区擦除区域 0x{:08X}-0x{:08X}", range.start, range.end);
+                erase(session, &mut progress, range.start, range.end, false)
+                    .map_err(|e| AppError::FlashError(e.to_string()))?;
+            }
+
+            let _ = window.emit(
+                "flash-progress",
+                FlashProgressEvent {
+                    phase: "complete".to_string(),
+                    progress: 1.0,
+                    message: "扇区擦除完成".to_string(),
+                },
+            );
+        }
+    }
+
+    Ok(EraseResult::new(regions_erased, started))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EraseSectorOptions {
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Flash 扇区
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashSector {
+    pub address: u64,
+    pub size: u64,
+}
+
+/// 单个 NVM 区域的扇区布局
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashRegionLayout {
+    pub name: Option<String>,
+    pub address: u64,
+    pub size: u64,
+    /// 外部存储器映射的 Flash (QSPI/OSPI)
+    pub external: bool,
+    /// 匹配到的 Flash 算法
+    pub algorithm: Option<String>,
+    /// 实际扇区列表（支持大小不一的扇区，如 STM32F4 的 16K/64K/128K）
+    pub sectors: Vec<FlashSector>,
+}
+
+/// 按算法的扇区描述 (相对 Flash 起始的偏移, 扇区大小) 展开区域内的所有扇区
+/// 每条描述一直生效到下一条描述或算法地址范围结束
+fn expand_sectors(
+    algo_range: &std::ops::Range<u64>,
+    descriptions: &[(u64, u64)],
+    region: &std::ops::Range<u64>,
+) -> Vec<FlashSector> {
+    let base = algo_range.start;
+    let end = algo_range.end.min(region.end);
+    let mut sectors = Vec::new();
+
+    for (i, &(offset, size)) in descriptions.iter().enumerate() {
+        if size == 0 {
+            continue;
+        }
+        let group_end = descriptions
+            .get(i + 1)
+            .map_or(end, |&(next, _)| (base + next).min(end));
+
+        let mut address = base + offset;
+        while address < group_end {
+            if address >= region.start {
+                sectors.push(FlashSector { address, size });
+            }
+            address += size;
+        }
+    }
+
+    sectors
+}
+
+/// 获取已连接目标的 Flash 扇区布局（供扇区擦除界面选择扇区）
+#[tauri::command]
+pub async fn get_flash_layout(state: State<'_, AppState>) -> AppResult<Vec<FlashRegionLayout>> {
+    let session_guard = state.lock_session();
+    let session = session_guard
+        .as_ref()
+        .ok_or(AppError::NotConnected)?;
+
+    Ok(flash_layout(session.target()))
+}
+
+/// 按目标定义计算所有非别名 NVM 区域的扇区布局
+fn flash_layout(target: &probe_rs::Target) -> Vec<FlashRegionLayout> {
+    target
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Nvm(nvm) if !nvm.is_alias => Some(nvm),
+            _ => None,
+        })
+        .map(|nvm| {
+            let algorithm = target
+                .flash_algorithms
+                .iter()
+                .filter(|a| a.flash_properties.address_range.contains(&nvm.range.start))
+                .max_by_key(|a| a.default);
+
+            FlashRegionLayout {
+                name: nvm.name.clone(),
+                address: nvm.range.start,
+                size: nvm.range.end - nvm.range.start,
+                external: is_external_nvm(nvm),
+                algorithm: algorithm.map(|a| a.name.clone()),
+                sectors: algorithm
+                    .map(|a| {
+                        let descriptions: Vec<(u64, u64)> = a
+                            .flash_properties
+                            .sectors
+                            .iter()
+                            .map(|d| (d.address, d.size))
+                            .collect();
+                        expand_sectors(&a.flash_properties.address_range, &descriptions, &nvm.range)
+                    })
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// 烧录实际使用的 Flash 算法参数
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveFlashAlgorithm {
+    pub name: String,
+    pub default: bool,
+    /// 算法代码加载地址
+    pub load_address: Option<u64>,
+    /// 生成目标定义或设置算法 RAM 区域时在 load_address 之前实际预留的字节数，内置目标为 None
+    pub header_reserve: Option<u64>,
+    /// header 起始地址 (load_address - header_reserve)
+    pub header_address: Option<u64>,
+    pub data_load_address: Option<u64>,
+    pub data_section_offset: u64,
+    pub stack_size: Option<u32>,
+    pub flash_start: u64,
+    pub flash_end: u64,
+    pub page_size: u32,
+    pub erased_byte_value: u8,
+    /// 扇区描述 (相对 flash_start 的偏移, 扇区大小)
+    pub sector_descriptions: Vec<(u64, u64)>,
+}
+
+/// 烧录使用的最终内存布局与算法配置
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveFlashConfig {
+    pub target_name: String,
+    /// 来自已连接会话（包含连接时的算法/RAM 覆盖）还是 Registry 中的目标定义
+    pub connected: bool,
+    pub memory_regions: Vec<crate::commands::probe::MemoryRegion>,
+    /// 各 NVM 区域选用的算法及扇区布局
+    pub flash_regions: Vec<FlashRegionLayout>,
+    pub algorithms: Vec<EffectiveFlashAlgorithm>,
+}
+
+/// 目标定义中实际生效的 header 预留
+/// 指定了算法 RAM 区域时 load_address 相对该区域起始；Pack 生成的目标相对所在 RAM 区域起始；
+/// 内置目标的 load_address 直接来自 probe-rs 定义，没有预留
+fn applied_header_reserve(
+    target: &probe_rs::Target,
+    load_address: Option<u64>,
+    from_pack: bool,
+    algorithm_ram_region: Option<(u64, u64)>,
+) -> Option<u64> {
+    let load_address = load_address?;
+    if let Some((start, _)) = algorithm_ram_region {
+        return load_address.checked_sub(start);
+    }
+    if !from_pack {
+        return None;
+    }
+    target.memory_map.iter().find_map(|region| match region {
+        probe_rs::config::MemoryRegion::Ram(ram) if ram.range.contains(&load_address) => {
+            Some(load_address - ram.range.start)
+        }
+        _ => None,
+    })
+}
+
+/// 汇总目标的内存布局与算法配置
+/// from_pack 表示目标来自导入的 Pack，algorithm_ram_region 为连接时指定的算法 RAM 区域
+pub fn effective_flash_config(
+    target: &probe_rs::Target,
+    connected: bool,
+    from_pack: bool,
+    algorithm_ram_region: Option<(u64, u64)>,
+) -> EffectiveFlashConfig {
+    use probe_rs::config::MemoryRegion;
+
+    EffectiveFlashConfig {
+        target_name: target.name.clone(),
+        connected,
+        memory_regions: target
+            .memory_map
+            .iter()
+            .map(|region| {
+                let (name, kind, range) = match region {
+                    MemoryRegion::Ram(r) => (&r.name, "RAM", &r.range),
+                    MemoryRegion::Nvm(r) => (&r.name, if r.is_alias { "Flash (alias)" } else { "Flash" }, &r.range),
+                    MemoryRegion::Generic(r) => (&r.name, "Generic", &r.range),
+                };
+                crate::commands::probe::MemoryRegion {
+                    name: name.clone().unwrap_or_default(),
+                    kind: kind.to_string(),
+                    address: range.start,
+                    size: range.end - range.start,
+                }
+            })
+            .collect(),
+        flash_regions: flash_layout(target),
+        algorithms: target
+            .flash_algorithms
+            .iter()
+            .map(|a| {
+                let header_reserve = applied_header_reserve(target, a.load_address, from_pack, algorithm_ram_region);
+                EffectiveFlashAlgorithm {
+                    name: a.name.clone(),
+                    default: a.default,
+                    load_address: a.load_address,
+                    header_reserve,
+                    header_address: a.load_address.zip(header_reserve).map(|(address, reserve)| address - reserve),
+                    data_load_address: a.data_load_address,
+                    data_section_offset: a.data_section_offset,
+                    stack_size: a.stack_size,
+                    flash_start: a.flash_properties.address_range.start,
+                    flash_end: a.flash_properties.address_range.end,
+                    page_size: a.flash_properties.page_size,
+                    erased_byte_value: a.flash_properties.erased_byte_value,
+                    sector_descriptions: a.flash_properties.sectors.iter().map(|d| (d.address, d.size)).collect(),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// 查看烧录将使用的最终内存布局：指定芯片名时读取 Registry 中的定义，否则使用已连接的目标
+#[tauri::command]
+pub async fn get_effective_flash_config(
+    chip_name: Option<String>,
+    state: State<'_, AppState>,
+) -> AppResult<EffectiveFlashConfig> {
+    match chip_name {
+        Some(name) => {
+            let registry = crate::commands::config::TARGET_REGISTRY.lock().unwrap();
+            let target = registry
+                .get_target_by_name(&name)
+                .map_err(|e| AppError::ConfigError(format!("未找到芯片 '{}': {}", name, e)))?;
+            let from_pack = crate::commands::config::pack_for_chip(&registry, &name).is_some();
+            Ok(effective_flash_config(&target, false, from_pack, None))
+        }
+        None => {
+            let session_guard = state.lock_session();
+            let session = session_guard.as_ref().ok_or(AppError::NotConnected)?;
+            let algorithm_ram_region = state.connect_options.lock().as_ref().and_then(|o| o.algorithm_ram_region);
+            let from_pack = {
+                let registry = crate::commands::config::TARGET_REGISTRY.lock().unwrap();
+                crate::commands::config::pack_for_chip(&registry, &session.target().name).is_some()
+            };
+            Ok(effective_flash_config(session.target(), true, from_pack, algorithm_ram_region))
+        }
+    }
+}
 
-            // 获取所有 Flash 区域并逐个扇区擦除
-            let flash_regions: Vec<_> = session.target().memory_map.iter()
-                .filter_map(|region| {
-                    if let probe_rs::config::MemoryRegion::Nvm(r) = region {
-                        Some((r.range.start, r.range.end - r.range.start))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+/// Flash 算法实机测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashAlgorithmTestResult {
+    pub chip: String,
+    pub algorithm: String,
+    /// 测试使用的扇区
+    pub sector_address: u64,
+    pub sector_size: u64,
+    /// 写入的测试数据长度
+    pub pattern_size: u64,
+    /// 写入并读回一致
+    pub success: bool,
+    pub program_ms: u64,
+    pub verify_ms: u64,
+    /// 扇区是否已恢复为测试前的内容
+    pub restored: bool,
+    pub error: Option<String>,
+}
+
+/// 在已连接的硬件上实际运行 Flash 算法：向第一个扇区写入测试数据、读回比对，然后恢复扇区
+/// 用于确认 Pack 生成的目标定义真的可用（静态解析 FLM 无法发现的问题）
+#[tauri::command]
+pub async fn test_flash_algorithm(
+    chip_name: String,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<FlashAlgorithmTestResult> {
+    let core_index = state.core_index(core_index);
+    let mut session_guard = state.lock_session_for_flash();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
 
-            for (address, size) in flash_regions {
-                // 使用 FlashLoader 进行扇区擦除
-                let mut loader = session.target().flash_loader();
+    let target = session.target();
+    if !target.name.eq_ignore_ascii_case(&chip_name) {
+        return Err(AppError::FlashError(format!(
+            "当前连接的目标为 {}，请先连接 {} 再测试",
+            target.name, chip_name
+        )));
+    }
 
-                // 添加 0xFF 数据来触发扇区擦除
-                let erase_data = vec![0xFFu8; size as usize];
-                loader.add_data(address, &erase_data)
-                    .map_err(|e| AppError::FlashError(e.to_string()))?;
+    // 第一个非别名 Flash 区域及其算法的第一个扇区
+    let (algorithm, sector, page_size, erased_byte) = target
+        .memory_map
+        .iter()
+        .find_map(|region| match region {
+            probe_rs::config::MemoryRegion::Nvm(nvm) if !nvm.is_alias => Some(nvm),
+            _ => None,
+        })
+        .and_then(|nvm| {
+            let algo = target
+                .flash_algorithms
+                .iter()
+                .filter(|a| a.flash_properties.address_range.contains(&nvm.range.start))
+                .max_by_key(|a| a.default)?;
+            let descriptions: Vec<(u64, u64)> = algo
+                .flash_properties
+                .sectors
+                .iter()
+                .map(|d| (d.address, d.size))
+                .collect();
+            let sector = expand_sectors(&algo.flash_properties.address_range, &descriptions, &nvm.range)
+                .into_iter()
+                .next()?;
+            Some((
+                algo.name.clone(),
+                sector,
+                algo.flash_properties.page_size as u64,
+                algo.flash_properties.erased_byte_value,
+            ))
+        })
+        .ok_or_else(|| AppError::FlashError(format!("目标 {} 没有可测试的 Flash 算法", chip_name)))?;
+
+    let pattern: Vec<u8> = (0..page_size.min(sector.size))
+        .map(|i| (i as u8) ^ 0xA5)
+        .collect();
+    log::info!(
+        "测试 Flash 算法 {}: 扇区 0x{:08X} ({} 字节)，测试数据 {} 字节",
+        algorithm, sector.address, sector.size, pattern.len()
+    );
 
-                let mut download_options = DownloadOptions::default();
-                download_options.do_chip_erase = false; // 使用扇区擦除
-                download_options.skip_erase = false;
+    // 备份扇区原内容
+    let mut backup = vec![0u8; sector.size as usize];
+    session
+        .core(core_index)
+        .map_err(|e| AppError::FlashError(e.to_string()))?
+        .read_8(sector.address, &mut backup)
+        .map_err(|e| AppError::FlashError(format!("备份扇区失败: {}", e)))?;
+
+    let mut result = FlashAlgorithmTestResult {
+        chip: chip_name,
+        algorithm,
+        sector_address: sector.address,
+        sector_size: sector.size,
+        pattern_size: pattern.len() as u64,
+        success: false,
+        program_ms: 0,
+        verify_ms: 0,
+        restored: false,
+        error: None,
+    };
 
-                loader.commit(session, download_options)
-                    .map_err(|e| AppError::FlashError(e.to_string()))?;
+    // 写入测试数据
+    let started = std::time::Instant::now();
+    let mut loader = session.target().flash_loader();
+    let programmed = loader
+        .add_data(sector.address, &pattern)
+        .map_err(|e| format!("{:#}", e))
+        .and_then(|_| loader.commit(session, DownloadOptions::default()).map_err(|e| format!("{:#}", e)));
+    result.program_ms = started.elapsed().as_millis() as u64;
+
+    match programmed {
+        Ok(()) => {
+            // 读回比对
+            let started = std::time::Instant::now();
+            let mut readback = vec![0u8; pattern.len()];
+            let read = session
+                .core(core_index)
+                .map_err(|e| e.to_string())
+                .and_then(|mut core| core.read_8(sector.address, &mut readback).map_err(|e| e.to_string()));
+            result.verify_ms = started.elapsed().as_millis() as u64;
+
+            match read {
+                Ok(()) if readback == pattern => result.success = true,
+                Ok(()) => {
+                    let offset = readback.iter().zip(&pattern).position(|(a, b)| a != b).unwrap_or(0);
+                    result.error = Some(format!(
+                        "读回数据不一致，首个差异位于 0x{:08X}",
+                        sector.address + offset as u64
+                    ));
+                }
+                Err(e) => result.error = Some(format!("读回失败: {}", e)),
             }
+        }
+        Err(e) => result.error = Some(format!("烧录测试数据失败: {}", e)),
+    }
 
-            let _ = window.emit(
-                "flash-progress",
-                FlashProgressEvent {
-                    phase: "complete".to_string(),
-                    progress: 1.0,
-                    message: "扇区擦除完成".to_string(),
-                },
-            );
+    // 恢复扇区：原来为空则擦除，否则写回原内容
+    let restore = if backup.iter().all(|&b| b == erased_byte) {
+        erase(session, &mut FlashProgress::new(|_| {}), sector.address, sector.address + sector.size, false)
+            .map_err(|e| e.to_string())
+    } else {
+        let mut loader = session.target().flash_loader();
+        loader
+            .add_data(sector.address, &backup)
+            .map_err(|e| format!("{:#}", e))
+            .and_then(|_| loader.commit(session, DownloadOptions::default()).map_err(|e| format!("{:#}", e)))
+    };
+    match restore {
+        Ok(()) => result.restored = true,
+        Err(e) => {
+            log::error!("恢复测试扇区失败: {}", e);
+            result.error.get_or_insert_with(|| format!("恢复扇区失败: {}", e));
         }
     }
 
-    Ok(())
-}
+    log::info!(
+        "Flash 算法测试{}: 烧录 {} ms，校验 {} ms",
+        if result.success { "通过" } else { "失败" },
+        result.program_ms,
+        result.verify_ms
+    );
 
-#[derive(Debug, Deserialize)]
-pub struct EraseSectorOptions {
-    pub address: u64,
-    pub size: u64,
+    Ok(result)
 }
 
 #[tauri::command]
 pub async fn erase_sector(
     options: EraseSectorOptions,
     state: State<'_, AppState>,
-) -> AppResult<()> {
-    let mut session_guard = state.session.lock();
+) -> AppResult<EraseResult> {
+    let mut session_guard = state.lock_session_for_flash();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
+    let started = std::time::Instant::now();
+    // Flash 算法按整扇区擦除，实际范围扩展到扇区边界
+    let sectors: Vec<FlashSector> = flash_layout(session.target())
+        .into_iter()
+        .flat_map(|region| region.sectors)
+        .collect();
+    let regions_erased = sector_erase_scope(&sectors, options.address..options.address + options.size);
+
     // 使用 probe-rs 的扇区擦除功能
     let mut loader = session
         .target()
@@ -427,16 +2189,18 @@ pub async fn erase_sector(
     loader.commit(session, DownloadOptions::default())
         .map_err(|e| AppError::FlashError(e.to_string()))?;
 
-    Ok(())
+    Ok(EraseResult::new(regions_erased, started))
 }
 
 #[tauri::command]
 pub async fn verify_firmware(
     file_path: String,
+    core_index: Option<usize>,
     state: State<'_, AppState>,
     window: Window,
 ) -> AppResult<bool> {
-    let mut session_guard = state.session.lock();
+    let core_index = state.core_index(core_index);
+    let mut session_guard = state.lock_session();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
@@ -455,63 +2219,71 @@ pub async fn verify_firmware(
         },
     );
 
-    // 读取文件
-    let file_data = std::fs::read(path)?;
-    let total_size = file_data.len();
+    // 待校验数据段：S-record 按记录地址校验，其余文件（支持 gzip 压缩的转储文件）从 Flash 起始地址比较
+    let is_srec = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| firmware::is_srec_extension(&e.to_lowercase()));
+    let segments = if is_srec {
+        firmware::srec::parse_file(path)?.segments
+    } else {
+        vec![firmware::FirmwareSegment {
+            // 获取Flash起始地址（假设是主Flash区域）
+            address: target_flash_base(session.target()),
+            data: crate::compression::read_file(path)?,
+        }]
+    };
+    let total_size: usize = segments.iter().map(|s| s.data.len()).sum();
 
-    // 获取Flash起始地址（假设是主Flash区域）
-    let target = session.target();
-    let flash_start = target.memory_map.iter()
-        .find_map(|region| {
-            if let probe_rs::config::MemoryRegion::Nvm(r) = region {
-                Some(r.range.start)
-            } else {
-                None
-            }
-        })
-        .unwrap_or(0x08000000); // 默认STM32 Flash地址
+    let mut core = session.core(core_index).map_err(|e| AppError::FlashError(e.to_string()))?;
 
-    let mut core = session.core(0).map_err(|e| AppError::FlashError(e.to_string()))?;
+    let started = std::time::Instant::now();
 
     // 分块校验 - 每块 4KB，大幅提升速度
     const CHUNK_SIZE: usize = 4096;
     let mut verified: usize = 0;
     let mut flash_buffer = vec![0u8; CHUNK_SIZE];
 
-    while verified < total_size {
-        let chunk_len = std::cmp::min(CHUNK_SIZE, total_size - verified);
-        let current_addr = flash_start + verified as u64;
-
-        // 读取当前块
-        core.read(current_addr, &mut flash_buffer[..chunk_len])
-            .map_err(|e| AppError::FlashError(e.to_string()))?;
-
-        // 比较当前块
-        if flash_buffer[..chunk_len] != file_data[verified..verified + chunk_len] {
-            let _ = window.emit(
-                "flash-progress",
-                FlashProgressEvent {
-                    phase: "error".to_string(),
-                    progress: verified as f32 / total_size as f32,
-                    message: format!("校验失败：地址 0x{:08X} 处数据不匹配", current_addr),
-                },
-            );
-            return Ok(false);
-        }
-
-        verified += chunk_len;
+    for segment in &segments {
+        let mut offset: usize = 0;
+
+        while offset < segment.data.len() {
+            let chunk_len = std::cmp::min(CHUNK_SIZE, segment.data.len() - offset);
+            let current_addr = segment.address + offset as u64;
+
+            // 读取当前块
+            core.read(current_addr, &mut flash_buffer[..chunk_len])
+                .map_err(|e| AppError::FlashError(e.to_string()))?;
+
+            // 比较当前块
+            if flash_buffer[..chunk_len] != segment.data[offset..offset + chunk_len] {
+                let _ = window.emit(
+                    "flash-progress",
+                    FlashProgressEvent {
+                        phase: "error".to_string(),
+                        progress: verified as f32 / total_size as f32,
+                        message: format!("校验失败：地址 0x{:08X} 处数据不匹配", current_addr),
+                    },
+                );
+                emit_flash_stats(&window, "verify", (verified + chunk_len) as u64, 0, started);
+                return Ok(false);
+            }
 
-        // 更新进度（每 64KB 更新一次，减少开销）
-        if verified % (64 * 1024) < CHUNK_SIZE || verified >= total_size {
-            let progress = verified as f32 / total_size as f32;
-            let _ = window.emit(
-                "flash-progress",
-                FlashProgressEvent {
-                    phase: "verify".to_string(),
-                    progress,
-                    message: format!("已校验 {}/{} 字节 ({:.1}%)", verified, total_size, progress * 100.0),
-                },
-            );
+            offset += chunk_len;
+            verified += chunk_len;
+
+            // 更新进度（每 64KB 更新一次，减少开销）
+            if verified % (64 * 1024) < CHUNK_SIZE || verified >= total_size {
+                let progress = verified as f32 / total_size as f32;
+                let _ = window.emit(
+                    "flash-progress",
+                    FlashProgressEvent {
+                        phase: "verify".to_string(),
+                        progress,
+                        message: format!("已校验 {}/{} 字节 ({:.1}%)", verified, total_size, progress * 100.0),
+                    },
+                );
+            }
         }
     }
 
@@ -523,14 +2295,133 @@ pub async fn verify_firmware(
             message: format!("校验通过 ({} 字节)", total_size),
         },
     );
+    emit_flash_stats(&window, "verify", total_size as u64, 0, started);
 
     Ok(true)
 }
 
+/// 差异区间
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DiffRange {
+    pub address: u64,
+    pub length: u64,
+}
+
+/// Flash 与文件的差异比较结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashDiffResult {
+    pub ranges: Vec<DiffRange>,
+    /// 不一致的字节总数
+    pub diff_bytes: u64,
+    /// 差异区间数超过上限而被截断
+    pub truncated: bool,
+}
+
+/// 最多报告的差异区间数
+const MAX_DIFF_RANGES: usize = 256;
+
+/// 比较一块数据，将不一致的字节合并为区间追加到 ranges（与上一个区间相邻时合并）
+/// 返回本块中不一致的字节数
+pub fn collect_diff_ranges(
+    address: u64,
+    expected: &[u8],
+    actual: &[u8],
+    ranges: &mut Vec<DiffRange>,
+    max_ranges: usize,
+) -> u64 {
+    let mut diff_bytes = 0;
+
+    for (i, (a, b)) in expected.iter().zip(actual.iter()).enumerate() {
+        if a == b {
+            continue;
+        }
+        diff_bytes += 1;
+
+        let byte_addr = address + i as u64;
+        if let Some(last) = ranges.last_mut() {
+            if last.address + last.length == byte_addr {
+                last.length += 1;
+                continue;
+            }
+        }
+        if ranges.len() < max_ranges {
+            ranges.push(DiffRange { address: byte_addr, length: 1 });
+        }
+    }
+
+    diff_bytes
+}
+
+/// 读取 Flash 并与文件逐字节比较，返回所有差异区间
+#[tauri::command]
+pub async fn diff_flash_against_file(
+    file_path: String,
+    base_address: Option<u64>,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<FlashDiffResult> {
+    let core_index = state.core_index(core_index);
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let path = Path::new(&file_path);
+    if !path.exists() {
+        return Err(AppError::FileError("文件不存在".to_string()));
+    }
+
+    let file_data = crate::compression::read_file(path)?;
+
+    let flash_start = base_address.unwrap_or_else(|| target_flash_base(session.target()));
+
+    let mut core = session.core(core_index).map_err(|e| AppError::FlashError(e.to_string()))?;
+
+    const CHUNK_SIZE: usize = 4096;
+    let mut flash_buffer = vec![0u8; CHUNK_SIZE];
+    let mut ranges = Vec::new();
+    let mut diff_bytes = 0u64;
+    let mut offset = 0usize;
+
+    while offset < file_data.len() {
+        let chunk_len = std::cmp::min(CHUNK_SIZE, file_data.len() - offset);
+        let current_addr = flash_start + offset as u64;
+
+        core.read(current_addr, &mut flash_buffer[..chunk_len])
+            .map_err(|e| AppError::FlashError(e.to_string()))?;
+
+        diff_bytes += collect_diff_ranges(
+            current_addr,
+            &file_data[offset..offset + chunk_len],
+            &flash_buffer[..chunk_len],
+            &mut ranges,
+            MAX_DIFF_RANGES,
+        );
+
+        offset += chunk_len;
+    }
+
+    let reported: u64 = ranges.iter().map(|r| r.length).sum();
+
+    log::info!(
+        "Flash 差异比较完成: {} 个区间, {} 字节不一致",
+        ranges.len(), diff_bytes
+    );
+
+    Ok(FlashDiffResult {
+        ranges,
+        diff_bytes,
+        truncated: reported < diff_bytes,
+    })
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ReadFlashOptions {
     pub address: u64,
     pub size: u64,
+    /// 操作的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
 }
 
 #[tauri::command]
@@ -538,12 +2429,14 @@ pub async fn read_flash(
     options: ReadFlashOptions,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<u8>> {
-    let mut session_guard = state.session.lock();
+    let mut session_guard = state.lock_session();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
-    let mut core = session.core(0).map_err(|e| AppError::FlashError(e.to_string()))?;
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::FlashError(e.to_string()))?;
 
     let mut data = vec![0u8; options.size as usize];
     core.read(options.address, &mut data)
@@ -552,6 +2445,138 @@ pub async fn read_flash(
     Ok(data)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct StartFlashReadOptions {
+    pub address: u64,
+    pub size: u64,
+    /// 每个分块的字节数（默认 4KB）
+    #[serde(default)]
+    pub chunk_size: Option<u64>,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// 后台读取的数据块 (flash-read-chunk 事件)，offset 相对读取起始地址
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashReadChunkEvent {
+    pub offset: u64,
+    pub data: Vec<u8>,
+}
+
+/// 后台读取结束 (flash-read-complete 事件)
+#[derive(Debug, Clone, Serialize)]
+pub struct FlashReadCompleteEvent {
+    pub bytes_read: u64,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+/// 在后台分块读取 Flash，逐块发送 flash-read-chunk 事件，可通过 cancel_flash_read 取消
+#[tauri::command]
+pub async fn start_flash_read(
+    options: StartFlashReadOptions,
+    state: State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> AppResult<()> {
+    if state.lock_session().is_none() {
+        return Err(AppError::NotConnected);
+    }
+    if state.flash_read_running.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return Err(AppError::FlashError("已有 Flash 读取任务在运行".to_string()));
+    }
+
+    let running = Arc::clone(&state.flash_read_running);
+    let session_arc = Arc::clone(&state.session);
+    let core_index = state.core_index(options.core_index);
+    let chunk_size = options.chunk_size.unwrap_or(4096).clamp(256, 64 * 1024);
+
+    tokio::spawn(async move {
+        log::info!("后台读取 Flash 0x{:08X}+0x{:X}", options.address, options.size);
+        let mut offset = 0u64;
+        let mut error = None;
+
+        while offset < options.size && running.load(std::sync::atomic::Ordering::SeqCst) {
+            let len = chunk_size.min(options.size - offset);
+            let mut data = vec![0u8; len as usize];
+            let result = {
+                let mut session_guard = SessionGuard::lock(&session_arc);
+                match session_guard.as_mut() {
+                    Some(session) => session
+                        .core(core_index)
+                        .map_err(|e| e.to_string())
+                        .and_then(|mut core| core.read(options.address + offset, &mut data).map_err(|e| e.to_string())),
+                    None => Err(AppError::NotConnected.to_string()),
+                }
+            };
+            if let Err(e) = result {
+                log::error!("后台读取 Flash 0x{:08X} 失败: {}", options.address + offset, e);
+                error = Some(e);
+                break;
+            }
+
+            let _ = app_handle.emit("flash-read-chunk", FlashReadChunkEvent { offset, data });
+            offset += len;
+            // 每块之间让出 session，其他命令可以插入执行
+            tokio::task::yield_now().await;
+        }
+
+        let cancelled = error.is_none() && offset < options.size;
+        running.store(false, std::sync::atomic::Ordering::SeqCst);
+        log::info!("后台读取 Flash 结束: {} 字节, 取消: {}", offset, cancelled);
+        let _ = app_handle.emit(
+            "flash-read-complete",
+            FlashReadCompleteEvent { bytes_read: offset, cancelled, error },
+        );
+    });
+
+    Ok(())
+}
+
+/// 取消后台 Flash 读取
+#[tauri::command]
+pub async fn cancel_flash_read(state: State<'_, AppState>) -> AppResult<()> {
+    state.flash_read_running.store(false, std::sync::atomic::Ordering::SeqCst);
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DumpFlashOptions {
+    pub address: u64,
+    pub size: u64,
+    pub file_path: String,
+    /// 是否使用 gzip 压缩保存
+    #[serde(default)]
+    pub compress: bool,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// 读取 Flash 并保存到文件
+#[tauri::command]
+pub async fn dump_flash_to_file(
+    options: DumpFlashOptions,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let data = read_flash(
+        ReadFlashOptions {
+            address: options.address,
+            size: options.size,
+            core_index: options.core_index,
+        },
+        state,
+    )
+    .await?;
+
+    crate::compression::write_file(Path::new(&options.file_path), &data, options.compress)?;
+
+    log::info!(
+        "已转储 Flash 0x{:08X}+0x{:X} 到 {} (压缩: {})",
+        options.address, options.size, options.file_path, options.compress
+    );
+
+    Ok(())
+}
+
 /// 固件文件信息
 #[derive(Debug, Clone, Serialize)]
 pub struct FirmwareFileInfo {
@@ -561,6 +2586,17 @@ pub struct FirmwareFileInfo {
     pub exists: bool,
 }
 
+/// 分析固件文件（格式、大小、入口地址、地址范围、CRC32），无需连接目标
+/// BIN 文件没有地址信息，地址范围按 bin_base（默认 0）计算
+#[tauri::command]
+pub async fn analyze_firmware(file_path: String, bin_base: Option<u64>) -> AppResult<FirmwareMetadata> {
+    let path = Path::new(&file_path);
+    if !path.exists() {
+        return Err(AppError::FileError(format!("文件不存在: {}", file_path)));
+    }
+    firmware::analyze(path, bin_base.unwrap_or(0))
+}
+
 /// 获取固件文件信息（用于烧录前重载）
 #[tauri::command]
 pub async fn get_firmware_info(file_path: String) -> AppResult<FirmwareFileInfo> {
@@ -589,3 +2625,241 @@ pub async fn get_firmware_info(file_path: String) -> AppResult<FirmwareFileInfo>
         exists: true,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::firmware::FirmwareSegment;
+    use probe_rs::architecture::arm::ArmError;
+    use probe_rs::config::{MemoryRegion, Registry};
+    use probe_rs::probe::DebugProbeError;
+    use std::path::PathBuf;
+
+    fn test_target() -> probe_rs::Target {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack_targets.yaml");
+        let mut registry = Registry::from_builtin_families();
+        registry
+            .add_target_family_from_yaml(&std::fs::read_to_string(path).unwrap())
+            .unwrap();
+        registry.get_target_by_name("TEST32F1CB").unwrap()
+    }
+
+    #[test]
+    fn test_custom_address_range_checks() {
+        let target = test_target();
+        assert_eq!(resolve_custom_flash_address(&target, 0x0800_4000, None).unwrap(), None);
+        assert_eq!(resolve_custom_flash_address(&target, 0x0800_4000, Some(0x1C000)).unwrap(), None);
+        // 范围超出区域末尾或大小为 0
+        assert!(resolve_custom_flash_address(&target, 0x0800_4000, Some(0x1C001)).is_err());
+        assert!(resolve_custom_flash_address(&target, 0x0800_4000, Some(0)).is_err());
+        // 不在任何 Flash 区域内
+        assert!(resolve_custom_flash_address(&target, 0x2000_0000, None).is_err());
+    }
+
+    #[test]
+    fn test_external_region_requires_algorithm() {
+        let mut target = test_target();
+        let qspi = target
+            .memory_map
+            .iter()
+            .find_map(|region| match region {
+                MemoryRegion::Nvm(nvm) => {
+                    let mut qspi = nvm.clone();
+                    qspi.name = Some("QSPI".to_string());
+                    qspi.range = 0x9000_0000..0x9080_0000;
+                    Some(MemoryRegion::Nvm(qspi))
+                }
+                _ => None,
+            })
+            .unwrap();
+        target.memory_map.push(qspi);
+
+        // 没有覆盖外部区域的算法时不能退回默认算法烧录
+        assert!(resolve_custom_flash_address(&target, 0x9000_0000, None).is_err());
+
+        let mut algo = target.flash_algorithms[0].clone();
+        algo.name = "QSPI_LOADER".to_string();
+        algo.default = false;
+        algo.flash_properties.address_range = 0x9000_0000..0x9080_0000;
+        target.flash_algorithms.push(algo);
+        assert_eq!(
+            resolve_custom_flash_address(&target, 0x9000_0000, Some(0x1000)).unwrap().as_deref(),
+            Some("QSPI_LOADER")
+        );
+    }
+
+    // 烧录生效配置测试
+    fn registry_with_fixture() -> Registry {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack_targets.yaml");
+        let yaml = std::fs::read_to_string(path).unwrap();
+
+        let mut registry = Registry::from_builtin_families();
+        registry.add_target_family_from_yaml(&yaml).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_pack_target_reports_generated_reserve() {
+        let registry = registry_with_fixture();
+        let target = registry.get_target_by_name("TEST32F1CB").unwrap();
+
+        let config = effective_flash_config(&target, false, true, None);
+        let algo = &config.algorithms[0];
+        assert_eq!(algo.header_reserve, Some(0x20));
+        assert_eq!(algo.header_address, Some(0x2000_0000));
+    }
+
+    #[test]
+    fn test_algorithm_ram_region_reserve_is_relative_to_region() {
+        let registry = registry_with_fixture();
+        let target = registry.get_target_by_name("TEST32F1CB").unwrap();
+
+        let config = effective_flash_config(&target, true, true, Some((0x2000_0010, 0x1000)));
+        assert_eq!(config.algorithms[0].header_reserve, Some(0x10));
+    }
+
+    #[test]
+    fn test_builtin_target_has_no_reserve() {
+        let registry = registry_with_fixture();
+        let target = registry.get_target_by_name("STM32F103C8").unwrap();
+
+        let config = effective_flash_config(&target, false, false, None);
+        assert!(!config.algorithms.is_empty());
+        assert!(config.algorithms.iter().all(|a| a.header_reserve.is_none() && a.header_address.is_none()));
+    }
+
+    // 扇区擦除范围测试
+    fn sectors() -> Vec<FlashSector> {
+        let mut sectors: Vec<FlashSector> = (0..4)
+            .map(|i| FlashSector { address: 0x0800_0000 + i * 0x400, size: 0x400 })
+            .collect();
+        // 第二个 Bank 与第一个不相邻
+        sectors.push(FlashSector { address: 0x0808_0000, size: 0x800 });
+        sectors
+    }
+
+    #[test]
+    fn test_partial_range_expands_to_sector_boundaries() {
+        let scope = sector_erase_scope(&sectors(), 0x0800_0100..0x0800_0500);
+        assert_eq!(scope, vec![ErasedRegion { address: 0x0800_0000, size: 0x800 }]);
+    }
+
+    #[test]
+    fn test_non_adjacent_sectors_are_separate_regions() {
+        let scope = sector_erase_scope(&sectors(), 0x0800_0C00..0x0808_0010);
+        assert_eq!(
+            scope,
+            vec![
+                ErasedRegion { address: 0x0800_0C00, size: 0x400 },
+                ErasedRegion { address: 0x0808_0000, size: 0x800 },
+            ]
+        );
+    }
+
+    // Flash 差异区间合并测试
+    #[test]
+    fn test_adjacent_bytes_are_coalesced() {
+        let expected = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+        let actual = [0x00, 0xFF, 0xFF, 0x33, 0xFF, 0x55];
+        let mut ranges = Vec::new();
+
+        let diff = collect_diff_ranges(0x0800_0000, &expected, &actual, &mut ranges, 16);
+
+        assert_eq!(diff, 3);
+        assert_eq!(
+            ranges,
+            vec![
+                DiffRange { address: 0x0800_0001, length: 2 },
+                DiffRange { address: 0x0800_0004, length: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranges_span_chunk_boundary() {
+        let mut ranges = Vec::new();
+
+        // 第一块末尾与第二块开头的差异应合并为一个区间
+        collect_diff_ranges(0x1000, &[0x00, 0x00], &[0x00, 0xFF], &mut ranges, 16);
+        collect_diff_ranges(0x1002, &[0x00, 0x00], &[0xFF, 0x00], &mut ranges, 16);
+
+        assert_eq!(ranges, vec![DiffRange { address: 0x1001, length: 2 }]);
+    }
+
+    #[test]
+    fn test_range_count_is_capped() {
+        let expected = [0x00; 8];
+        let actual = [0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00, 0xFF, 0x00];
+        let mut ranges = Vec::new();
+
+        let diff = collect_diff_ranges(0, &expected, &actual, &mut ranges, 2);
+
+        // 字节计数不受上限影响，区间数被截断
+        assert_eq!(diff, 4);
+        assert_eq!(ranges.len(), 2);
+    }
+
+    // 烧录重试错误分类测试
+    #[test]
+    fn test_communication_errors_are_transient() {
+        assert!(is_transient_flash_error(&ArmError::Timeout));
+        assert!(is_transient_flash_error(&probe_rs::Error::Arm(ArmError::Timeout)));
+        assert!(is_transient_flash_error(&probe_rs::Error::Probe(DebugProbeError::Timeout)));
+    }
+
+    #[test]
+    fn test_errors_without_probe_cause_are_not_retried() {
+        // 文案中带有 timeout/probe 等字样也不再被当作通信错误
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "probe timeout while parsing file");
+        assert!(!is_transient_flash_error(&io));
+        assert!(!is_transient_flash_error(&probe_rs::Error::Other("Verification failed".to_string())));
+    }
+
+    // 逐扇区烧录的数据切分测试
+    fn bank_sectors() -> Vec<FlashSector> {
+        (0..4)
+            .map(|i| FlashSector { address: 0x0800_0000 + i * 0x400, size: 0x400 })
+            .collect()
+    }
+
+    #[test]
+    fn test_segment_spanning_sectors_is_split() {
+        let segments = vec![FirmwareSegment { address: 0x0800_03F0, data: vec![0xAA; 0x20] }];
+
+        let plan = split_into_sectors(&segments, &bank_sectors()).unwrap();
+
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].0.address, 0x0800_0000);
+        assert_eq!(plan[0].1[0].address, 0x0800_03F0);
+        assert_eq!(plan[0].1[0].data.len(), 0x10);
+        assert_eq!(plan[1].0.address, 0x0800_0400);
+        assert_eq!(plan[1].1[0].address, 0x0800_0400);
+        assert_eq!(plan[1].1[0].data.len(), 0x10);
+    }
+
+    #[test]
+    fn test_untouched_sectors_are_skipped() {
+        let segments = vec![
+            FirmwareSegment { address: 0x0800_0000, data: vec![1; 4] },
+            FirmwareSegment { address: 0x0800_0C00, data: vec![2; 4] },
+        ];
+
+        let plan = split_into_sectors(&segments, &bank_sectors()).unwrap();
+
+        let addresses: Vec<u64> = plan.iter().map(|(s, _)| s.address).collect();
+        assert_eq!(addresses, vec![0x0800_0000, 0x0800_0C00]);
+    }
+
+    #[test]
+    fn test_data_outside_flash_is_reported() {
+        let segments = vec![FirmwareSegment { address: 0x0800_0FFC, data: vec![0; 8] }];
+
+        assert_eq!(split_into_sectors(&segments, &bank_sectors()).err(), Some(0x0800_1000));
+    }
+}
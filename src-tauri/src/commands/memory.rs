@@ -1,13 +1,23 @@
+use crate::address;
 use crate::error::{AppError, AppResult};
-use crate::state::AppState;
-use probe_rs::MemoryInterface;
+use crate::firmware::symbols::{self, ElfSymbol, SymbolType};
+use crate::state::{AccessWidth, AppState, CoreOperation, MemoryWatch, MemoryWatchState};
+use probe_rs::{MemoryInterface, RegisterId};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Debug, Deserialize)]
 pub struct ReadMemoryOptions {
     pub address: u64,
     pub size: u32,
+    /// 操作的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
+    /// 读取期间暂停内核以获得一致的快照，读取后恢复运行（默认 false，不打断目标运行）
+    #[serde(default)]
+    pub halt_during_read: bool,
 }
 
 #[tauri::command]
@@ -15,24 +25,138 @@ pub async fn read_memory(
     options: ReadMemoryOptions,
     state: State<'_, AppState>,
 ) -> AppResult<Vec<u8>> {
-    let mut session_guard = state.session.lock();
+    read_memory_with(&options, &state, |core, address, data| core.read_8(address, data))
+}
+
+/// 快速读取任意地址与长度的内存：首尾未对齐部分按字节读取，中间对齐部分按 32 位字读取
+#[tauri::command]
+pub async fn read_memory_fast(
+    options: ReadMemoryOptions,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<u8>> {
+    read_memory_with(&options, &state, read_unaligned)
+}
+
+/// 按选项打开内核（可选暂停）并用指定方式读取
+fn read_memory_with<F>(options: &ReadMemoryOptions, state: &AppState, read: F) -> AppResult<Vec<u8>>
+where
+    F: FnOnce(&mut probe_rs::Core<'_>, u64, &mut [u8]) -> Result<(), probe_rs::Error>,
+{
+    let mut session_guard = state.lock_session();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
-    let mut core = session.core(0).map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    // 整个暂停-读取-恢复过程都持有 session 锁，其他轮询任务不会观察到这次临时暂停
+    let resume = if options.halt_during_read {
+        let halted = core.core_halted().map_err(|e| AppError::MemoryError(e.to_string()))?;
+        if !halted {
+            core.halt(state.core_timeout(CoreOperation::Halt))
+                .map_err(|e| AppError::MemoryError(format!("暂停内核失败: {}", e)))?;
+        }
+        !halted
+    } else {
+        false
+    };
 
     let mut data = vec![0u8; options.size as usize];
-    core.read_8(options.address, &mut data)
-        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let result = read(&mut core, options.address, &mut data);
+
+    if resume {
+        core.run().map_err(|e| AppError::MemoryError(format!("恢复内核运行失败: {}", e)))?;
+    }
+
+    result.map_err(|e| AppError::MemoryError(e.to_string()))?;
 
     Ok(data)
 }
 
+/// 将 [address, address+len) 拆分为 (首部字节数, 中间 32 位字数, 尾部字节数)
+pub fn split_word_aligned(address: u64, len: usize) -> (usize, usize, usize) {
+    let head = ((4 - (address % 4) as usize) % 4).min(len);
+    let words = (len - head) / 4;
+    let tail = len - head - words * 4;
+    (head, words, tail)
+}
+
+/// 读取任意地址的数据，避免对未对齐地址发起字访问
+pub(crate) fn read_unaligned(
+    core: &mut probe_rs::Core<'_>,
+    address: u64,
+    data: &mut [u8],
+) -> Result<(), probe_rs::Error> {
+    let (head, words, tail) = split_word_aligned(address, data.len());
+
+    if head > 0 {
+        core.read_8(address, &mut data[..head])?;
+    }
+
+    if words > 0 {
+        let mut buffer = vec![0u32; words];
+        core.read_32(address + head as u64, &mut buffer)?;
+        for (chunk, word) in data[head..head + words * 4].chunks_exact_mut(4).zip(&buffer) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    if tail > 0 {
+        let offset = head + words * 4;
+        core.read_8(address + offset as u64, &mut data[offset..])?;
+    }
+
+    Ok(())
+}
+
+/// 内存读取性能对比结果
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryReadBenchmark {
+    pub size: u32,
+    /// 逐字节读取耗时 (毫秒)
+    pub byte_read_ms: f64,
+    /// 字对齐快速读取耗时 (毫秒)
+    pub fast_read_ms: f64,
+    /// 两种方式读取结果是否一致
+    pub identical: bool,
+}
+
+/// 对比逐字节读取与快速读取的耗时（同一区域各读一次）
+#[tauri::command]
+pub async fn benchmark_memory_read(
+    options: ReadMemoryOptions,
+    state: State<'_, AppState>,
+) -> AppResult<MemoryReadBenchmark> {
+    let start = std::time::Instant::now();
+    let slow = read_memory_with(&options, &state, |core, address, data| core.read_8(address, data))?;
+    let byte_read_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let start = std::time::Instant::now();
+    let fast = read_memory_with(&options, &state, read_unaligned)?;
+    let fast_read_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    log::info!(
+        "内存读取对比 {} 字节: 逐字节 {:.1} ms, 快速 {:.1} ms",
+        options.size, byte_read_ms, fast_read_ms
+    );
+
+    Ok(MemoryReadBenchmark {
+        size: options.size,
+        byte_read_ms,
+        fast_read_ms,
+        identical: slow == fast,
+    })
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WriteMemoryOptions {
     pub address: u64,
     pub data: Vec<u8>,
+    /// 操作的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
 }
 
 #[tauri::command]
@@ -40,12 +164,14 @@ pub async fn write_memory(
     options: WriteMemoryOptions,
     state: State<'_, AppState>,
 ) -> AppResult<()> {
-    let mut session_guard = state.session.lock();
+    let mut session_guard = state.lock_session();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
-    let mut core = session.core(0).map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
 
     core.write_8(options.address, &options.data)
         .map_err(|e| AppError::MemoryError(e.to_string()))?;
@@ -53,6 +179,480 @@ pub async fn write_memory(
     Ok(())
 }
 
+/// 字节序（目标内存按小端存储，Big 表示按大端解释数值）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// 在目标原生值与用户值之间转换（转换是对称的）
+    pub fn convert(self, value: u64, width: AccessWidth) -> u64 {
+        match self {
+            Endianness::Little => value,
+            Endianness::Big => match width {
+                AccessWidth::U16 => (value as u16).swap_bytes() as u64,
+                AccessWidth::U32 => (value as u32).swap_bytes() as u64,
+                AccessWidth::U64 => value.swap_bytes(),
+            },
+        }
+    }
+}
+
+/// 检查地址是否按访问宽度对齐
+fn check_alignment(address: u64, width: AccessWidth) -> AppResult<()> {
+    if !address.is_multiple_of(width.bytes()) {
+        return Err(AppError::MemoryError(format!(
+            "地址 0x{:08X} 未按 {} 字节对齐",
+            address,
+            width.bytes()
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadMemoryWordOptions {
+    pub address: u64,
+    pub width: AccessWidth,
+    #[serde(default)]
+    pub endianness: Endianness,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// 校验并规范化用户输入的地址（0x 十六进制、十进制，允许 _ 分隔，不超过 2^53 - 1）
+#[tauri::command]
+pub async fn parse_address(input: String) -> AppResult<u64> {
+    address::parse_frontend_address(&input).map_err(AppError::MemoryError)
+}
+
+/// 按字宽读取内存（用于外设寄存器访问）
+#[tauri::command]
+pub async fn read_memory_word(
+    options: ReadMemoryWordOptions,
+    state: State<'_, AppState>,
+) -> AppResult<u64> {
+    check_alignment(options.address, options.width)?;
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let value = match options.width {
+        AccessWidth::U16 => core.read_word_16(options.address).map(|v| v as u64),
+        AccessWidth::U32 => core.read_word_32(options.address).map(|v| v as u64),
+        AccessWidth::U64 => core.read_word_64(options.address),
+    }
+    .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    Ok(options.endianness.convert(value, options.width))
+}
+
+/// 单次 64 位宽读取的最大字数（64 KB）
+const READ_64_LIMIT_WORDS: u32 = 64 * 1024 / 8;
+
+#[derive(Debug, Deserialize)]
+pub struct ReadMemory64Options {
+    pub address: u64,
+    /// 读取的 64 位字数
+    pub count: u32,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// 64 位宽读取结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Memory64Read {
+    pub words: Vec<u64>,
+    /// 实际使用的访问宽度
+    pub access_width: AccessWidth,
+    /// 目标不支持原生 64 位访问，改用两次 32 位读取拼接
+    pub fallback: bool,
+}
+
+/// 按小端顺序将成对的 32 位字拼接为 64 位字（低地址为低 32 位）
+pub fn combine_words_32(words: &[u32]) -> Vec<u64> {
+    words
+        .chunks_exact(2)
+        .map(|pair| (pair[0] as u64) | ((pair[1] as u64) << 32))
+        .collect()
+}
+
+/// 按 64 位宽读取内存，目标不支持原生 64 位访问时退回 32 位读取并标记
+#[tauri::command]
+pub async fn read_memory_64(
+    options: ReadMemory64Options,
+    state: State<'_, AppState>,
+) -> AppResult<Memory64Read> {
+    check_alignment(options.address, AccessWidth::U64)?;
+    if options.count > READ_64_LIMIT_WORDS {
+        return Err(AppError::MemoryError(format!(
+            "读取 {} 个 64 位字超过单次读取上限 {} 个",
+            options.count, READ_64_LIMIT_WORDS
+        )));
+    }
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let count = options.count as usize;
+    let result = if core.supports_native_64bit_access() {
+        let mut words = vec![0u64; count];
+        core.read_64(options.address, &mut words).map(|_| Memory64Read {
+            words,
+            access_width: AccessWidth::U64,
+            fallback: false,
+        })
+    } else {
+        log::debug!("目标不支持原生 64 位访问，使用 32 位读取 0x{:08X}", options.address);
+        let mut halves = vec![0u32; count * 2];
+        core.read_32(options.address, &mut halves).map(|_| Memory64Read {
+            words: combine_words_32(&halves),
+            access_width: AccessWidth::U32,
+            fallback: true,
+        })
+    };
+
+    result.map_err(|e| AppError::MemoryError(e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WriteMemoryWordOptions {
+    pub address: u64,
+    pub value: u64,
+    pub width: AccessWidth,
+    #[serde(default)]
+    pub endianness: Endianness,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// 按字宽写入内存（用于外设寄存器访问）
+#[tauri::command]
+pub async fn write_memory_word(
+    options: WriteMemoryWordOptions,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    check_alignment(options.address, options.width)?;
+
+    if options.value > options.width.max_value() {
+        return Err(AppError::MemoryError(format!(
+            "数值 0x{:X} 超出 {} 字节宽度范围",
+            options.value,
+            options.width.bytes()
+        )));
+    }
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(options.core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let value = options.endianness.convert(options.value, options.width);
+    match options.width {
+        AccessWidth::U16 => core.write_word_16(options.address, value as u16),
+        AccessWidth::U32 => core.write_word_32(options.address, value as u32),
+        AccessWidth::U64 => core.write_word_64(options.address, value),
+    }
+    .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// 测试某个字地址是否为可写 RAM：写入原值取反、读回比较，然后恢复原值
+/// 读写过程中出现总线错误视为不可写
+pub(crate) fn ram_word_writable(core: &mut probe_rs::Core<'_>, address: u64) -> bool {
+    let original = match core.read_word_32(address) {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+
+    let pattern = !original;
+    if core.write_word_32(address, pattern).is_err() {
+        return false;
+    }
+    let readback = core.read_word_32(address);
+
+    if let Err(e) = core.write_word_32(address, original) {
+        log::warn!("恢复地址 0x{:08X} 的原值失败: {}", address, e);
+    }
+
+    matches!(readback, Ok(value) if value == pattern)
+}
+
+/// 探测指定地址的 RAM 是否实际存在且可写（原值会被恢复）
+#[tauri::command]
+pub async fn probe_ram_writable(
+    address: u64,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<bool> {
+    check_alignment(address, AccessWidth::U32)?;
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let writable = ram_word_writable(&mut core, address);
+    log::info!("RAM 地址 0x{:08X} {}", address, if writable { "可写" } else { "不可写" });
+
+    Ok(writable)
+}
+
+/// 单个监视项的当前值
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryWatchValue {
+    pub address: u64,
+    pub width: AccessWidth,
+    pub label: Option<String>,
+    pub value: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 逐项读取监视列表，单项失败不影响其他项
+fn read_watch_values(core: &mut probe_rs::Core<'_>, watches: &[MemoryWatch]) -> Vec<MemoryWatchValue> {
+    watches
+        .iter()
+        .map(|watch| {
+            let result = match watch.width {
+                AccessWidth::U16 => core.read_word_16(watch.address).map(|v| v as u64),
+                AccessWidth::U32 => core.read_word_32(watch.address).map(|v| v as u64),
+                AccessWidth::U64 => core.read_word_64(watch.address),
+            };
+            let (value, error) = match result {
+                Ok(value) => (Some(value), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            MemoryWatchValue {
+                address: watch.address,
+                width: watch.width,
+                label: watch.label.clone(),
+                value,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// 内存监视事件 (发送到前端)
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryWatchEvent {
+    pub values: Vec<MemoryWatchValue>,
+    pub timestamp: u64,
+}
+
+/// 设置内存监视列表并启动后台刷新（列表为空时停止）
+#[tauri::command]
+pub async fn set_memory_watches(
+    watches: Vec<MemoryWatch>,
+    interval_ms: Option<u64>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> AppResult<()> {
+    for watch in &watches {
+        check_alignment(watch.address, watch.width)?;
+    }
+
+    let watch_state = Arc::clone(&state.memory_watch_state);
+
+    if watches.is_empty() {
+        watch_state.watches.lock().clear();
+        watch_state.set_running(false);
+        return Ok(());
+    }
+
+    *watch_state.watches.lock() = watches;
+    if let Some(ms) = interval_ms {
+        *watch_state.poll_interval_ms.lock() = ms.max(10);
+    }
+
+    if watch_state.is_running() {
+        return Ok(());
+    }
+    let generation = watch_state.start();
+
+    let session_arc = Arc::clone(&state.session);
+    let active_core = Arc::clone(&state.active_core);
+    let flash_in_progress = Arc::clone(&state.flash_in_progress);
+    tokio::spawn(async move {
+        log::info!("内存监视任务已启动");
+        memory_watch_task(watch_state, generation, session_arc, active_core, flash_in_progress, app_handle, "memory-watch", true).await;
+        log::info!("内存监视任务已结束");
+    });
+
+    Ok(())
+}
+
+/// 一次性读取监视列表中所有地址的当前值
+#[tauri::command]
+pub async fn read_watch_list(
+    entries: Vec<MemoryWatch>,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<MemoryWatchValue>> {
+    for entry in &entries {
+        check_alignment(entry.address, entry.width)?;
+    }
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    Ok(read_watch_values(&mut core, &entries))
+}
+
+/// 启动监视列表轮询，按间隔发送 watch-update 事件（内核运行时也读取）
+#[tauri::command]
+pub async fn start_watch_poll(
+    entries: Vec<MemoryWatch>,
+    interval_ms: Option<u64>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> AppResult<()> {
+    for entry in &entries {
+        check_alignment(entry.address, entry.width)?;
+    }
+    if entries.is_empty() {
+        return Err(AppError::MemoryError("监视列表为空".to_string()));
+    }
+
+    let watch_state = Arc::clone(&state.watch_poll_state);
+    *watch_state.watches.lock() = entries;
+    *watch_state.poll_interval_ms.lock() = interval_ms.unwrap_or(200).max(10);
+
+    if watch_state.is_running() {
+        return Ok(());
+    }
+    let generation = watch_state.start();
+
+    let session_arc = Arc::clone(&state.session);
+    let active_core = Arc::clone(&state.active_core);
+    let flash_in_progress = Arc::clone(&state.flash_in_progress);
+    tokio::spawn(async move {
+        log::info!("监视列表轮询已启动");
+        memory_watch_task(watch_state, generation, session_arc, active_core, flash_in_progress, app_handle, "watch-update", false).await;
+        log::info!("监视列表轮询已结束");
+    });
+
+    Ok(())
+}
+
+/// 停止监视列表轮询
+#[tauri::command]
+pub async fn stop_watch_poll(state: State<'_, AppState>) -> AppResult<()> {
+    state.watch_poll_state.set_running(false);
+    Ok(())
+}
+
+/// 内存监视轮询任务，连接断开、被停止或被新启动的任务取代时退出
+/// require_halted 为 true 时仅在内核暂停时读取，内核开始运行即停止监视
+/// 每次刷新前重新读取间隔，修改间隔无需重启任务
+#[allow(clippy::too_many_arguments)]
+async fn memory_watch_task(
+    watch_state: Arc<MemoryWatchState>,
+    generation: u64,
+    session: Arc<parking_lot::Mutex<Option<probe_rs::Session>>>,
+    active_core: Arc<parking_lot::Mutex<usize>>,
+    flash_in_progress: Arc<std::sync::atomic::AtomicUsize>,
+    app_handle: AppHandle,
+    event: &'static str,
+    require_halted: bool,
+) {
+    loop {
+        let poll_interval_ms = *watch_state.poll_interval_ms.lock();
+        tokio::time::sleep(Duration::from_millis(poll_interval_ms)).await;
+
+        if !watch_state.is_current(generation) {
+            break;
+        }
+
+        // 烧录/擦除期间让出 session
+        if flash_in_progress.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+            continue;
+        }
+
+        let watches = watch_state.watches.lock().clone();
+
+        let values = {
+            let mut session_guard = match session.try_lock_for(Duration::from_millis(500)) {
+                Some(guard) => guard,
+                None => continue,
+            };
+
+            let session = match session_guard.as_mut() {
+                Some(s) => s,
+                None => {
+                    log::info!("Session 已断开，停止内存监视");
+                    break;
+                }
+            };
+
+            let mut core = match session.core(*active_core.lock()) {
+                Ok(c) => c,
+                Err(e) => {
+                    log::warn!("内存监视获取 core 失败: {}", e);
+                    break;
+                }
+            };
+
+            if require_halted {
+                match core.core_halted() {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        log::info!("内核已运行，停止内存监视");
+                        break;
+                    }
+                    Err(e) => {
+                        log::warn!("检查 core 状态失败: {}", e);
+                        break;
+                    }
+                }
+            }
+
+            read_watch_values(&mut core, &watches)
+        };
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        if let Err(e) = app_handle.emit(event, MemoryWatchEvent { values, timestamp }) {
+            log::error!("发送内存监视事件失败: {}", e);
+        }
+    }
+
+    watch_state.finish(generation);
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterValue {
     pub name: String,
@@ -60,14 +660,24 @@ pub struct RegisterValue {
 }
 
 #[tauri::command]
-pub async fn read_registers(state: State<'_, AppState>) -> AppResult<Vec<RegisterValue>> {
-    let mut session_guard = state.session.lock();
+pub async fn read_registers(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<RegisterValue>> {
+    let mut session_guard = state.lock_session();
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
 
-    let mut core = session.core(0).map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    Ok(collect_registers(&mut core))
+}
 
+/// 读取 PC、通用寄存器与参数寄存器
+fn collect_registers(core: &mut probe_rs::Core<'_>) -> Vec<RegisterValue> {
     // 获取目标架构的寄存器描述
     let register_file = core.registers();
 
@@ -107,5 +717,438 @@ pub async fn read_registers(state: State<'_, AppState>) -> AppResult<Vec<Registe
         }
     }
 
-    Ok(registers)
+    registers
+}
+
+/// 寄存器快照导出格式
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RegisterDumpFormat {
+    /// JSON（含时间戳与目标名称）
+    Json,
+    /// 类似 GDB `info registers` 的文本格式
+    Gdb,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RegisterDump {
+    timestamp: String,
+    target: Option<String>,
+    core_index: usize,
+    registers: Vec<RegisterValue>,
+}
+
+/// 导出完整寄存器快照（核心寄存器、PC、SP、LR、xPSR）到文件
+#[tauri::command]
+pub async fn dump_registers_to_file(
+    path: String,
+    format: RegisterDumpFormat,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let target = state.connection_info.lock().as_ref().map(|info| info.target_name.clone());
+    let core_index = state.core_index(core_index);
+
+    let registers = {
+        let mut session_guard = state.lock_session();
+        let session = session_guard
+            .as_mut()
+            .ok_or(AppError::NotConnected)?;
+
+        let mut core = session
+            .core(core_index)
+            .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+        let mut registers = collect_registers(&mut core);
+
+        // 补充 SP、LR、xPSR（部分架构的核心寄存器列表中不包含）
+        let mut extra = vec![
+            ("SP", Some(core.stack_pointer().id())),
+            ("LR", Some(core.return_address().id())),
+        ];
+        if matches!(
+            core.core_type(),
+            probe_rs::CoreType::Armv6m
+                | probe_rs::CoreType::Armv7m
+                | probe_rs::CoreType::Armv7em
+                | probe_rs::CoreType::Armv8m
+        ) {
+            extra.push(("XPSR", Some(RegisterId(REG_XPSR))));
+        }
+        for (name, id) in extra {
+            let Some(id) = id else { continue };
+            if registers.iter().any(|r| r.name.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            if let Ok(value) = core.read_core_reg::<u64>(id) {
+                registers.push(RegisterValue { name: name.to_string(), value });
+            }
+        }
+        registers
+    };
+
+    let timestamp = chrono::Local::now().to_rfc3339();
+    let content = match format {
+        RegisterDumpFormat::Json => serde_json::to_string_pretty(&RegisterDump {
+            timestamp,
+            target,
+            core_index,
+            registers,
+        })
+        .map_err(|e| AppError::FileError(format!("序列化寄存器快照失败: {}", e)))?,
+        RegisterDumpFormat::Gdb => {
+            let mut text = format!(
+                "# target: {}\n# core: {}\n# time: {}\n",
+                target.as_deref().unwrap_or("unknown"),
+                core_index,
+                timestamp
+            );
+            for reg in &registers {
+                text.push_str(&format!("{:<15}0x{:<17x} {}\n", reg.name.to_lowercase(), reg.value, reg.value));
+            }
+            text
+        }
+    };
+
+    std::fs::write(&path, content)?;
+    log::info!("寄存器快照已导出到 {}", path);
+
+    Ok(())
+}
+
+/// Cortex-M DCRSR 寄存器编号
+const REG_LR: u16 = 14;
+const REG_XPSR: u16 = 16;
+const REG_MSP: u16 = 17;
+const REG_PSP: u16 = 18;
+
+/// 异常栈帧（硬件自动压栈的寄存器）
+#[derive(Debug, Clone, Serialize)]
+pub struct ExceptionFrame {
+    /// 当前异常号 (IPSR)
+    pub exception_number: u32,
+    /// LR 中的 EXC_RETURN 值
+    pub exc_return: u32,
+    /// 压栈使用的栈指针: "MSP" | "PSP"
+    pub stack_pointer: String,
+    /// 栈帧地址
+    pub frame_address: u64,
+    /// 是否为包含 FPU 寄存器的扩展栈帧
+    pub extended_frame: bool,
+    pub r0: u32,
+    pub r1: u32,
+    pub r2: u32,
+    pub r3: u32,
+    pub r12: u32,
+    pub lr: u32,
+    pub pc: u32,
+    pub xpsr: u32,
+}
+
+/// 读取异常栈帧，还原进入异常前的寄存器状态
+/// 要求内核已暂停且处于异常处理程序中
+#[tauri::command]
+pub async fn read_exception_frame(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<ExceptionFrame> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let halted = core.core_halted().map_err(|e| AppError::MemoryError(e.to_string()))?;
+    if !halted {
+        return Err(AppError::MemoryError("内核未暂停，请先暂停目标".to_string()));
+    }
+
+    let xpsr: u32 = core
+        .read_core_reg(RegisterId(REG_XPSR))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let exception_number = xpsr & 0x1FF;
+    if exception_number == 0 {
+        return Err(AppError::MemoryError("内核当前不在异常上下文中 (IPSR = 0)".to_string()));
+    }
+
+    let exc_return: u32 = core
+        .read_core_reg(RegisterId(REG_LR))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+    if exc_return & 0xFF00_0000 != 0xFF00_0000 {
+        return Err(AppError::MemoryError(format!(
+            "LR (0x{:08X}) 不是有效的 EXC_RETURN 值，可能已被处理程序覆盖",
+            exc_return
+        )));
+    }
+
+    // EXC_RETURN bit2: 0 = MSP, 1 = PSP
+    let use_psp = exc_return & 0x4 != 0;
+    let sp_reg = if use_psp { REG_PSP } else { REG_MSP };
+    let sp: u32 = core
+        .read_core_reg(RegisterId(sp_reg))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let mut frame = [0u32; 8];
+    core.read_32(sp as u64, &mut frame)
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    Ok(ExceptionFrame {
+        exception_number,
+        exc_return,
+        stack_pointer: if use_psp { "PSP" } else { "MSP" }.to_string(),
+        frame_address: sp as u64,
+        // EXC_RETURN bit4: 0 = 扩展栈帧 (包含 FPU 寄存器)
+        extended_frame: exc_return & 0x10 == 0,
+        r0: frame[0],
+        r1: frame[1],
+        r2: frame[2],
+        r3: frame[3],
+        r12: frame[4],
+        lr: frame[5],
+        pc: frame[6],
+        xpsr: frame[7],
+    })
+}
+
+/// 调试异常与监视控制寄存器（TRCENA 打开 DWT/ITM）
+const DEMCR: u64 = 0xE000_EDFC;
+const DEMCR_TRCENA: u32 = 1 << 24;
+/// DWT 控制寄存器
+const DWT_CTRL: u64 = 0xE000_1000;
+const DWT_CTRL_CYCCNTENA: u32 = 1 << 0;
+const DWT_CTRL_NOCYCCNT: u32 = 1 << 25;
+/// DWT 周期计数器
+const DWT_CYCCNT: u64 = 0xE000_1004;
+
+/// DWT 周期计数器读数
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleCounter {
+    /// 内核是否支持 DWT 周期计数器
+    pub supported: bool,
+    /// 当前 CYCCNT 值（32 位，会回绕）；不支持时为 None
+    pub cycles: Option<u32>,
+    /// 本次调用是否打开了之前未开启的计数器
+    pub enabled_now: bool,
+    pub core_type: String,
+    /// 不支持时的原因
+    pub reason: Option<String>,
+}
+
+/// 内核架构是否可能带有 DWT 周期计数器（ARMv6-M 如 Cortex-M0/M0+ 没有）
+pub fn core_has_cycle_counter(core_type: probe_rs::CoreType) -> bool {
+    use probe_rs::CoreType;
+    matches!(core_type, CoreType::Armv7m | CoreType::Armv7em | CoreType::Armv8m)
+}
+
+/// DWT_CTRL 是否表明实现了周期计数器（NOCYCCNT 位为 0）
+pub fn dwt_has_cycle_counter(dwt_ctrl: u32) -> bool {
+    dwt_ctrl & DWT_CTRL_NOCYCCNT == 0
+}
+
+/// 检查并按需打开周期计数器（DEMCR.TRCENA、DWT_CTRL.CYCCNTENA）
+fn prepare_cycle_counter(core: &mut probe_rs::Core<'_>) -> AppResult<CycleCounter> {
+    let core_type = core.core_type();
+    let mut counter = CycleCounter {
+        supported: false,
+        cycles: None,
+        enabled_now: false,
+        core_type: format!("{:?}", core_type),
+        reason: None,
+    };
+    if !core_has_cycle_counter(core_type) {
+        counter.reason = Some(format!("{:?} 内核没有 DWT 周期计数器", core_type));
+        return Ok(counter);
+    }
+
+    let demcr = core.read_word_32(DEMCR).map_err(|e| AppError::MemoryError(e.to_string()))?;
+    if demcr & DEMCR_TRCENA == 0 {
+        core.write_word_32(DEMCR, demcr | DEMCR_TRCENA)
+            .map_err(|e| AppError::MemoryError(format!("开启 DEMCR.TRCENA 失败: {}", e)))?;
+    }
+
+    let ctrl = core.read_word_32(DWT_CTRL).map_err(|e| AppError::MemoryError(e.to_string()))?;
+    if !dwt_has_cycle_counter(ctrl) {
+        counter.reason = Some("DWT 未实现周期计数器 (DWT_CTRL.NOCYCCNT = 1)".to_string());
+        return Ok(counter);
+    }
+
+    if ctrl & DWT_CTRL_CYCCNTENA == 0 {
+        core.write_word_32(DWT_CTRL, ctrl | DWT_CTRL_CYCCNTENA)
+            .map_err(|e| AppError::MemoryError(format!("开启 DWT_CTRL.CYCCNTENA 失败: {}", e)))?;
+        let readback = core.read_word_32(DWT_CTRL).map_err(|e| AppError::MemoryError(e.to_string()))?;
+        if readback & DWT_CTRL_CYCCNTENA == 0 {
+            counter.reason = Some("写入后 CYCCNTENA 仍为 0，计数器不可用".to_string());
+            return Ok(counter);
+        }
+        log::info!("已开启 DWT 周期计数器");
+        counter.enabled_now = true;
+    }
+
+    counter.supported = true;
+    Ok(counter)
+}
+
+/// 读取 DWT 周期计数器 (CYCCNT)，未开启时自动开启
+/// 两次暂停之间读数相减即为代码段消耗的 CPU 周期（注意 32 位回绕）
+#[tauri::command]
+pub async fn read_cycle_counter(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<CycleCounter> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let mut counter = prepare_cycle_counter(&mut core)?;
+    if counter.supported {
+        counter.cycles = Some(
+            core.read_word_32(DWT_CYCCNT)
+                .map_err(|e| AppError::MemoryError(e.to_string()))?,
+        );
+    }
+    Ok(counter)
+}
+
+/// 将 DWT 周期计数器清零，未开启时自动开启
+#[tauri::command]
+pub async fn reset_cycle_counter(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<CycleCounter> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard
+        .as_mut()
+        .ok_or(AppError::NotConnected)?;
+
+    let mut core = session
+        .core(state.core_index(core_index))
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+
+    let mut counter = prepare_cycle_counter(&mut core)?;
+    if !counter.supported {
+        return Err(AppError::MemoryError(
+            counter.reason.unwrap_or_else(|| "不支持 DWT 周期计数器".to_string()),
+        ));
+    }
+    core.write_word_32(DWT_CYCCNT, 0)
+        .map_err(|e| AppError::MemoryError(format!("清零 CYCCNT 失败: {}", e)))?;
+    counter.cycles = Some(0);
+    Ok(counter)
+}
+
+/// 单次按符号读取的最大字节数
+const SYMBOL_READ_LIMIT: u64 = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ReadSymbolOptions {
+    /// 带符号表的固件 ELF
+    pub elf_path: String,
+    pub symbol: String,
+    /// 类型提示，默认按符号大小选择 u8/u16/u32/u64，其他大小按字节数组
+    #[serde(default)]
+    pub type_hint: Option<SymbolType>,
+    #[serde(default)]
+    pub core_index: Option<usize>,
+    #[serde(default)]
+    pub halt_during_read: bool,
+}
+
+/// 符号读取结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolValue {
+    pub symbol: ElfSymbol,
+    /// 实际读取的字节数（符号大小为 0 时按类型提示的元素大小读取）
+    pub size: u64,
+    pub type_hint: SymbolType,
+    pub bytes: Vec<u8>,
+    /// 按类型提示格式化的值，数组时每个元素一项
+    pub values: Vec<String>,
+}
+
+/// 未指定类型提示时按符号大小推断
+fn default_symbol_type(size: u64) -> SymbolType {
+    match size {
+        1 => SymbolType::U8,
+        2 => SymbolType::U16,
+        4 => SymbolType::U32,
+        8 => SymbolType::U64,
+        _ => SymbolType::Array,
+    }
+}
+
+/// 按名称读取全局变量：从 ELF 符号表解析地址与大小后读取目标内存
+#[tauri::command]
+pub async fn read_symbol(
+    options: ReadSymbolOptions,
+    state: State<'_, AppState>,
+) -> AppResult<SymbolValue> {
+    let data = std::fs::read(&options.elf_path)?;
+    let symbol = symbols::find_symbol(&data, &options.symbol)?;
+
+    let type_hint = options.type_hint.unwrap_or_else(|| default_symbol_type(symbol.size));
+    let size = if symbol.size > 0 { symbol.size } else { type_hint.element_size() as u64 };
+    if size > SYMBOL_READ_LIMIT {
+        return Err(AppError::MemoryError(format!(
+            "符号 {} 大小为 {} 字节，超过单次读取上限 {} 字节",
+            symbol.name, size, SYMBOL_READ_LIMIT
+        )));
+    }
+
+    let read_options = ReadMemoryOptions {
+        address: symbol.address,
+        size: size as u32,
+        core_index: options.core_index,
+        halt_during_read: options.halt_during_read,
+    };
+    let bytes = read_memory_with(&read_options, &state, read_unaligned)?;
+    log::info!("读取符号 {} @ 0x{:08X} ({} 字节)", symbol.name, symbol.address, size);
+
+    Ok(SymbolValue {
+        values: symbols::format_symbol_values(&bytes, type_hint),
+        symbol,
+        size,
+        type_hint,
+        bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 64 位宽读取回退拼接测试
+    #[test]
+    fn test_low_word_first() {
+        let words = combine_words_32(&[0x89AB_CDEF, 0x0123_4567, 0xFFFF_FFFF, 0x0000_0000]);
+        assert_eq!(words, vec![0x0123_4567_89AB_CDEF, 0x0000_0000_FFFF_FFFF]);
+    }
+
+    #[test]
+    fn test_odd_trailing_word_ignored() {
+        assert_eq!(combine_words_32(&[1, 2, 3]), vec![0x0000_0002_0000_0001]);
+        assert!(combine_words_32(&[]).is_empty());
+    }
+
+    // 非对齐内存读取拆分测试
+    #[test]
+    fn test_unaligned_head_and_tail() {
+        // 0x1001..0x100B: 3 字节首部 + 1 个字 + 3 字节尾部
+        assert_eq!(split_word_aligned(0x1001, 10), (3, 1, 3));
+        assert_eq!(split_word_aligned(0x2000_0000, 9), (0, 2, 1));
+    }
+
+    #[test]
+    fn test_short_reads_stay_within_length() {
+        // 长度小于到下一个对齐边界的距离时，全部按字节读取
+        assert_eq!(split_word_aligned(0x1001, 2), (2, 0, 0));
+        assert_eq!(split_word_aligned(0x1003, 0), (0, 0, 0));
+    }
 }
@@ -1,6 +1,9 @@
 pub mod config;
+pub mod control;
 pub mod flash;
 pub mod memory;
 pub mod probe;
+pub mod production;
 pub mod rtt;
 pub mod serial;
+pub mod swo;
@@ -1,13 +1,20 @@
 use crate::commands::config::TARGET_REGISTRY;
+use crate::commands::flash::{flash_log, EventSink};
 use crate::error::{AppError, AppResult};
-use crate::state::{AppState, ConnectionInfo, ConnectMode, InterfaceType};
+use crate::state::{AppState, ConnectionInfo, ConnectMode, CoreOperation, InterfaceType, SessionGuard, CORE_OPERATION_TIMEOUT_RANGE_MS};
 use probe_rs::{
-    architecture::arm::dp::{DpAddress, DpRegisterAddress},
+    architecture::arm::{
+        dp::{DpAddress, DpRegisterAddress},
+        FullyQualifiedApAddress,
+    },
+    config::{Registry, Target, TargetSelector},
     probe::{list::Lister, WireProtocol},
     MemoryInterface, Permissions, Session,
 };
+use probe_rs_target::{ApAddress, CoreAccessOptions};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProbeInfo {
@@ -19,6 +26,15 @@ pub struct ProbeInfo {
     pub probe_type: String,
     pub dap_version: Option<String>,
     pub debug_info: Option<String>,  // 诊断信息
+    /// 同名探针中的枚举序号，用于区分多个无序列号的相同探针
+    #[serde(default)]
+    pub probe_index: usize,
+    /// 存在 VID/PID 相同且序列号相同（或都为空）的其他探针，按序列号无法区分
+    #[serde(default)]
+    pub serial_conflict: bool,
+    /// USB 物理位置 "总线-地址"，用于区分序列号冲突的探针
+    #[serde(default)]
+    pub usb_location: Option<String>,
 }
 
 /// USB 设备诊断信息
@@ -50,6 +66,12 @@ pub struct TargetInfo {
     pub memory_regions: Vec<MemoryRegion>,
     pub flash_algorithms: Vec<String>,
     pub chip_id: Option<u32>,
+    /// 调试时钟高于目标内核时钟的安全比例时给出的建议
+    #[serde(default)]
+    pub clock_recommendation: Option<ClockRecommendation>,
+    /// 芯片上次复位的原因（按 RCC_CSR 等复位标志寄存器解码）
+    #[serde(default)]
+    pub reset_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +89,8 @@ struct CmsisDapCaps {
     serial_number: Option<String>,
     has_hid: bool,
     has_v2: bool,
+    hid_interface: Option<u8>,
+    v2_interface: Option<u8>,
     debug_info: String,  // 诊断信息
 }
 
@@ -104,6 +128,8 @@ fn collect_cmsis_dap_caps() -> Vec<CmsisDapCaps> {
 
         let mut has_hid = false;
         let mut has_v2 = false;
+        let mut hid_interface = None;
+        let mut v2_interface = None;
 
         for iface in device.interfaces() {
             let iface_num = iface.interface_number();
@@ -122,9 +148,11 @@ fn collect_cmsis_dap_caps() -> Vec<CmsisDapCaps> {
             if iface_class == 0x03 && (iface_is_cmsis || product_is_cmsis) {
                 debug_lines.push(format!("  -> HID interface (DAPv1)"));
                 has_hid = true;
+                hid_interface.get_or_insert(iface_num);
             } else if iface_class == 0xFF && (iface_is_cmsis || product_is_cmsis) {
                 debug_lines.push(format!("  -> Vendor Specific (potential DAPv2)"));
                 has_v2 = true;
+                v2_interface.get_or_insert(iface_num);
             }
         }
 
@@ -137,6 +165,8 @@ fn collect_cmsis_dap_caps() -> Vec<CmsisDapCaps> {
                 serial_number: device.serial_number().map(|s| s.to_string()).filter(|s| !s.is_empty()),
                 has_hid,
                 has_v2,
+                hid_interface,
+                v2_interface,
                 debug_info: debug_lines.join("\n"),
             });
         }
@@ -176,6 +206,76 @@ fn match_caps_for_probe<'a>(
     None
 }
 
+/// 探针 USB 标识 (VID, PID, 序列号)
+type UsbProbeKey = (u16, u16, Option<String>);
+
+fn normalized_serial(serial: &Option<String>) -> Option<&str> {
+    serial.as_deref().filter(|s| !s.is_empty())
+}
+
+/// 标记 VID/PID 相同且序列号相同（空序列号视为相同）的探针
+pub fn serial_conflicts(keys: &[(u16, u16, Option<String>)]) -> Vec<bool> {
+    keys.iter()
+        .enumerate()
+        .map(|(i, (vid, pid, serial))| {
+            keys.iter().enumerate().any(|(j, (other_vid, other_pid, other_serial))| {
+                i != j
+                    && vid == other_vid
+                    && pid == other_pid
+                    && normalized_serial(serial) == normalized_serial(other_serial)
+            })
+        })
+        .collect()
+}
+
+/// 每个 probe-rs 条目对应第几个同标识 (VID/PID/序列号) 的 USB 设备
+/// 同一 CMSIS-DAP 设备的 HID 与 v2 接口会各列一次，按接口类型分别计数，使两个条目对应到同一设备
+pub fn usb_device_occurrences(keys: &[(u16, u16, Option<String>, bool)]) -> Vec<usize> {
+    keys.iter()
+        .enumerate()
+        .map(|(i, (vid, pid, serial, is_hid))| {
+            keys[..i]
+                .iter()
+                .filter(|(v, p, s, hid)| {
+                    v == vid && p == pid && hid == is_hid && normalized_serial(s) == normalized_serial(serial)
+                })
+                .count()
+        })
+        .collect()
+}
+
+/// 按 VID/PID/序列号将 probe-rs 枚举的探针对应到 nusb 设备，返回 "总线-地址"
+/// list_probes 与 select_probe 都基于 probe-rs 的原始枚举结果调用，保证同一探针得到相同位置；
+/// probe-rs 同样通过 nusb 枚举 USB 设备，标识相同的设备按相同顺序对应
+fn probe_usb_locations(probes: &[probe_rs::probe::DebugProbeInfo]) -> Vec<Option<String>> {
+    let devices: Vec<_> = match nusb::list_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            log::warn!("nusb list_devices failed: {}", e);
+            return vec![None; probes.len()];
+        }
+    };
+
+    let keys: Vec<_> = probes
+        .iter()
+        .map(|p| (p.vendor_id, p.product_id, p.serial_number.clone(), p.is_hid_interface))
+        .collect();
+    keys.iter()
+        .zip(usb_device_occurrences(&keys))
+        .map(|((vid, pid, serial, _), occurrence)| {
+            devices
+                .iter()
+                .filter(|d| {
+                    d.vendor_id() == *vid
+                        && d.product_id() == *pid
+                        && d.serial_number().filter(|s| !s.is_empty()) == normalized_serial(serial)
+                })
+                .nth(occurrence)
+                .map(|d| format!("{}-{}", d.bus_number(), d.device_address()))
+        })
+        .collect()
+}
+
 fn build_probe_id(vendor_id: u16, product_id: u16, serial_number: &Option<String>) -> String {
     let serial = serial_number.as_deref().unwrap_or("");
     format!("{:04x}:{:04x}:{}", vendor_id, product_id, serial)
@@ -207,6 +307,663 @@ fn read_chip_id(session: &mut Session) -> Option<u32> {
     None
 }
 
+/// 按芯片名称前缀匹配的片上 Flash 容量寄存器（16 位，单位 KB）
+const FLASH_SIZE_REGISTERS: &[(&str, u64)] = &[
+    ("STM32F0", 0x1FFFF7CC),
+    ("STM32F1", 0x1FFFF7E0),
+    ("STM32F3", 0x1FFFF7CC),
+    ("STM32F2", 0x1FFF7A22),
+    ("STM32F4", 0x1FFF7A22),
+    ("STM32F7", 0x1FF0F442),
+    ("STM32G0", 0x1FFF75E0),
+    ("STM32G4", 0x1FFF75E0),
+    ("STM32L4", 0x1FFF75E0),
+    ("STM32WB", 0x1FFF75E0),
+    ("STM32L0", 0x1FF8007C),
+    ("STM32H7", 0x1FF1E880),
+    ("STM32WL", 0x1FFF75E0),
+    ("STM32L5", 0x0BFA05E0),
+    ("STM32U5", 0x0BFA07A0),
+    ("STM32H5", 0x08FFF80C),
+    ("GD32F1", 0x1FFFF7E0),
+    ("GD32F3", 0x1FFFF7E0),
+    // FICR INFO.FLASH，取 32 位 KB 值的低半字
+    ("NRF52", 0x10000110),
+];
+
+/// 按芯片名称前缀匹配的片上 RAM 容量寄存器（16 位，单位 KB）
+const RAM_SIZE_REGISTERS: &[(&str, u64)] = &[
+    // FICR INFO.RAM，取 32 位 KB 值的低半字
+    ("NRF52", 0x1000010C),
+];
+
+/// 读取 16 位容量寄存器（单位 KB），擦除值或 0 表示未写入
+fn read_size_register_kb(session: &mut Session, address: u64) -> AppResult<Option<u32>> {
+    let mut core = session.core(0).map_err(|e| AppError::ProbeError(e.to_string()))?;
+    match core.read_word_16(address) {
+        Ok(kb) if kb != 0 && kb != 0xFFFF => Ok(Some(kb as u32)),
+        Ok(_) => Ok(None),
+        Err(e) => {
+            log::warn!("读取容量寄存器 0x{:08X} 失败: {}", address, e);
+            Ok(None)
+        }
+    }
+}
+
+/// 比较目标定义的容量（字节）与芯片上报的容量（KB）
+/// Flash 必须完全一致；目标定义可能拆分或省略 RAM 块，因此 RAM 只在定义大于实际时告警
+pub fn memory_size_warnings(
+    declared_flash: u64,
+    detected_flash_kb: Option<u32>,
+    declared_ram: u64,
+    detected_ram_kb: Option<u32>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if let (Some(detected), true) = (detected_flash_kb, declared_flash > 0) {
+        let detected = detected as u64 * 1024;
+        if detected < declared_flash {
+            warnings.push(format!(
+                "Flash 容量不一致：目标定义 {} KB 大于芯片实际 {} KB，烧录可能超出实际 Flash",
+                declared_flash / 1024,
+                detected / 1024
+            ));
+        } else if detected > declared_flash {
+            warnings.push(format!(
+                "Flash 容量不一致：目标定义 {} KB，芯片实际 {} KB，可能选错了型号",
+                declared_flash / 1024,
+                detected / 1024
+            ));
+        }
+    }
+
+    if let (Some(detected), true) = (detected_ram_kb, declared_ram > 0) {
+        let detected = detected as u64 * 1024;
+        if detected < declared_ram {
+            warnings.push(format!(
+                "RAM 容量不一致：目标定义 {} KB 大于芯片实际 {} KB",
+                declared_ram / 1024,
+                detected / 1024
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// 芯片上报的片上存储容量
+#[derive(Debug, Clone, Serialize)]
+pub struct OnchipMemorySizes {
+    pub target_name: String,
+    pub flash_size_address: Option<u64>,
+    pub flash_kb: Option<u32>,
+    pub declared_flash_kb: u64,
+    pub ram_size_address: Option<u64>,
+    pub ram_kb: Option<u32>,
+    pub declared_ram_kb: u64,
+    pub matches: bool,
+    pub warnings: Vec<String>,
+}
+
+/// 按芯片名称前缀匹配的 DBGMCU_IDCODE DEV_ID（位 11:0）期望值
+/// 较长的前缀排在前面，以便最具体的条目优先匹配
+const EXPECTED_DEV_IDS: &[(&str, &[u16])] = &[
+    ("STM32F401", &[0x423, 0x433]),
+    ("STM32F405", &[0x413]),
+    ("STM32F407", &[0x413]),
+    ("STM32F411", &[0x431]),
+    ("STM32F427", &[0x419]),
+    ("STM32F429", &[0x419]),
+    ("STM32F446", &[0x421]),
+    ("STM32F0", &[0x440, 0x442, 0x444, 0x445, 0x448]),
+    ("STM32F1", &[0x410, 0x412, 0x414, 0x418, 0x420, 0x428, 0x430]),
+    ("STM32F3", &[0x422, 0x432, 0x438, 0x439, 0x446]),
+    ("STM32F7", &[0x449, 0x451, 0x452]),
+    ("STM32G0", &[0x456, 0x460, 0x466, 0x467]),
+    ("STM32G4", &[0x468, 0x469, 0x479]),
+    ("STM32L4", &[0x415, 0x435, 0x461, 0x462, 0x464, 0x470, 0x471]),
+    ("STM32H7", &[0x450, 0x480, 0x483]),
+];
+
+fn lookup_by_prefix<T: Copy>(table: &[(&str, T)], chip_name: &str) -> Option<T> {
+    let upper = chip_name.to_uppercase();
+    table
+        .iter()
+        .find(|(prefix, _)| upper.starts_with(prefix))
+        .map(|(_, value)| *value)
+}
+
+/// 系统时钟状态字段：(RCC_CFGR 地址, SWS 位移, SWS 掩码, 复位时钟对应的 SWS 值, 复位时钟频率 Hz)
+type ClockStatusLayout = (u64, u32, u32, u32, u32);
+
+/// 按芯片名称前缀匹配的系统时钟状态解码表
+/// 只解码复位时钟源（HSI/MSI），PLL/HSE 频率取决于固件配置
+const SYSTEM_CLOCK_STATUS: &[(&str, ClockStatusLayout)] = &[
+    ("STM32F0", (0x40021004, 2, 0b11, 0b00, 8_000_000)),
+    ("STM32F1", (0x40021004, 2, 0b11, 0b00, 8_000_000)),
+    ("STM32F3", (0x40021004, 2, 0b11, 0b00, 8_000_000)),
+    ("STM32F2", (0x40023808, 2, 0b11, 0b00, 16_000_000)),
+    ("STM32F4", (0x40023808, 2, 0b11, 0b00, 16_000_000)),
+    ("STM32F7", (0x40023808, 2, 0b11, 0b00, 16_000_000)),
+    ("STM32G0", (0x40021008, 3, 0b111, 0b000, 16_000_000)),
+    ("STM32G4", (0x40021008, 2, 0b11, 0b01, 16_000_000)),
+    ("STM32L4", (0x40021008, 2, 0b11, 0b00, 4_000_000)),
+    ("GD32F1", (0x40021004, 2, 0b11, 0b00, 8_000_000)),
+    ("GD32F3", (0x40021004, 2, 0b11, 0b00, 8_000_000)),
+];
+
+/// 调试时钟上限为内核时钟的 1/N，N 即该值
+pub const SAFE_CORE_CLOCK_DIVISOR: u32 = 6;
+
+/// 调试时钟建议，通过 `clock-warning` 事件发出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockRecommendation {
+    pub requested_hz: u32,
+    pub core_clock_hz: u32,
+    pub recommended_hz: u32,
+    pub message: String,
+}
+
+/// 请求的调试时钟超过内核时钟安全比例时生成建议
+pub fn clock_recommendation(requested_hz: u32, core_clock_hz: u32) -> Option<ClockRecommendation> {
+    let recommended_hz = core_clock_hz / SAFE_CORE_CLOCK_DIVISOR;
+    if core_clock_hz == 0 || requested_hz <= recommended_hz {
+        return None;
+    }
+    Some(ClockRecommendation {
+        requested_hz,
+        core_clock_hz,
+        recommended_hz,
+        message: format!(
+            "调试时钟 {} kHz 高于目标内核时钟 {} MHz 的 1/{}，复位后可能连接不稳定，建议降低到 {} kHz 以下",
+            requested_hz / 1000,
+            core_clock_hz / 1_000_000,
+            SAFE_CORE_CLOCK_DIVISOR,
+            recommended_hz / 1000
+        ),
+    })
+}
+
+/// 读取可解码的当前内核时钟（仅限目标运行在复位时钟上）
+fn read_core_clock(session: &mut Session) -> Option<u32> {
+    let (cfgr, shift, mask, reset_source, reset_hz) =
+        lookup_by_prefix(SYSTEM_CLOCK_STATUS, &session.target().name)?;
+    let mut core = session.core(0).ok()?;
+    let value = core.read_word_32(cfgr).ok()?;
+    ((value >> shift) & mask == reset_source).then_some(reset_hz)
+}
+
+/// 比较请求的调试时钟与解码出的内核时钟
+fn check_clock_speed(session: &mut Session, requested_hz: Option<u32>) -> Option<ClockRecommendation> {
+    let requested_hz = requested_hz?;
+    let core_clock_hz = read_core_clock(session)?;
+    clock_recommendation(requested_hz, core_clock_hz)
+}
+
+/// 芯片系列保护寄存器的解码方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProtectionScheme {
+    /// FLASH_OBR 位 1（STM32F1/GD32 的 RDPRT/SPC）
+    ObrBit,
+    /// FLASH_OBR 位 2:1 表示读保护等级（STM32F0/F3）
+    ObrLevel,
+    /// 指定位偏移处的 RDP 字节：0xAA 为等级 0，0xCC 为等级 2，其他值为等级 1
+    RdpByte(u32),
+    /// nRF UICR.APPROTECT：0x5A（HwDisabled）或擦除值表示允许调试访问
+    NrfApprotect,
+}
+
+/// 按芯片名称前缀匹配的保护寄存器
+/// 较长的前缀排在前面，以便最具体的条目优先匹配
+const PROTECTION_REGISTERS: &[(&str, (u64, ProtectionScheme))] = &[
+    ("STM32F0", (0x4002201C, ProtectionScheme::ObrLevel)),
+    ("STM32F1", (0x4002201C, ProtectionScheme::ObrBit)),
+    ("STM32F3", (0x4002201C, ProtectionScheme::ObrLevel)),
+    ("STM32F2", (0x40023C14, ProtectionScheme::RdpByte(8))),
+    ("STM32F4", (0x40023C14, ProtectionScheme::RdpByte(8))),
+    ("STM32F7", (0x40023C14, ProtectionScheme::RdpByte(8))),
+    ("STM32G0", (0x40022020, ProtectionScheme::RdpByte(0))),
+    ("STM32G4", (0x40022020, ProtectionScheme::RdpByte(0))),
+    ("STM32L4", (0x40022020, ProtectionScheme::RdpByte(0))),
+    ("STM32WB", (0x58004020, ProtectionScheme::RdpByte(0))),
+    ("STM32L0", (0x4002201C, ProtectionScheme::RdpByte(0))),
+    ("STM32H7", (0x5200201C, ProtectionScheme::RdpByte(8))),
+    ("GD32F4", (0x40023C14, ProtectionScheme::RdpByte(8))),
+    ("GD32F1", (0x4002201C, ProtectionScheme::ObrBit)),
+    ("GD32F3", (0x4002201C, ProtectionScheme::ObrBit)),
+    ("NRF52", (0x10001208, ProtectionScheme::NrfApprotect)),
+];
+
+/// 没有调试/读保护的芯片系列
+const UNPROTECTABLE_FAMILIES: &[(&str, ())] = &[("RP2040", ())];
+
+/// 归一化的保护状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProtectionState {
+    Unprotected,
+    ReadProtected,
+    DebugLocked,
+    Unknown,
+}
+
+/// 前端应提供的解除保护流程
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnprotectMethod {
+    /// 通过选项字节将 STM32/GD32 读保护降回等级 0（会整片擦除）
+    OptionBytes,
+    /// 通过 nRF CTRL-AP ERASEALL 恢复
+    CtrlApRecover,
+}
+
+/// 保护状态查询结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtectionStatus {
+    pub target: String,
+    pub state: ProtectionState,
+    pub register_address: Option<u64>,
+    pub raw_value: Option<u32>,
+    pub unprotect_method: Option<UnprotectMethod>,
+}
+
+/// 解码保护寄存器原始值
+pub fn decode_protection(scheme: ProtectionScheme, value: u32) -> ProtectionState {
+    match scheme {
+        ProtectionScheme::ObrBit => {
+            if value & 0b10 != 0 {
+                ProtectionState::ReadProtected
+            } else {
+                ProtectionState::Unprotected
+            }
+        }
+        ProtectionScheme::ObrLevel => match (value >> 1) & 0b11 {
+            0 => ProtectionState::Unprotected,
+            0b11 => ProtectionState::DebugLocked,
+            _ => ProtectionState::ReadProtected,
+        },
+        ProtectionScheme::RdpByte(shift) => match (value >> shift) & 0xFF {
+            0xAA => ProtectionState::Unprotected,
+            0xCC => ProtectionState::DebugLocked,
+            _ => ProtectionState::ReadProtected,
+        },
+        ProtectionScheme::NrfApprotect => match value & 0xFF {
+            0x5A | 0xFF => ProtectionState::Unprotected,
+            _ => ProtectionState::DebugLocked,
+        },
+    }
+}
+
+/// 芯片系列的 DBGMCU_CR 寄存器布局
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DbgmcuLayout {
+    pub address: u64,
+    /// DBG_SLEEP 位；Sleep 模式下始终保持调试的系列为 None
+    pub sleep_bit: Option<u32>,
+    pub stop_bit: u32,
+    pub standby_bit: u32,
+    /// DBGMCU 位于 APB 时，控制其时钟的 RCC 寄存器和位
+    pub clock_enable: Option<(u64, u32)>,
+}
+
+/// 位于 Cortex-M3/M4/M7 私有外设总线上的 DBGMCU
+const DBGMCU_PPB: DbgmcuLayout = DbgmcuLayout {
+    address: 0xE0042004,
+    sleep_bit: Some(0),
+    stop_bit: 1,
+    standby_bit: 2,
+    clock_enable: None,
+};
+
+/// 位于 APB 上的 DBGMCU（Cortex-M0/M0+），时钟使能前写入无效
+const fn dbgmcu_apb(sleep_bit: Option<u32>, rcc_register: u64, rcc_bit: u32) -> DbgmcuLayout {
+    DbgmcuLayout {
+        address: 0x40015804,
+        sleep_bit,
+        stop_bit: 1,
+        standby_bit: 2,
+        clock_enable: Some((rcc_register, rcc_bit)),
+    }
+}
+
+/// Armv8-M 芯片，Sleep 模式下始终保持调试
+const fn dbgmcu_v8m(address: u64) -> DbgmcuLayout {
+    DbgmcuLayout {
+        address,
+        sleep_bit: None,
+        stop_bit: 1,
+        standby_bit: 2,
+        clock_enable: None,
+    }
+}
+
+/// 按芯片名称前缀匹配的 DBGMCU_CR
+const DBGMCU_REGISTERS: &[(&str, DbgmcuLayout)] = &[
+    ("STM32F0", dbgmcu_apb(None, 0x40021018, 22)),
+    ("STM32G0", dbgmcu_apb(None, 0x4002103C, 27)),
+    ("STM32C0", dbgmcu_apb(None, 0x4002103C, 27)),
+    ("STM32L0", dbgmcu_apb(Some(0), 0x40021034, 22)),
+    ("STM32H7", DbgmcuLayout { address: 0x5C001004, ..DBGMCU_PPB }),
+    ("STM32H5", dbgmcu_v8m(0x44024004)),
+    ("STM32U5", dbgmcu_v8m(0xE0044004)),
+    ("STM32L5", dbgmcu_v8m(0xE0044004)),
+    ("STM32F1", DBGMCU_PPB),
+    ("STM32F2", DBGMCU_PPB),
+    ("STM32F3", DBGMCU_PPB),
+    ("STM32F4", DBGMCU_PPB),
+    ("STM32F7", DBGMCU_PPB),
+    ("STM32L1", DBGMCU_PPB),
+    ("STM32L4", DBGMCU_PPB),
+    ("STM32G4", DBGMCU_PPB),
+    ("STM32WB", DBGMCU_PPB),
+    ("STM32WL", DBGMCU_PPB),
+    ("GD32F1", DBGMCU_PPB),
+    ("GD32F3", DBGMCU_PPB),
+    ("GD32F4", DBGMCU_PPB),
+];
+
+/// 获取芯片的 DBGMCU 布局，未知系列返回 None
+pub fn dbgmcu_layout(chip_name: &str) -> Option<DbgmcuLayout> {
+    lookup_by_prefix(DBGMCU_REGISTERS, chip_name)
+}
+
+/// 从 DBGMCU_CR 值解码 (sleep, stop, standby) 调试位
+pub fn decode_low_power_debug(layout: &DbgmcuLayout, value: u32) -> (Option<bool>, bool, bool) {
+    (
+        layout.sleep_bit.map(|bit| value & (1 << bit) != 0),
+        value & (1 << layout.stop_bit) != 0,
+        value & (1 << layout.standby_bit) != 0,
+    )
+}
+
+/// 将请求的调试位写入 DBGMCU_CR 值，None 表示保持该位不变
+pub fn apply_low_power_debug(
+    layout: &DbgmcuLayout,
+    value: u32,
+    sleep: Option<bool>,
+    stop: Option<bool>,
+    standby: Option<bool>,
+) -> u32 {
+    let set = |value: u32, bit: u32, enable: Option<bool>| match enable {
+        Some(true) => value | (1 << bit),
+        Some(false) => value & !(1 << bit),
+        None => value,
+    };
+
+    let mut value = set(value, layout.stop_bit, stop);
+    value = set(value, layout.standby_bit, standby);
+    if let Some(bit) = layout.sleep_bit {
+        value = set(value, bit, sleep);
+    }
+    value
+}
+
+/// 低功耗模式调试状态
+#[derive(Debug, Clone, Serialize)]
+pub struct LowPowerDebugStatus {
+    pub target: String,
+    pub supported: bool,
+    pub register_address: Option<u64>,
+    pub raw_value: Option<u32>,
+    /// 系列没有 DBG_SLEEP 位时为 None（Sleep 模式下始终可调试）
+    pub sleep: Option<bool>,
+    pub stop: bool,
+    pub standby: bool,
+}
+
+/// 请求的低功耗调试位，None 表示保持该模式不变
+#[derive(Debug, Clone, Deserialize)]
+pub struct LowPowerDebugOptions {
+    #[serde(default)]
+    pub sleep: Option<bool>,
+    #[serde(default)]
+    pub stop: Option<bool>,
+    #[serde(default)]
+    pub standby: Option<bool>,
+}
+
+/// 复位原因标志寄存器：地址、(位, 原因) 对以及引脚复位位
+/// STM32/GD32 的内部复位都会同时拉低 NRST，因此引脚标志只在单独出现时才报告
+#[derive(Debug, Clone, Copy)]
+pub struct ResetCauseLayout {
+    pub address: u64,
+    pub causes: &'static [(u32, &'static str)],
+    pub pin_bit: Option<u32>,
+}
+
+const RESET_CAUSES_F1: &[(u32, &str)] = &[
+    (31, "低功耗复位"),
+    (30, "窗口看门狗复位"),
+    (29, "独立看门狗复位"),
+    (28, "软件复位"),
+    (27, "上电/掉电复位"),
+    (26, "引脚复位"),
+    (25, "选项字节加载复位"),
+];
+
+const RESET_CAUSES_F4: &[(u32, &str)] = &[
+    (31, "低功耗复位"),
+    (30, "窗口看门狗复位"),
+    (29, "独立看门狗复位"),
+    (28, "软件复位"),
+    (27, "上电/掉电复位"),
+    (26, "引脚复位"),
+    (25, "欠压复位"),
+];
+
+const RESET_CAUSES_L4: &[(u32, &str)] = &[
+    (31, "低功耗复位"),
+    (30, "窗口看门狗复位"),
+    (29, "独立看门狗复位"),
+    (28, "软件复位"),
+    (27, "欠压复位"),
+    (26, "引脚复位"),
+    (25, "选项字节加载复位"),
+    (24, "防火墙复位"),
+];
+
+const RESET_CAUSES_L0: &[(u32, &str)] = &[
+    (31, "低功耗复位"),
+    (30, "窗口看门狗复位"),
+    (29, "独立看门狗复位"),
+    (28, "软件复位"),
+    (27, "上电/掉电复位"),
+    (26, "引脚复位"),
+    (25, "选项字节加载复位"),
+    (24, "防火墙复位"),
+];
+
+const RESET_CAUSES_H7: &[(u32, &str)] = &[
+    (30, "低功耗复位"),
+    (28, "窗口看门狗复位"),
+    (26, "独立看门狗复位"),
+    (24, "软件复位"),
+    (23, "上电/掉电复位"),
+    (22, "引脚复位"),
+    (21, "欠压复位"),
+    (17, "内核复位"),
+];
+
+const RESET_CAUSES_NRF52: &[(u32, &str)] = &[
+    (0, "引脚复位"),
+    (1, "看门狗复位"),
+    (2, "软件复位"),
+    (3, "内核锁定复位"),
+    (16, "从 System OFF 唤醒 (GPIO)"),
+    (17, "从 System OFF 唤醒 (LPCOMP)"),
+    (18, "从 System OFF 唤醒 (调试接口)"),
+    (19, "从 System OFF 唤醒 (NFC)"),
+];
+
+const fn reset_causes(address: u64, causes: &'static [(u32, &'static str)], pin_bit: u32) -> ResetCauseLayout {
+    ResetCauseLayout { address, causes, pin_bit: Some(pin_bit) }
+}
+
+/// 按芯片名称前缀匹配的 RCC_CSR / RCC_RSR / POWER.RESETREAS
+const RESET_CAUSE_REGISTERS: &[(&str, ResetCauseLayout)] = &[
+    ("STM32F0", reset_causes(0x40021024, RESET_CAUSES_F1, 26)),
+    ("STM32F1", reset_causes(0x40021024, RESET_CAUSES_F1, 26)),
+    ("STM32F3", reset_causes(0x40021024, RESET_CAUSES_F1, 26)),
+    ("STM32F2", reset_causes(0x40023874, RESET_CAUSES_F4, 26)),
+    ("STM32F4", reset_causes(0x40023874, RESET_CAUSES_F4, 26)),
+    ("STM32F7", reset_causes(0x40023874, RESET_CAUSES_F4, 26)),
+    ("STM32G0", reset_causes(0x40021060, RESET_CAUSES_F1, 26)),
+    ("STM32L0", reset_causes(0x40021050, RESET_CAUSES_L0, 26)),
+    ("STM32L4", reset_causes(0x40021094, RESET_CAUSES_L4, 26)),
+    ("STM32G4", reset_causes(0x40021094, RESET_CAUSES_L4, 26)),
+    ("STM32WB", reset_causes(0x58000094, RESET_CAUSES_L4, 26)),
+    ("STM32H7", reset_causes(0x580244D0, RESET_CAUSES_H7, 22)),
+    ("GD32F1", reset_causes(0x40021024, RESET_CAUSES_F1, 26)),
+    ("GD32F3", reset_causes(0x40021024, RESET_CAUSES_F1, 26)),
+    ("GD32F4", reset_causes(0x40023874, RESET_CAUSES_F4, 26)),
+    // nRF52 没有附带的引脚标志；寄存器为空表示上电复位
+    ("NRF52", ResetCauseLayout { address: 0x40000400, causes: RESET_CAUSES_NRF52, pin_bit: None }),
+];
+
+/// 获取芯片的复位原因寄存器布局，未知系列返回 None
+pub fn reset_cause_layout(chip_name: &str) -> Option<ResetCauseLayout> {
+    lookup_by_prefix(RESET_CAUSE_REGISTERS, chip_name)
+}
+
+/// 将复位原因寄存器值解码为可读描述
+pub fn decode_reset_reason(layout: &ResetCauseLayout, value: u32) -> Option<String> {
+    let set: Vec<(u32, &str)> = layout
+        .causes
+        .iter()
+        .copied()
+        .filter(|(bit, _)| value & (1 << bit) != 0)
+        .collect();
+
+    let causes: Vec<&str> = set
+        .iter()
+        .filter(|(bit, _)| set.len() == 1 || Some(*bit) != layout.pin_bit)
+        .map(|(_, cause)| *cause)
+        .collect();
+
+    if !causes.is_empty() {
+        Some(causes.join("、"))
+    } else if layout.pin_bit.is_none() {
+        Some("上电复位".to_string())
+    } else {
+        None
+    }
+}
+
+/// 读取并解码已连接芯片的复位原因，失败时只记录日志
+/// 仅在连接选项启用时读取；复位下连接时寄存器只反映探针施加的复位，因此跳过
+fn read_reset_reason(session: &mut Session, options: &ConnectOptions) -> Option<String> {
+    if !options.read_reset_reason {
+        return None;
+    }
+    if options.connect_mode == ConnectMode::UnderReset {
+        log::info!("复位下连接，跳过读取复位原因（结果为调试器引起的引脚复位）");
+        return None;
+    }
+    let layout = reset_cause_layout(&session.target().name)?;
+    let mut core = session.core(0).ok()?;
+    match core.read_word_32(layout.address) {
+        Ok(value) => decode_reset_reason(&layout, value),
+        Err(e) => {
+            log::warn!("读取复位原因寄存器 0x{:08X} 失败: {}", layout.address, e);
+            None
+        }
+    }
+}
+
+/// 探针类型的固有功能：SWD、JTAG、SWO 采集、目标供电输出
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ProbeFeatures {
+    pub swd: bool,
+    pub jtag: bool,
+    pub swo: bool,
+    pub target_power: bool,
+}
+
+const fn probe_features(swd: bool, jtag: bool, swo: bool, target_power: bool) -> ProbeFeatures {
+    ProbeFeatures { swd, jtag, swo, target_power }
+}
+
+/// 按探针类型名称前缀（probe-rs 上报）匹配的探针功能
+/// CMSIS-DAP 的 SWO 和 JTAG 取决于固件，这里按常见情况处理
+const PROBE_FEATURES: &[(&str, ProbeFeatures)] = &[
+    ("CMSISDAP", probe_features(true, true, true, false)),
+    ("STLINK", probe_features(true, true, true, false)),
+    ("JLINK", probe_features(true, true, true, true)),
+    ("BLACKMAGIC", probe_features(true, true, true, true)),
+    ("WCHLINK", probe_features(true, false, false, false)),
+    ("FTDI", probe_features(false, true, false, false)),
+    ("ESPJTAG", probe_features(false, true, false, false)),
+];
+
+/// 获取探针类型的功能，未知类型返回 None
+pub fn probe_features_for(probe_type: &str) -> Option<ProbeFeatures> {
+    lookup_by_prefix(PROBE_FEATURES, probe_type)
+}
+
+/// Cortex-M PPB ROM 表地址
+const PPB_ROM_TABLE: u64 = 0xE00F_F000;
+/// ROM 表最多扫描的条目数
+const ROM_TABLE_MAX_ENTRIES: u64 = 32;
+const ITM_BASE: u64 = 0xE000_0000;
+const TPIU_BASE: u64 = 0xE004_0000;
+
+/// 将 ROM 表条目解析为组件基地址
+/// 遇到第一个 0 条目即停止，未置存在位的条目跳过
+pub fn decode_rom_table_entries(rom_base: u64, entries: &[u32]) -> Vec<u64> {
+    entries
+        .iter()
+        .take_while(|&&entry| entry != 0)
+        .filter(|&&entry| entry & 1 != 0)
+        .map(|&entry| rom_base.wrapping_add((entry & 0xFFFF_F000) as i32 as i64 as u64) & 0xFFFF_FFFF)
+        .collect()
+}
+
+fn scan_rom_table(core: &mut probe_rs::Core) -> Vec<u64> {
+    let mut entries = Vec::new();
+    for index in 0..ROM_TABLE_MAX_ENTRIES {
+        match core.read_word_32(PPB_ROM_TABLE + index * 4) {
+            Ok(0) => break,
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                log::warn!("读取 ROM 表失败: {}", e);
+                break;
+            }
+        }
+    }
+    decode_rom_table_entries(PPB_ROM_TABLE, &entries)
+}
+
+/// 当前探针与目标组合支持的操作
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionCapabilities {
+    pub probe_type: Option<String>,
+    /// 探针类型未知时为 None
+    pub probe: Option<ProbeFeatures>,
+    pub itm_present: bool,
+    pub tpiu_present: bool,
+    /// SWO/ITM 可用：探针支持 SWO 采集且 ROM 表中有 ITM 和 TPIU
+    pub swo: bool,
+    pub hardware_breakpoints: Option<u32>,
+    /// RTT 需要有 RAM 区域存放控制块
+    pub rtt: bool,
+    pub flash: bool,
+    pub flash_algorithms: usize,
+}
+
+/// 目标芯片身份校验结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetIdentityCheck {
+    pub target_name: String,
+    /// 所选目标定义声明的 Flash 容量（字节）
+    pub declared_flash_size: u64,
+    /// 从片上寄存器读取的 Flash 容量（字节）
+    pub detected_flash_size: Option<u64>,
+    pub chip_id: Option<u32>,
+    pub dev_id: Option<u16>,
+    pub expected_dev_ids: Vec<u16>,
+    pub matches: bool,
+    pub warnings: Vec<String>,
+}
+
 /// Try to read the DP IDCODE (DPIDR) from the debug port
 /// This identifies the debug access port implementation
 fn read_dp_idcode(session: &mut Session) -> Option<u32> {
@@ -239,6 +996,7 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
     // probe-rs 枚举
     let lister = Lister::new();
     let probes = lister.list_all();
+    let locations = probe_usb_locations(&probes);
 
     log::info!("=== Probe enumeration (probe-rs) ===");
     log::info!("Total probes found: {}", probes.len());
@@ -274,6 +1032,9 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
                     probe_type: "CmsisDap".to_string(),
                     dap_version: Some("DAPv1+v2 (HID/WinUSB)".to_string()),
                     debug_info: Some(cap.debug_info.clone()),
+                    probe_index: 0,
+                    serial_conflict: false,
+                    usb_location: None,
                 });
             } else if cap.has_v2 {
                 probe_infos.push(ProbeInfo {
@@ -285,6 +1046,9 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
                     probe_type: "CmsisDapV2".to_string(),
                     dap_version: Some("DAPv2 (WinUSB)".to_string()),
                     debug_info: Some(cap.debug_info.clone()),
+                    probe_index: 0,
+                    serial_conflict: false,
+                    usb_location: None,
                 });
             } else if cap.has_hid {
                 probe_infos.push(ProbeInfo {
@@ -296,6 +1060,9 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
                     probe_type: "CmsisDap".to_string(),
                     dap_version: Some("DAPv1 (HID)".to_string()),
                     debug_info: Some(cap.debug_info.clone()),
+                    probe_index: 0,
+                    serial_conflict: false,
+                    usb_location: None,
                 });
             } else {
                 // 未知类型，直接添加
@@ -308,6 +1075,9 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
                     probe_type: probe_type_str,
                     dap_version: None,
                     debug_info: Some(cap.debug_info.clone()),
+                    probe_index: 0,
+                    serial_conflict: false,
+                    usb_location: None,
                 });
             }
         } else {
@@ -332,28 +1102,414 @@ pub async fn list_probes() -> AppResult<Vec<ProbeInfo>> {
                 probe_type: probe_type_str,
                 dap_version,
                 debug_info: None,
+                probe_index: 0,
+                serial_conflict: false,
+                usb_location: None,
             });
         }
     }
 
+    let keys: Vec<UsbProbeKey> = probe_infos
+        .iter()
+        .map(|p| (p.vendor_id, p.product_id, p.serial_number.clone()))
+        .collect();
+    let conflicts = serial_conflicts(&keys);
+    for ((info, conflict), location) in probe_infos.iter_mut().zip(conflicts).zip(locations) {
+        info.serial_conflict = conflict;
+        info.usb_location = location;
+        if conflict {
+            log::warn!(
+                "探针 {} 的序列号与其他探针重复，请按 USB 位置 {:?} 区分",
+                info.identifier, info.usb_location
+            );
+        }
+    }
+
+    // 同名探针按枚举顺序编号；VID/PID/序列号完全相同（如无序列号的克隆探针）时 probe_id 附加序号以保持唯一
+    let probe_ids: Vec<String> = probe_infos.iter().map(|p| p.probe_id.clone()).collect();
+    for i in 0..probe_infos.len() {
+        probe_infos[i].probe_index = probe_infos[..i]
+            .iter()
+            .filter(|p| p.identifier == probe_infos[i].identifier)
+            .count();
+        if probe_ids.iter().filter(|id| **id == probe_ids[i]).count() > 1 {
+            probe_infos[i].probe_id = format!("{}#{}", probe_ids[i], probe_infos[i].probe_index);
+        }
+    }
+
     log::info!("=== Probe enumeration end, total {} entries ===", probe_infos.len());
 
     Ok(probe_infos)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectOptions {
     pub probe_identifier: String,
+    /// 同名探针中的序号（见 ProbeInfo::probe_index），默认第一个
+    #[serde(default)]
+    pub probe_index: Option<usize>,
+    /// 按 USB 物理位置选择探针（见 ProbeInfo::usb_location），优先于 probe_index
+    #[serde(default)]
+    pub usb_location: Option<String>,
     pub target: String,
     pub interface_type: InterfaceType,
     pub clock_speed: Option<u32>,
     pub connect_mode: ConnectMode,
+    /// 主内核所在的 AP 编号（非 AP0 的芯片使用）
+    #[serde(default)]
+    pub ap_index: Option<u8>,
+    /// 主内核调试组件基地址
+    #[serde(default)]
+    pub debug_base: Option<u64>,
+    /// Flash 算法加载使用的 RAM 区域 (起始地址, 大小)，覆盖目标定义的自动选择
+    #[serde(default)]
+    pub algorithm_ram_region: Option<(u64, u64)>,
+    /// 强制使用的 Flash 算法名称，覆盖 probe-rs 按地址范围的自动选择
+    #[serde(default)]
+    pub flash_algorithm: Option<String>,
+    /// 同时提供 HID 和 WinUSB 的 CMSIS-DAP 探针强制使用的传输方式，默认由 probe-rs 自动选择
+    #[serde(default)]
+    pub force_dap_version: Option<DapVersion>,
+    /// 连接时读取上次复位原因（默认关闭；复位下连接时不读取，结果必然是引脚复位）
+    #[serde(default)]
+    pub read_reset_reason: bool,
+}
+
+/// CMSIS-DAP 传输方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DapVersion {
+    /// DAPv1 (HID)
+    V1,
+    /// DAPv2 (WinUSB 批量传输)
+    V2,
+}
+
+impl DapVersion {
+    fn label(self) -> &'static str {
+        match self {
+            DapVersion::V1 => "DAPv1 (HID)",
+            DapVersion::V2 => "DAPv2 (WinUSB)",
+        }
+    }
+}
+
+/// 按强制的 DAP 版本选择探针的 USB 接口，未指定时原样返回
+pub(crate) fn apply_dap_version(
+    probe_info: &probe_rs::probe::DebugProbeInfo,
+    version: Option<DapVersion>,
+) -> AppResult<probe_rs::probe::DebugProbeInfo> {
+    let mut info = probe_info.clone();
+    let Some(version) = version else {
+        return Ok(info);
+    };
+
+    let caps = collect_cmsis_dap_caps();
+    let cap = match_caps_for_probe(probe_info, &caps)
+        .ok_or_else(|| AppError::ProbeError("该探针不是 CMSIS-DAP 探针，无法指定 DAP 版本".to_string()))?;
+
+    let interface = match version {
+        DapVersion::V1 => cap.hid_interface,
+        DapVersion::V2 => cap.v2_interface,
+    }
+    .ok_or_else(|| AppError::ProbeError(format!("该探针不支持 {} 接口", version.label())))?;
+
+    log::info!("强制使用 {} 接口 {}", version.label(), interface);
+    info.interface = Some(interface);
+    info.is_hid_interface = version == DapVersion::V1;
+    Ok(info)
+}
+
+/// 根据连接选项构造目标选择器
+/// 指定了 AP 编号或调试基地址时，基于注册表中的目标定义覆盖主内核的访问参数
+fn build_target_selector(options: &ConnectOptions, registry: &Registry) -> AppResult<TargetSelector> {
+    // 任何 Registry 可解析的名称（内置、probe-rs 数据库或 Pack 导入）都可以连接
+    let resolved = crate::commands::config::resolve_chip(registry, &options.target)
+        .map_err(|e| AppError::ProbeError(format!("{}，请确认芯片型号或导入对应的 Pack", e)))?;
+    log::info!("目标芯片 {} 解析为 {} (来源: {})", options.target, resolved.name, resolved.source);
+
+    let mut target = registry
+        .get_target_by_name(&resolved.name)
+        .map_err(|e| AppError::ProbeError(format!("未找到芯片 '{}': {}", options.target, e)))?;
+
+    if let Some(ref name) = options.flash_algorithm {
+        apply_flash_algorithm(&mut target, name)?;
+    }
+
+    if let Some(region) = options.algorithm_ram_region {
+        let overrides = crate::pack::target_gen::TargetGenOverrides::from_config(&crate::app_config::load_config());
+        let pack_name = crate::commands::config::pack_for_chip(registry, &resolved.name).unwrap_or_default();
+        let header_reserve =
+            crate::pack::target_gen::resolve_header_reserve(&overrides.header_reserves, &pack_name, &resolved.name);
+        let sizing =
+            crate::pack::target_gen::resolve_algorithm_sizing(&overrides.algorithm_sizing, &pack_name, &resolved.name);
+        apply_algorithm_ram_region(&mut target, region, header_reserve, sizing)?;
+    }
+
+    if options.ap_index.is_none() && options.debug_base.is_none() {
+        return Ok(TargetSelector::Specified(target));
+    }
+
+    let core = target
+        .cores
+        .first_mut()
+        .ok_or_else(|| AppError::ProbeError(format!("芯片 '{}' 未定义内核", options.target)))?;
+
+    match &mut core.core_access_options {
+        CoreAccessOptions::Arm(arm) => {
+            if let Some(ap) = options.ap_index {
+                log::info!("覆盖主内核 AP: {}", ap);
+                arm.ap = ApAddress::V1(ap);
+            }
+            if let Some(base) = options.debug_base {
+                log::info!("覆盖主内核调试基地址: 0x{:08X}", base);
+                arm.debug_base = Some(base);
+            }
+        }
+        _ => {
+            return Err(AppError::ProbeError("仅 ARM 内核支持指定 AP 编号和调试基地址".to_string()));
+        }
+    }
+
+    Ok(TargetSelector::Specified(target))
+}
+
+/// 强制使用指定名称的 Flash 算法：移除与其地址范围重叠的其他算法并设为默认
+/// 不重叠的算法（如其他 Flash bank）保留，以便烧录其余区域
+pub(crate) fn apply_flash_algorithm(target: &mut Target, name: &str) -> AppResult<()> {
+    let range = target
+        .flash_algorithms
+        .iter()
+        .find(|algo| algo.name.eq_ignore_ascii_case(name))
+        .map(|algo| algo.flash_properties.address_range.clone())
+        .ok_or_else(|| {
+            let available: Vec<&str> = target.flash_algorithms.iter().map(|a| a.name.as_str()).collect();
+            AppError::ProbeError(format!(
+                "目标 '{}' 没有名为 '{}' 的 Flash 算法，可用算法: {}",
+                target.name,
+                name,
+                available.join(", ")
+            ))
+        })?;
+
+    target.flash_algorithms.retain(|algo| {
+        let other = &algo.flash_properties.address_range;
+        algo.name.eq_ignore_ascii_case(name) || other.end <= range.start || range.end <= other.start
+    });
+    for algo in &mut target.flash_algorithms {
+        if algo.name.eq_ignore_ascii_case(name) {
+            algo.default = true;
+        }
+    }
+
+    log::info!("强制使用 Flash 算法: {} (0x{:08X}-0x{:08X})", name, range.start, range.end);
+    Ok(())
+}
+
+/// 将所有 Flash 算法的加载地址指定到给定 RAM 区域 (起始地址, 大小)
+/// 区域必须完整落在目标定义的某个 RAM 区域内，且能容纳算法 blob、页缓冲和栈（按 algorithm_sizing 配置）
+/// 算法加载地址前预留 header_reserve 字节（按 header_reserves 配置）
+fn apply_algorithm_ram_region(
+    target: &mut Target,
+    (start, size): (u64, u64),
+    header_reserve: u64,
+    sizing: Option<&crate::app_config::AlgorithmSizing>,
+) -> AppResult<()> {
+    let end = start
+        .checked_add(size)
+        .ok_or_else(|| AppError::ProbeError("算法 RAM 区域超出地址范围".to_string()))?;
+
+    if size <= header_reserve {
+        return Err(AppError::ProbeError(format!(
+            "算法 RAM 区域过小: 0x{:X} 字节",
+            size
+        )));
+    }
+
+    let inside_ram = target.memory_map.iter().any(|region| match region {
+        probe_rs::config::MemoryRegion::Ram(ram) => ram.range.start <= start && end <= ram.range.end,
+        _ => false,
+    });
+    if !inside_ram {
+        return Err(AppError::ProbeError(format!(
+            "算法 RAM 区域 0x{:08X}-0x{:08X} 不在目标 '{}' 的 RAM 范围内",
+            start, end, target.name
+        )));
+    }
+
+    // 每个算法的 blob、页缓冲和栈都必须放得下
+    for algo in &target.flash_algorithms {
+        let page_size = algo.flash_properties.page_size as u64;
+        let footprint = crate::pack::target_gen::AlgorithmFootprint {
+            blob_size: algo.instructions.len() as u64,
+            page_size,
+            stack_size: sizing
+                .and_then(|s| s.stack_size)
+                .or(algo.stack_size.map(u64::from))
+                .unwrap_or_else(|| crate::pack::target_gen::default_stack_size(page_size)),
+            page_buffers: sizing.and_then(|s| s.page_buffers).unwrap_or(1) as u64,
+        };
+        crate::pack::target_gen::check_algorithm_fits_ram(start + header_reserve, &footprint, start, size)
+        .map_err(|e| AppError::ProbeError(format!("算法 {}: {}", algo.name, e)))?;
+    }
+
+    log::info!("覆盖 Flash 算法 RAM 区域: 0x{:08X}-0x{:08X}", start, end);
+    for algo in &mut target.flash_algorithms {
+        algo.load_address = Some(start + header_reserve);
+    }
+
+    Ok(())
+}
+
+/// 按连接选项重新打开探针并附加到指定目标（用于临时切换目标定义后重连）
+pub(crate) fn attach_session(
+    options: &ConnectOptions,
+    target_selector: TargetSelector,
+    registry: &Registry,
+) -> AppResult<Session> {
+    let lister = Lister::new();
+    let probes = lister.list_all();
+    let probe_info = apply_dap_version(select_probe(&probes, options)?, options.force_dap_version)?;
+
+    let mut probe = open_probe_with_retry(&probe_info)
+        .map_err(|e| AppError::ProbeError(e.to_string()))?;
+
+    let protocol = match options.interface_type {
+        InterfaceType::Swd => WireProtocol::Swd,
+        InterfaceType::Jtag => WireProtocol::Jtag,
+    };
+    probe
+        .select_protocol(protocol)
+        .map_err(|e| AppError::ProbeError(e.to_string()))?;
+
+    if let Some(speed_hz) = options.clock_speed {
+        let speed_khz = speed_hz / 1000;
+        probe
+            .set_speed(speed_khz)
+            .map_err(|e| AppError::ProbeError(format!("设置时钟速度失败 ({} kHz): {}", speed_khz, e)))?;
+    }
+
+    if options.connect_mode == ConnectMode::UnderReset {
+        probe
+            .attach_under_reset_with_registry(target_selector, Permissions::default(), registry)
+            .map_err(|e| AppError::ProbeError(e.to_string()))
+    } else {
+        probe
+            .attach_with_registry(target_selector, Permissions::default(), registry)
+            .map_err(|e| AppError::ProbeError(e.to_string()))
+    }
+}
+
+/// 判断打开探针的错误是否为暂时占用（上一次连接的句柄尚未完全释放），权限错误不重试
+pub fn is_transient_open_error(message: &str) -> bool {
+    const TRANSIENT: [&str; 5] = ["busy", "claim", "in use", "temporarily unavailable", "being used by another"];
+    const PERMISSION: [&str; 3] = ["permission", "access denied", "not permitted"];
+
+    let lower = message.to_lowercase();
+    TRANSIENT.iter().any(|p| lower.contains(p)) && !PERMISSION.iter().any(|p| lower.contains(p))
+}
+
+/// 打开探针，遇到设备忙等暂时性错误时按配置重试
+pub(crate) fn open_probe_with_retry(
+    probe_info: &probe_rs::probe::DebugProbeInfo,
+) -> Result<probe_rs::probe::Probe, probe_rs::probe::DebugProbeError> {
+    let retry = crate::app_config::load_config().probe_open_retry;
+    let attempts = retry.attempts.max(1);
+
+    let mut attempt = 1;
+    loop {
+        match probe_info.open() {
+            Ok(probe) => return Ok(probe),
+            Err(e) if attempt < attempts && is_transient_open_error(&e.to_string()) => {
+                log::warn!("打开探针失败 (第 {}/{} 次)，{} ms 后重试: {}", attempt, attempts, retry.delay_ms, e);
+                std::thread::sleep(std::time::Duration::from_millis(retry.delay_ms));
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 按标识和同名序号选择探针
+pub(crate) fn select_probe<'a>(
+    probes: &'a [probe_rs::probe::DebugProbeInfo],
+    options: &ConnectOptions,
+) -> AppResult<&'a probe_rs::probe::DebugProbeInfo> {
+    if let Some(ref location) = options.usb_location {
+        return probes
+            .iter()
+            .zip(probe_usb_locations(probes))
+            .find(|(p, l)| p.identifier == options.probe_identifier && l.as_deref() == Some(location.as_str()))
+            .map(|(p, _)| p)
+            .ok_or_else(|| AppError::ProbeError(format!("未找到位于 USB {} 的探针", location)));
+    }
+
+    let index = options.probe_index.unwrap_or(0);
+    probes
+        .iter()
+        .filter(|p| p.identifier == options.probe_identifier)
+        .nth(index)
+        .ok_or_else(|| {
+            if index == 0 {
+                AppError::ProbeError("未找到指定的探针".to_string())
+            } else {
+                AppError::ProbeError(format!("未找到指定的探针 ({} #{})", options.probe_identifier, index))
+            }
+        })
+}
+
+/// 按原连接选项重新附加到目标（包含 AP/调试基地址覆盖）
+pub(crate) fn reattach_session(options: &ConnectOptions, registry: &Registry) -> AppResult<Session> {
+    let target_selector = build_target_selector(options, registry)?;
+    attach_session(options, target_selector, registry)
+}
+
+/// 连接丢失事件（临时重连后无法恢复原会话）
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionLostEvent {
+    pub message: String,
+}
+
+/// 临时重连失败或结束后按原连接选项恢复会话
+/// 恢复失败时清理连接状态并通知前端，避免 connection_info 仍描述已不存在的会话
+pub(crate) fn restore_session<E: EventSink>(
+    state: &AppState,
+    session: &mut Option<Session>,
+    previous: &ConnectOptions,
+    registry: &Registry,
+    events: &E,
+) -> AppResult<()> {
+    match reattach_session(previous, registry) {
+        Ok(restored) => {
+            *session = Some(restored);
+            Ok(())
+        }
+        Err(e) => {
+            *session = None;
+            *state.connection_info.lock() = None;
+            *state.connect_options.lock() = None;
+            let message = format!("恢复原连接失败，已断开: {}", e);
+            log::error!("{}", message);
+            events.emit_event("connection-lost", ConnectionLostEvent { message });
+            Err(e)
+        }
+    }
 }
 
 #[tauri::command]
 pub async fn connect_target(
     options: ConnectOptions,
     state: State<'_, AppState>,
+    app: AppHandle,
+) -> AppResult<TargetInfo> {
+    connect_target_with(options, &state, &app)
+}
+
+/// 连接主流程，日志经 events 输出（GUI 为前端，命令行模式为终端）
+pub fn connect_target_with<E: EventSink>(
+    options: ConnectOptions,
+    state: &AppState,
+    events: &E,
 ) -> AppResult<TargetInfo> {
     log::info!("=== 开始连接目标 ===");
     log::info!("探针标识: {}", options.probe_identifier);
@@ -364,200 +1520,1292 @@ pub async fn connect_target(
 
     // 关闭现有连接
     {
-        let mut session_guard = state.session.lock();
+        let mut session_guard = state.lock_session();
         *session_guard = None;
     }
 
     let lister = Lister::new();
     let probes = lister.list_all();
 
-    let probe_info = probes
-        .iter()
-        .find(|p| p.identifier == options.probe_identifier)
-        .ok_or_else(|| {
-            log::error!("未找到指定的探针: {}", options.probe_identifier);
-            AppError::ProbeError("未找到指定的探针".to_string())
-        })?;
+    let probe_info = select_probe(&probes, &options).inspect_err(|_| {
+        flash_log(events, log::Level::Error, format!("未找到指定的探针: {}", options.probe_identifier));
+    })?;
+
+    log::info!("找到探针: {:?}", probe_info.identifier);
+
+    let probe_info = apply_dap_version(probe_info, options.force_dap_version).inspect_err(|e| {
+        flash_log(events, log::Level::Error, e.to_string());
+    })?;
+
+    let mut probe = open_probe_with_retry(&probe_info)
+        .map_err(|e| {
+            flash_log(events, log::Level::Error, format!("打开探针失败: {}", e));
+            AppError::ProbeError(e.to_string())
+        })?;
+
+    flash_log(events, log::Level::Info, format!("探针已打开: {}", probe_info.identifier));
+
+    // 设置协议
+    let protocol = match options.interface_type {
+        InterfaceType::Swd => WireProtocol::Swd,
+        InterfaceType::Jtag => WireProtocol::Jtag,
+    };
+    probe
+        .select_protocol(protocol)
+        .map_err(|e| {
+            flash_log(events, log::Level::Error, format!("设置协议失败 ({:?}): {}", protocol, e));
+            AppError::ProbeError(e.to_string())
+        })?;
+
+    flash_log(events, log::Level::Info, format!("协议已设置: {:?}", protocol));
+
+    // 设置时钟速度（前端传递的是Hz，probe-rs需要kHz）
+    if let Some(speed_hz) = options.clock_speed {
+        let speed_khz = speed_hz / 1000;
+        probe
+            .set_speed(speed_khz)
+            .map_err(|e| {
+                flash_log(events, log::Level::Error, format!("设置时钟速度失败 ({} kHz): {}", speed_khz, e));
+                AppError::ProbeError(format!("设置时钟速度失败 ({} kHz): {}", speed_khz, e))
+            })?;
+        flash_log(events, log::Level::Info, format!("时钟速度已设置: {} kHz", speed_khz));
+    }
+
+    // 连接目标
+    log::info!("正在连接目标芯片: {}", options.target);
+
+    // 获取自定义 Registry（包含从 Pack 导入的设备）
+    let registry = TARGET_REGISTRY.lock().unwrap();
+    let target_selector = build_target_selector(&options, &registry)?;
+
+    let mut session = if options.connect_mode == ConnectMode::UnderReset {
+        flash_log(events, log::Level::Info, format!("使用 UnderReset 模式连接 {}", options.target));
+        probe
+            .attach_under_reset_with_registry(target_selector, Permissions::default(), &registry)
+            .map_err(|e| {
+                flash_log(events, log::Level::Error, format!("连接目标失败 (UnderReset): {}", e));
+                log::error!("可能的原因:");
+                log::error!("  1. 芯片型号 '{}' 不在 probe-rs 支持列表中", options.target);
+                log::error!("  2. 需要导入对应的 CMSIS-Pack 文件");
+                log::error!("  3. 芯片连接有问题（检查电源、接线）");
+                AppError::ProbeError(format!(
+                    "无法连接到芯片 '{}': {}。请检查: 1) 芯片型号是否正确 2) 是否已导入对应的Pack文件 3) 硬件连接是否正常",
+                    options.target, e
+                ))
+            })?
+    } else {
+        flash_log(events, log::Level::Info, format!("使用 Normal 模式连接 {}", options.target));
+        probe
+            .attach_with_registry(target_selector, Permissions::default(), &registry)
+            .map_err(|e| {
+                flash_log(events, log::Level::Error, format!("连接目标失败 (Normal): {}", e));
+                log::error!("可能的原因:");
+                log::error!("  1. 芯片型号 '{}' 不在 probe-rs 支持列表中", options.target);
+                log::error!("  2. 需要导入对应的 CMSIS-Pack 文件");
+                log::error!("  3. 芯片连接有问题（检查电源、接线）");
+                AppError::ProbeError(format!(
+                    "无法连接到芯片 '{}': {}。请检查: 1) 芯片型号是否正确 2) 是否已导入对应的Pack文件 3) 硬件连接是否正常",
+                    options.target, e
+                ))
+            })?
+    };
+
+    // 释放 registry 锁
+    drop(registry);
+
+    flash_log(events, log::Level::Info, "✓ 成功连接到目标芯片".to_string());
+
+    // 读取芯片ID（DBGMCU_IDCODE）
+    let chip_id = read_chip_id(&mut session);
+    if let Some(id) = chip_id {
+        flash_log(events, log::Level::Info, format!("芯片ID (DBGMCU_IDCODE): 0x{:08X}", id));
+    } else {
+        flash_log(events, log::Level::Warn, "无法读取芯片ID".to_string());
+    }
+
+    // 读取 DP IDCODE (DPIDR) - 调试端口标识码
+    let target_idcode = read_dp_idcode(&mut session);
+    if let Some(id) = target_idcode {
+        flash_log(events, log::Level::Info, format!("调试端口ID (DPIDR): 0x{:08X}", id));
+    } else {
+        flash_log(events, log::Level::Warn, "无法读取调试端口ID".to_string());
+    }
+
+    // 读取上次复位原因
+    let reset_reason = read_reset_reason(&mut session, &options);
+    if let Some(ref reason) = reset_reason {
+        flash_log(events, log::Level::Info, format!("上次复位原因: {}", reason));
+    }
+
+    // 调试时钟过快检查
+    let clock_recommendation = check_clock_speed(&mut session, options.clock_speed);
+    if let Some(ref recommendation) = clock_recommendation {
+        flash_log(events, log::Level::Warn, recommendation.message.clone());
+        events.emit_event("clock-warning", recommendation);
+    }
+
+    // 获取目标信息
+    let target = session.target();
+    log::info!("目标芯片名称: {}", target.name);
+    log::info!("核心类型: {:?}", target.cores.first().map(|c| c.core_type));
+    log::info!("内存区域数量: {}", target.memory_map.len());
+    log::info!("Flash算法数量: {}", target.flash_algorithms.len());
+
+    let target_info = TargetInfo {
+        name: target.name.clone(),
+        core_type: format!("{:?}", target.cores.first().map(|c| c.core_type)),
+        memory_regions: target
+            .memory_map
+            .iter()
+            .map(|region| {
+                let (name, kind, address, size) = match region {
+                    probe_rs::config::MemoryRegion::Ram(r) => {
+                        (r.name.clone().unwrap_or_default(), "RAM", r.range.start, r.range.end - r.range.start)
+                    }
+                    probe_rs::config::MemoryRegion::Nvm(r) => {
+                        (r.name.clone().unwrap_or_default(), "Flash", r.range.start, r.range.end - r.range.start)
+                    }
+                    probe_rs::config::MemoryRegion::Generic(r) => {
+                        (r.name.clone().unwrap_or_default(), "Generic", r.range.start, r.range.end - r.range.start)
+                    }
+                };
+                MemoryRegion {
+                    name,
+                    kind: kind.to_string(),
+                    address,
+                    size,
+                }
+            })
+            .collect(),
+        flash_algorithms: target
+            .flash_algorithms
+            .iter()
+            .map(|a| a.name.clone())
+            .collect(),
+        chip_id,
+        clock_recommendation,
+        reset_reason: reset_reason.clone(),
+    };
+
+    // 存储连接信息
+    {
+        let mut conn_info = state.connection_info.lock();
+        *conn_info = Some(ConnectionInfo {
+            probe_name: options.probe_identifier.clone(),
+            probe_serial: probe_info.serial_number.clone(),  // 保存探针序列号
+            target_name: options.target.clone(),
+            core_type: target_info.core_type.clone(),
+            chip_id,
+            target_idcode,  // 保存目标IDCODE
+            rtt_control_block: None,
+            reset_reason,
+        });
+    }
+
+    // 存储session
+    {
+        let mut session_guard = state.lock_session();
+        *session_guard = Some(session);
+    }
+    *state.active_core.lock() = 0;
+    *state.connect_options.lock() = Some(options.clone());
+    state.unified_session.store(false, std::sync::atomic::Ordering::SeqCst);
+
+    // 记录本次连接参数供快速连接使用
+    let mut config = crate::app_config::load_config();
+    config.last_connection = Some(crate::app_config::LastConnection {
+        options: options.clone(),
+        probe_serial: probe_info.serial_number.clone().filter(|s| !s.is_empty()),
+        vendor_id: probe_info.vendor_id,
+        product_id: probe_info.product_id,
+    });
+    if let Err(e) = crate::app_config::save_config(&config) {
+        log::warn!("保存最近连接参数失败: {}", e);
+    }
+
+    log::info!("=== 连接完成 ===");
+
+    Ok(target_info)
+}
+
+/// 检查当前连接的调试时钟是否高于目标内核时钟的安全比例
+#[tauri::command]
+pub async fn get_clock_recommendation(state: State<'_, AppState>) -> AppResult<Option<ClockRecommendation>> {
+    let requested_hz = state.connect_options.lock().as_ref().and_then(|o| o.clock_speed);
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    Ok(check_clock_speed(session, requested_hz))
+}
+
+/// 使用最近一次成功连接的参数重新连接
+/// 探针按序列号重新定位（标识可能变化），找不到时返回错误
+#[tauri::command]
+pub async fn quick_connect(state: State<'_, AppState>, app: AppHandle) -> AppResult<TargetInfo> {
+    let last = crate::app_config::load_config()
+        .last_connection
+        .ok_or_else(|| AppError::ProbeError("没有最近的连接记录".to_string()))?;
+    let mut options = last.options;
+
+    if let Some(ref serial) = last.probe_serial {
+        let probes = Lister::new().list_all();
+        let probe = probes
+            .iter()
+            .find(|p| {
+                p.vendor_id == last.vendor_id
+                    && p.product_id == last.product_id
+                    && p.serial_number.as_deref() == Some(serial.as_str())
+            })
+            .ok_or_else(|| {
+                AppError::ProbeError(format!(
+                    "上次使用的探针 ({:04x}:{:04x} 序列号 {}) 未连接",
+                    last.vendor_id, last.product_id, serial
+                ))
+            })?;
+        options.probe_index = Some(
+            probes
+                .iter()
+                .take_while(|p| !std::ptr::eq(*p, probe))
+                .filter(|p| p.identifier == probe.identifier)
+                .count(),
+        );
+        options.probe_identifier = probe.identifier.clone();
+        // 重新插拔后 USB 地址会变化，已按序列号定位
+        options.usb_location = None;
+    }
+
+    log::info!("快速连接: {} -> {}", options.probe_identifier, options.target);
+    connect_target(options, state, app).await
+}
+
+/// 断开连接
+/// leave_halted 为 true 时不恢复内核运行，保持断开前的暂停状态
+#[tauri::command]
+pub async fn disconnect(leave_halted: Option<bool>, state: State<'_, AppState>) -> AppResult<()> {
+    // 简单地释放session，让probe-rs自动处理清理
+    {
+        let mut session_guard = state.lock_session();
+        if let Some(session) = session_guard.as_mut() {
+            if leave_halted.unwrap_or(false) {
+                log::info!("断开连接时保持内核暂停状态");
+            } else if let Ok(mut core) = session.core(0) {
+                // 尝试让芯片恢复运行（不做复位操作，避免触发probe-rs的bug）
+                let _ = core.run();
+            }
+        }
+        // 释放session，这会自动关闭探针
+        *session_guard = None;
+    }
+
+    // 统一模式下 RTT 随主连接一起断开
+    if state.unified_session.swap(false, std::sync::atomic::Ordering::SeqCst) {
+        state.rtt_state.set_running(false);
+        *state.rtt_connection_info.lock() = None;
+    }
+
+    // 清除连接信息
+    let mut conn_info = state.connection_info.lock();
+    *conn_info = None;
+    *state.connect_options.lock() = None;
+
+    Ok(())
+}
+
+/// 复位目标
+/// halt 为 true 时复位后停在复位向量处，否则复位后直接运行
+#[tauri::command]
+pub async fn reset_target(
+    halt: Option<bool>,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    let core_index = state.core_index(core_index);
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    let mut core = session
+        .core(core_index)
+        .map_err(|e| AppError::ProbeError(e.to_string()))?;
+
+    if halt.unwrap_or(false) {
+        core.reset_and_halt(state.core_timeout(CoreOperation::Reset))
+            .map_err(|e| AppError::ProbeError(format!("复位并暂停失败: {}", e)))?;
+        log::info!("内核 {} 已复位并暂停", core_index);
+    } else {
+        core.reset()
+            .map_err(|e| AppError::ProbeError(format!("复位失败: {}", e)))?;
+        log::info!("内核 {} 已复位", core_index);
+    }
+    Ok(())
+}
+
+/// 获取内核操作 (暂停/单步/复位) 统一超时覆盖，单位毫秒；未设置时各操作使用各自的默认值
+#[tauri::command]
+pub async fn get_core_operation_timeout(state: State<'_, AppState>) -> AppResult<Option<u64>> {
+    Ok(state.settings.lock().core_operation_timeout_ms)
+}
+
+/// 设置内核操作统一超时并保存到配置，慢速目标可调大，调试探针响应快时可调小；传空恢复默认
+#[tauri::command]
+pub async fn set_core_operation_timeout(timeout_ms: Option<u64>, state: State<'_, AppState>) -> AppResult<()> {
+    if let Some(ms) = timeout_ms {
+        if !CORE_OPERATION_TIMEOUT_RANGE_MS.contains(&ms) {
+            return Err(AppError::ConfigError(format!(
+                "内核操作超时需在 {}~{} ms 之间: {}",
+                CORE_OPERATION_TIMEOUT_RANGE_MS.start(),
+                CORE_OPERATION_TIMEOUT_RANGE_MS.end(),
+                ms
+            )));
+        }
+    }
+    let mut config = crate::app_config::load_config();
+    config.core_operation_timeout_ms = timeout_ms;
+    crate::app_config::save_config(&config)?;
+    state.settings.lock().core_operation_timeout_ms = timeout_ms;
+    match timeout_ms {
+        Some(ms) => log::info!("内核操作超时已设置为 {} ms", ms),
+        None => log::info!("内核操作超时已恢复为默认值"),
+    }
+    Ok(())
+}
+
+/// 读取已连接芯片的读保护/调试保护状态（STM32 RDP、GD32 SPC、nRF APPROTECT 等）
+#[tauri::command]
+pub async fn get_protection_status(state: State<'_, AppState>) -> AppResult<ProtectionStatus> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    let target = session.target().name.clone();
+
+    let mut status = ProtectionStatus {
+        target: target.clone(),
+        state: ProtectionState::Unknown,
+        register_address: None,
+        raw_value: None,
+        unprotect_method: None,
+    };
+
+    if lookup_by_prefix(UNPROTECTABLE_FAMILIES, &target).is_some() {
+        status.state = ProtectionState::Unprotected;
+        return Ok(status);
+    }
+
+    let Some((address, scheme)) = lookup_by_prefix(PROTECTION_REGISTERS, &target) else {
+        log::info!("{} 的保护状态寄存器未知", target);
+        return Ok(status);
+    };
+    status.register_address = Some(address);
+    status.unprotect_method = Some(match scheme {
+        ProtectionScheme::NrfApprotect => UnprotectMethod::CtrlApRecover,
+        _ => UnprotectMethod::OptionBytes,
+    });
+
+    let mut core = session.core(0).map_err(|e| AppError::ProbeError(e.to_string()))?;
+    match core.read_word_32(address) {
+        Ok(value) => {
+            status.raw_value = Some(value);
+            status.state = decode_protection(scheme, value);
+            log::info!("{} 保护寄存器 0x{:08X} = 0x{:08X}: {:?}", target, address, value, status.state);
+        }
+        Err(e) => log::warn!("读取保护寄存器 0x{:08X} 失败: {}", address, e),
+    }
+
+    Ok(status)
+}
+
+/// 读取 DBGMCU_CR 并生成低功耗调试状态
+fn read_low_power_debug(session: &mut Session) -> AppResult<LowPowerDebugStatus> {
+    let target = session.target().name.clone();
+    let mut status = LowPowerDebugStatus {
+        target: target.clone(),
+        supported: false,
+        register_address: None,
+        raw_value: None,
+        sleep: None,
+        stop: false,
+        standby: false,
+    };
+
+    let Some(layout) = dbgmcu_layout(&target) else {
+        return Ok(status);
+    };
+    status.supported = true;
+    status.register_address = Some(layout.address);
+
+    let mut core = session.core(0).map_err(|e| AppError::ProbeError(e.to_string()))?;
+    let value = core
+        .read_word_32(layout.address)
+        .map_err(|e| AppError::ProbeError(format!("读取 DBGMCU_CR 失败: {}", e)))?;
+    let (sleep, stop, standby) = decode_low_power_debug(&layout, value);
+    status.raw_value = Some(value);
+    status.sleep = sleep;
+    status.stop = stop;
+    status.standby = standby;
+
+    Ok(status)
+}
+
+/// 读取目标在 Sleep/Stop/Standby 模式下保持调试连接的设置 (STM32 DBGMCU_CR)
+#[tauri::command]
+pub async fn get_debug_in_low_power(state: State<'_, AppState>) -> AppResult<LowPowerDebugStatus> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    read_low_power_debug(session)
+}
+
+/// 设置目标在低功耗模式下保持调试连接，无需固件调用 __HAL_DBGMCU_* 宏
+/// DBGMCU_CR 仅在上电复位时清除，系统复位后设置仍然有效
+#[tauri::command]
+pub async fn set_debug_in_low_power(
+    options: LowPowerDebugOptions,
+    state: State<'_, AppState>,
+) -> AppResult<LowPowerDebugStatus> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    let target = session.target().name.clone();
+
+    let layout = dbgmcu_layout(&target)
+        .ok_or_else(|| AppError::ProbeError(format!("不支持设置 {} 的低功耗调试", target)))?;
+
+    {
+        let mut core = session.core(0).map_err(|e| AppError::ProbeError(e.to_string()))?;
+
+        if let Some((rcc_register, rcc_bit)) = layout.clock_enable {
+            let rcc = core
+                .read_word_32(rcc_register)
+                .map_err(|e| AppError::ProbeError(format!("读取 RCC 寄存器失败: {}", e)))?;
+            core.write_word_32(rcc_register, rcc | (1 << rcc_bit))
+                .map_err(|e| AppError::ProbeError(format!("使能 DBGMCU 时钟失败: {}", e)))?;
+        }
+
+        let value = core
+            .read_word_32(layout.address)
+            .map_err(|e| AppError::ProbeError(format!("读取 DBGMCU_CR 失败: {}", e)))?;
+        let updated = apply_low_power_debug(&layout, value, options.sleep, options.stop, options.standby);
+        core.write_word_32(layout.address, updated)
+            .map_err(|e| AppError::ProbeError(format!("写入 DBGMCU_CR 失败: {}", e)))?;
+        log::info!("{} DBGMCU_CR: 0x{:08X} -> 0x{:08X}", target, value, updated);
+    }
+
+    read_low_power_debug(session)
+}
+
+/// 查询当前探针与目标组合支持的功能（SWO/ITM、硬件断点、目标供电、SWD/JTAG、RTT、Flash 算法）
+#[tauri::command]
+pub async fn get_capabilities(state: State<'_, AppState>) -> AppResult<ConnectionCapabilities> {
+    let options = state.connect_options.lock().clone().ok_or(AppError::NotConnected)?;
+
+    // 探针类型在锁定会话前枚举，连接后探针可能已被占用而不再列出
+    let probes = Lister::new().list_all();
+    let probe_type = select_probe(&probes, &options)
+        .ok()
+        .map(|p| format!("{:?}", p.probe_type()));
+    let probe = probe_type.as_deref().and_then(probe_features_for);
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+
+    let rtt = session
+        .target()
+        .memory_map
+        .iter()
+        .any(|region| matches!(region, probe_rs::config::MemoryRegion::Ram(_)));
+    let flash_algorithms = session.target().flash_algorithms.len();
+
+    let core_index = *state.active_core.lock();
+    let mut core = session.core(core_index).map_err(|e| AppError::ProbeError(e.to_string()))?;
+    let hardware_breakpoints = core
+        .available_breakpoint_units()
+        .map_err(|e| log::warn!("读取硬件断点数量失败: {}", e))
+        .ok();
+    let components = if core.core_type().is_cortex_m() {
+        scan_rom_table(&mut core)
+    } else {
+        Vec::new()
+    };
+    let itm_present = components.contains(&ITM_BASE);
+    let tpiu_present = components.contains(&TPIU_BASE);
+
+    Ok(ConnectionCapabilities {
+        swo: itm_present && tpiu_present && probe.is_none_or(|p| p.swo),
+        probe_type,
+        probe,
+        itm_present,
+        tpiu_present,
+        hardware_breakpoints,
+        rtt,
+        flash: flash_algorithms > 0,
+        flash_algorithms,
+    })
+}
+
+/// 片内 Flash 容量：只统计默认算法（没有默认算法时为全部算法）覆盖的 NVM 区域，
+/// 不计入 QSPI、OTP、选项字节等其他 NVM，与芯片容量寄存器的含义一致
+pub fn internal_flash_size(target: &Target) -> u64 {
+    let has_default = target.flash_algorithms.iter().any(|a| a.default);
+    let ranges: Vec<_> = target
+        .flash_algorithms
+        .iter()
+        .filter(|a| a.default || !has_default)
+        .map(|a| &a.flash_properties.address_range)
+        .collect();
+
+    target
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Nvm(nvm)
+                if !nvm.is_alias
+                    && ranges.iter().any(|r| r.start <= nvm.range.start && nvm.range.end <= r.end) =>
+            {
+                Some(nvm.range.end - nvm.range.start)
+            }
+            _ => None,
+        })
+        .sum()
+}
+
+/// 校验已连接芯片与所选目标定义是否一致（Flash 容量、DEV_ID）
+#[tauri::command]
+pub async fn verify_target_identity(state: State<'_, AppState>) -> AppResult<TargetIdentityCheck> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+
+    let target_name = session.target().name.clone();
+    let declared_flash_size = internal_flash_size(session.target());
+
+    let chip_id = read_chip_id(session);
+    let dev_id = chip_id.map(|id| (id & 0xFFF) as u16);
+    let expected_dev_ids = lookup_by_prefix(EXPECTED_DEV_IDS, &target_name)
+        .map(|ids| ids.to_vec())
+        .unwrap_or_default();
+
+    let detected_flash_kb = match lookup_by_prefix(FLASH_SIZE_REGISTERS, &target_name) {
+        Some(addr) => read_size_register_kb(session, addr)?,
+        None => None,
+    };
+    let detected_flash_size = detected_flash_kb.map(|kb| kb as u64 * 1024);
+
+    let mut warnings = memory_size_warnings(declared_flash_size, detected_flash_kb, 0, None);
+
+    if let Some(dev_id) = dev_id {
+        if !expected_dev_ids.is_empty() && !expected_dev_ids.contains(&dev_id) {
+            warnings.push(format!(
+                "DEV_ID 不一致：芯片为 0x{:03X}，{} 预期为 {}",
+                dev_id,
+                target_name,
+                expected_dev_ids
+                    .iter()
+                    .map(|id| format!("0x{:03X}", id))
+                    .collect::<Vec<_>>()
+                    .join("/")
+            ));
+        }
+    }
+
+    for warning in &warnings {
+        log::warn!("{}", warning);
+    }
+
+    Ok(TargetIdentityCheck {
+        target_name,
+        declared_flash_size,
+        detected_flash_size,
+        chip_id,
+        dev_id,
+        expected_dev_ids,
+        matches: warnings.is_empty(),
+        warnings,
+    })
+}
+
+/// 读取芯片自身记录的 Flash/RAM 容量，与所选目标定义对比
+/// 未收录的系列可显式指定容量寄存器地址（16 位，单位 KB）
+#[tauri::command]
+pub async fn read_onchip_memory_sizes(
+    flash_size_address: Option<u64>,
+    ram_size_address: Option<u64>,
+    state: State<'_, AppState>,
+) -> AppResult<OnchipMemorySizes> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+
+    let target_name = session.target().name.clone();
+    // Flash 与 verify_target_identity 使用同一口径，只统计片内 Flash
+    let declared_flash = internal_flash_size(session.target());
+    let declared_ram: u64 = session
+        .target()
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Ram(r) => Some(r.range.end - r.range.start),
+            _ => None,
+        })
+        .sum();
+
+    let flash_size_address = flash_size_address.or_else(|| lookup_by_prefix(FLASH_SIZE_REGISTERS, &target_name));
+    let ram_size_address = ram_size_address.or_else(|| lookup_by_prefix(RAM_SIZE_REGISTERS, &target_name));
+
+    let flash_kb = match flash_size_address {
+        Some(addr) => read_size_register_kb(session, addr)?,
+        None => None,
+    };
+    let ram_kb = match ram_size_address {
+        Some(addr) => read_size_register_kb(session, addr)?,
+        None => None,
+    };
+
+    let warnings = memory_size_warnings(declared_flash, flash_kb, declared_ram, ram_kb);
+    for warning in &warnings {
+        log::warn!("{}", warning);
+    }
+    log::info!("{} 芯片容量: Flash {:?} KB, RAM {:?} KB", target_name, flash_kb, ram_kb);
+
+    Ok(OnchipMemorySizes {
+        target_name,
+        flash_size_address,
+        flash_kb,
+        declared_flash_kb: declared_flash / 1024,
+        ram_size_address,
+        ram_kb,
+        declared_ram_kb: declared_ram / 1024,
+        matches: warnings.is_empty(),
+        warnings,
+    })
+}
+
+/// SCB CPUID 寄存器地址
+const SCB_CPUID: u64 = 0xE000ED00;
+
+/// 解码后的 Cortex-M CPUID 寄存器
+#[derive(Debug, Clone, Serialize)]
+pub struct CpuidInfo {
+    pub raw: u32,
+    /// 实现者代码（0x41 = ARM）
+    pub implementer: u8,
+    pub implementer_name: String,
+    pub variant: u8,
+    pub architecture: u8,
+    pub part_number: u16,
+    /// 根据部件号解码的内核名称，如 "Cortex-M4"
+    pub core_name: String,
+    pub revision: u8,
+    /// 目标定义声明的内核类型
+    pub declared_core: String,
+}
+
+/// 将 ARM 部件号映射为 Cortex-M 内核名称
+fn cortex_m_part_name(part_number: u16) -> Option<&'static str> {
+    match part_number {
+        0xC20 => Some("Cortex-M0"),
+        0xC60 => Some("Cortex-M0+"),
+        0xC21 => Some("Cortex-M1"),
+        0xC23 => Some("Cortex-M3"),
+        0xC24 => Some("Cortex-M4"),
+        0xC27 => Some("Cortex-M7"),
+        0xD20 => Some("Cortex-M23"),
+        0xD21 => Some("Cortex-M33"),
+        0xD22 => Some("Cortex-M55"),
+        0xD23 => Some("Cortex-M85"),
+        _ => None,
+    }
+}
+
+/// 读取并解析 SCB CPUID 寄存器，确认芯片实际内核类型
+#[tauri::command]
+pub async fn read_cpuid(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<CpuidInfo> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+
+    let index = state.core_index(core_index);
+    let declared_core = session
+        .target()
+        .cores
+        .get(index)
+        .map(|c| format!("{:?}", c.core_type))
+        .unwrap_or_default();
+
+    let mut core = session.core(index).map_err(|e| AppError::ProbeError(e.to_string()))?;
+    let raw = core
+        .read_word_32(SCB_CPUID)
+        .map_err(|e| AppError::ProbeError(format!("读取 CPUID 失败: {}", e)))?;
+
+    if raw == 0 || raw == 0xFFFFFFFF {
+        return Err(AppError::ProbeError(format!("CPUID 值无效: 0x{:08X}", raw)));
+    }
+
+    let implementer = (raw >> 24) as u8;
+    let part_number = ((raw >> 4) & 0xFFF) as u16;
+    let core_name = if implementer == 0x41 {
+        cortex_m_part_name(part_number).map(str::to_string)
+    } else {
+        None
+    }
+    .unwrap_or_else(|| format!("未知内核 (0x{:03X})", part_number));
+
+    log::info!("CPUID: 0x{:08X} ({})，目标定义内核: {}", raw, core_name, declared_core);
+
+    Ok(CpuidInfo {
+        raw,
+        implementer,
+        implementer_name: match implementer {
+            0x41 => "ARM".to_string(),
+            other => format!("0x{:02X}", other),
+        },
+        variant: ((raw >> 20) & 0xF) as u8,
+        architecture: ((raw >> 16) & 0xF) as u8,
+        part_number,
+        core_name,
+        revision: (raw & 0xF) as u8,
+        declared_core,
+    })
+}
+
+/// SWD 连接质量测试结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionQuality {
+    pub iterations: u32,
+    pub successes: u32,
+    /// 读取值与参考 DPIDR 不一致的次数
+    pub mismatches: u32,
+    /// 因传输错误失败的读取次数
+    pub errors: u32,
+    /// WAIT 重试由 probe-rs 内部处理，无法获取
+    pub wait_retries: Option<u32>,
+    pub success_rate: f64,
+    pub average_transfer_us: f64,
+    pub reference_value: Option<u32>,
+    pub first_error: Option<String>,
+}
+
+/// 反复读取 DPIDR，统计错误率和平均传输时间，用于诊断接线/信号质量问题
+#[tauri::command]
+pub async fn test_connection_quality(
+    iterations: Option<u32>,
+    state: State<'_, AppState>,
+) -> AppResult<ConnectionQuality> {
+    let iterations = iterations.unwrap_or(200).clamp(1, 10_000);
+
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    let interface = session
+        .get_arm_interface()
+        .map_err(|e| AppError::ProbeError(format!("仅 ARM 调试接口支持连接质量测试: {}", e)))?;
+
+    let dp_addr = DpAddress::Default;
+    let reg_addr = DpRegisterAddress { address: 0x0, bank: None };
+
+    let mut reference_value = None;
+    let mut successes = 0u32;
+    let mut mismatches = 0u32;
+    let mut errors = 0u32;
+    let mut first_error = None;
+    let mut total_time = std::time::Duration::ZERO;
+
+    for _ in 0..iterations {
+        let started = std::time::Instant::now();
+        let result = interface.read_raw_dp_register(dp_addr, reg_addr);
+        total_time += started.elapsed();
+
+        match result {
+            Ok(value) => match reference_value {
+                None => {
+                    reference_value = Some(value);
+                    successes += 1;
+                }
+                Some(reference) if reference == value => successes += 1,
+                Some(_) => mismatches += 1,
+            },
+            Err(e) => {
+                errors += 1;
+                first_error.get_or_insert_with(|| e.to_string());
+            }
+        }
+    }
+
+    let success_rate = successes as f64 / iterations as f64;
+    let average_transfer_us = total_time.as_secs_f64() * 1_000_000.0 / iterations as f64;
+
+    log::info!(
+        "连接质量测试: {}/{} 成功 ({:.1}%), {} 次数据不一致, {} 次传输错误, 平均 {:.1} us",
+        successes,
+        iterations,
+        success_rate * 100.0,
+        mismatches,
+        errors,
+        average_transfer_us
+    );
+
+    Ok(ConnectionQuality {
+        iterations,
+        successes,
+        mismatches,
+        errors,
+        wait_retries: None,
+        success_rate,
+        average_transfer_us,
+        reference_value,
+        first_error,
+    })
+}
+
+/// DP CTRL/STAT 寄存器地址（bank 0）
+const DP_CTRL_STAT: u8 = 0x4;
+/// MEM-AP 寄存器偏移（ADIv5）
+const AP_CSW: u64 = 0x00;
+const AP_CFG: u64 = 0xF4;
+const AP_BASE: u64 = 0xF8;
+const AP_IDR: u64 = 0xFC;
+
+/// DP CTRL/STAT 寄存器中电源请求/应答与错误标志位的解析结果
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DpCtrlStat {
+    pub csyspwrupack: bool,
+    pub csyspwrupreq: bool,
+    pub cdbgpwrupack: bool,
+    pub cdbgpwrupreq: bool,
+    pub cdbgrstack: bool,
+    pub cdbgrstreq: bool,
+    pub wdataerr: bool,
+    pub readok: bool,
+    pub stickyerr: bool,
+    pub stickycmp: bool,
+    pub stickyorun: bool,
+    pub orundetect: bool,
+    /// 调试域与系统域均已上电应答
+    pub powered: bool,
+    /// 存在粘滞错误标志（需写 ABORT 寄存器清除）
+    pub has_fault: bool,
+}
+
+/// 按 ADIv5 位定义解析 CTRL/STAT 原始值：bit31~26 为电源/复位请求与应答，bit7~0 为读写错误与粘滞标志
+pub fn decode_ctrl_stat(value: u32) -> DpCtrlStat {
+    let bit = |n: u32| value & (1 << n) != 0;
+    let decoded = DpCtrlStat {
+        csyspwrupack: bit(31),
+        csyspwrupreq: bit(30),
+        cdbgpwrupack: bit(29),
+        cdbgpwrupreq: bit(28),
+        cdbgrstack: bit(27),
+        cdbgrstreq: bit(26),
+        wdataerr: bit(7),
+        readok: bit(6),
+        stickyerr: bit(5),
+        stickycmp: bit(4),
+        stickyorun: bit(1),
+        orundetect: bit(0),
+        powered: false,
+        has_fault: false,
+    };
+    DpCtrlStat {
+        powered: decoded.csyspwrupack && decoded.cdbgpwrupack,
+        has_fault: decoded.stickyerr || decoded.stickyorun || decoded.wdataerr,
+        ..decoded
+    }
+}
+
+/// ADIv5 DP/AP 寄存器原始快照，读取失败的寄存器为 None
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugRegisterDump {
+    pub dpidr: Option<u32>,
+    pub ctrl_stat: Option<u32>,
+    pub ctrl_stat_decoded: Option<DpCtrlStat>,
+    pub ap_index: Option<u8>,
+    pub ap_idr: Option<u32>,
+    pub ap_csw: Option<u32>,
+    pub ap_cfg: Option<u32>,
+    /// 读取到的 MEM-AP BASE 寄存器原始值
+    pub ap_base: Option<u32>,
+    /// ROM 表基地址（BASE 去掉格式/存在标志位）
+    pub rom_table_base: Option<u32>,
+    pub errors: Vec<String>,
+}
+
+/// 读取 ADIv5 DP/AP 关键寄存器（DPIDR、CTRL/STAT、CSW、ROM 表基地址），用于诊断连接部分成功但烧录失败等问题
+#[tauri::command]
+pub async fn read_debug_registers(
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<DebugRegisterDump> {
+    let core_index = state.core_index(core_index);
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+    if session.architecture() != probe_rs::Architecture::Arm {
+        return Err(AppError::ProbeError("仅 ARM 目标支持读取调试寄存器".to_string()));
+    }
+    let core_count = session.target().cores.len();
+    if core_index >= core_count {
+        return Err(AppError::ProbeError(format!(
+            "内核编号 {} 超出范围（共 {} 个内核）",
+            core_index, core_count
+        )));
+    }
+
+    let ap = session
+        .target()
+        .cores
+        .get(core_index)
+        .and_then(|core| match &core.core_access_options {
+            CoreAccessOptions::Arm(arm) => Some(arm.ap.clone()),
+            _ => None,
+        });
 
-    log::info!("找到探针: {:?}", probe_info.identifier);
+    let interface = session
+        .get_arm_interface()
+        .map_err(|e| AppError::ProbeError(format!("获取 ARM 调试接口失败: {}", e)))?;
+
+    let dp_addr = DpAddress::Default;
+    let mut errors = Vec::new();
+    let mut read_dp = |name: &str, address: u8, bank: Option<u8>, errors: &mut Vec<String>| {
+        interface
+            .read_raw_dp_register(dp_addr, DpRegisterAddress { address, bank })
+            .map_err(|e| errors.push(format!("{}: {}", name, e)))
+            .ok()
+    };
+    let dpidr = read_dp("DPIDR", 0x0, None, &mut errors);
+    let ctrl_stat = read_dp("CTRL/STAT", DP_CTRL_STAT, Some(0), &mut errors);
+
+    let mut dump = DebugRegisterDump {
+        dpidr,
+        ctrl_stat,
+        ctrl_stat_decoded: ctrl_stat.map(decode_ctrl_stat),
+        ap_index: None,
+        ap_idr: None,
+        ap_csw: None,
+        ap_cfg: None,
+        ap_base: None,
+        rom_table_base: None,
+        errors,
+    };
 
-    let mut probe = probe_info
-        .open()
-        .map_err(|e| {
-            log::error!("打开探针失败: {}", e);
-            AppError::ProbeError(e.to_string())
-        })?;
+    match ap {
+        Some(ApAddress::V1(ap_index)) => {
+            let ap_addr = FullyQualifiedApAddress::v1_with_dp(dp_addr, ap_index);
+            let mut read_ap = |name: &str, offset: u64, errors: &mut Vec<String>| {
+                interface
+                    .read_raw_ap_register(&ap_addr, offset)
+                    .map_err(|e| errors.push(format!("AP{} {}: {}", ap_index, name, e)))
+                    .ok()
+            };
+            dump.ap_index = Some(ap_index);
+            dump.ap_idr = read_ap("IDR", AP_IDR, &mut dump.errors);
+            dump.ap_csw = read_ap("CSW", AP_CSW, &mut dump.errors);
+            dump.ap_cfg = read_ap("CFG", AP_CFG, &mut dump.errors);
+            dump.ap_base = read_ap("BASE", AP_BASE, &mut dump.errors);
+            dump.rom_table_base = dump.ap_base.map(|base| base & 0xFFFF_F000);
+        }
+        Some(_) => dump.errors.push("ADIv6 AP 暂不支持读取原始 AP 寄存器".to_string()),
+        None => dump.errors.push(format!("内核 {} 不是 ARM 内核", core_index)),
+    }
 
-    log::info!("探针已打开");
+    log::info!(
+        "调试寄存器: DPIDR={:08X?} CTRL/STAT={:08X?} CSW={:08X?} BASE={:08X?}, {} 个读取错误",
+        dump.dpidr,
+        dump.ctrl_stat,
+        dump.ap_csw,
+        dump.ap_base,
+        dump.errors.len()
+    );
+    Ok(dump)
+}
 
-    // 设置协议
-    let protocol = match options.interface_type {
-        InterfaceType::Swd => WireProtocol::Swd,
-        InterfaceType::Jtag => WireProtocol::Jtag,
-    };
-    probe
-        .select_protocol(protocol)
-        .map_err(|e| {
-            log::error!("设置协议失败 ({:?}): {}", protocol, e);
-            AppError::ProbeError(e.to_string())
-        })?;
+/// 吞吐量测试默认读取大小
+pub const BENCHMARK_DEFAULT_SIZE: u32 = 64 * 1024;
+
+/// 吞吐量测试单次读取的最大字节数（地址不在已知 RAM 内时同样适用）
+pub const BENCHMARK_MAX_SIZE: u32 = 1024 * 1024;
+
+/// 一次 32 位 SWD 读传输约占用的时钟周期数（请求 8 + 转向 1 + ACK 3 + 数据 32 + 校验 1 + 空闲 1）
+pub const SWD_CLOCKS_PER_WORD: f64 = 46.0;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProbeBenchmarkOptions {
+    /// 读取起始地址（默认使用目标的第一个 RAM 区域）
+    #[serde(default)]
+    pub address: Option<u64>,
+    /// 每次读取的字节数（默认 64KB，最大 1MB，不超出地址所在的 RAM 区域）
+    #[serde(default)]
+    pub size: Option<u32>,
+    /// 每个时钟速度下的读取次数（默认 3）
+    #[serde(default)]
+    pub iterations: Option<u32>,
+    /// 依次测试的时钟速度 (Hz)，为空时只测试当前速度
+    #[serde(default)]
+    pub clock_speeds: Vec<u32>,
+    /// 操作的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
 
-    log::info!("协议已设置: {:?}", protocol);
+/// 单个时钟速度下的吞吐量
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeThroughput {
+    /// 请求的时钟速度 (Hz)，None 表示当前连接速度
+    pub clock_hz: Option<u32>,
+    pub bytes_per_sec: f64,
+    /// 按吞吐量估算的有效 SWD 时钟 (Hz)
+    pub effective_clock_hz: f64,
+    pub best_ms: f64,
+    pub average_ms: f64,
+    /// 该速度下连接或读取失败的原因
+    pub error: Option<String>,
+}
 
-    // 设置时钟速度（前端传递的是Hz，probe-rs需要kHz）
-    if let Some(speed_hz) = options.clock_speed {
-        let speed_khz = speed_hz / 1000;
-        probe
-            .set_speed(speed_khz)
-            .map_err(|e| {
-                log::error!("设置时钟速度失败 ({} kHz): {}", speed_khz, e);
-                AppError::ProbeError(format!("设置时钟速度失败 ({} kHz): {}", speed_khz, e))
-            })?;
-        log::info!("时钟速度已设置: {} kHz", speed_khz);
+impl ProbeThroughput {
+    fn failed(clock_hz: Option<u32>, error: String) -> Self {
+        Self {
+            clock_hz,
+            bytes_per_sec: 0.0,
+            effective_clock_hz: 0.0,
+            best_ms: 0.0,
+            average_ms: 0.0,
+            error: Some(error),
+        }
     }
+}
 
-    // 连接目标
-    log::info!("正在连接目标芯片: {}", options.target);
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeBenchmarkResult {
+    pub address: u64,
+    pub size: u32,
+    pub iterations: u32,
+    pub results: Vec<ProbeThroughput>,
+}
 
-    // 获取自定义 Registry（包含从 Pack 导入的设备）
-    let registry = TARGET_REGISTRY.lock().unwrap();
+/// 吞吐量测试进度事件 (发送到前端)
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeBenchmarkProgressEvent {
+    pub clock_hz: Option<u32>,
+    /// 当前测试的速度序号（从 0 开始）
+    pub step: usize,
+    pub total_steps: usize,
+    /// 当前速度下已完成的读取次数
+    pub iteration: u32,
+    pub iterations: u32,
+}
 
-    let mut session = if options.connect_mode == ConnectMode::UnderReset {
-        log::info!("使用 UnderReset 模式连接");
-        probe
-            .attach_under_reset_with_registry(&options.target, Permissions::default(), &*registry)
-            .map_err(|e| {
-                log::error!("连接目标失败 (UnderReset): {}", e);
-                log::error!("可能的原因:");
-                log::error!("  1. 芯片型号 '{}' 不在 probe-rs 支持列表中", options.target);
-                log::error!("  2. 需要导入对应的 CMSIS-Pack 文件");
-                log::error!("  3. 芯片连接有问题（检查电源、接线）");
-                AppError::ProbeError(format!(
-                    "无法连接到芯片 '{}': {}。请检查: 1) 芯片型号是否正确 2) 是否已导入对应的Pack文件 3) 硬件连接是否正常",
-                    options.target, e
-                ))
-            })?
-    } else {
-        log::info!("使用 Normal 模式连接");
-        probe
-            .attach_with_registry(&options.target, Permissions::default(), &*registry)
-            .map_err(|e| {
-                log::error!("连接目标失败 (Normal): {}", e);
-                log::error!("可能的原因:");
-                log::error!("  1. 芯片型号 '{}' 不在 probe-rs 支持列表中", options.target);
-                log::error!("  2. 需要导入对应的 CMSIS-Pack 文件");
-                log::error!("  3. 芯片连接有问题（检查电源、接线）");
-                AppError::ProbeError(format!(
-                    "无法连接到芯片 '{}': {}。请检查: 1) 芯片型号是否正确 2) 是否已导入对应的Pack文件 3) 硬件连接是否正常",
-                    options.target, e
-                ))
-            })?
-    };
+/// 由每次读取的耗时计算吞吐量（按平均耗时）
+pub fn throughput_from_durations(clock_hz: Option<u32>, size: u32, durations: &[Duration]) -> ProbeThroughput {
+    if durations.is_empty() {
+        return ProbeThroughput::failed(clock_hz, "没有完成任何读取".to_string());
+    }
 
-    // 释放 registry 锁
-    drop(registry);
+    let total: Duration = durations.iter().sum();
+    let average = total.as_secs_f64() / durations.len() as f64;
+    let best = durations.iter().min().copied().unwrap_or_default().as_secs_f64();
+    let bytes_per_sec = if average > 0.0 { size as f64 / average } else { 0.0 };
+
+    ProbeThroughput {
+        clock_hz,
+        bytes_per_sec,
+        effective_clock_hz: bytes_per_sec / 4.0 * SWD_CLOCKS_PER_WORD,
+        best_ms: best * 1000.0,
+        average_ms: average * 1000.0,
+        error: None,
+    }
+}
 
-    log::info!("✓ 成功连接到目标芯片");
+/// 计算吞吐量测试的读取大小：限制在 [4, BENCHMARK_MAX_SIZE] 内，且不超出地址所在的 RAM 区域
+pub fn benchmark_read_size(requested: Option<u32>, address: u64, ram: Option<&std::ops::Range<u64>>) -> u32 {
+    let size = requested.unwrap_or(BENCHMARK_DEFAULT_SIZE).clamp(4, BENCHMARK_MAX_SIZE);
+    match ram {
+        Some(ram) => size.min((ram.end - address).min(u32::MAX as u64) as u32),
+        None => size,
+    }
+}
 
-    // 读取芯片ID（DBGMCU_IDCODE）
-    let chip_id = read_chip_id(&mut session);
-    if let Some(id) = chip_id {
-        log::info!("芯片ID (DBGMCU_IDCODE): 0x{:08X}", id);
-    } else {
-        log::warn!("无法读取芯片ID");
+/// 反复读取同一内存块，返回每次耗时
+fn measure_read_durations(
+    session: &mut Session,
+    core_index: usize,
+    address: u64,
+    size: u32,
+    iterations: u32,
+    mut on_iteration: impl FnMut(u32),
+) -> AppResult<Vec<Duration>> {
+    let mut core = session
+        .core(core_index)
+        .map_err(|e| AppError::MemoryError(e.to_string()))?;
+    let mut data = vec![0u8; size as usize];
+
+    let mut durations = Vec::with_capacity(iterations as usize);
+    for iteration in 0..iterations {
+        let started = std::time::Instant::now();
+        crate::commands::memory::read_unaligned(&mut core, address, &mut data)
+            .map_err(|e| AppError::MemoryError(e.to_string()))?;
+        durations.push(started.elapsed());
+        on_iteration(iteration + 1);
     }
+    Ok(durations)
+}
 
-    // 读取 DP IDCODE (DPIDR) - 调试端口标识码
-    let target_idcode = read_dp_idcode(&mut session);
-    if let Some(id) = target_idcode {
-        log::info!("调试端口ID (DPIDR): 0x{:08X}", id);
-    } else {
-        log::warn!("无法读取调试端口ID");
+/// 测量探针实际读取吞吐量，可选依次切换多个时钟速度测试，结束后恢复原连接速度
+#[tauri::command]
+pub async fn benchmark_probe(
+    options: ProbeBenchmarkOptions,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> AppResult<ProbeBenchmarkResult> {
+    let iterations = options.iterations.unwrap_or(3).clamp(1, 100);
+    let core_index = state.core_index(options.core_index);
+    let sweep = !options.clock_speeds.is_empty();
+
+    // 切换时钟需要重新附加目标，UnderReset 模式下每次附加都会复位目标
+    let under_reset = state
+        .connect_options
+        .lock()
+        .as_ref()
+        .is_some_and(|o| o.connect_mode == ConnectMode::UnderReset);
+    if sweep && under_reset {
+        return Err(AppError::ProbeError(
+            "复位下连接时切换时钟会反复复位目标，请使用普通连接模式测试多个时钟速度".to_string(),
+        ));
     }
 
-    // 获取目标信息
-    let target = session.target();
-    log::info!("目标芯片名称: {}", target.name);
-    log::info!("核心类型: {:?}", target.cores.first().map(|c| c.core_type));
-    log::info!("内存区域数量: {}", target.memory_map.len());
-    log::info!("Flash算法数量: {}", target.flash_algorithms.len());
+    // 切换时钟需要重新附加目标，期间让轮询任务让出 session
+    let mut session_guard = if sweep {
+        state.lock_session_for_flash()
+    } else {
+        state.lock_session()
+    };
 
-    let target_info = TargetInfo {
-        name: target.name.clone(),
-        core_type: format!("{:?}", target.cores.first().map(|c| c.core_type)),
-        memory_regions: target
+    let (address, size) = {
+        let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+        let ram_regions: Vec<_> = session
+            .target()
             .memory_map
             .iter()
-            .map(|region| {
-                let (name, kind, address, size) = match region {
-                    probe_rs::config::MemoryRegion::Ram(r) => {
-                        (r.name.clone().unwrap_or_default(), "RAM", r.range.start, r.range.end - r.range.start)
-                    }
-                    probe_rs::config::MemoryRegion::Nvm(r) => {
-                        (r.name.clone().unwrap_or_default(), "Flash", r.range.start, r.range.end - r.range.start)
-                    }
-                    probe_rs::config::MemoryRegion::Generic(r) => {
-                        (r.name.clone().unwrap_or_default(), "Generic", r.range.start, r.range.end - r.range.start)
-                    }
-                };
-                MemoryRegion {
-                    name,
-                    kind: kind.to_string(),
-                    address,
-                    size,
-                }
+            .filter_map(|region| match region {
+                probe_rs::config::MemoryRegion::Ram(ram) => Some(ram.range.clone()),
+                _ => None,
             })
-            .collect(),
-        flash_algorithms: target
-            .flash_algorithms
-            .iter()
-            .map(|a| a.name.clone())
-            .collect(),
-        chip_id,
+            .collect();
+
+        let address = options
+            .address
+            .or_else(|| ram_regions.first().map(|r| r.start))
+            .ok_or_else(|| AppError::ProbeError("目标没有 RAM 区域，请指定测试地址".to_string()))?;
+        let ram = ram_regions.iter().find(|r| r.contains(&address));
+        (address, benchmark_read_size(options.size, address, ram))
     };
 
-    // 存储连接信息
-    {
-        let mut conn_info = state.connection_info.lock();
-        *conn_info = Some(ConnectionInfo {
-            probe_name: options.probe_identifier.clone(),
-            probe_serial: probe_info.serial_number.clone(),  // 保存探针序列号
-            target_name: options.target.clone(),
-            core_type: target_info.core_type.clone(),
-            chip_id,
-            target_idcode,  // 保存目标IDCODE
+    let original = state.connect_options.lock().clone();
+    let speeds: Vec<Option<u32>> = if sweep {
+        options.clock_speeds.iter().map(|&hz| Some(hz)).collect()
+    } else {
+        vec![None]
+    };
+    let total_steps = speeds.len();
+
+    let mut results = Vec::with_capacity(total_steps);
+    for (step, &clock_hz) in speeds.iter().enumerate() {
+        if let Some(hz) = clock_hz {
+            let mut connect_options = original.clone().ok_or(AppError::NotConnected)?;
+            connect_options.clock_speed = Some(hz);
+
+            let registry = TARGET_REGISTRY.lock().unwrap();
+            *session_guard = None;
+            match reattach_session(&connect_options, &registry) {
+                Ok(session) => *session_guard = Some(session),
+                Err(e) => {
+                    log::warn!("以 {} Hz 重新连接失败: {}", hz, e);
+                    results.push(ProbeThroughput::failed(clock_hz, e.to_string()));
+                    continue;
+                }
+            }
+        }
+
+        let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+        let measured = measure_read_durations(session, core_index, address, size, iterations, |iteration| {
+            let _ = app.emit(
+                "probe-benchmark-progress",
+                ProbeBenchmarkProgressEvent {
+                    clock_hz,
+                    step,
+                    total_steps,
+                    iteration,
+                    iterations,
+                },
+            );
         });
+
+        let throughput = match measured {
+            Ok(durations) => throughput_from_durations(clock_hz, size, &durations),
+            Err(e) => ProbeThroughput::failed(clock_hz, e.to_string()),
+        };
+        log::info!(
+            "吞吐量测试 {}: {:.1} KB/s, 估算有效时钟 {:.0} kHz",
+            clock_hz.map_or("当前速度".to_string(), |hz| format!("{} Hz", hz)),
+            throughput.bytes_per_sec / 1024.0,
+            throughput.effective_clock_hz / 1000.0
+        );
+        results.push(throughput);
     }
 
-    // 存储session
-    {
-        let mut session_guard = state.session.lock();
-        *session_guard = Some(session);
+    // 恢复原连接速度，失败时清除连接状态并通知前端
+    if sweep {
+        if let Some(ref connect_options) = original {
+            let registry = TARGET_REGISTRY.lock().unwrap();
+            *session_guard = None;
+            restore_session(state.inner(), &mut session_guard, connect_options, &registry, &app)
+                .map_err(|e| AppError::ProbeError(format!("恢复原时钟速度失败: {}", e)))?;
+        }
     }
 
-    log::info!("=== 连接完成 ===");
+    Ok(ProbeBenchmarkResult {
+        address,
+        size,
+        iterations,
+        results,
+    })
+}
 
-    Ok(target_info)
+/// 内核信息
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreInfo {
+    pub index: usize,
+    pub name: String,
+    pub core_type: String,
+    pub active: bool,
 }
 
+/// 列出当前连接目标的所有内核
 #[tauri::command]
-pub async fn disconnect(state: State<'_, AppState>) -> AppResult<()> {
-    // 简单地释放session，让probe-rs自动处理清理
-    {
-        let mut session_guard = state.session.lock();
-        if let Some(session) = session_guard.as_mut() {
-            // 尝试让芯片恢复运行（不做复位操作，避免触发probe-rs的bug）
-            if let Ok(mut core) = session.core(0) {
-                let _ = core.run();
-            }
-        }
-        // 释放session，这会自动关闭探针
-        *session_guard = None;
+pub async fn list_cores(state: State<'_, AppState>) -> AppResult<Vec<CoreInfo>> {
+    let session_guard = state.lock_session();
+    let session = session_guard.as_ref().ok_or(AppError::NotConnected)?;
+    let active = *state.active_core.lock();
+
+    Ok(session
+        .target()
+        .cores
+        .iter()
+        .enumerate()
+        .map(|(index, core)| CoreInfo {
+            index,
+            name: core.name.clone(),
+            core_type: format!("{:?}", core.core_type),
+            active: index == active,
+        })
+        .collect())
+}
+
+/// 设置默认操作的内核（未显式指定 core_index 的命令使用）
+#[tauri::command]
+pub async fn set_active_core(core_index: usize, state: State<'_, AppState>) -> AppResult<()> {
+    let session_guard = state.lock_session();
+    let session = session_guard.as_ref().ok_or(AppError::NotConnected)?;
+
+    let core_count = session.target().cores.len();
+    if core_index >= core_count {
+        return Err(AppError::ProbeError(format!(
+            "内核编号 {} 超出范围（共 {} 个内核）",
+            core_index, core_count
+        )));
     }
 
-    // 清除连接信息
-    let mut conn_info = state.connection_info.lock();
-    *conn_info = None;
+    *state.active_core.lock() = core_index;
+    log::info!("活动内核切换为 #{}", core_index);
 
     Ok(())
 }
@@ -570,7 +2818,7 @@ pub struct ConnectionStatus {
 
 #[tauri::command]
 pub async fn get_connection_status(state: State<'_, AppState>) -> AppResult<ConnectionStatus> {
-    let session_guard = state.session.lock();
+    let session_guard = state.lock_session();
     let connected = session_guard.is_some();
 
     let conn_info = state.connection_info.lock();
@@ -590,20 +2838,16 @@ pub async fn connect_rtt(
 ) -> AppResult<TargetInfo> {
     // 关闭现有 RTT 连接
     {
-        let mut rtt_session_guard = state.rtt_session.lock();
+        let mut rtt_session_guard = SessionGuard::lock(&state.rtt_session);
         *rtt_session_guard = None;
     }
 
     let lister = Lister::new();
     let probes = lister.list_all();
 
-    let probe_info = probes
-        .iter()
-        .find(|p| p.identifier == options.probe_identifier)
-        .ok_or_else(|| AppError::ProbeError("未找到指定的探针".to_string()))?;
+    let probe_info = apply_dap_version(select_probe(&probes, &options)?, options.force_dap_version)?;
 
-    let mut probe = probe_info
-        .open()
+    let mut probe = open_probe_with_retry(&probe_info)
         .map_err(|e| AppError::ProbeError(e.to_string()))?;
 
     // 设置协议
@@ -626,14 +2870,15 @@ pub async fn connect_rtt(
     // 连接目标
     // 获取自定义 Registry（包含从 Pack 导入的设备）
     let registry = TARGET_REGISTRY.lock().unwrap();
+    let target_selector = build_target_selector(&options, &registry)?;
 
     let mut session = if options.connect_mode == ConnectMode::UnderReset {
         probe
-            .attach_under_reset_with_registry(&options.target, Permissions::default(), &*registry)
+            .attach_under_reset_with_registry(target_selector, Permissions::default(), &registry)
             .map_err(|e| AppError::ProbeError(e.to_string()))?
     } else {
         probe
-            .attach_with_registry(&options.target, Permissions::default(), &*registry)
+            .attach_with_registry(target_selector, Permissions::default(), &registry)
             .map_err(|e| AppError::ProbeError(e.to_string()))?
     };
 
@@ -646,6 +2891,10 @@ pub async fn connect_rtt(
     // 读取 DP IDCODE (DPIDR) - 调试端口标识码
     let target_idcode = read_dp_idcode(&mut session);
 
+    let reset_reason = read_reset_reason(&mut session, &options);
+
+    let clock_recommendation = check_clock_speed(&mut session, options.clock_speed);
+
     // 获取目标信息
     let target = session.target();
     let target_info = TargetInfo {
@@ -680,6 +2929,8 @@ pub async fn connect_rtt(
             .map(|a| a.name.clone())
             .collect(),
         chip_id,
+        clock_recommendation,
+        reset_reason: reset_reason.clone(),
     };
 
     // 存储 RTT 连接信息
@@ -692,12 +2943,14 @@ pub async fn connect_rtt(
             core_type: target_info.core_type.clone(),
             chip_id,
             target_idcode,
+            rtt_control_block: None,
+            reset_reason,
         });
     }
 
     // 存储 RTT session
     {
-        let mut rtt_session_guard = state.rtt_session.lock();
+        let mut rtt_session_guard = SessionGuard::lock(&state.rtt_session);
         *rtt_session_guard = Some(session);
     }
 
@@ -709,9 +2962,15 @@ pub async fn disconnect_rtt(state: State<'_, AppState>) -> AppResult<()> {
     // 停止 RTT
     state.rtt_state.set_running(false);
 
+    // 统一模式下只退出 RTT，保留主连接
+    if state.unified_session.swap(false, std::sync::atomic::Ordering::SeqCst) {
+        *state.rtt_connection_info.lock() = None;
+        return Ok(());
+    }
+
     // 释放 RTT session
     {
-        let mut rtt_session_guard = state.rtt_session.lock();
+        let mut rtt_session_guard = SessionGuard::lock(&state.rtt_session);
         if let Some(session) = rtt_session_guard.as_mut() {
             if let Ok(mut core) = session.core(0) {
                 let _ = core.run();
@@ -727,9 +2986,33 @@ pub async fn disconnect_rtt(state: State<'_, AppState>) -> AppResult<()> {
     Ok(())
 }
 
+/// 统一模式连接：只打开一次探针，烧录/内存操作与 RTT 共用同一个 session
+/// 适用于无法被同时打开两次的单接口探针
+#[tauri::command]
+pub async fn connect_unified(
+    options: ConnectOptions,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> AppResult<TargetInfo> {
+    // 关闭独立的 RTT 连接，释放探针
+    state.rtt_state.set_running(false);
+    *SessionGuard::lock(&state.rtt_session) = None;
+    *state.rtt_connection_info.lock() = None;
+
+    let target_info = connect_target(options, state.clone(), app).await?;
+
+    state.unified_session.store(true, std::sync::atomic::Ordering::SeqCst);
+    *state.rtt_connection_info.lock() = state.connection_info.lock().clone();
+
+    log::info!("已进入统一连接模式，RTT 复用主连接");
+
+    Ok(target_info)
+}
+
 #[tauri::command]
 pub async fn get_rtt_connection_status(state: State<'_, AppState>) -> AppResult<ConnectionStatus> {
-    let rtt_session_guard = state.rtt_session.lock();
+    let rtt_session = state.rtt_session_handle();
+    let rtt_session_guard = SessionGuard::lock(&rtt_session);
     let connected = rtt_session_guard.is_some();
 
     let rtt_conn_info = state.rtt_connection_info.lock();
@@ -906,3 +3189,295 @@ pub async fn install_udev_rules() -> AppResult<String> {
 pub async fn get_udev_install_instructions() -> AppResult<String> {
     Ok(crate::udev::get_manual_install_instructions())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use probe_rs::config::{MemoryRegion, Registry};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    // 功能查询 (ROM 表解析、探针功能表) 测试
+    #[test]
+    fn test_cortex_m4_rom_table() {
+        // SCS, DWT, FPB, ITM, TPIU, ETM，随后的 0 结束扫描
+        let entries = [
+            0xFFF0F003, 0xFFF02003, 0xFFF03003, 0xFFF01003, 0xFFF41003, 0xFFF42002, 0x00000000, 0xFFF43003,
+        ];
+        let components = decode_rom_table_entries(0xE00FF000, &entries);
+        assert_eq!(components, vec![0xE000E000, 0xE0001000, 0xE0002000, 0xE0000000, 0xE0040000]);
+    }
+
+    #[test]
+    fn test_probe_features_by_type() {
+        let stlink = probe_features_for("StLink").unwrap();
+        assert!(stlink.swd && stlink.swo && !stlink.target_power);
+        assert!(!probe_features_for("EspJtag").unwrap().swd);
+        assert!(probe_features_for("UnknownProbe").is_none());
+    }
+
+    // 调试时钟建议测试
+    #[test]
+    fn test_fast_swd_on_reset_clock_is_flagged() {
+        let recommendation = clock_recommendation(10_000_000, 8_000_000).unwrap();
+
+        assert_eq!(recommendation.recommended_hz, 8_000_000 / SAFE_CORE_CLOCK_DIVISOR);
+        assert_eq!(recommendation.core_clock_hz, 8_000_000);
+    }
+
+    #[test]
+    fn test_safe_swd_clock_is_accepted() {
+        assert!(clock_recommendation(1_000_000, 8_000_000).is_none());
+        assert!(clock_recommendation(4_000_000, 168_000_000).is_none());
+    }
+
+    // DP CTRL/STAT 寄存器解码测试
+    #[test]
+    fn test_powered_without_fault() {
+        // 调试域/系统域上电请求与应答均置位
+        let decoded = decode_ctrl_stat(0xF000_0000);
+        assert!(decoded.csyspwrupack && decoded.csyspwrupreq);
+        assert!(decoded.cdbgpwrupack && decoded.cdbgpwrupreq);
+        assert!(!decoded.cdbgrstack && !decoded.cdbgrstreq);
+        assert!(decoded.powered);
+        assert!(!decoded.has_fault);
+    }
+
+    #[test]
+    fn test_power_request_without_ack() {
+        let decoded = decode_ctrl_stat(0x5000_0000);
+        assert!(decoded.csyspwrupreq && decoded.cdbgpwrupreq);
+        assert!(!decoded.powered);
+    }
+
+    #[test]
+    fn test_sticky_fault_bits() {
+        let decoded = decode_ctrl_stat(0xF000_0020);
+        assert!(decoded.stickyerr);
+        assert!(decoded.has_fault);
+
+        let decoded = decode_ctrl_stat(0x0000_0003);
+        assert!(decoded.stickyorun && decoded.orundetect);
+        assert!(decoded.has_fault);
+
+        let decoded = decode_ctrl_stat(0x0000_0080);
+        assert!(decoded.wdataerr);
+        assert!(decoded.has_fault);
+
+        // READOK/STICKYCMP 不属于错误
+        let decoded = decode_ctrl_stat(0x0000_0050);
+        assert!(decoded.readok && decoded.stickycmp);
+        assert!(!decoded.has_fault);
+    }
+
+    // 片内 Flash 容量统计测试
+    #[test]
+    fn test_other_nvm_regions_are_not_counted() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack_targets.yaml");
+        let mut registry = Registry::from_builtin_families();
+        registry
+            .add_target_family_from_yaml(&std::fs::read_to_string(path).unwrap())
+            .unwrap();
+
+        let mut target = registry.get_target_by_name("TEST32F1CB").unwrap();
+        assert_eq!(internal_flash_size(&target), 0x20000);
+
+        // 追加一个没有算法覆盖的 OTP 区域，不应计入片内 Flash
+        let otp = target
+            .memory_map
+            .iter()
+            .find_map(|region| match region {
+                MemoryRegion::Nvm(nvm) => {
+                    let mut otp = nvm.clone();
+                    otp.range = 0x1FFF_7800..0x1FFF_7A10;
+                    Some(MemoryRegion::Nvm(otp))
+                }
+                _ => None,
+            })
+            .unwrap();
+        target.memory_map.push(otp);
+        assert_eq!(internal_flash_size(&target), 0x20000);
+    }
+
+    // 低功耗调试 (DBGMCU_CR) 位操作测试
+    #[test]
+    fn test_enable_all_on_stm32f4() {
+        let layout = dbgmcu_layout("STM32F407VGTx").unwrap();
+        assert_eq!(layout.address, 0xE0042004);
+
+        let value = apply_low_power_debug(&layout, 0x0000_0020, Some(true), Some(true), Some(true));
+        assert_eq!(value, 0x0000_0027);
+        assert_eq!(decode_low_power_debug(&layout, value), (Some(true), true, true));
+    }
+
+    #[test]
+    fn test_apb_dbgmcu_needs_clock_and_has_no_sleep_bit() {
+        let layout = dbgmcu_layout("STM32G071RBTx").unwrap();
+        assert_eq!(layout.address, 0x40015804);
+        assert!(layout.clock_enable.is_some());
+
+        // 没有 DBG_SLEEP 位的系列忽略 sleep 请求
+        let value = apply_low_power_debug(&layout, 0, Some(true), Some(true), None);
+        assert_eq!(value, 0b010);
+        assert_eq!(decode_low_power_debug(&layout, value), (None, true, false));
+    }
+
+    #[test]
+    fn test_disable_leaves_other_bits() {
+        let layout = dbgmcu_layout("STM32H743ZITx").unwrap();
+        let value = apply_low_power_debug(&layout, 0x0060_0007, None, Some(false), None);
+        assert_eq!(value, 0x0060_0005);
+        assert!(dbgmcu_layout("nRF52840_xxAA").is_none());
+    }
+
+    // 芯片容量对比测试
+    #[test]
+    fn test_matching_sizes_have_no_warnings() {
+        assert!(memory_size_warnings(512 * 1024, Some(512), 128 * 1024, None).is_empty());
+        assert!(memory_size_warnings(512 * 1024, None, 128 * 1024, None).is_empty());
+    }
+
+    #[test]
+    fn test_declared_flash_larger_than_silicon_is_flagged() {
+        let warnings = memory_size_warnings(1024 * 1024, Some(512), 0, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("超出"));
+    }
+
+    #[test]
+    fn test_ram_only_flagged_when_declared_exceeds_silicon() {
+        assert!(memory_size_warnings(0, None, 64 * 1024, Some(256)).is_empty());
+        assert_eq!(memory_size_warnings(0, None, 256 * 1024, Some(64)).len(), 1);
+    }
+
+    // 探针吞吐量计算测试
+    #[test]
+    fn test_throughput_uses_average_duration() {
+        let durations = [Duration::from_millis(100), Duration::from_millis(300)];
+        let throughput = throughput_from_durations(Some(4_000_000), 65536, &durations);
+
+        assert_eq!(throughput.clock_hz, Some(4_000_000));
+        assert!((throughput.average_ms - 200.0).abs() < 1e-6);
+        assert!((throughput.best_ms - 100.0).abs() < 1e-6);
+        assert!((throughput.bytes_per_sec - 327_680.0).abs() < 1e-3);
+        assert!((throughput.effective_clock_hz - 327_680.0 / 4.0 * SWD_CLOCKS_PER_WORD).abs() < 1e-3);
+        assert!(throughput.error.is_none());
+    }
+
+    #[test]
+    fn test_no_reads_reports_error() {
+        let throughput = throughput_from_durations(None, 1024, &[]);
+
+        assert_eq!(throughput.bytes_per_sec, 0.0);
+        assert!(throughput.error.is_some());
+    }
+
+    #[test]
+    fn test_read_size_is_bounded() {
+        let ram = 0x2000_0000..0x2000_5000;
+        assert_eq!(benchmark_read_size(None, 0x2000_0000, Some(&ram)), 0x5000);
+        assert_eq!(benchmark_read_size(Some(1), 0x2000_0000, Some(&ram)), 4);
+        // 不在已知 RAM 内的地址同样受上限约束
+        assert_eq!(benchmark_read_size(None, 0x6000_0000, None), BENCHMARK_DEFAULT_SIZE);
+        assert_eq!(benchmark_read_size(Some(u32::MAX), 0x6000_0000, None), BENCHMARK_MAX_SIZE);
+    }
+
+    // 探针打开重试判定测试
+    #[test]
+    fn test_busy_errors_are_transient() {
+        assert!(is_transient_open_error("USB error: Resource busy"));
+        assert!(is_transient_open_error("Failed to claim interface 0"));
+        assert!(is_transient_open_error("Device or resource temporarily unavailable"));
+    }
+
+    #[test]
+    fn test_permission_errors_are_not_retried() {
+        assert!(!is_transient_open_error("Permission denied (os error 13)"));
+        assert!(!is_transient_open_error("Failed to claim interface: Access denied"));
+        assert!(!is_transient_open_error("Probe not found"));
+    }
+
+    // 探针序列号冲突检测测试
+    #[test]
+    fn test_identical_serials_conflict() {
+        let keys = vec![
+            (0x0483, 0x3748, Some("CLONE001".to_string())),
+            (0x0483, 0x3748, Some("CLONE001".to_string())),
+            (0x0483, 0x3748, Some("REAL0002".to_string())),
+        ];
+        assert_eq!(serial_conflicts(&keys), vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_empty_and_missing_serials_conflict() {
+        let keys = vec![
+            (0xC251, 0xF001, None),
+            (0xC251, 0xF001, Some(String::new())),
+            (0x1366, 0x0101, None),
+        ];
+        assert_eq!(serial_conflicts(&keys), vec![true, true, false]);
+    }
+
+    #[test]
+    fn test_hid_and_v2_entries_map_to_same_device() {
+        let keys = vec![
+            (0xC251, 0xF001, Some("A".to_string()), true),
+            (0xC251, 0xF001, Some("A".to_string()), false),
+            (0xC251, 0xF001, Some("A".to_string()), true),
+            (0xC251, 0xF001, Some("A".to_string()), false),
+        ];
+        assert_eq!(usb_device_occurrences(&keys), vec![0, 0, 1, 1]);
+    }
+
+    // 读保护状态解码测试
+    #[test]
+    fn test_stm32_rdp_byte_levels() {
+        let scheme = ProtectionScheme::RdpByte(8);
+
+        assert_eq!(decode_protection(scheme, 0x0FFF_AAED), ProtectionState::Unprotected);
+        assert_eq!(decode_protection(scheme, 0x0FFF_55ED), ProtectionState::ReadProtected);
+        assert_eq!(decode_protection(scheme, 0x0FFF_CCED), ProtectionState::DebugLocked);
+    }
+
+    #[test]
+    fn test_stm32f1_obr_bit() {
+        assert_eq!(decode_protection(ProtectionScheme::ObrBit, 0x03FF_FFFC), ProtectionState::Unprotected);
+        assert_eq!(decode_protection(ProtectionScheme::ObrBit, 0x03FF_FFFE), ProtectionState::ReadProtected);
+    }
+
+    #[test]
+    fn test_nrf_approtect() {
+        assert_eq!(decode_protection(ProtectionScheme::NrfApprotect, 0xFFFF_FFFF), ProtectionState::Unprotected);
+        assert_eq!(decode_protection(ProtectionScheme::NrfApprotect, 0x0000_005A), ProtectionState::Unprotected);
+        assert_eq!(decode_protection(ProtectionScheme::NrfApprotect, 0xFFFF_FF00), ProtectionState::DebugLocked);
+    }
+
+    // 复位原因解码测试
+    #[test]
+    fn test_pin_flag_hidden_behind_internal_reset() {
+        let layout = reset_cause_layout("STM32F407VGTx").unwrap();
+        assert_eq!(layout.address, 0x40023874);
+
+        // 独立看门狗复位同时置位 PINRSTF
+        let value = (1 << 29) | (1 << 26);
+        assert_eq!(decode_reset_reason(&layout, value).as_deref(), Some("独立看门狗复位"));
+        // 只有引脚标志时为外部引脚复位
+        assert_eq!(decode_reset_reason(&layout, 1 << 26).as_deref(), Some("引脚复位"));
+        assert_eq!(decode_reset_reason(&layout, 0), None);
+    }
+
+    #[test]
+    fn test_multiple_causes_and_nrf_power_on() {
+        let layout = reset_cause_layout("STM32F103C8").unwrap();
+        let value = (1 << 28) | (1 << 27) | (1 << 26);
+        assert_eq!(decode_reset_reason(&layout, value).as_deref(), Some("软件复位、上电/掉电复位"));
+
+        let nrf = reset_cause_layout("nRF52840_xxAA").unwrap();
+        assert_eq!(decode_reset_reason(&nrf, 0).as_deref(), Some("上电复位"));
+        assert_eq!(decode_reset_reason(&nrf, 1 << 1).as_deref(), Some("看门狗复位"));
+        assert!(reset_cause_layout("ATSAMD21G18A").is_none());
+    }
+}
@@ -0,0 +1,383 @@
+use crate::commands::flash::{flash_firmware, verify_firmware, FlashOptions};
+use crate::commands::rtt::RttLineMatcher;
+use crate::error::{AppError, AppResult};
+use crate::state::{AppState, CoreOperation, SessionGuard};
+use probe_rs::{CoreStatus, MemoryInterface, RegisterId};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, State, Window};
+
+/// 可配置故障状态寄存器 (UFSR/BFSR/MMFSR)
+const SCB_CFSR: u64 = 0xE000_ED28;
+/// 硬故障状态寄存器
+const SCB_HFSR: u64 = 0xE000_ED2C;
+/// xPSR 寄存器编号
+const REG_XPSR: u16 = 16;
+
+/// 启动输出在步骤信息中保留的最大字节数
+const BOOT_OUTPUT_LIMIT: usize = 2048;
+
+/// 量产烧录的启动检查选项
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProductionCheckOptions {
+    /// 捕获启动 RTT 输出的通道（默认 0，RTT 未运行时跳过捕获）
+    #[serde(default)]
+    pub rtt_channel: Option<usize>,
+    /// 捕获启动输出的时长 (毫秒，默认 1000)；设置了 boot_pattern 时为等待匹配的超时
+    #[serde(default)]
+    pub boot_capture_ms: Option<u64>,
+    /// 启动输出中必须出现的正则，未设置时只采集不判定
+    #[serde(default)]
+    pub boot_pattern: Option<String>,
+    /// 复位后等待多久做启动健康检查 (毫秒，默认 500)
+    #[serde(default)]
+    pub health_check_delay_ms: Option<u64>,
+}
+
+/// 单个检查步骤的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductionStep {
+    pub name: String,
+    pub passed: bool,
+    /// 前置条件不满足而跳过（不影响整体结果）
+    pub skipped: bool,
+    pub message: String,
+    pub elapsed_ms: u64,
+}
+
+/// 量产烧录结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductionFlashResult {
+    pub passed: bool,
+    pub steps: Vec<ProductionStep>,
+}
+
+/// 按故障寄存器判断启动是否健康，返回故障描述
+/// cfsr/hfsr 为 None 表示内核没有这些寄存器 (Armv6-M)，只按当前异常号判断
+pub fn evaluate_boot_health(cfsr: Option<u32>, hfsr: Option<u32>, ipsr: u32) -> Result<(), String> {
+    let mut faults = Vec::new();
+
+    if let Some(cfsr) = cfsr.filter(|&v| v != 0) {
+        let parts = [
+            (cfsr & 0xFF, "MemManage"),
+            ((cfsr >> 8) & 0xFF, "BusFault"),
+            ((cfsr >> 16) & 0xFFFF, "UsageFault"),
+        ];
+        for (bits, name) in parts {
+            if bits != 0 {
+                faults.push(format!("{} (0x{:X})", name, bits));
+            }
+        }
+    }
+    if let Some(hfsr) = hfsr.filter(|&v| v != 0) {
+        faults.push(format!("HardFault (HFSR=0x{:08X})", hfsr));
+    }
+    // 异常号 2-6 为 NMI/HardFault/MemManage/BusFault/UsageFault
+    if (2..=6).contains(&ipsr) {
+        faults.push(format!("内核停留在故障处理程序中 (异常号 {})", ipsr));
+    }
+
+    if faults.is_empty() {
+        Ok(())
+    } else {
+        Err(faults.join(", "))
+    }
+}
+
+fn step(name: &str, started: Instant, result: Result<String, String>) -> ProductionStep {
+    let (passed, message) = match result {
+        Ok(message) => (true, message),
+        Err(message) => (false, message),
+    };
+    ProductionStep {
+        name: name.to_string(),
+        passed,
+        skipped: false,
+        message,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn skipped(name: &str, message: &str) -> ProductionStep {
+    ProductionStep {
+        name: name.to_string(),
+        passed: true,
+        skipped: true,
+        message: message.to_string(),
+        elapsed_ms: 0,
+    }
+}
+
+/// 暂停内核读取故障寄存器后恢复运行
+fn check_boot_health(state: &AppState, core_index: usize) -> Result<String, String> {
+    let mut session_guard = state.lock_session();
+    let session = session_guard.as_mut().ok_or_else(|| AppError::NotConnected.to_string())?;
+    let mut core = session.core(core_index).map_err(|e| e.to_string())?;
+
+    match core.status().map_err(|e| e.to_string())? {
+        CoreStatus::LockedUp => return Err("内核处于锁定状态 (Lockup)".to_string()),
+        CoreStatus::Halted(reason) => return Err(format!("内核未运行，已暂停: {:?}", reason)),
+        _ => {}
+    }
+
+    core.halt(state.core_timeout(CoreOperation::Halt))
+        .map_err(|e| format!("暂停内核失败: {}", e))?;
+    let cfsr = core.read_word_32(SCB_CFSR).ok();
+    let hfsr = core.read_word_32(SCB_HFSR).ok();
+    let xpsr: Result<u32, _> = core.read_core_reg(RegisterId(REG_XPSR));
+    let run = core.run();
+
+    let ipsr = xpsr.map_err(|e| format!("读取 xPSR 失败: {}", e))? & 0x1FF;
+    run.map_err(|e| format!("恢复内核运行失败: {}", e))?;
+
+    evaluate_boot_health(cfsr, hfsr, ipsr)?;
+    Ok(format!(
+        "内核运行正常 (CFSR=0x{:08X}, HFSR=0x{:08X})",
+        cfsr.unwrap_or(0),
+        hfsr.unwrap_or(0)
+    ))
+}
+
+/// 捕获复位后的 RTT 启动输出，设置了正则时等待匹配
+async fn capture_boot_output(
+    state: &AppState,
+    channel: usize,
+    mark: u64,
+    pattern: Option<regex::Regex>,
+    duration: Duration,
+) -> Result<String, String> {
+    let rtt_state = Arc::clone(&state.rtt_state);
+    let started = Instant::now();
+    let mut captured = Vec::new();
+    let mut mark = mark;
+    let mut matcher = pattern.map(|regex| RttLineMatcher::new(regex, 0));
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let (data, next_mark) = rtt_state.history_since(channel, mark);
+        mark = next_mark;
+        captured.extend_from_slice(&data);
+
+        if let Some(matcher) = matcher.as_mut() {
+            if let Some((line, _)) = matcher.feed(&data) {
+                return Ok(format!("{} ms 后匹配: {}", started.elapsed().as_millis(), line));
+            }
+        }
+        if started.elapsed() >= duration || !rtt_state.is_running() {
+            break;
+        }
+    }
+
+    let output = String::from_utf8_lossy(&captured[captured.len().saturating_sub(BOOT_OUTPUT_LIMIT)..]).into_owned();
+    if matcher.is_some() {
+        Err(format!("{} ms 内未匹配到启动标记，输出: {}", duration.as_millis(), output))
+    } else {
+        Ok(output)
+    }
+}
+
+/// 量产烧录：烧录 → 校验 → 复位 → 捕获启动 RTT 输出 → 检查内核无故障运行
+/// 任一步骤失败即停止，返回每个步骤的结果
+#[tauri::command]
+pub async fn production_flash(
+    options: FlashOptions,
+    checks: Option<ProductionCheckOptions>,
+    state: State<'_, AppState>,
+    window: Window,
+) -> AppResult<ProductionFlashResult> {
+    let checks = checks.unwrap_or_default();
+    let boot_pattern = checks
+        .boot_pattern
+        .as_deref()
+        .map(regex::Regex::new)
+        .transpose()
+        .map_err(|e| AppError::ConfigError(format!("无效的启动标记正则: {}", e)))?;
+    let core_index = state.core_index(options.core_index);
+    let rtt_channel = checks.rtt_channel.unwrap_or(0);
+    let file_path = options.file_path.clone();
+
+    let mut steps = Vec::new();
+    let finish = |steps: Vec<ProductionStep>| -> AppResult<ProductionFlashResult> {
+        let passed = steps.iter().all(|s| s.passed);
+        let result = ProductionFlashResult { passed, steps };
+        let _ = window.emit("production-flash-result", &result);
+        log::info!("量产烧录{}", if passed { "通过" } else { "未通过" });
+        Ok(result)
+    };
+
+    // 复位、校验和启动检查由本命令按顺序执行
+    let flash_options = FlashOptions {
+        verify: false,
+        reset_after: false,
+        halt_after: false,
+        ..options
+    };
+    let started = Instant::now();
+    let result = flash_firmware(flash_options, state.clone(), window.clone()).await;
+    let result = result
+        .map(|r| format!("烧录完成，编程 {} 字节，擦除 {} 字节", r.bytes_programmed, r.bytes_erased))
+        .map_err(|e| e.to_string());
+    steps.push(step("flash", started, result));
+    if !steps.last().unwrap().passed {
+        return finish(steps);
+    }
+
+    let started = Instant::now();
+    let result = match verify_firmware(file_path, Some(core_index), state.clone(), window.clone()).await {
+        Ok(true) => Ok("校验通过".to_string()),
+        Ok(false) => Err("校验不一致".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    steps.push(step("verify", started, result));
+    if !steps.last().unwrap().passed {
+        return finish(steps);
+    }
+
+    // 复位前记录 RTT 历史位置，启动输出从这里开始计
+    let rtt_mark = state
+        .rtt_state
+        .is_running()
+        .then(|| state.rtt_state.history_mark(rtt_channel));
+
+    let started = Instant::now();
+    let result = {
+        let mut session_guard = state.lock_session();
+        match session_guard.as_mut() {
+            Some(session) => session
+                .core(core_index)
+                .and_then(|mut core| core.reset())
+                .map(|_| "芯片已复位".to_string())
+                .map_err(|e| e.to_string()),
+            None => Err(AppError::NotConnected.to_string()),
+        }
+    };
+    steps.push(step("reset", started, result));
+    if !steps.last().unwrap().passed {
+        return finish(steps);
+    }
+
+    let capture = Duration::from_millis(checks.boot_capture_ms.unwrap_or(1000));
+    match rtt_mark {
+        Some(mark) => {
+            let started = Instant::now();
+            let result = capture_boot_output(&state, rtt_channel, mark, boot_pattern, capture).await;
+            steps.push(step("boot_output", started, result));
+        }
+        None => steps.push(skipped("boot_output", "RTT 未运行，跳过启动输出捕获")),
+    }
+    if !steps.last().unwrap().passed {
+        return finish(steps);
+    }
+
+    let delay = Duration::from_millis(checks.health_check_delay_ms.unwrap_or(500));
+    let elapsed = Duration::from_millis(steps.last().unwrap().elapsed_ms);
+    tokio::time::sleep(delay.saturating_sub(elapsed)).await;
+    let started = Instant::now();
+    steps.push(step("boot_health", started, check_boot_health(&state, core_index)));
+
+    finish(steps)
+}
+
+/// 启动耗时测量结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BootTimeResult {
+    /// 复位完成到通道出现第一个字节的耗时 (毫秒)，精度受 RTT 轮询间隔限制
+    pub boot_time_ms: u64,
+    pub channel: usize,
+    /// 首次读到的输出（最多 BOOT_OUTPUT_LIMIT 字节）
+    pub first_output: String,
+}
+
+/// 等待通道在 mark 之后出现第一批数据，超时返回 None
+async fn wait_for_first_output(
+    rtt_state: &crate::state::RttState,
+    channel: usize,
+    mark: u64,
+    timeout: Duration,
+) -> Option<(Duration, Vec<u8>)> {
+    let started = Instant::now();
+    while started.elapsed() < timeout && rtt_state.is_running() {
+        let (data, _) = rtt_state.history_since(channel, mark);
+        if !data.is_empty() {
+            return Some((started.elapsed(), data));
+        }
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+    None
+}
+
+/// 复位目标并测量到指定 RTT 通道首次输出的耗时，用于启动时间回归检查
+/// 要求 RTT 已在运行；复位通过 RTT 所用的连接执行
+#[tauri::command]
+pub async fn measure_boot_time(
+    channel: Option<usize>,
+    timeout_ms: Option<u64>,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<BootTimeResult> {
+    if !state.rtt_state.is_running() {
+        return Err(AppError::RttError("RTT 未运行，请先启动 RTT 再测量启动耗时".to_string()));
+    }
+    let channel = channel.unwrap_or(0);
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+    let core_index = state.core_index(core_index);
+
+    // 复位前记录历史位置，只统计复位之后的输出
+    let mark = state.rtt_state.history_mark(channel);
+    {
+        let session_handle = state.rtt_session_handle();
+        let mut session_guard = SessionGuard::lock(&session_handle);
+        let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+        session
+            .core(core_index)
+            .and_then(|mut core| core.reset())
+            .map_err(|e| AppError::ProbeError(format!("复位失败: {}", e)))?;
+    }
+
+    let (elapsed, data) = wait_for_first_output(&state.rtt_state, channel, mark, timeout)
+        .await
+        .ok_or_else(|| {
+            let reason = if state.rtt_state.is_running() { "" } else { "（RTT 已停止）" };
+            AppError::RttError(format!(
+                "复位后 {} ms 内 RTT 通道 {} 没有输出{}",
+                timeout.as_millis(),
+                channel,
+                reason
+            ))
+        })?;
+
+    let result = BootTimeResult {
+        boot_time_ms: elapsed.as_millis() as u64,
+        channel,
+        first_output: String::from_utf8_lossy(&data[..data.len().min(BOOT_OUTPUT_LIMIT)]).into_owned(),
+    };
+    log::info!("启动耗时: {} ms (RTT 通道 {})", result.boot_time_ms, channel);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_registers_pass() {
+        assert!(evaluate_boot_health(Some(0), Some(0), 0).is_ok());
+        // Armv6-M 没有 CFSR/HFSR，线程模式运行即为正常
+        assert!(evaluate_boot_health(None, None, 0).is_ok());
+        // 普通中断处理中（如 SysTick）不是故障
+        assert!(evaluate_boot_health(Some(0), Some(0), 15).is_ok());
+    }
+
+    #[test]
+    fn test_faults_are_reported() {
+        let err = evaluate_boot_health(Some(0x0000_8200), Some(0x4000_0000), 3).unwrap_err();
+        assert!(err.contains("BusFault"));
+        assert!(err.contains("HardFault"));
+        assert!(err.contains("异常号 3"));
+
+        let err = evaluate_boot_health(Some(0x0001_0000), None, 0).unwrap_err();
+        assert!(err.contains("UsageFault"));
+    }
+}
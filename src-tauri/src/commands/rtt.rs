@@ -1,7 +1,10 @@
 use crate::error::{AppError, AppResult};
-use crate::state::AppState;
+use crate::app_config::RttViewConfig;
+use crate::state::{AppState, CoreOperation, RttChannelFilter, SessionGuard, RTT_HISTORY_CAPACITY};
 use probe_rs::rtt::{Rtt, ScanRegion};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, State};
@@ -13,6 +16,82 @@ pub struct RttChannel {
     pub index: usize,
     pub name: String,
     pub buffer_size: usize,
+    /// 推测的数据格式（上行通道收到数据后更新）
+    #[serde(default)]
+    pub likely_format: RttChannelFormat,
+}
+
+/// RTT 通道数据格式推测结果
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RttChannelFormat {
+    /// 尚无足够数据判断
+    #[default]
+    Unknown,
+    /// 可打印文本
+    Text,
+    /// defmt 编码帧
+    Defmt,
+    /// 其他二进制数据
+    Binary,
+}
+
+/// rtt-channels 事件：通道列表及格式推测
+#[derive(Debug, Clone, Serialize)]
+pub struct RttChannelsEvent {
+    pub up_channels: Vec<RttChannel>,
+}
+
+/// 判断格式所需的最少采样字节数
+const FORMAT_SAMPLE_MIN: usize = 16;
+/// 每个通道最多采样的字节数
+const FORMAT_SAMPLE_MAX: usize = 256;
+
+/// 根据通道名称与初始数据推测格式
+/// elf_has_defmt 表示提供的 ELF 中包含 defmt 符号表
+pub fn classify_rtt_channel(name: &str, sample: &[u8], elf_has_defmt: bool) -> RttChannelFormat {
+    // defmt-rtt 固定使用名为 "defmt" 的通道
+    if name.to_lowercase().contains("defmt") {
+        return RttChannelFormat::Defmt;
+    }
+    if sample.len() < FORMAT_SAMPLE_MIN {
+        return RttChannelFormat::Unknown;
+    }
+
+    let printable = sample
+        .iter()
+        .filter(|&&b| matches!(b, b'\t' | b'\n' | b'\r' | 0x20..=0x7E) || b >= 0x80)
+        .count();
+    let is_utf8 = std::str::from_utf8(sample).is_ok()
+        // 采样可能截断在多字节字符中间
+        || std::str::from_utf8(&sample[..sample.len().saturating_sub(3)]).is_ok();
+    if is_utf8 && printable * 100 >= sample.len() * 95 {
+        return RttChannelFormat::Text;
+    }
+
+    // defmt (rzCOBS) 帧以 0x00 分隔
+    if elf_has_defmt || sample.contains(&0) {
+        RttChannelFormat::Defmt
+    } else {
+        RttChannelFormat::Binary
+    }
+}
+
+/// ELF 中是否包含 defmt 符号表 (.defmt 段)
+fn elf_has_defmt_table(path: &str) -> bool {
+    use object::{Object, ObjectSection};
+
+    let Ok(data) = std::fs::read(path) else {
+        log::warn!("读取 ELF 文件失败: {}", path);
+        return false;
+    };
+    match object::File::parse(&*data) {
+        Ok(elf) => elf.sections().any(|s| s.name().is_ok_and(|n| n == ".defmt")),
+        Err(e) => {
+            log::warn!("解析 ELF 文件失败: {}", e);
+            false
+        }
+    }
 }
 
 /// RTT 配置响应
@@ -38,6 +117,33 @@ pub struct RttStartOptions {
     pub poll_interval: Option<u64>,
     /// 是否在读取时暂停目标 (默认 true，设为 false 可能更快但不稳定)
     pub halt_on_read: Option<bool>,
+    /// 控制块扫描超时 (毫秒)，默认 5000ms
+    pub scan_timeout_ms: Option<u64>,
+    /// 固件 ELF 路径（可选，用于识别 defmt 通道）
+    #[serde(default)]
+    pub elf_path: Option<String>,
+}
+
+/// 默认控制块扫描超时
+const DEFAULT_RTT_SCAN_TIMEOUT_MS: u64 = 5000;
+
+/// 在阻塞线程中执行 RTT 附加，超过时限时返回错误
+/// 超时后后台扫描无法中断，会在扫描结束后自行释放 session 锁
+async fn attach_with_timeout<T: Send + 'static>(
+    timeout_ms: u64,
+    task: impl FnOnce() -> AppResult<T> + Send + 'static,
+) -> AppResult<T> {
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), tokio::task::spawn_blocking(task)).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(AppError::RttError(format!("RTT 附加任务异常: {}", e))),
+        Err(_) => {
+            log::error!("RTT 控制块扫描超过 {} ms", timeout_ms);
+            Err(AppError::RttError(format!(
+                "RTT 控制块扫描超时 ({} ms)。RAM 较大时自动扫描很慢，请使用精确地址模式指定控制块地址（可在 map 文件中查找 _SEGGER_RTT 符号）",
+                timeout_ms
+            )))
+        }
+    }
 }
 
 /// RTT 数据事件 (发送到前端)
@@ -46,6 +152,8 @@ pub struct RttDataEvent {
     pub channel: usize,
     pub data: Vec<u8>,
     pub timestamp: u64,
+    /// 数据方向: "rx" 为目标上行数据, "tx" 为写入下行通道的回显
+    pub direction: String,
 }
 
 /// RTT 状态事件 (发送到前端)
@@ -55,6 +163,106 @@ pub struct RttStatusEvent {
     pub error: Option<String>,
 }
 
+/// 目标内存映射中的所有 RAM 区域（包括 CCM 等非 0x20000000 起始的区域）
+fn target_ram_regions(session: &probe_rs::Session) -> Vec<Range<u64>> {
+    session
+        .target()
+        .memory_map
+        .iter()
+        .filter_map(|region| match region {
+            probe_rs::config::MemoryRegion::Ram(ram) => Some(ram.range.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 以目标 RAM 区域作为扫描范围，内存映射中没有 RAM 时回退为 probe-rs 默认的 RAM 扫描
+fn ram_scan_region(ram_regions: Vec<Range<u64>>) -> ScanRegion {
+    if ram_regions.is_empty() {
+        ScanRegion::Ram
+    } else {
+        ScanRegion::Ranges(ram_regions)
+    }
+}
+
+/// 根据扫描模式确定扫描范围，未指定地址时使用第一个 RAM 区域的起始地址
+fn build_scan_region(options: &RttStartOptions, cached_address: Option<u64>, ram_regions: &[Range<u64>]) -> ScanRegion {
+    let ram_start = ram_regions.first().map_or(0x20000000, |r| r.start);
+
+    match options.scan_mode.as_str() {
+        "exact" => {
+            let addr = options.address.unwrap_or(ram_start);
+            ScanRegion::Exact(addr)
+        }
+        "range" => {
+            let start = options.range_start.unwrap_or(ram_start);
+            let size = options.range_size.unwrap_or(0x10000);
+            ScanRegion::range(start..(start + size))
+        }
+        _ => match cached_address {
+            // auto: 已扫描过控制块时直接使用缓存地址，跳过耗时的扫描
+            Some(addr) => {
+                log::info!("使用缓存的 RTT 控制块地址: 0x{:08X}", addr);
+                ScanRegion::Exact(addr)
+            }
+            // auto: 扫描目标内存映射中的所有 RAM 区域
+            None => {
+                log::info!("RTT 扫描 RAM 区域: {:X?}", ram_regions);
+                ram_scan_region(ram_regions.to_vec())
+            }
+        },
+    }
+}
+
+/// 读取 RTT 连接信息中缓存的控制块地址
+fn cached_control_block(state: &AppState) -> Option<u64> {
+    state
+        .rtt_connection_info
+        .lock()
+        .as_ref()
+        .and_then(|info| info.rtt_control_block)
+}
+
+/// 将控制块地址缓存到 RTT 连接信息中
+fn cache_control_block(state: &AppState, address: Option<u64>) {
+    if let Some(info) = state.rtt_connection_info.lock().as_mut() {
+        info.rtt_control_block = address;
+    }
+}
+
+/// 扫描一次 RTT 控制块并缓存其地址，之后 auto 模式的 start_rtt 直接使用该地址
+#[tauri::command]
+pub async fn scan_rtt_control_block(
+    timeout_ms: Option<u64>,
+    state: State<'_, AppState>,
+) -> AppResult<u64> {
+    if state.rtt_state.is_running() {
+        return Err(AppError::RttError("RTT 正在运行，请先停止".to_string()));
+    }
+
+    let rtt_session = state.rtt_session_handle();
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_RTT_SCAN_TIMEOUT_MS);
+    let address = attach_with_timeout(timeout_ms, move || {
+        let mut rtt_session_guard = SessionGuard::lock(&rtt_session);
+        let session = rtt_session_guard
+            .as_mut()
+            .ok_or(AppError::RttError("RTT 未连接，请先连接 RTT".to_string()))?;
+        let scan_region = ram_scan_region(target_ram_regions(session));
+        let mut core = session.core(0).map_err(|e| AppError::RttError(e.to_string()))?;
+
+        let scan_start = std::time::Instant::now();
+        let rtt = Rtt::attach_region(&mut core, &scan_region)
+            .map_err(|e| AppError::RttError(format!("未找到 RTT 控制块: {}", e)))?;
+        log::info!("RTT 控制块扫描完成: 0x{:08X}，耗时 {:?}", rtt.ptr(), scan_start.elapsed());
+        Ok(rtt.ptr())
+    })
+    .await?;
+
+    cache_control_block(&state, Some(address));
+
+    Ok(address)
+}
+
 /// 启动 RTT 并开始持续轮询
 #[tauri::command]
 pub async fn start_rtt(
@@ -67,38 +275,39 @@ pub async fn start_rtt(
         return Err(AppError::RttError("RTT 已在运行中".to_string()));
     }
 
-    // 根据扫描模式确定扫描范围
-    let scan_region = match options.scan_mode.as_str() {
-        "exact" => {
-            let addr = options.address.unwrap_or(0x20000000);
-            ScanRegion::Exact(addr)
-        }
-        "range" => {
-            let start = options.range_start.unwrap_or(0x20000000);
-            let size = options.range_size.unwrap_or(0x10000);
-            ScanRegion::range(start..(start + size))
-        }
-        _ => {
-            // auto: 从 RAM 开始扫描
-            ScanRegion::Ram
-        }
-    };
+    let cached_address = cached_control_block(&state);
 
     // 获取通道信息并找到控制块地址
     log::info!("开始附加 RTT，扫描模式: {:?}", options.scan_mode);
-    let (up_channels, down_channels, found_address) = {
-        let mut rtt_session_guard = state.rtt_session.lock();
+    let rtt_session = state.rtt_session_handle();
+    let attach_options = options.clone();
+    let timeout_ms = options.scan_timeout_ms.unwrap_or(DEFAULT_RTT_SCAN_TIMEOUT_MS);
+    let (up_channels, down_channels, found_address) = attach_with_timeout(timeout_ms, move || {
+        let options = attach_options;
+        let mut rtt_session_guard = SessionGuard::lock(&rtt_session);
         let session = rtt_session_guard
             .as_mut()
             .ok_or(AppError::RttError("RTT 未连接，请先连接 RTT".to_string()))?;
 
+        // 根据扫描模式和目标内存映射确定扫描范围
+        let ram_regions = target_ram_regions(session);
+        let scan_region = build_scan_region(&options, cached_address, &ram_regions);
+
         log::info!("获取 core 0");
         let mut core = session.core(0).map_err(|e| AppError::RttError(e.to_string()))?;
 
         // 附加 RTT
         log::info!("开始扫描 RTT 控制块...");
         let attach_start = std::time::Instant::now();
-        let mut rtt = Rtt::attach_region(&mut core, &scan_region)
+        let attach_result = match Rtt::attach_region(&mut core, &scan_region) {
+            // 缓存地址失效（固件已更新）时回退为完整扫描
+            Err(e) if options.scan_mode == "auto" && cached_address.is_some() => {
+                log::warn!("缓存的 RTT 控制块地址无效 ({})，重新扫描 RAM", e);
+                Rtt::attach_region(&mut core, &ram_scan_region(ram_regions))
+            }
+            result => result,
+        };
+        let mut rtt = attach_result
             .map_err(|e| {
                 let elapsed = attach_start.elapsed();
                 log::error!("RTT 附加失败 (耗时 {:?}): {}", elapsed, e);
@@ -122,9 +331,11 @@ pub async fn start_rtt(
         // 收集通道信息
         let mut up_channels = Vec::new();
         for channel in rtt.up_channels().iter() {
+            let name = channel.name().unwrap_or("").to_string();
             up_channels.push(RttChannel {
                 index: channel.number(),
-                name: channel.name().unwrap_or("").to_string(),
+                likely_format: classify_rtt_channel(&name, &[], false),
+                name,
                 buffer_size: channel.buffer_size(),
             });
         }
@@ -135,11 +346,14 @@ pub async fn start_rtt(
                 index: channel.number(),
                 name: channel.name().unwrap_or("").to_string(),
                 buffer_size: channel.buffer_size(),
+                likely_format: RttChannelFormat::Unknown,
             });
         }
 
-        (up_channels, down_channels, found_address)
-    };
+        Ok((up_channels, down_channels, found_address))
+    })
+    .await?;
+    cache_control_block(&state, found_address);
 
     // 保存配置
     let poll_interval = options.poll_interval.unwrap_or(10); // 默认 10ms
@@ -147,19 +361,34 @@ pub async fn start_rtt(
     let halt_on_read = options.halt_on_read.unwrap_or(false);
     *state.rtt_state.poll_interval_ms.lock() = poll_interval;
     *state.rtt_state.control_block_address.lock() = found_address;
+    *state.rtt_state.view_filters.lock() =
+        compile_view_filters(&crate::app_config::load_config().rtt_view_config).unwrap_or_else(|e| {
+            log::warn!("RTT 查看器过滤规则无效，已忽略: {}", e);
+            HashMap::new()
+        });
+    state.rtt_state.reset_stats();
     state.rtt_state.set_running(true);
 
     log::info!("RTT 配置: 轮询间隔={}ms, 暂停读取={}", poll_interval, halt_on_read);
 
+    let elf_has_defmt = options.elf_path.as_deref().is_some_and(elf_has_defmt_table);
+    if elf_has_defmt {
+        log::info!("ELF 中包含 defmt 符号表");
+    }
+    let _ = app_handle.emit("rtt-channels", RttChannelsEvent { up_channels: up_channels.clone() });
+
     // 启动后台轮询任务
     let rtt_state = Arc::clone(&state.rtt_state);
-    let session_arc = Arc::clone(&state.rtt_session);
+    let session_arc = state.rtt_session_handle();
+    let channel_probe = ChannelFormatProbe::new(up_channels.clone(), elf_has_defmt);
+    let flash_in_progress = Arc::clone(&state.flash_in_progress);
+    let settings = Arc::clone(&state.settings);
 
     log::info!("准备启动 RTT 轮询任务，轮询间隔: {}ms", poll_interval);
 
     tokio::spawn(async move {
         log::info!("RTT 轮询任务已启动");
-        rtt_polling_task(rtt_state, session_arc, app_handle, poll_interval, halt_on_read).await;
+        rtt_polling_task(rtt_state, session_arc, app_handle, poll_interval, halt_on_read, channel_probe, flash_in_progress, settings).await;
         log::info!("RTT 轮询任务已结束");
     });
 
@@ -170,13 +399,55 @@ pub async fn start_rtt(
     })
 }
 
+/// 轮询期间对上行通道的初始数据采样并推测格式
+struct ChannelFormatProbe {
+    channels: Vec<RttChannel>,
+    samples: HashMap<usize, Vec<u8>>,
+    elf_has_defmt: bool,
+}
+
+impl ChannelFormatProbe {
+    fn new(channels: Vec<RttChannel>, elf_has_defmt: bool) -> Self {
+        Self {
+            channels,
+            samples: HashMap::new(),
+            elf_has_defmt,
+        }
+    }
+
+    /// 记录通道数据，格式推测发生变化时返回 true
+    fn observe(&mut self, channel: usize, data: &[u8]) -> bool {
+        let Some(info) = self.channels.iter_mut().find(|c| c.index == channel) else {
+            return false;
+        };
+        let sample = self.samples.entry(channel).or_default();
+        if sample.len() >= FORMAT_SAMPLE_MAX {
+            return false;
+        }
+        sample.extend_from_slice(&data[..data.len().min(FORMAT_SAMPLE_MAX - sample.len())]);
+
+        let format = classify_rtt_channel(&info.name, sample, self.elf_has_defmt);
+        if format != info.likely_format {
+            log::info!("RTT 通道 {} 推测格式: {:?}", channel, format);
+            info.likely_format = format;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 /// RTT 轮询任务
+#[allow(clippy::too_many_arguments)]
 async fn rtt_polling_task(
     rtt_state: Arc<crate::state::RttState>,
     session: Arc<parking_lot::Mutex<Option<probe_rs::Session>>>,
     app_handle: AppHandle,
     poll_interval_ms: u64,
     halt_on_read: bool,
+    mut channel_probe: ChannelFormatProbe,
+    flash_in_progress: Arc<std::sync::atomic::AtomicUsize>,
+    settings: Arc<parking_lot::Mutex<crate::state::DeviceSettings>>,
 ) {
     log::info!("RTT 轮询任务开始执行");
 
@@ -190,8 +461,6 @@ async fn rtt_polling_task(
     // 批量发送缓冲区
     let mut batch_events: Vec<RttDataEvent> = Vec::new();
     let mut last_emit = std::time::Instant::now();
-    const BATCH_TIMEOUT_MS: u64 = 50; // 批量发送超时 50ms
-    const BATCH_SIZE_THRESHOLD: usize = 10; // 批量大小阈值 10 个事件
 
     // 获取保存的控制块地址
     let control_block_addr = *rtt_state.control_block_address.lock();
@@ -220,16 +489,41 @@ async fn rtt_polling_task(
             break;
         }
 
+        // 烧录/擦除期间让出 session，避免与烧录争抢
+        if flash_in_progress.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+            continue;
+        }
+
+        // 批量参数可在运行时调整
+        let batch_timeout_ms = *rtt_state.batch_timeout_ms.lock();
+        let batch_size_threshold = *rtt_state.batch_size_threshold.lock();
+
         // 尝试读取数据（所有操作在同步块中完成）
-        let poll_result = poll_rtt_once(&session, &mut buffer, &mut consecutive_errors, MAX_CONSECUTIVE_ERRORS, control_block_addr, halt_on_read);
+        let halt_timeout = settings.lock().core_timeout(CoreOperation::RttHalt);
+        let poll_result = poll_rtt_once(&session, &mut buffer, &mut consecutive_errors, MAX_CONSECUTIVE_ERRORS, control_block_addr, halt_on_read, halt_timeout);
 
         match poll_result {
             PollResult::Data(events) => {
-                // 累积事件到批量缓冲区
-                batch_events.extend(events);
+                let mut formats_changed = false;
+                for event in &events {
+                    rtt_state.add_received(event.channel, event.data.len());
+                    rtt_state.record_history(event.channel, &event.data);
+                    formats_changed |= channel_probe.observe(event.channel, &event.data);
+                }
+                if formats_changed {
+                    let _ = app_handle.emit("rtt-channels", RttChannelsEvent {
+                        up_channels: channel_probe.channels.clone(),
+                    });
+                }
+
+                // 按查看器设置预过滤后累积到批量缓冲区
+                batch_events.extend(events.into_iter().filter_map(|mut event| {
+                    event.data = rtt_state.filter_for_view(event.channel, &event.data)?;
+                    Some(event)
+                }));
 
                 // 如果批量缓冲区达到阈值，立即发送
-                if batch_events.len() >= BATCH_SIZE_THRESHOLD {
+                if batch_events.len() >= batch_size_threshold {
                     for event in batch_events.drain(..) {
                         if let Err(e) = app_handle.emit("rtt-data", &event) {
                             log::error!("发送 RTT 数据事件失败: {}", e);
@@ -254,7 +548,7 @@ async fn rtt_polling_task(
         }
 
         // 如果有累积的事件且超过超时时间，发送
-        if !batch_events.is_empty() && last_emit.elapsed().as_millis() as u64 >= BATCH_TIMEOUT_MS {
+        if !batch_events.is_empty() && last_emit.elapsed().as_millis() as u64 >= batch_timeout_ms {
             for event in batch_events.drain(..) {
                 if let Err(e) = app_handle.emit("rtt-data", &event) {
                     log::error!("发送 RTT 数据事件失败: {}", e);
@@ -293,6 +587,7 @@ fn poll_rtt_once(
     max_errors: u32,
     control_block_addr: Option<u64>,
     halt_on_read: bool,
+    halt_timeout: Duration,
 ) -> PollResult {
     // 尝试获取锁，带超时
     let session_guard = match session.try_lock_for(Duration::from_millis(500)) {
@@ -346,7 +641,7 @@ fn poll_rtt_once(
         };
         let running = !halted;
         if running {
-            if let Err(e) = core.halt(Duration::from_millis(50)) {
+            if let Err(e) = core.halt(halt_timeout) {
                 log::debug!("暂停目标芯片失败: {}", e);
                 return PollResult::NoData;
             }
@@ -424,6 +719,7 @@ fn read_rtt_data(core: &mut probe_rs::Core, buffer: &mut [u8], control_block_add
                         channel: channel_num,
                         data: buffer[..count].to_vec(),
                         timestamp,
+                        direction: "rx".to_string(),
                     });
 
                     log::trace!("RTT 通道 {} 读取 {} 字节", channel_num, count);
@@ -458,12 +754,14 @@ pub async fn write_rtt(
     channel: usize,
     data: Vec<u8>,
     state: State<'_, AppState>,
+    app_handle: AppHandle,
 ) -> AppResult<usize> {
     if !state.rtt_state.is_running() {
         return Err(AppError::RttError("RTT 未运行".to_string()));
     }
 
-    let mut session_guard = state.rtt_session.lock();
+    let rtt_session = state.rtt_session_handle();
+    let mut session_guard = SessionGuard::lock(&rtt_session);
     let session = session_guard
         .as_mut()
         .ok_or(AppError::NotConnected)?;
@@ -484,6 +782,28 @@ pub async fn write_rtt(
         .write(&mut core, &data)
         .map_err(|e| AppError::RttError(e.to_string()))?;
 
+    state.rtt_state.add_sent(channel, written);
+    if written > 0 {
+        state.rtt_state.record_write(channel, &data[..written]);
+    }
+
+    // 回显已写入的数据，使输入与输出出现在同一时间线中
+    if written > 0 {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let _ = app_handle.emit(
+            "rtt-data",
+            RttDataEvent {
+                channel,
+                data: data[..written].to_vec(),
+                timestamp,
+                direction: "tx".to_string(),
+            },
+        );
+    }
+
     Ok(written)
 }
 
@@ -500,5 +820,339 @@ pub async fn get_rtt_status(state: State<'_, AppState>) -> AppResult<RttStatusEv
 #[tauri::command]
 pub async fn clear_rtt_buffer(state: State<'_, AppState>) -> AppResult<()> {
     state.rtt_state.line_buffers.lock().clear();
+    state.rtt_state.history.lock().clear();
     Ok(())
 }
+
+/// 获取指定通道最近接收的至多 max_bytes 字节（RTT 停止后仍可读取）
+#[tauri::command]
+pub async fn get_rtt_history(
+    channel: usize,
+    max_bytes: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<u8>> {
+    Ok(state
+        .rtt_state
+        .history
+        .lock()
+        .get(&channel)
+        .map(|h| h.tail(max_bytes.unwrap_or(RTT_HISTORY_CAPACITY)))
+        .unwrap_or_default())
+}
+
+/// wait_for_rtt_pattern 匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RttPatternMatch {
+    /// 匹配的行（未以换行结束的行也参与匹配）
+    pub line: String,
+    /// 匹配行之前的若干行
+    pub context_before: Vec<String>,
+    /// 从开始等待到匹配的耗时
+    pub elapsed_ms: u64,
+}
+
+/// 按行累积 RTT 文本并匹配正则，跨多次读取保留未完成的行
+pub struct RttLineMatcher {
+    regex: regex::Regex,
+    partial: String,
+    recent: std::collections::VecDeque<String>,
+    context_lines: usize,
+}
+
+impl RttLineMatcher {
+    pub fn new(regex: regex::Regex, context_lines: usize) -> Self {
+        Self {
+            regex,
+            partial: String::new(),
+            recent: std::collections::VecDeque::new(),
+            context_lines,
+        }
+    }
+
+    /// 输入新数据，返回第一个匹配的行及其之前的上下文
+    pub fn feed(&mut self, data: &[u8]) -> Option<(String, Vec<String>)> {
+        self.partial.push_str(&String::from_utf8_lossy(data));
+
+        while let Some(pos) = self.partial.find('\n') {
+            let line = self.partial[..pos].trim_end_matches('\r').to_string();
+            self.partial.drain(..=pos);
+            if self.regex.is_match(&line) {
+                return Some((line, self.recent.iter().cloned().collect()));
+            }
+            self.push_recent(line);
+        }
+
+        if self.regex.is_match(&self.partial) {
+            let line = std::mem::take(&mut self.partial);
+            return Some((line, self.recent.iter().cloned().collect()));
+        }
+        None
+    }
+
+    fn push_recent(&mut self, line: String) {
+        if self.context_lines == 0 {
+            return;
+        }
+        if self.recent.len() == self.context_lines {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(line);
+    }
+}
+
+/// 等待指定通道输出匹配正则的文本（用于脚本化的“烧录后等待启动完成”）
+/// 只检查开始等待之后收到的数据，超时返回错误
+#[tauri::command]
+pub async fn wait_for_rtt_pattern(
+    channel: usize,
+    pattern: String,
+    timeout_ms: u64,
+    context_lines: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<RttPatternMatch> {
+    let regex = regex::Regex::new(&pattern)
+        .map_err(|e| AppError::RttError(format!("无效的正则表达式 '{}': {}", pattern, e)))?;
+    if !state.rtt_state.is_running() {
+        return Err(AppError::RttError("RTT 未运行，请先启动 RTT".to_string()));
+    }
+
+    let rtt_state = Arc::clone(&state.rtt_state);
+    let mut matcher = RttLineMatcher::new(regex, context_lines.unwrap_or(5));
+    let mut mark = rtt_state.history_mark(channel);
+    let started = std::time::Instant::now();
+    let timeout = Duration::from_millis(timeout_ms);
+    let mut interval_timer = interval(Duration::from_millis(20));
+    interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval_timer.tick().await;
+
+        let (data, next_mark) = rtt_state.history_since(channel, mark);
+        mark = next_mark;
+        if let Some((line, context_before)) = matcher.feed(&data) {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            log::info!("RTT 通道 {} 在 {} ms 后匹配 '{}': {}", channel, elapsed_ms, pattern, line);
+            return Ok(RttPatternMatch { line, context_before, elapsed_ms });
+        }
+
+        if started.elapsed() >= timeout {
+            return Err(AppError::RttError(format!(
+                "等待 RTT 通道 {} 输出 '{}' 超时 ({} ms)",
+                channel, pattern, timeout_ms
+            )));
+        }
+        if !rtt_state.is_running() {
+            return Err(AppError::RttError("等待期间 RTT 已停止".to_string()));
+        }
+    }
+}
+
+/// 获取下行通道最近发送的命令（最旧在前）
+#[tauri::command]
+pub async fn get_rtt_write_history(channel: usize, state: State<'_, AppState>) -> AppResult<Vec<String>> {
+    Ok(state.rtt_state.write_history(channel))
+}
+
+/// RTT 单通道统计
+#[derive(Debug, Clone, Serialize)]
+pub struct RttChannelStatsInfo {
+    pub channel: usize,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+    /// 接收速率 (字节/秒，自上次查询以来)
+    pub rx_rate: f64,
+    /// 发送速率 (字节/秒，自上次查询以来)
+    pub tx_rate: f64,
+}
+
+/// 获取 RTT 各通道字节统计与当前速率
+#[tauri::command]
+pub async fn get_rtt_stats(state: State<'_, AppState>) -> AppResult<Vec<RttChannelStatsInfo>> {
+    let stats = state.rtt_state.channel_stats.lock().clone();
+    let mut snapshot = state.rtt_state.stats_snapshot.lock();
+
+    let elapsed = snapshot.0.elapsed().as_secs_f64();
+    let mut result: Vec<RttChannelStatsInfo> = stats
+        .iter()
+        .map(|(&channel, s)| {
+            let (last_rx, last_tx) = snapshot.1.get(&channel).copied().unwrap_or((0, 0));
+            let rate = |now: u64, last: u64| {
+                if elapsed > 0.0 {
+                    now.saturating_sub(last) as f64 / elapsed
+                } else {
+                    0.0
+                }
+            };
+            RttChannelStatsInfo {
+                channel,
+                bytes_received: s.bytes_received,
+                bytes_sent: s.bytes_sent,
+                rx_rate: rate(s.bytes_received, last_rx),
+                tx_rate: rate(s.bytes_sent, last_tx),
+            }
+        })
+        .collect();
+    result.sort_by_key(|s| s.channel);
+
+    *snapshot = (
+        std::time::Instant::now(),
+        stats
+            .iter()
+            .map(|(&channel, s)| (channel, (s.bytes_received, s.bytes_sent)))
+            .collect(),
+    );
+
+    Ok(result)
+}
+
+/// RTT 批量发送超时的允许范围 (毫秒)，0 表示每次轮询立即发送
+pub const RTT_BATCH_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 0..=1000;
+
+/// RTT 批量大小阈值的允许范围 (事件数)
+pub const RTT_BATCH_SIZE_RANGE: std::ops::RangeInclusive<usize> = 1..=1000;
+
+/// 校验 RTT 批量参数
+pub fn validate_rtt_batching(timeout_ms: u64, size_threshold: usize) -> AppResult<()> {
+    if !RTT_BATCH_TIMEOUT_RANGE.contains(&timeout_ms) {
+        return Err(AppError::RttError(format!(
+            "批量超时 {} ms 超出范围 ({}-{} ms)",
+            timeout_ms,
+            RTT_BATCH_TIMEOUT_RANGE.start(),
+            RTT_BATCH_TIMEOUT_RANGE.end()
+        )));
+    }
+    if !RTT_BATCH_SIZE_RANGE.contains(&size_threshold) {
+        return Err(AppError::RttError(format!(
+            "批量大小阈值 {} 超出范围 ({}-{})",
+            size_threshold,
+            RTT_BATCH_SIZE_RANGE.start(),
+            RTT_BATCH_SIZE_RANGE.end()
+        )));
+    }
+    Ok(())
+}
+
+/// 运行时调整 RTT 批量发送参数：较小的超时降低交互延迟，较大的阈值提高高吞吐日志的效率
+#[tauri::command]
+pub async fn set_rtt_batching(
+    timeout_ms: u64,
+    size_threshold: usize,
+    state: State<'_, AppState>,
+) -> AppResult<()> {
+    validate_rtt_batching(timeout_ms, size_threshold)?;
+    *state.rtt_state.batch_timeout_ms.lock() = timeout_ms;
+    *state.rtt_state.batch_size_threshold.lock() = size_threshold;
+    log::info!("RTT 批量参数: 超时 {} ms, 阈值 {} 个事件", timeout_ms, size_threshold);
+    Ok(())
+}
+
+/// 重置 RTT 通道统计
+#[tauri::command]
+pub async fn reset_rtt_stats(state: State<'_, AppState>) -> AppResult<()> {
+    state.rtt_state.reset_stats();
+    Ok(())
+}
+
+/// 校验并编译查看器过滤规则；未开启后端过滤时返回空表
+fn compile_view_filters(config: &RttViewConfig) -> AppResult<HashMap<usize, RttChannelFilter>> {
+    let mut filters = HashMap::new();
+
+    for view in &config.channels {
+        let patterns = view
+            .filters
+            .iter()
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                regex::Regex::new(p).map_err(|e| {
+                    AppError::ConfigError(format!("通道 {} 的过滤规则 '{}' 无效: {}", view.channel, p, e))
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        if config.backend_filter {
+            filters.insert(view.channel, RttChannelFilter {
+                visible: view.visible,
+                patterns,
+            });
+        }
+    }
+
+    Ok(filters)
+}
+
+/// 保存 RTT 查看器设置（通道可见性、显示名称、过滤正则），并立即应用到运行中的预过滤
+#[tauri::command]
+pub async fn save_rtt_view(config: RttViewConfig, state: State<'_, AppState>) -> AppResult<()> {
+    let filters = compile_view_filters(&config)?;
+
+    let mut app_config = crate::app_config::load_config();
+    app_config.rtt_view_config = config;
+    crate::app_config::save_config(&app_config)?;
+
+    *state.rtt_state.view_filters.lock() = filters;
+    // 过滤规则变化后丢弃未完成的行，避免按旧规则拼接
+    state.rtt_state.line_buffers.lock().clear();
+    Ok(())
+}
+
+/// 读取已保存的 RTT 查看器设置
+#[tauri::command]
+pub async fn load_rtt_view() -> AppResult<RttViewConfig> {
+    Ok(crate::app_config::load_config().rtt_view_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RTT 通道格式推测测试
+    #[test]
+    fn test_text_channel() {
+        let sample = b"[INFO] boot ok, tick=1234\r\n[WARN] \xe6\xb8\xa9\xe5\xba\xa6\xe8\xbf\x87\xe9\xab\x98\r\n";
+        assert_eq!(classify_rtt_channel("Terminal", sample, true), RttChannelFormat::Text);
+    }
+
+    #[test]
+    fn test_defmt_channel() {
+        // 按名称识别
+        assert_eq!(classify_rtt_channel("defmt", &[], false), RttChannelFormat::Defmt);
+
+        // rzCOBS 帧以 0x00 分隔
+        let frames = [0x03, 0x01, 0x91, 0x00, 0x05, 0x02, 0xFE, 0x17, 0x40, 0x00, 0x03, 0x01, 0x92, 0x00, 0x02, 0x7F, 0x00];
+        assert_eq!(classify_rtt_channel("Terminal", &frames, false), RttChannelFormat::Defmt);
+    }
+
+    #[test]
+    fn test_short_sample_is_unknown() {
+        assert_eq!(classify_rtt_channel("Terminal", b"hi", false), RttChannelFormat::Unknown);
+    }
+
+    // RTT 输出等待匹配测试
+    #[test]
+    fn test_pattern_split_across_reads_is_matched() {
+        let mut matcher = RttLineMatcher::new(regex::Regex::new(r"Boot OK").unwrap(), 2);
+
+        assert!(matcher.feed(b"init clocks\r\ninit uart\nBo").is_none());
+        let (line, context) = matcher.feed(b"ot OK v1.2\n").unwrap();
+
+        assert_eq!(line, "Boot OK v1.2");
+        assert_eq!(context, vec!["init clocks".to_string(), "init uart".to_string()]);
+    }
+
+    #[test]
+    fn test_context_is_limited() {
+        let mut matcher = RttLineMatcher::new(regex::Regex::new(r"^ready$").unwrap(), 1);
+
+        let (line, context) = matcher.feed(b"a\nb\nc\nready\n").unwrap();
+
+        assert_eq!(line, "ready");
+        assert_eq!(context, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_unterminated_line_can_match() {
+        let mut matcher = RttLineMatcher::new(regex::Regex::new(r"login:").unwrap(), 0);
+
+        assert_eq!(matcher.feed(b"login: ").map(|(line, _)| line), Some("login: ".to_string()));
+    }
+}
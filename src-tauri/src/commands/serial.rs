@@ -1,6 +1,10 @@
-use crate::serial::{list_serial_ports, LocalSerial, SerialConfig, SerialPortInfo, TcpSerial};
-use crate::state::{AppState, DataSource, SerialStats};
+use crate::serial::encoding::{detect_encoding, EncodingGuess};
+use crate::serial::{
+    list_serial_ports, LocalSerial, SerialConfig, SerialOpenError, SerialPortInfo, TcpSerial,
+};
+use crate::state::{AppState, DataSource, SerialStats, SERIAL_HISTORY_CAPACITY};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Emitter, State};
@@ -20,7 +24,7 @@ pub struct SerialStatus {
 struct SerialDataEvent {
     data: Vec<u8>,
     timestamp: i64,
-    direction: String, // "rx" for received data
+    direction: String, // "rx" for received data, "tx" for local echo
 }
 
 /// Serial status event payload
@@ -39,7 +43,10 @@ pub fn list_serial_ports_cmd() -> Result<Vec<SerialPortInfo>, String> {
 
 /// Connect to a serial port
 #[tauri::command]
-pub fn connect_serial(config: SerialConfig, state: State<'_, AppState>) -> Result<(), String> {
+pub fn connect_serial(
+    config: SerialConfig,
+    state: State<'_, AppState>,
+) -> Result<(), SerialOpenError> {
     // Stop any existing polling first
     state.serial_state.set_running(false);
 
@@ -61,14 +68,19 @@ pub fn connect_serial(config: SerialConfig, state: State<'_, AppState>) -> Resul
             stop_bits,
             parity,
             flow_control,
-        } => Box::new(LocalSerial::new(
-            port,
-            baud_rate,
-            data_bits,
-            stop_bits,
-            &parity,
-            &flow_control,
-        )),
+        } => {
+            let mut local = LocalSerial::new(
+                port,
+                baud_rate,
+                data_bits,
+                stop_bits,
+                &parity,
+                &flow_control,
+            );
+            // 在此处打开串口，以保留分类后的错误信息
+            local.open()?;
+            Box::new(local)
+        }
         SerialConfig::Tcp {
             host,
             port,
@@ -77,7 +89,7 @@ pub fn connect_serial(config: SerialConfig, state: State<'_, AppState>) -> Resul
     };
 
     // Connect
-    datasource.connect()?;
+    datasource.connect().map_err(SerialOpenError::other)?;
 
     // Store the data source
     *state.serial_state.datasource.lock() = Some(datasource);
@@ -106,22 +118,98 @@ pub fn disconnect_serial(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
-/// Write data to serial port
-#[tauri::command]
-pub async fn write_serial(data: Vec<u8>, state: State<'_, AppState>) -> Result<usize, String> {
+/// 写入数据，并可选地以 "tx" 事件回显到控制台
+async fn write_with_echo(
+    data: Vec<u8>,
+    local_echo: Option<bool>,
+    app: &AppHandle,
+    state: &State<'_, AppState>,
+) -> Result<usize, String> {
+    let echo = local_echo.unwrap_or_else(|| state.serial_state.local_echo.load(Ordering::SeqCst));
+
     // 克隆 Arc 以便在 spawn_blocking 中使用
     let serial_state = Arc::clone(&state.serial_state);
 
-    tokio::task::spawn_blocking(move || {
+    let (written, data) = tokio::task::spawn_blocking(move || {
         let mut guard = serial_state.datasource.lock();
         let ds = guard
             .as_mut()
             .ok_or_else(|| "Serial port not connected".to_string())?;
 
-        ds.write(&data)
+        ds.write(&data).map(|n| (n, data))
     })
     .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    if echo && written > 0 {
+        let _ = app.emit(
+            "serial-data",
+            SerialDataEvent {
+                data: data[..written].to_vec(),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                direction: "tx".to_string(),
+            },
+        );
+    }
+
+    Ok(written)
+}
+
+/// Write data to serial port
+#[tauri::command]
+pub async fn write_serial(
+    data: Vec<u8>,
+    local_echo: Option<bool>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    write_with_echo(data, local_echo, &app, &state).await
+}
+
+/// 设置串口写入的默认本地回显行为
+#[tauri::command]
+pub fn set_serial_local_echo(enabled: bool, state: State<'_, AppState>) {
+    state.serial_state.local_echo.store(enabled, Ordering::SeqCst);
+}
+
+/// 批量发送超时的允许范围（毫秒），0 表示每次轮询都发送
+pub const SERIAL_BATCH_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 0..=1000;
+
+/// 批量发送大小阈值的允许范围（字节）
+pub const SERIAL_BATCH_SIZE_RANGE: std::ops::RangeInclusive<usize> = 1..=65536;
+
+/// 校验串口批量发送参数
+pub fn validate_serial_batching(timeout_ms: u64, size_threshold: usize) -> Result<(), String> {
+    if !SERIAL_BATCH_TIMEOUT_RANGE.contains(&timeout_ms) {
+        return Err(format!(
+            "Batch timeout {} ms out of range ({}-{} ms)",
+            timeout_ms,
+            SERIAL_BATCH_TIMEOUT_RANGE.start(),
+            SERIAL_BATCH_TIMEOUT_RANGE.end()
+        ));
+    }
+    if !SERIAL_BATCH_SIZE_RANGE.contains(&size_threshold) {
+        return Err(format!(
+            "Batch size threshold {} out of range ({}-{} bytes)",
+            size_threshold,
+            SERIAL_BATCH_SIZE_RANGE.start(),
+            SERIAL_BATCH_SIZE_RANGE.end()
+        ));
+    }
+    Ok(())
+}
+
+/// 设置串口轮询的批量发送参数：值越小控制台延迟越低，值越大大流量传输时的 IPC 开销越小
+#[tauri::command]
+pub fn set_serial_batching(
+    timeout_ms: u64,
+    size_threshold: usize,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    validate_serial_batching(timeout_ms, size_threshold)?;
+    *state.serial_state.batch_timeout_ms.lock() = timeout_ms;
+    *state.serial_state.batch_size_threshold.lock() = size_threshold;
+    Ok(())
 }
 
 /// Write string to serial port with optional encoding and line ending
@@ -130,6 +218,8 @@ pub async fn write_serial_string(
     text: String,
     encoding: String,
     line_ending: String,
+    local_echo: Option<bool>,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<usize, String> {
     // Apply line ending
@@ -151,19 +241,7 @@ pub async fn write_serial_string(
         _ => text_with_ending.as_bytes().to_vec(),
     };
 
-    // 克隆 Arc 以便在 spawn_blocking 中使用
-    let serial_state = Arc::clone(&state.serial_state);
-
-    tokio::task::spawn_blocking(move || {
-        let mut guard = serial_state.datasource.lock();
-        let ds = guard
-            .as_mut()
-            .ok_or_else(|| "Serial port not connected".to_string())?;
-
-        ds.write(&data)
-    })
-    .await
-    .map_err(|e| format!("Task join error: {}", e))?
+    write_with_echo(data, local_echo, &app, &state).await
 }
 
 /// Start serial polling
@@ -195,8 +273,6 @@ pub async fn start_serial(
 
         let mut batch_buffer = Vec::with_capacity(65536); // 批量缓冲区 64KB
         let mut last_emit = std::time::Instant::now();
-        const BATCH_TIMEOUT_MS: u64 = 10; // 批量发送超时 10ms
-        const BATCH_SIZE_THRESHOLD: usize = 4096; // 批量大小阈值 4KB
 
         loop {
             interval_timer.tick().await;
@@ -205,6 +281,10 @@ pub async fn start_serial(
                 break;
             }
 
+            // 批量参数可在运行中调整
+            let batch_timeout_ms = *serial_state.batch_timeout_ms.lock();
+            let batch_size_threshold = *serial_state.batch_size_threshold.lock();
+
             // 连续读取，直到没有数据
             loop {
                 // 使用 spawn_blocking 避免阻塞异步运行时
@@ -222,11 +302,20 @@ pub async fn start_serial(
 
                 match read_result {
                     Ok(Ok((n, local_buf))) if n > 0 => {
+                        serial_state.record_rx(&local_buf[..n]);
+                        serial_state.history.lock().push(&local_buf[..n]);
+
+                        // 暂停显示：继续读空串口，但暂存数据不发送
+                        if serial_state.is_paused() {
+                            serial_state.buffer_paused(&local_buf[..n]);
+                            continue;
+                        }
+
                         // 将数据添加到批量缓冲区
                         batch_buffer.extend_from_slice(&local_buf[..n]);
 
                         // 如果批量缓冲区达到阈值，立即发送
-                        if batch_buffer.len() >= BATCH_SIZE_THRESHOLD {
+                        if batch_buffer.len() >= batch_size_threshold {
                             let timestamp = chrono::Utc::now().timestamp_millis();
                             let _ = app.emit(
                                 "serial-data",
@@ -265,7 +354,7 @@ pub async fn start_serial(
             }
 
             // 如果有累积的数据且超过超时时间，发送
-            if !batch_buffer.is_empty() && last_emit.elapsed().as_millis() as u64 >= BATCH_TIMEOUT_MS {
+            if !batch_buffer.is_empty() && last_emit.elapsed().as_millis() as u64 >= batch_timeout_ms {
                 let timestamp = chrono::Utc::now().timestamp_millis();
                 let _ = app.emit(
                     "serial-data",
@@ -314,6 +403,52 @@ pub fn stop_serial(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
+/// 获取最近接收的数据，最多 `max_bytes` 字节
+#[tauri::command]
+pub fn get_serial_history(max_bytes: Option<usize>, state: State<'_, AppState>) -> Vec<u8> {
+    state
+        .serial_state
+        .history
+        .lock()
+        .tail(max_bytes.unwrap_or(SERIAL_HISTORY_CAPACITY))
+}
+
+/// 恢复暂停的串口控制台的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SerialResumeResult {
+    /// 输出到控制台的字节数
+    pub flushed: usize,
+    /// 因暂停缓冲区已满而丢弃的字节数
+    pub dropped: u64,
+}
+
+/// 暂停串口控制台显示；数据继续读取并暂存到恢复为止
+#[tauri::command]
+pub fn pause_serial(state: State<'_, AppState>) {
+    state.serial_state.paused.store(true, Ordering::SeqCst);
+}
+
+/// 恢复串口控制台显示，并输出暂停期间收到的全部数据
+#[tauri::command]
+pub fn resume_serial(app: AppHandle, state: State<'_, AppState>) -> SerialResumeResult {
+    state.serial_state.paused.store(false, Ordering::SeqCst);
+    let (data, dropped) = state.serial_state.take_paused();
+    let flushed = data.len();
+
+    if !data.is_empty() {
+        let _ = app.emit(
+            "serial-data",
+            SerialDataEvent {
+                data,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                direction: "rx".to_string(),
+            },
+        );
+    }
+
+    SerialResumeResult { flushed, dropped }
+}
+
 /// Get serial status
 #[tauri::command]
 pub fn get_serial_status(state: State<'_, AppState>) -> SerialStatus {
@@ -336,6 +471,9 @@ pub fn get_serial_status(state: State<'_, AppState>) -> SerialStatus {
 #[tauri::command]
 pub fn clear_serial_buffer(state: State<'_, AppState>) -> Result<(), String> {
     state.serial_state.line_buffer.lock().clear();
+    state.serial_state.recent_rx.lock().clear();
+    state.serial_state.take_paused();
+    state.serial_state.history.lock().clear();
 
     // Reset stats
     if let Some(ds) = state.serial_state.datasource.lock().as_mut() {
@@ -344,3 +482,16 @@ pub fn clear_serial_buffer(state: State<'_, AppState>) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 推测串口接收数据的编码
+/// 使用传入的样本，未提供时使用最近接收的数据
+#[tauri::command]
+pub fn detect_serial_encoding(
+    sample: Option<Vec<u8>>,
+    state: State<'_, AppState>,
+) -> EncodingGuess {
+    match sample {
+        Some(data) => detect_encoding(&data),
+        None => detect_encoding(&state.serial_state.recent_rx.lock()),
+    }
+}
@@ -0,0 +1,464 @@
+use crate::error::{AppError, AppResult};
+use crate::state::{AppState, SwoState};
+use probe_rs::architecture::arm::component::TraceSink;
+use probe_rs::architecture::arm::SwoConfig;
+use probe_rs::{MemoryInterface, Session};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::time::interval;
+
+/// ITM 激励端口使能寄存器
+const ITM_TER: u64 = 0xE000_0E00;
+
+/// ITM 激励端口数量
+pub const ITM_PORT_COUNT: u8 = 32;
+
+/// 解码得到的 ITM 激励端口数据包
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItmPacket {
+    pub port: u8,
+    pub payload: Vec<u8>,
+}
+
+/// 从 SWO 字节流中解码 ITM 激励端口数据包
+/// 已解码的字节从 buffer 中移除，末尾不完整的数据包保留到下次解码；
+/// 同步包、溢出包、时间戳、扩展包及硬件源 (DWT) 数据包被跳过
+pub fn decode_itm(buffer: &mut Vec<u8>) -> Vec<ItmPacket> {
+    let mut packets = Vec::new();
+    let mut i = 0;
+
+    // 从 start 开始的连续字节 (bit7 为继续标志) 的结束位置，不完整时返回 None
+    let continuation_end = |buffer: &[u8], start: usize| -> Option<usize> {
+        let mut j = start;
+        loop {
+            let byte = *buffer.get(j)?;
+            j += 1;
+            if byte & 0x80 == 0 {
+                return Some(j);
+            }
+        }
+    };
+
+    while i < buffer.len() {
+        let header = buffer[i];
+
+        let next = if header == 0x00 {
+            // 同步包：若干 0x00 后跟 0x80
+            let mut j = i;
+            while j < buffer.len() && buffer[j] == 0x00 {
+                j += 1;
+            }
+            match buffer.get(j) {
+                Some(0x80) => j + 1,
+                Some(_) => j,
+                None => break,
+            }
+        } else if header & 0x03 != 0 {
+            // 源数据包：bit2 为 0 时为软件激励端口
+            let size = match header & 0x03 {
+                1 => 1,
+                2 => 2,
+                _ => 4,
+            };
+            if i + 1 + size > buffer.len() {
+                break;
+            }
+            if header & 0x04 == 0 {
+                packets.push(ItmPacket {
+                    port: header >> 3,
+                    payload: buffer[i + 1..i + 1 + size].to_vec(),
+                });
+            }
+            i + 1 + size
+        } else if header == 0x70 {
+            // 溢出包
+            i + 1
+        } else if header & 0x80 != 0 {
+            // 带继续标志的时间戳/扩展包
+            match continuation_end(buffer, i + 1) {
+                Some(end) => end,
+                None => break,
+            }
+        } else {
+            // 单字节时间戳/扩展包或保留值
+            i + 1
+        };
+
+        i = next;
+    }
+
+    buffer.drain(..i);
+    packets
+}
+
+/// 将激励端口列表转换为 ITM_TER 掩码
+pub fn stimulus_mask_from_ports(ports: &[u8]) -> AppResult<u32> {
+    ports.iter().try_fold(0u32, |mask, &port| {
+        if port >= ITM_PORT_COUNT {
+            return Err(AppError::SwoError(format!(
+                "激励端口 {} 超出范围 (0-{})",
+                port,
+                ITM_PORT_COUNT - 1
+            )));
+        }
+        Ok(mask | (1 << port))
+    })
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwoStartOptions {
+    /// TPIU 时钟 (通常为内核时钟)
+    pub core_clock_hz: u32,
+    /// SWO 波特率
+    pub baud_rate: u32,
+    /// 启用的激励端口，优先于 stimulus_mask
+    #[serde(default)]
+    pub stimulus_ports: Option<Vec<u8>>,
+    /// 启用的激励端口掩码（默认全部启用）
+    #[serde(default)]
+    pub stimulus_mask: Option<u32>,
+    /// 轮询间隔 (毫秒)
+    #[serde(default)]
+    pub poll_interval: Option<u64>,
+    /// 操作的内核编号（默认使用当前活动内核）
+    #[serde(default)]
+    pub core_index: Option<usize>,
+}
+
+/// SWO 数据事件 (发送到前端)
+#[derive(Debug, Clone, Serialize)]
+pub struct SwoDataEvent {
+    pub port: u8,
+    pub data: Vec<u8>,
+    pub timestamp: u64,
+}
+
+/// 激励端口活动统计
+#[derive(Debug, Clone, Serialize)]
+pub struct ItmPortActivity {
+    pub port: u8,
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// 配置 TPIU/ITM 以 SWO 输出，并设置激励端口使能掩码
+fn setup_swo(
+    session: &mut Session,
+    core_index: usize,
+    core_clock_hz: u32,
+    baud_rate: u32,
+    stimulus_mask: u32,
+) -> AppResult<()> {
+    let config = SwoConfig::new(core_clock_hz)
+        .set_baud(baud_rate)
+        .set_continuous_formatting(false);
+    session
+        .setup_tracing(core_index, TraceSink::Swo(config))
+        .map_err(|e| AppError::SwoError(format!("配置 SWO 失败: {}", e)))?;
+
+    let mut core = session
+        .core(core_index)
+        .map_err(|e| AppError::SwoError(e.to_string()))?;
+    core.write_word_32(ITM_TER, stimulus_mask)
+        .map_err(|e| AppError::SwoError(format!("写入 ITM_TER 失败: {}", e)))?;
+
+    log::info!(
+        "SWO 已配置: 内核时钟 {} Hz, 波特率 {}, 激励端口掩码 0x{:08X}",
+        core_clock_hz, baud_rate, stimulus_mask
+    );
+    Ok(())
+}
+
+/// 启动 SWO/ITM 跟踪，按激励端口发送 swo-data 事件
+#[tauri::command]
+pub async fn start_swo(
+    options: SwoStartOptions,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> AppResult<()> {
+    let stimulus_mask = match options.stimulus_ports {
+        Some(ref ports) => stimulus_mask_from_ports(ports)?,
+        None => options.stimulus_mask.unwrap_or(u32::MAX),
+    };
+    let core_index = state.core_index(options.core_index);
+
+    // 先占用运行标志，避免与 list_itm_ports 采样或另一次启动同时配置跟踪
+    if !state.swo_state.try_start() {
+        return Err(AppError::SwoError("SWO 已在运行".to_string()));
+    }
+
+    let setup = {
+        let mut session_guard = state.lock_session();
+        match session_guard.as_mut() {
+            Some(session) => setup_swo(session, core_index, options.core_clock_hz, options.baud_rate, stimulus_mask),
+            None => Err(AppError::NotConnected),
+        }
+    };
+    if let Err(e) = setup {
+        state.swo_state.set_running(false);
+        return Err(e);
+    }
+
+    let swo_state = Arc::clone(&state.swo_state);
+    swo_state.port_stats.lock().clear();
+    *swo_state.poll_interval_ms.lock() = options.poll_interval.unwrap_or(10).max(1);
+
+    let session_arc = Arc::clone(&state.session);
+    let flash_in_progress = Arc::clone(&state.flash_in_progress);
+    tokio::spawn(async move {
+        log::info!("SWO 轮询任务已启动");
+        swo_polling_task(swo_state, session_arc, flash_in_progress, app_handle).await;
+        log::info!("SWO 轮询任务已结束");
+    });
+
+    Ok(())
+}
+
+/// 停止 SWO/ITM 跟踪
+#[tauri::command]
+pub async fn stop_swo(state: State<'_, AppState>) -> AppResult<()> {
+    state.swo_state.set_running(false);
+    Ok(())
+}
+
+/// 短时间采样 SWO 数据流，返回产生了数据的激励端口
+/// SWO 正在运行（或另一次采样进行中）时直接返回运行期间的统计
+#[tauri::command]
+pub async fn list_itm_ports(
+    core_clock_hz: u32,
+    baud_rate: u32,
+    duration_ms: Option<u64>,
+    core_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> AppResult<Vec<ItmPortActivity>> {
+    // 采样期间占用 SWO 运行标志，start_swo 会因此拒绝启动
+    if !state.swo_state.try_start() {
+        return Ok(port_activity(&state.swo_state));
+    }
+
+    let core_index = state.core_index(core_index);
+    let duration = Duration::from_millis(duration_ms.unwrap_or(500));
+    let sampled = sample_itm_ports(&state, core_index, core_clock_hz, baud_rate, duration).await;
+    state.swo_state.set_running(false);
+
+    let ports = port_activity(&sampled?);
+    log::info!("ITM 采样完成，活动端口: {:?}", ports.iter().map(|p| p.port).collect::<Vec<_>>());
+    Ok(ports)
+}
+
+/// 临时开启全部激励端口采样 SWO 数据，结束后恢复原 ITM_TER 并关闭 SWV 输出
+async fn sample_itm_ports(
+    state: &AppState,
+    core_index: usize,
+    core_clock_hz: u32,
+    baud_rate: u32,
+    duration: Duration,
+) -> AppResult<SwoState> {
+    let previous_ter = {
+        let mut session_guard = state.lock_session();
+        let session = session_guard.as_mut().ok_or(AppError::NotConnected)?;
+        let previous_ter = session
+            .core(core_index)
+            .map_err(|e| AppError::SwoError(e.to_string()))?
+            .read_word_32(ITM_TER)
+            .map_err(|e| AppError::SwoError(format!("读取 ITM_TER 失败: {}", e)))?;
+        setup_swo(session, core_index, core_clock_hz, baud_rate, u32::MAX)?;
+        previous_ter
+    };
+
+    let sample = SwoState::default();
+    let deadline = std::time::Instant::now() + duration;
+    let mut pending = Vec::new();
+    let mut result = Ok(());
+
+    // stop_swo 会清除运行标志，提前结束采样
+    while std::time::Instant::now() < deadline && state.swo_state.is_running() {
+        let data = {
+            let mut session_guard = state.lock_session();
+            match session_guard.as_mut() {
+                Some(session) => session
+                    .read_trace_data()
+                    .map_err(|e| AppError::SwoError(format!("读取 SWO 数据失败: {}", e))),
+                None => Err(AppError::NotConnected),
+            }
+        };
+        match data {
+            Ok(data) => pending.extend_from_slice(&data),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+        for packet in decode_itm(&mut pending) {
+            sample.record_packet(packet.port, packet.payload.len());
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // 恢复采样前的跟踪配置，失败只记录警告
+    if let Some(session) = state.lock_session().as_mut() {
+        if let Err(e) = session.disable_swv(core_index) {
+            log::warn!("采样后关闭 SWV 失败: {}", e);
+        }
+        match session.core(core_index) {
+            Ok(mut core) => {
+                if let Err(e) = core.write_word_32(ITM_TER, previous_ter) {
+                    log::warn!("采样后恢复 ITM_TER 失败: {}", e);
+                }
+            }
+            Err(e) => log::warn!("采样后恢复 ITM_TER 失败: {}", e),
+        }
+    }
+
+    result.map(|_| sample)
+}
+
+fn port_activity(swo_state: &SwoState) -> Vec<ItmPortActivity> {
+    swo_state
+        .port_stats
+        .lock()
+        .iter()
+        .map(|(&port, stats)| ItmPortActivity {
+            port,
+            packets: stats.packets,
+            bytes: stats.bytes,
+        })
+        .collect()
+}
+
+/// SWO 轮询任务：读取跟踪数据、解码 ITM 数据包并按端口合并发送，连接断开时停止
+async fn swo_polling_task(
+    swo_state: Arc<SwoState>,
+    session: Arc<parking_lot::Mutex<Option<Session>>>,
+    flash_in_progress: Arc<AtomicUsize>,
+    app_handle: AppHandle,
+) {
+    let poll_interval_ms = *swo_state.poll_interval_ms.lock();
+    let mut interval_timer = interval(Duration::from_millis(poll_interval_ms));
+    interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut pending = Vec::new();
+
+    loop {
+        interval_timer.tick().await;
+
+        if !swo_state.is_running() {
+            break;
+        }
+
+        // 烧录/擦除期间让出 session
+        if flash_in_progress.load(Ordering::SeqCst) > 0 {
+            continue;
+        }
+
+        let data = {
+            let mut session_guard = match session.try_lock_for(Duration::from_millis(500)) {
+                Some(guard) => guard,
+                None => continue,
+            };
+
+            let session = match session_guard.as_mut() {
+                Some(s) => s,
+                None => {
+                    log::info!("Session 已断开，停止 SWO");
+                    break;
+                }
+            };
+
+            match session.read_trace_data() {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("读取 SWO 数据失败: {}", e);
+                    let _ = app_handle.emit("swo-error", e.to_string());
+                    break;
+                }
+            }
+        };
+
+        if data.is_empty() {
+            continue;
+        }
+        pending.extend_from_slice(&data);
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        // 同一端口的连续数据包合并为一个事件
+        let mut events: Vec<SwoDataEvent> = Vec::new();
+        for packet in decode_itm(&mut pending) {
+            swo_state.record_packet(packet.port, packet.payload.len());
+            match events.last_mut() {
+                Some(last) if last.port == packet.port => last.data.extend_from_slice(&packet.payload),
+                _ => events.push(SwoDataEvent {
+                    port: packet.port,
+                    data: packet.payload,
+                    timestamp,
+                }),
+            }
+        }
+
+        for event in events {
+            if let Err(e) = app_handle.emit("swo-data", event) {
+                log::error!("发送 SWO 数据事件失败: {}", e);
+            }
+        }
+    }
+
+    swo_state.set_running(false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stimulus_packets_are_decoded_per_port() {
+        // 端口 0 单字节 'A'，端口 3 四字节，端口 1 两字节
+        let mut buffer = vec![0x01, b'A', 0x1B, 1, 2, 3, 4, 0x0A, b'h', b'i'];
+
+        let packets = decode_itm(&mut buffer);
+
+        assert_eq!(
+            packets,
+            vec![
+                ItmPacket { port: 0, payload: vec![b'A'] },
+                ItmPacket { port: 3, payload: vec![1, 2, 3, 4] },
+                ItmPacket { port: 1, payload: vec![b'h', b'i'] },
+            ]
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_sync_timestamp_and_hardware_packets_are_skipped() {
+        // 同步包、本地时间戳 (带继续字节)、DWT 硬件源包、溢出包
+        let mut buffer = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0xC0, 0x81, 0x01, 0x05, 0xAA, 0x70, 0x01, b'x'];
+
+        let packets = decode_itm(&mut buffer);
+
+        assert_eq!(packets, vec![ItmPacket { port: 0, payload: vec![b'x'] }]);
+    }
+
+    #[test]
+    fn test_incomplete_packet_is_kept_for_next_read() {
+        let mut buffer = vec![0x01, b'a', 0x03, 0x11, 0x22];
+
+        let packets = decode_itm(&mut buffer);
+        assert_eq!(packets, vec![ItmPacket { port: 0, payload: vec![b'a'] }]);
+        assert_eq!(buffer, vec![0x03, 0x11, 0x22]);
+
+        buffer.extend_from_slice(&[0x33, 0x44]);
+        let packets = decode_itm(&mut buffer);
+        assert_eq!(packets, vec![ItmPacket { port: 0, payload: vec![0x11, 0x22, 0x33, 0x44] }]);
+    }
+
+    #[test]
+    fn test_stimulus_mask_from_ports() {
+        assert_eq!(stimulus_mask_from_ports(&[0, 1, 31]).unwrap(), 0x8000_0003);
+        assert!(stimulus_mask_from_ports(&[32]).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+// 文件压缩辅助模块
+// 用于扫描报告、Flash 转储等较大文件的可选 gzip 压缩
+
+use crate::error::AppResult;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// gzip 文件头魔数
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// 返回压缩文件路径（追加 .gz 后缀）
+pub fn gz_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+/// 写入文件，compress 为 true 时使用 gzip 压缩
+pub fn write_file(path: &Path, data: &[u8], compress: bool) -> AppResult<()> {
+    if compress {
+        let file = fs::File::create(path)?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()?;
+    } else {
+        fs::write(path, data)?;
+    }
+    Ok(())
+}
+
+/// 读取文件，自动识别并解压 gzip 内容
+pub fn read_file(path: &Path) -> AppResult<Vec<u8>> {
+    let raw = fs::read(path)?;
+
+    if raw.starts_with(&GZIP_MAGIC) {
+        let mut decoder = GzDecoder::new(&raw[..]);
+        let mut data = Vec::new();
+        decoder.read_to_end(&mut data)?;
+        Ok(data)
+    } else {
+        Ok(raw)
+    }
+}
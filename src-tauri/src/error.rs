@@ -11,12 +11,19 @@ pub enum AppError {
     #[error("Flash操作失败: {0}")]
     FlashError(String),
 
+    /// 探针通信等暂时性原因导致的烧录失败，可整体重试（显示与 FlashError 相同）
+    #[error("Flash操作失败: {0}")]
+    TransientFlashError(String),
+
     #[error("内存操作失败: {0}")]
     MemoryError(String),
 
     #[error("RTT错误: {0}")]
     RttError(String),
 
+    #[error("SWO错误: {0}")]
+    SwoError(String),
+
     #[error("Pack解析错误: {0}")]
     PackError(String),
 
@@ -26,6 +33,10 @@ pub enum AppError {
     #[error("配置错误: {0}")]
     ConfigError(String),
 
+    /// 操作存在风险，需要用户确认后重试；内容为 JSON 格式的详情
+    #[error("requires_confirmation: {0}")]
+    RequiresConfirmation(String),
+
     #[error("IO错误: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -0,0 +1,264 @@
+// ELF ARM 架构信息解析
+// 读取 e_flags 与 .ARM.attributes 构建属性，用于烧录前检查固件与目标内核是否匹配
+
+use crate::error::{AppError, AppResult};
+
+/// e_flags: 硬件浮点调用约定
+const EF_ARM_ABI_FLOAT_HARD: u32 = 0x400;
+
+/// .ARM.attributes 中的标签
+const TAG_FILE: u64 = 1;
+const TAG_CPU_RAW_NAME: u64 = 4;
+const TAG_CPU_NAME: u64 = 5;
+const TAG_CPU_ARCH: u64 = 6;
+const TAG_FP_ARCH: u64 = 10;
+const TAG_COMPATIBILITY: u64 = 32;
+const TAG_ABI_VFP_ARGS: u64 = 28;
+
+/// 固件声明的 ARM 架构信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElfArchInfo {
+    /// Tag_CPU_name（如 "cortex-m4"）
+    pub cpu_name: Option<String>,
+    /// Tag_CPU_arch 原始值
+    pub cpu_arch: Option<u64>,
+    /// Tag_FP_arch（0 表示不使用 FPU）
+    pub fp_arch: Option<u64>,
+    /// 使用硬件浮点调用约定（e_flags 或 Tag_ABI_VFP_args）
+    pub hard_float: bool,
+}
+
+impl ElfArchInfo {
+    /// 固件要求的最低内核架构（probe-rs 命名）
+    pub fn required_core(&self) -> Option<&'static str> {
+        match self.cpu_arch? {
+            11 | 12 => Some("armv6m"),
+            10 => Some("armv7m"),
+            13 => Some("armv7em"),
+            16 | 17 | 21 => Some("armv8m"),
+            _ => None,
+        }
+    }
+
+    /// 固件是否使用 FPU 指令或硬件浮点调用约定
+    pub fn uses_fpu(&self) -> bool {
+        self.hard_float || self.fp_arch.is_some_and(|fp| fp != 0)
+    }
+}
+
+/// 从 ELF 文件中读取架构信息
+pub fn parse_elf_arch(data: &[u8]) -> AppResult<ElfArchInfo> {
+    use object::read::elf::{ElfFile32, FileHeader};
+    use object::{Object, ObjectSection};
+
+    let elf = ElfFile32::<object::Endianness>::parse(data)
+        .map_err(|e| AppError::FileError(format!("解析 ELF 文件失败: {}", e)))?;
+    let e_flags = elf.elf_header().e_flags(elf.endian());
+
+    let mut info = match elf.section_by_name(".ARM.attributes") {
+        Some(section) => {
+            let bytes = section
+                .data()
+                .map_err(|_| AppError::FileError("读取 .ARM.attributes 失败".to_string()))?;
+            parse_arm_attributes(bytes)
+        }
+        None => ElfArchInfo::default(),
+    };
+    info.hard_float |= e_flags & EF_ARM_ABI_FLOAT_HARD != 0;
+
+    Ok(info)
+}
+
+/// 解析 .ARM.attributes 段内容（仅读取 "aeabi" 厂商的文件级属性）
+pub fn parse_arm_attributes(data: &[u8]) -> ElfArchInfo {
+    let mut info = ElfArchInfo::default();
+
+    if data.first() != Some(&b'A') {
+        return info;
+    }
+
+    let mut pos = 1;
+    while pos + 4 <= data.len() {
+        let len = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        if len < 4 || pos + len > data.len() {
+            break;
+        }
+        let section = &data[pos + 4..pos + len];
+        pos += len;
+
+        let Some(name_end) = section.iter().position(|&b| b == 0) else {
+            continue;
+        };
+        if &section[..name_end] != b"aeabi" {
+            continue;
+        }
+        parse_aeabi_subsections(&section[name_end + 1..], &mut info);
+    }
+
+    info
+}
+
+fn parse_aeabi_subsections(mut data: &[u8], info: &mut ElfArchInfo) {
+    while !data.is_empty() {
+        let mut pos = 0;
+        let Some(tag) = read_uleb128(data, &mut pos) else { return };
+        if pos + 4 > data.len() {
+            return;
+        }
+        let size = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        if size < pos + 4 || size > data.len() {
+            return;
+        }
+
+        if tag == TAG_FILE {
+            parse_attributes(&data[pos + 4..size], info);
+        }
+        data = &data[size..];
+    }
+}
+
+fn parse_attributes(data: &[u8], info: &mut ElfArchInfo) {
+    let mut pos = 0;
+    while pos < data.len() {
+        let Some(tag) = read_uleb128(data, &mut pos) else { return };
+
+        match tag {
+            TAG_CPU_RAW_NAME | TAG_CPU_NAME => {
+                let Some(value) = read_ntbs(data, &mut pos) else { return };
+                if tag == TAG_CPU_NAME {
+                    info.cpu_name = Some(value);
+                }
+            }
+            TAG_COMPATIBILITY => {
+                if read_uleb128(data, &mut pos).is_none() || read_ntbs(data, &mut pos).is_none() {
+                    return;
+                }
+            }
+            // 编号大于 32 的奇数标签为字符串，其余为 ULEB128
+            t if t > 32 && t % 2 == 1 => {
+                if read_ntbs(data, &mut pos).is_none() {
+                    return;
+                }
+            }
+            _ => {
+                let Some(value) = read_uleb128(data, &mut pos) else { return };
+                match tag {
+                    TAG_CPU_ARCH => info.cpu_arch = Some(value),
+                    TAG_FP_ARCH => info.fp_arch = Some(value),
+                    TAG_ABI_VFP_ARGS => info.hard_float |= value == 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn read_uleb128(data: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        if shift < 64 {
+            result |= ((byte & 0x7F) as u64) << shift;
+        }
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+        shift += 7;
+    }
+}
+
+fn read_ntbs(data: &[u8], pos: &mut usize) -> Option<String> {
+    let end = data[*pos..].iter().position(|&b| b == 0)? + *pos;
+    let value = String::from_utf8_lossy(&data[*pos..end]).to_string();
+    *pos = end + 1;
+    Some(value)
+}
+
+/// 架构能力等级，高等级内核可运行低等级代码
+fn core_level(core: &str) -> Option<u8> {
+    match core {
+        "armv6m" => Some(1),
+        "armv7m" => Some(2),
+        "armv7em" => Some(3),
+        "armv8m" => Some(4),
+        _ => None,
+    }
+}
+
+/// 比较固件架构与目标内核类型（probe-rs 命名，如 "armv7em"），返回不匹配警告
+/// 仅作提示：存在交叉兼容的情况，不应阻止烧录
+pub fn arch_mismatch_warnings(info: &ElfArchInfo, target_core: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let target_core = target_core.to_lowercase();
+
+    if let (Some(required), Some(target_level)) = (info.required_core(), core_level(&target_core)) {
+        if core_level(required).is_some_and(|level| level > target_level) {
+            warnings.push(format!(
+                "固件为 {}{} 架构编译，目标内核为 {}，烧录后可能无法运行",
+                required,
+                info.cpu_name.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default(),
+                target_core
+            ));
+        }
+    }
+
+    if info.uses_fpu() && matches!(target_core.as_str(), "armv6m" | "armv7m") {
+        warnings.push(format!(
+            "固件使用了硬件浮点 (FPU)，但目标内核 {} 不带 FPU，运行时会触发 UsageFault",
+            target_core
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造 .ARM.attributes 段: Tag_CPU_name="cortex-m4", Tag_CPU_arch=13 (v7E-M),
+    /// Tag_FP_arch=6, Tag_ABI_VFP_args=1
+    fn cortex_m4_hard_float_attributes() -> Vec<u8> {
+        let mut attrs = Vec::new();
+        attrs.push(5);
+        attrs.extend_from_slice(b"cortex-m4\0");
+        attrs.extend_from_slice(&[6, 13, 10, 6, 28, 1]);
+
+        let mut file_subsection = vec![1];
+        file_subsection.extend_from_slice(&((attrs.len() + 5) as u32).to_le_bytes());
+        file_subsection.extend_from_slice(&attrs);
+
+        let mut vendor = Vec::new();
+        vendor.extend_from_slice(b"aeabi\0");
+        vendor.extend_from_slice(&file_subsection);
+
+        let mut data = vec![b'A'];
+        data.extend_from_slice(&((vendor.len() + 4) as u32).to_le_bytes());
+        data.extend_from_slice(&vendor);
+        data
+    }
+
+    #[test]
+    fn test_parse_arm_attributes() {
+        let info = parse_arm_attributes(&cortex_m4_hard_float_attributes());
+
+        assert_eq!(info.cpu_name.as_deref(), Some("cortex-m4"));
+        assert_eq!(info.cpu_arch, Some(13));
+        assert_eq!(info.fp_arch, Some(6));
+        assert!(info.hard_float);
+        assert_eq!(info.required_core(), Some("armv7em"));
+    }
+
+    #[test]
+    fn test_mismatch_warnings() {
+        let info = parse_arm_attributes(&cortex_m4_hard_float_attributes());
+
+        // M4 + FPU 固件烧录到 M0 目标：架构和 FPU 均不匹配
+        assert_eq!(arch_mismatch_warnings(&info, "armv6m").len(), 2);
+        // 同架构或更高架构目标不告警
+        assert!(arch_mismatch_warnings(&info, "armv7em").is_empty());
+        assert!(arch_mismatch_warnings(&info, "Armv8m").is_empty());
+    }
+}
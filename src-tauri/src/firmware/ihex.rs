@@ -0,0 +1,93 @@
+// Intel HEX 解析
+// 支持 00 数据、01 结束、02 扩展段地址、04 扩展线性地址记录，并校验每行校验和
+
+use super::FirmwareSegment;
+use crate::error::{AppError, AppResult};
+
+pub fn parse(content: &str) -> AppResult<Vec<FirmwareSegment>> {
+    let mut segments = Vec::new();
+    let mut base: u64 = 0;
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let err = |msg: &str| AppError::FileError(format!("HEX 第 {} 行{}", line_no, msg));
+
+        let payload = line
+            .strip_prefix(':')
+            .and_then(super::decode_hex)
+            .ok_or_else(|| err("格式错误"))?;
+        if payload.len() < 5 || payload.len() != payload[0] as usize + 5 {
+            return Err(err("长度字段与实际长度不符"));
+        }
+
+        let sum = payload.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        if sum != 0 {
+            return Err(err("校验和错误"));
+        }
+
+        let offset = u16::from_be_bytes([payload[1], payload[2]]) as u64;
+        let data = &payload[4..payload.len() - 1];
+
+        match payload[3] {
+            0x00 => super::push_data(&mut segments, base + offset, data),
+            0x01 => break,
+            0x02 if data.len() == 2 => base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 4,
+            0x04 if data.len() == 2 => base = (u16::from_be_bytes([data[0], data[1]]) as u64) << 16,
+            // 03/05 起始地址记录不含数据
+            0x03 | 0x05 => {}
+            _ => return Err(err("记录类型不支持")),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// 读取起始地址记录：05 (线性 EIP) 优先，其次 03 (CS:IP)
+pub fn start_address(content: &str) -> Option<u64> {
+    let mut segment_start = None;
+    for line in content.lines() {
+        let Some(payload) = line.trim().strip_prefix(':').and_then(super::decode_hex) else {
+            continue;
+        };
+        if payload.len() != 9 || payload[0] != 4 {
+            continue;
+        }
+        let value = u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]);
+        match payload[3] {
+            0x05 => return Some(value as u64),
+            0x03 => segment_start = Some(((value >> 16) << 4) as u64 + (value & 0xFFFF) as u64),
+            _ => {}
+        }
+    }
+    segment_start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_linear_address() {
+        let content = "\
+:020000040800F2
+:0400000001020304F2
+:0400040005060708DE
+:00000001FF
+";
+        let segments = parse(content).unwrap();
+
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].address, 0x0800_0000);
+        assert_eq!(segments[0].data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_checksum_error_is_rejected() {
+        assert!(parse(":0400000001020304F3\n").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+// 固件文件解析模块
+
+pub mod arch;
+pub mod ihex;
+pub mod srec;
+pub mod symbols;
+
+use crate::error::{AppError, AppResult};
+use serde::Serialize;
+use std::path::Path;
+
+/// 固件中一段连续的地址/数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct FirmwareSegment {
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+/// 固件镜像
+#[derive(Debug, Clone)]
+pub enum FirmwareImage {
+    /// 带地址信息的数据段 (ELF/HEX/S-record)
+    Segments(Vec<FirmwareSegment>),
+    /// 无地址信息的原始二进制 (BIN)
+    Binary(Vec<u8>),
+}
+
+/// 固件文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirmwareFormat {
+    Elf,
+    Hex,
+    Bin,
+    Srec,
+}
+
+impl FirmwareFormat {
+    /// 按扩展名（小写）识别，未知扩展名返回 None
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            e if is_srec_extension(e) => Some(Self::Srec),
+            "hex" | "ihex" => Some(Self::Hex),
+            "bin" => Some(Self::Bin),
+            "elf" | "axf" | "out" => Some(Self::Elf),
+            _ => None,
+        }
+    }
+
+    /// 识别文件格式，未知扩展名按 ELF 处理
+    pub fn detect(path: &Path) -> Self {
+        file_extension(path)
+            .as_deref()
+            .and_then(Self::from_extension)
+            .unwrap_or(Self::Elf)
+    }
+}
+
+/// 小写的文件扩展名
+pub fn file_extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase())
+}
+
+/// S-record 文件扩展名
+pub fn is_srec_extension(ext: &str) -> bool {
+    matches!(ext, "s19" | "s28" | "s37" | "srec" | "mot")
+}
+
+/// 按扩展名加载固件文件，未知扩展名按 ELF 解析
+pub fn load_image(path: &Path) -> AppResult<FirmwareImage> {
+    match FirmwareFormat::detect(path) {
+        FirmwareFormat::Srec => Ok(FirmwareImage::Segments(srec::parse_file(path)?.segments)),
+        FirmwareFormat::Hex => Ok(FirmwareImage::Segments(ihex::parse(&std::fs::read_to_string(path)?)?)),
+        FirmwareFormat::Bin => Ok(FirmwareImage::Binary(crate::compression::read_file(path)?)),
+        FirmwareFormat::Elf => Ok(FirmwareImage::Segments(parse_elf(&std::fs::read(path)?)?)),
+    }
+}
+
+/// 固件元数据（无需连接目标）
+#[derive(Debug, Clone, Serialize)]
+pub struct FirmwareMetadata {
+    pub format: FirmwareFormat,
+    /// 数据总字节数（不含段间空隙）
+    pub total_bytes: u64,
+    pub segment_count: usize,
+    /// 入口地址（ELF e_entry、HEX 05/03 记录、S-record S7/S8/S9 记录），BIN 无此信息
+    pub entry_point: Option<u64>,
+    /// 覆盖的地址范围 [start, end)；BIN 按 bin_base 计算
+    pub address_range: (u64, u64),
+    /// 按地址顺序拼接所有段数据计算的 CRC32，内容相同的不同格式文件结果一致
+    pub crc32: u32,
+}
+
+/// 根据已加载的镜像计算元数据
+pub fn image_metadata(format: FirmwareFormat, image: &FirmwareImage, entry_point: Option<u64>, bin_base: u64) -> FirmwareMetadata {
+    let mut segments: Vec<(u64, &[u8])> = match image {
+        FirmwareImage::Segments(segments) => segments.iter().map(|s| (s.address, s.data.as_slice())).collect(),
+        FirmwareImage::Binary(data) => vec![(bin_base, data.as_slice())],
+    };
+    segments.sort_by_key(|(address, _)| *address);
+
+    let mut crc = flate2::Crc::new();
+    for (_, data) in &segments {
+        crc.update(data);
+    }
+    let start = segments.first().map(|(address, _)| *address).unwrap_or(bin_base);
+    let end = segments
+        .iter()
+        .map(|(address, data)| address + data.len() as u64)
+        .max()
+        .unwrap_or(start);
+
+    FirmwareMetadata {
+        format,
+        total_bytes: segments.iter().map(|(_, data)| data.len() as u64).sum(),
+        segment_count: segments.len(),
+        entry_point,
+        address_range: (start, end),
+        crc32: crc.sum(),
+    }
+}
+
+/// 解析固件文件并计算元数据
+pub fn analyze(path: &Path, bin_base: u64) -> AppResult<FirmwareMetadata> {
+    let format = FirmwareFormat::detect(path);
+    let (image, entry_point) = match format {
+        FirmwareFormat::Srec => {
+            let srec = srec::parse_file(path)?;
+            (FirmwareImage::Segments(srec.segments), srec.entry.map(u64::from))
+        }
+        FirmwareFormat::Hex => {
+            let content = std::fs::read_to_string(path)?;
+            (FirmwareImage::Segments(ihex::parse(&content)?), ihex::start_address(&content))
+        }
+        FirmwareFormat::Bin => (FirmwareImage::Binary(crate::compression::read_file(path)?), None),
+        FirmwareFormat::Elf => {
+            use object::Object;
+            let data = std::fs::read(path)?;
+            let entry = object::File::parse(data.as_slice()).ok().map(|f| f.entry());
+            (FirmwareImage::Segments(parse_elf(&data)?), entry)
+        }
+    };
+    Ok(image_metadata(format, &image, entry_point, bin_base))
+}
+
+/// 提取 ELF 中的可加载段（按物理地址 LMA）
+pub fn parse_elf(data: &[u8]) -> AppResult<Vec<FirmwareSegment>> {
+    use object::read::elf::{ElfFile32, ProgramHeader};
+
+    let elf = ElfFile32::<object::Endianness>::parse(data)
+        .map_err(|e| AppError::FileError(format!("解析 ELF 文件失败: {}", e)))?;
+    let endian = elf.endian();
+
+    let mut segments = Vec::new();
+    for header in elf.elf_program_headers() {
+        if header.p_type(endian) != object::elf::PT_LOAD || header.p_filesz(endian) == 0 {
+            continue;
+        }
+        let bytes = header
+            .data(endian, data)
+            .map_err(|_| AppError::FileError("读取 ELF 段数据失败".to_string()))?;
+        push_data(&mut segments, header.p_paddr(endian) as u64, bytes);
+    }
+
+    if segments.is_empty() {
+        return Err(AppError::FileError("ELF 文件不包含可加载段".to_string()));
+    }
+
+    Ok(segments)
+}
+
+/// 追加数据，与上一段地址连续时合并
+fn push_data(segments: &mut Vec<FirmwareSegment>, address: u64, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    if let Some(last) = segments.last_mut() {
+        if last.address + last.data.len() as u64 == address {
+            last.data.extend_from_slice(data);
+            return;
+        }
+    }
+    segments.push(FirmwareSegment {
+        address,
+        data: data.to_vec(),
+    });
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || s.is_empty() {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc_matches_across_formats() {
+        let bin = image_metadata(FirmwareFormat::Bin, &FirmwareImage::Binary(b"123456789".to_vec()), None, 0x0800_0000);
+        assert_eq!(bin.crc32, 0xCBF4_3926);
+        assert_eq!(bin.address_range, (0x0800_0000, 0x0800_0009));
+
+        // 乱序的段按地址排序后拼接，CRC 与 BIN 一致
+        let segments = FirmwareImage::Segments(vec![
+            FirmwareSegment { address: 0x0800_0004, data: b"56789".to_vec() },
+            FirmwareSegment { address: 0x0800_0000, data: b"1234".to_vec() },
+        ]);
+        let hex = image_metadata(FirmwareFormat::Hex, &segments, Some(0x0800_0001), 0);
+        assert_eq!(hex.crc32, bin.crc32);
+        assert_eq!(hex.segment_count, 2);
+        assert_eq!(hex.total_bytes, 9);
+        assert_eq!(hex.entry_point, Some(0x0800_0001));
+    }
+
+    #[test]
+    fn test_hex_start_address() {
+        assert_eq!(ihex::start_address(":040000050800012DC1\n:00000001FF\n"), Some(0x0800_012D));
+        assert_eq!(ihex::start_address(":0400000312340010A3\n"), Some(0x12340 + 0x10));
+        assert_eq!(ihex::start_address(":00000001FF\n"), None);
+    }
+
+    #[test]
+    fn test_analyze_hex_file() {
+        let path = std::env::temp_dir().join(format!("fw_meta_{}.hex", std::process::id()));
+        std::fs::write(
+            &path,
+            ":020000040800F2\n:0400000001020304F2\n:040000050800012DC1\n:00000001FF\n",
+        )
+        .unwrap();
+
+        let meta = analyze(&path, 0).unwrap();
+        assert_eq!(meta.format, FirmwareFormat::Hex);
+        assert_eq!(meta.address_range, (0x0800_0000, 0x0800_0004));
+        assert_eq!(meta.entry_point, Some(0x0800_012D));
+        assert_eq!(meta.total_bytes, 4);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
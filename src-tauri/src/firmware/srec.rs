@@ -0,0 +1,125 @@
+// Motorola S-record 解析
+// 支持 S1/S2/S3 数据记录、S5/S6 计数记录、S7/S8/S9 结束记录，并校验每行校验和
+
+use super::FirmwareSegment;
+use crate::error::{AppError, AppResult};
+use std::path::Path;
+
+/// 解析后的 S-record 镜像
+#[derive(Debug, Clone, Default)]
+pub struct SrecImage {
+    /// 按地址合并后的数据段
+    pub segments: Vec<FirmwareSegment>,
+    /// 结束记录中的入口地址
+    pub entry: Option<u32>,
+}
+
+pub fn parse_file(path: &Path) -> AppResult<SrecImage> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content)
+}
+
+pub fn parse(content: &str) -> AppResult<SrecImage> {
+    let mut image = SrecImage::default();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let err = |msg: &str| AppError::FileError(format!("S-record 第 {} 行{}", line_no, msg));
+
+        let bytes = line.as_bytes();
+        if bytes.len() < 4 || bytes[0] != b'S' {
+            return Err(err("格式错误"));
+        }
+        let record_type = bytes[1];
+
+        let payload = line
+            .get(2..)
+            .and_then(super::decode_hex)
+            .ok_or_else(|| err("包含非法十六进制字符"))?;
+        let count = payload[0] as usize;
+        if payload.len() != count + 1 {
+            return Err(err("长度字段与实际长度不符"));
+        }
+
+        let sum: u32 = payload[..count].iter().map(|&b| b as u32).sum();
+        if (!(sum as u8)) != payload[count] {
+            return Err(err("校验和错误"));
+        }
+
+        let addr_len = match record_type {
+            b'0' | b'1' | b'5' | b'9' => 2,
+            b'2' | b'6' | b'8' => 3,
+            b'3' | b'7' => 4,
+            _ => return Err(err("记录类型不支持")),
+        };
+        if count < addr_len + 1 {
+            return Err(err("记录过短"));
+        }
+
+        let address = payload[1..1 + addr_len]
+            .iter()
+            .fold(0u32, |acc, &b| (acc << 8) | b as u32);
+        let data = &payload[1 + addr_len..count];
+
+        match record_type {
+            b'1' | b'2' | b'3' => super::push_data(&mut image.segments, address as u64, data),
+            b'7' | b'8' | b'9' => {
+                image.entry = Some(address);
+                break;
+            }
+            // S0 头记录与 S5/S6 计数记录不含数据
+            _ => {}
+        }
+    }
+
+    if image.segments.is_empty() {
+        return Err(AppError::FileError("S-record 文件不包含数据记录".to_string()));
+    }
+
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = "\
+S00700007465737438
+S31508000000000102030405060708090A0B0C0D0E0F6A
+S30908000010AABBCCDDD0
+S3090800100001020304D4
+S5030003F9
+S70508000101F0
+";
+
+    #[test]
+    fn test_parse_s3_records_with_32bit_addresses() {
+        let image = parse(FIXTURE).unwrap();
+
+        assert_eq!(image.segments.len(), 2);
+        assert_eq!(image.segments[0].address, 0x0800_0000);
+        assert_eq!(image.segments[0].data.len(), 20);
+        assert_eq!(&image.segments[0].data[16..], &[0xAA, 0xBB, 0xCC, 0xDD]);
+        assert_eq!(image.segments[1].address, 0x0800_1000);
+        assert_eq!(image.segments[1].data, vec![1, 2, 3, 4]);
+        assert_eq!(image.entry, Some(0x0800_0101));
+    }
+
+    #[test]
+    fn test_parse_s1_record() {
+        let image = parse("S1051000DEAD5F\nS9030000FC\n").unwrap();
+
+        assert_eq!(image.segments[0].address, 0x1000);
+        assert_eq!(image.segments[0].data, vec![0xDE, 0xAD]);
+    }
+
+    #[test]
+    fn test_checksum_error_is_rejected() {
+        assert!(parse("S1051000DEAD5E\n").is_err());
+    }
+}
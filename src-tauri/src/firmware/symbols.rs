@@ -0,0 +1,117 @@
+// ELF 符号表查询与变量值格式化
+// 按名称查找全局变量/函数的地址与大小，读取目标内存后按类型提示解析
+
+use crate::error::{AppError, AppResult};
+use object::{Object, ObjectSymbol, SymbolKind};
+use serde::{Deserialize, Serialize};
+
+/// ELF 中的符号
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ElfSymbol {
+    pub name: String,
+    pub address: u64,
+    /// 符号大小（字节），部分汇编符号为 0
+    pub size: u64,
+    /// 是否为函数（地址已去掉 Thumb 位）
+    pub is_function: bool,
+}
+
+/// 读取符号时的类型提示，元素按小端解析；符号大于单个元素时按数组解析
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    /// 原始字节
+    Array,
+}
+
+impl SymbolType {
+    /// 单个元素的字节数
+    pub fn element_size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 | Self::Array => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+}
+
+/// 在 ELF 符号表中按名称查找符号
+pub fn find_symbol(data: &[u8], name: &str) -> AppResult<ElfSymbol> {
+    let file = object::File::parse(data)
+        .map_err(|e| AppError::FileError(format!("解析 ELF 文件失败: {}", e)))?;
+
+    let symbol = file
+        .symbols()
+        .find(|s| s.is_definition() && s.name() == Ok(name))
+        .ok_or_else(|| AppError::FileError(format!("ELF 中未找到符号: {}", name)))?;
+
+    let is_function = symbol.kind() == SymbolKind::Text;
+    Ok(ElfSymbol {
+        name: name.to_string(),
+        address: if is_function { symbol.address() & !1 } else { symbol.address() },
+        size: symbol.size(),
+        is_function,
+    })
+}
+
+/// 按类型提示把读取的字节格式化为值（小端），不足一个元素的尾部字节忽略
+pub fn format_symbol_values(bytes: &[u8], hint: SymbolType) -> Vec<String> {
+    bytes
+        .chunks_exact(hint.element_size())
+        .map(|chunk| {
+            let mut raw = [0u8; 8];
+            raw[..chunk.len()].copy_from_slice(chunk);
+            let value = u64::from_le_bytes(raw);
+            match hint {
+                SymbolType::U8 | SymbolType::U16 | SymbolType::U32 | SymbolType::U64 => {
+                    format!("{} (0x{:0width$X})", value, value, width = chunk.len() * 2)
+                }
+                SymbolType::I8 => (value as u8 as i8).to_string(),
+                SymbolType::I16 => (value as u16 as i16).to_string(),
+                SymbolType::I32 => (value as u32 as i32).to_string(),
+                SymbolType::I64 => (value as i64).to_string(),
+                SymbolType::F32 => f32::from_bits(value as u32).to_string(),
+                SymbolType::F64 => f64::from_bits(value).to_string(),
+                SymbolType::Array => format!("0x{:02X}", value),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsigned_and_signed() {
+        let bytes = [0x78, 0x56, 0x34, 0x12];
+        assert_eq!(format_symbol_values(&bytes, SymbolType::U32), vec!["305419896 (0x12345678)"]);
+        assert_eq!(format_symbol_values(&[0xFF, 0xFF], SymbolType::I16), vec!["-1"]);
+        assert_eq!(format_symbol_values(&[0x80], SymbolType::I8), vec!["-128"]);
+    }
+
+    #[test]
+    fn test_array_of_elements() {
+        let bytes = [1, 0, 2, 0, 3];
+        // 尾部不足一个元素的字节忽略
+        assert_eq!(format_symbol_values(&bytes, SymbolType::U16), vec!["1 (0x0001)", "2 (0x0002)"]);
+        assert_eq!(format_symbol_values(&bytes[..2], SymbolType::Array), vec!["0x01", "0x00"]);
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(format_symbol_values(&1.5f32.to_le_bytes(), SymbolType::F32), vec!["1.5"]);
+        assert_eq!(SymbolType::F64.element_size(), 8);
+    }
+}
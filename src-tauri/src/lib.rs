@@ -1,12 +1,16 @@
+pub mod address;
+pub mod cli;
 pub mod commands;
+pub mod compression;
 pub mod error;
+pub mod firmware;
 pub mod pack;
 pub mod serial;
 pub mod state;
 pub mod udev;
 pub mod app_config;
 
-use commands::{config, flash, memory, probe, rtt, serial as serial_cmd};
+use commands::{config, control, flash, memory, probe, production, rtt, serial as serial_cmd, swo};
 use state::AppState;
 use tauri::Manager;
 
@@ -14,13 +18,19 @@ use tauri::Manager;
 pub fn run() {
     env_logger::init();
 
+    // 带命令行模式参数时不启动界面，执行完直接以退出码结束
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run_from_args(&args) {
+        std::process::exit(code);
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .setup(|app| {
-            app.manage(AppState::new());
+            app.manage(AppState::from_config(&app_config::load_config()));
 
             // Linux 系统启动时检查 udev 规则
             #[cfg(target_os = "linux")]
@@ -31,51 +41,127 @@ pub fn run() {
                 });
             }
 
+            // 按配置开启本地控制服务（默认关闭）
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(control::start_from_config(app_handle));
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             // 探针命令
             probe::list_probes,
             probe::connect_target,
+            probe::quick_connect,
             probe::disconnect,
+            probe::reset_target,
+            probe::get_core_operation_timeout,
+            probe::set_core_operation_timeout,
+            probe::get_clock_recommendation,
             probe::get_connection_status,
+            probe::verify_target_identity,
+            probe::read_onchip_memory_sizes,
+            probe::get_protection_status,
+            probe::get_debug_in_low_power,
+            probe::set_debug_in_low_power,
+            probe::get_capabilities,
+            probe::read_cpuid,
+            probe::test_connection_quality,
+            probe::read_debug_registers,
+            probe::benchmark_probe,
+            probe::list_cores,
+            probe::set_active_core,
             probe::diagnose_usb_devices,
             probe::check_usb_permissions,
             probe::install_udev_rules,
             probe::get_udev_install_instructions,
             // RTT 独立连接命令
             probe::connect_rtt,
+            probe::connect_unified,
             probe::disconnect_rtt,
             probe::get_rtt_connection_status,
             // Flash命令
             flash::flash_firmware,
+            flash::flash_with_external_loader,
             flash::erase_chip,
             flash::erase_sector,
+            flash::get_flash_layout,
+            flash::get_effective_flash_config,
+            flash::test_flash_algorithm,
             flash::verify_firmware,
+            flash::diff_flash_against_file,
             flash::read_flash,
+            flash::start_flash_read,
+            flash::cancel_flash_read,
+            flash::dump_flash_to_file,
             flash::get_firmware_info,
+            flash::analyze_firmware,
+            flash::get_default_flash_base,
+            production::production_flash,
+            production::measure_boot_time,
             // 内存命令
             memory::read_memory,
+            memory::read_memory_fast,
+            memory::benchmark_memory_read,
             memory::write_memory,
+            memory::parse_address,
+            memory::read_memory_word,
+            memory::read_memory_64,
+            memory::write_memory_word,
             memory::read_registers,
+            memory::dump_registers_to_file,
+            memory::set_memory_watches,
+            memory::read_exception_frame,
+            memory::read_cycle_counter,
+            memory::reset_cycle_counter,
+            memory::read_symbol,
+            memory::probe_ram_writable,
+            memory::read_watch_list,
+            memory::start_watch_poll,
+            memory::stop_watch_poll,
             // RTT命令
+            rtt::scan_rtt_control_block,
             rtt::start_rtt,
             rtt::stop_rtt,
             rtt::write_rtt,
             rtt::get_rtt_status,
             rtt::clear_rtt_buffer,
+            rtt::get_rtt_history,
+            rtt::get_rtt_write_history,
+            rtt::wait_for_rtt_pattern,
+            rtt::save_rtt_view,
+            rtt::load_rtt_view,
+            rtt::get_rtt_stats,
+            rtt::reset_rtt_stats,
+            rtt::set_rtt_batching,
+            // SWO/ITM 命令
+            swo::start_swo,
+            swo::stop_swo,
+            swo::list_itm_ports,
             // 配置命令
             config::get_supported_chips,
             config::search_chips,
             config::get_chip_info,
+            config::get_chip_info_batch,
             config::init_packs,
+            config::reload_targets,
+            config::export_all_targets_yaml,
             config::import_pack,
+            config::import_packs_from_directory,
             config::list_imported_packs,
             config::delete_pack,
             config::get_flash_algorithms,
+            config::is_chip_supported,
+            config::add_fallback_mapping,
+            config::list_fallback_mappings,
+            config::suggest_targets_for_firmware,
+            config::override_target_yaml,
+            config::remove_target_override,
+            config::set_header_reserve,
+            config::set_algorithm_sizing,
             config::save_project_config,
             config::load_project_config,
             config::get_pack_scan_report,
+            config::find_chip_provider,
             config::get_devices_without_algorithm,
             // Pack版本管理命令
             config::check_outdated_packs,
@@ -84,16 +170,25 @@ pub fn run() {
             // Pack目录管理命令
             config::get_packs_directory,
             config::set_custom_packs_directory,
+            // 本地控制服务命令
+            control::get_control_server_status,
+            control::set_control_server_config,
             // 串口命令
             serial_cmd::list_serial_ports_cmd,
             serial_cmd::connect_serial,
             serial_cmd::disconnect_serial,
             serial_cmd::write_serial,
             serial_cmd::write_serial_string,
+            serial_cmd::set_serial_local_echo,
+            serial_cmd::set_serial_batching,
             serial_cmd::start_serial,
             serial_cmd::stop_serial,
+            serial_cmd::pause_serial,
+            serial_cmd::resume_serial,
             serial_cmd::get_serial_status,
             serial_cmd::clear_serial_buffer,
+            serial_cmd::get_serial_history,
+            serial_cmd::detect_serial_encoding,
         ])
         .run(tauri::generate_context!())
         .expect("启动应用程序时出错");
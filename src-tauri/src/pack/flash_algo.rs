@@ -171,13 +171,15 @@ pub fn extract_flash_algorithm_from_flm(
     let instructions = base64::engine::general_purpose::STANDARD.encode(&blob);
 
     // 5. 构建扇区信息
-    let sectors = if let Some(ref fd) = flash_device {
+    let mut sectors = if let Some(ref fd) = flash_device {
         // 从 FlashDevice 中获取扇区信息
         build_sectors_from_flash_device(fd, flash_start)
     } else {
         // 回退：生成默认扇区
         generate_default_sectors(flash_size)
     };
+    // 同一 FLM 用于容量更小的设备时，去掉超出设备 Flash 范围的扇区
+    sectors.retain(|sector| sector.address < flash_size);
 
     // 6. 获取 page_size 和超时设置
     let (page_size, program_timeout, erase_timeout, erased_value) = if let Some(ref fd) = flash_device {
@@ -1,7 +1,9 @@
 use crate::error::{AppError, AppResult};
 use crate::pack::paths;
+use crate::pack::progress::{ImportProgressCallback, PackImportProgress};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
 
@@ -14,6 +16,31 @@ pub struct PackInfo {
     pub device_count: usize,
 }
 
+/// 更换 Pack 目录时的迁移方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackMigrationMode {
+    /// 复制，保留旧目录中的 Pack
+    Copy,
+    /// 复制后删除旧目录中的 Pack
+    Move,
+}
+
+/// Pack 迁移结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackMigrationReport {
+    pub migrated: Vec<String>,
+    /// 新目录中已存在同名 Pack 而跳过
+    pub skipped: Vec<String>,
+    pub failed: Vec<PackMigrationFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackMigrationFailure {
+    pub pack: String,
+    pub error: String,
+}
+
 pub struct PackManager {
     packs_dir: PathBuf,
 }
@@ -53,43 +80,62 @@ impl PackManager {
     /// 从旧位置迁移Pack数据（仅Linux）
     #[cfg(target_os = "linux")]
     fn migrate_legacy_packs(from: &Path, to: &Path) -> AppResult<()> {
-        if !from.exists() || !from.is_dir() {
-            return Ok(());
+        let report = Self::migrate_packs(from, to, PackMigrationMode::Copy)?;
+        if !report.migrated.is_empty() {
+            log::info!("Pack数据迁移完成，共迁移 {} 个Pack", report.migrated.len());
         }
+        Ok(())
+    }
 
-        log::info!("开始迁移Pack数据: {:?} -> {:?}", from, to);
+    /// 将 from 下的所有 Pack 目录迁移到 to，目标已存在同名 Pack 时跳过
+    /// Move 模式在复制成功后删除源目录
+    pub fn migrate_packs(from: &Path, to: &Path, mode: PackMigrationMode) -> AppResult<PackMigrationReport> {
+        let mut report = PackMigrationReport::default();
+        if !from.is_dir() {
+            return Ok(report);
+        }
 
-        let mut migrated_count = 0;
+        log::info!("开始迁移Pack数据 ({:?}): {:?} -> {:?}", mode, from, to);
+        fs::create_dir_all(to)?;
 
         for entry in fs::read_dir(from)? {
-            let entry = entry?;
-            let src = entry.path();
-
-            if src.is_dir() {
-                let pack_name = src.file_name().unwrap();
-                let dst = to.join(pack_name);
-
-                if !dst.exists() {
-                    log::info!("迁移Pack: {:?}", pack_name);
-                    if let Err(e) = Self::copy_dir_recursive(&src, &dst) {
-                        log::warn!("迁移Pack {:?} 失败: {}", pack_name, e);
-                    } else {
-                        migrated_count += 1;
-                    }
-                }
+            let src = entry?.path();
+            // 新目录位于旧目录内部时不能把它复制进自身
+            if !src.is_dir() || to.starts_with(&src) {
+                continue;
+            }
+            let pack_name = src.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let dst = to.join(&pack_name);
+
+            if dst.exists() {
+                report.skipped.push(pack_name);
+                continue;
             }
-        }
 
-        if migrated_count > 0 {
-            log::info!("Pack数据迁移完成，共迁移 {} 个Pack", migrated_count);
+            log::info!("迁移Pack: {}", pack_name);
+            let result = match Self::copy_dir_recursive(&src, &dst) {
+                Ok(()) if mode == PackMigrationMode::Move => fs::remove_dir_all(&src).map_err(AppError::from),
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    // 不留下复制了一半的 Pack
+                    let _ = fs::remove_dir_all(&dst);
+                    Err(e)
+                }
+            };
+            match result {
+                Ok(()) => report.migrated.push(pack_name),
+                Err(e) => {
+                    log::warn!("迁移Pack {} 失败: {}", pack_name, e);
+                    report.failed.push(PackMigrationFailure { pack: pack_name, error: e.to_string() });
+                }
+            }
         }
 
-        Ok(())
+        Ok(report)
     }
 
     /// 递归复制目录
-    #[cfg(target_os = "linux")]
-    fn copy_dir_recursive(src: &Path, dst: &Path) -> AppResult<()> {
+    pub fn copy_dir_recursive(src: &Path, dst: &Path) -> AppResult<()> {
         fs::create_dir_all(dst)?;
 
         for entry in fs::read_dir(src)? {
@@ -108,6 +154,15 @@ impl PackManager {
     }
 
     pub fn import_pack(&self, pack_path: &Path) -> AppResult<PackInfo> {
+        self.import_pack_with_progress(pack_path, None)
+    }
+
+    /// 导入 Pack，解压过程中通过 progress 回调报告已解压字节数与当前文件
+    pub fn import_pack_with_progress(
+        &self,
+        pack_path: &Path,
+        progress: Option<&ImportProgressCallback>,
+    ) -> AppResult<PackInfo> {
         log::info!("🔄 开始导入 Pack: {:?}", pack_path);
 
         let file = fs::File::open(pack_path)?;
@@ -148,14 +203,36 @@ impl PackManager {
         let mut archive = ZipArchive::new(file)
             .map_err(|e| AppError::PackError(format!("无法打开Pack文件: {}", e)))?;
 
-        for i in 0..archive.len() {
+        let total_files = archive.len();
+        let total_bytes: u64 = (0..total_files)
+            .filter_map(|i| archive.by_index_raw(i).ok().map(|f| f.size()))
+            .sum();
+        let pack_file = pack_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let report = |current: &str, bytes: u64, files: usize| {
+            if let Some(callback) = progress {
+                callback(PackImportProgress::new(&pack_file, current, bytes, total_bytes).with_files(files, total_files));
+            }
+        };
+
+        // 大约每 1% 报告一次，避免小文件众多的 Pack 刷屏
+        let report_step = (total_bytes / 100).max(1);
+        let mut next_report = 0u64;
+        let mut extracted = 0u64;
+        let mut buffer = vec![0u8; 64 * 1024];
+        report("", 0, 0);
+
+        for i in 0..total_files {
             let mut file = archive
                 .by_index(i)
                 .map_err(|e| AppError::PackError(e.to_string()))?;
 
-            let outpath = pack_dir.join(file.name());
+            let name = file.name().to_string();
+            let outpath = pack_dir.join(&name);
 
-            if file.name().ends_with('/') {
+            if name.ends_with('/') {
                 fs::create_dir_all(&outpath)?;
             } else {
                 if let Some(p) = outpath.parent() {
@@ -164,9 +241,21 @@ impl PackManager {
                     }
                 }
                 let mut outfile = fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
+                loop {
+                    let n = file.read(&mut buffer)?;
+                    if n == 0 {
+                        break;
+                    }
+                    outfile.write_all(&buffer[..n])?;
+                    extracted += n as u64;
+                    if extracted >= next_report {
+                        report(&name, extracted, i);
+                        next_report = extracted + report_step;
+                    }
+                }
             }
         }
+        report("", extracted, total_files);
 
         log::info!("✅ Pack 导入成功!");
         Ok(pack_info)
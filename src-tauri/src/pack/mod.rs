@@ -5,3 +5,4 @@ pub mod flash_algo;
 pub mod progress;
 pub mod scan_report;
 pub mod paths;
+pub mod target_override;
@@ -12,9 +12,17 @@ use std::path::PathBuf;
 /// 2. Linux: XDG 标准目录 ~/.local/share/zuolan-daplink/packs
 /// 3. 其他平台: 可执行文件同级目录 <exe_dir>/data/packs
 pub fn get_packs_dir() -> PathBuf {
+    let custom_dir = app_config::get_custom_packs_dir();
+    if let Some(ref dir) = custom_dir {
+        log::info!("使用自定义Pack目录: {:?}", dir);
+    }
+    packs_dir_for(custom_dir)
+}
+
+/// 按给定的自定义路径解析 Pack 数据目录（不读取配置），用于保存配置前确定新目录
+pub fn packs_dir_for(custom_dir: Option<PathBuf>) -> PathBuf {
     // 1. 优先使用用户自定义路径
-    if let Some(custom_dir) = app_config::get_custom_packs_dir() {
-        log::info!("使用自定义Pack目录: {:?}", custom_dir);
+    if let Some(custom_dir) = custom_dir {
         return custom_dir;
     }
 
@@ -91,3 +91,71 @@ impl PackScanProgress {
 
 /// 进度回调函数类型
 pub type ProgressCallback = Box<dyn Fn(PackScanProgress) + Send + Sync>;
+
+/// Pack 导入（解压）进度信息，在设备扫描开始前发送
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackImportProgress {
+    /// 导入的 Pack 文件名
+    pub pack_file: String,
+    /// 正在解压的文件
+    pub current_file: String,
+    /// 已解压字节数（解压后大小）
+    pub bytes_extracted: u64,
+    /// 解压后总字节数
+    pub total_bytes: u64,
+    pub files_extracted: usize,
+    pub total_files: usize,
+    /// 进度百分比 (0.0-1.0)
+    pub progress: f64,
+}
+
+impl PackImportProgress {
+    /// 按已解压字节数计算进度
+    pub fn new(pack_file: &str, current_file: &str, bytes_extracted: u64, total_bytes: u64) -> Self {
+        let progress = if total_bytes > 0 {
+            (bytes_extracted as f64 / total_bytes as f64).min(1.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            pack_file: pack_file.to_string(),
+            current_file: current_file.to_string(),
+            bytes_extracted,
+            total_bytes,
+            files_extracted: 0,
+            total_files: 0,
+            progress,
+        }
+    }
+
+    /// 设置文件计数
+    pub fn with_files(mut self, files_extracted: usize, total_files: usize) -> Self {
+        self.files_extracted = files_extracted;
+        self.total_files = total_files;
+        self
+    }
+}
+
+/// 导入进度回调函数类型
+pub type ImportProgressCallback = Box<dyn Fn(PackImportProgress) + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_from_bytes() {
+        let p = PackImportProgress::new("Vendor.DFP.1.0.0.pack", "Flash/a.FLM", 25, 100).with_files(3, 10);
+        assert_eq!(p.pack_file, "Vendor.DFP.1.0.0.pack");
+        assert_eq!(p.current_file, "Flash/a.FLM");
+        assert!((p.progress - 0.25).abs() < f64::EPSILON);
+        assert_eq!((p.files_extracted, p.total_files), (3, 10));
+    }
+
+    #[test]
+    fn test_progress_bounds() {
+        assert_eq!(PackImportProgress::new("x.pack", "", 0, 0).progress, 0.0);
+        assert_eq!(PackImportProgress::new("x.pack", "", 150, 100).progress, 1.0);
+    }
+}
@@ -55,6 +55,12 @@ pub struct AlgorithmInfo {
     pub page_size: u32,
     /// 扇区数量
     pub sector_count: usize,
+    /// load_address 前为 flash loader header 预留的字节数
+    #[serde(default)]
+    pub header_reserve: Option<u64>,
+    /// 算法 + 页缓冲 + 栈超出 RAM 时的说明
+    #[serde(default)]
+    pub ram_error: Option<String>,
 }
 
 /// 设备状态
@@ -141,6 +147,11 @@ impl PackScanReport {
             .collect()
     }
 
+    /// 按名称查找设备（不区分大小写）
+    pub fn find_device(&self, name: &str) -> Option<&DeviceReport> {
+        self.devices.iter().find(|d| d.name.eq_ignore_ascii_case(name))
+    }
+
     /// 获取有问题的设备列表
     pub fn get_problematic_devices(&self) -> Vec<&DeviceReport> {
         self.devices
@@ -149,3 +160,67 @@ impl PackScanReport {
             .collect()
     }
 }
+
+impl DeviceReport {
+    /// 设备状态的原因说明，状态正常时为 None
+    pub fn status_reason(&self) -> Option<String> {
+        match self.status {
+            DeviceStatus::Ok => None,
+            DeviceStatus::Error => Some("设备配置异常".to_string()),
+            DeviceStatus::Warning => Some(match self.algorithm {
+                Some(AlgorithmInfo { ram_error: Some(ref error), .. }) => error.clone(),
+                Some(_) => "算法配置存在问题".to_string(),
+                None if self.flash_size > 0 => "Pack 中没有可用的 Flash 算法（未匹配到或 FLM 解析失败）".to_string(),
+                None => "未知原因".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, algorithm: Option<AlgorithmInfo>, status: DeviceStatus) -> DeviceReport {
+        DeviceReport {
+            name: name.to_string(),
+            core: "Cortex-M4".to_string(),
+            flash_start: 0x0800_0000,
+            flash_size: 0x10_0000,
+            ram_start: 0x2000_0000,
+            ram_size: 0x2_0000,
+            algorithm,
+            status,
+        }
+    }
+
+    fn algorithm(ram_error: Option<&str>) -> AlgorithmInfo {
+        AlgorithmInfo {
+            name: "STM32F4xx_1024".to_string(),
+            flm_file: "STM32F4xx_1024.FLM".to_string(),
+            page_size: 1024,
+            sector_count: 12,
+            header_reserve: Some(32),
+            ram_error: ram_error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_device_and_status_reason() {
+        let mut report = PackScanReport::new("Keil.STM32F4xx_DFP".to_string());
+        report.add_device(device("STM32F407VG", Some(algorithm(None)), DeviceStatus::Ok));
+        report.add_device(device("STM32F429ZI", None, DeviceStatus::Warning));
+        report.add_device(device("STM32F469NI", Some(algorithm(Some("RAM 不足"))), DeviceStatus::Warning));
+
+        let ok = report.find_device("stm32f407vg").unwrap();
+        assert_eq!(ok.status_reason(), None);
+        assert!(report
+            .find_device("STM32F429ZI")
+            .unwrap()
+            .status_reason()
+            .unwrap()
+            .contains("Flash 算法"));
+        assert_eq!(report.find_device("STM32F469NI").unwrap().status_reason().as_deref(), Some("RAM 不足"));
+        assert!(report.find_device("STM32F103C8").is_none());
+    }
+}
@@ -1,18 +1,139 @@
 // Pack 到 probe-rs 目标定义的转换模块
 // 参考 probe-rs 的 target-gen 工具实现
 
+use crate::address::parse_hex_or_dec;
 use crate::error::{AppError, AppResult};
-
-/// Pack 扫描器版本
-/// 用于检测旧版本生成的配置文件,提示用户重新扫描
-pub const PACK_SCANNER_VERSION: &str = "2.0.0";
 use crate::pack::flash_algo;
 use crate::pack::progress::{PackScanProgress, ProgressCallback, ScanPhase};
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Pack 扫描器版本
+/// 用于检测旧版本生成的配置文件,提示用户重新扫描
+pub const PACK_SCANNER_VERSION: &str = "2.0.0";
+
+/// Flash 算法 load_address 相对 RAM 起始的默认预留空间（0x20 字节）
+/// probe-rs 会在 load_address 之前分配 flash loader header
+/// 可通过配置 `header_reserves` 按 Pack 或设备覆盖，见 [`TargetGenOverrides`]
+pub const FLASH_LOADER_HEADER_RESERVE: u64 = 0x20;
+
+/// 按设备名前缀（最长匹配优先）或 Pack 名称查找 header 预留大小，未配置时使用默认值
+pub fn resolve_header_reserve(
+    overrides: &std::collections::HashMap<String, u64>,
+    pack_name: &str,
+    device_name: &str,
+) -> u64 {
+    let device_upper = device_name.to_uppercase();
+
+    overrides
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && device_upper.starts_with(&key.to_uppercase()))
+        .max_by_key(|(key, _)| key.len())
+        .or_else(|| overrides.iter().find(|(key, _)| key.eq_ignore_ascii_case(pack_name)))
+        .map(|(_, reserve)| *reserve)
+        .unwrap_or(FLASH_LOADER_HEADER_RESERVE)
+}
+
+/// probe-rs 未指定 stack_size 时为 flash loader 分配的栈大小
+pub const FLASH_LOADER_STACK_SIZE: u64 = 0x200;
+
+/// 按页大小推算的保守栈大小：大页算法通常在栈上保存更多状态
+pub fn default_stack_size(page_size: u64) -> u64 {
+    match page_size {
+        0..=0x400 => FLASH_LOADER_STACK_SIZE,
+        0x401..=0x1000 => 0x400,
+        _ => 0x800,
+    }
+}
+
+/// 按设备名前缀（最长匹配优先）或 Pack 名称查找算法 RAM 尺寸覆盖
+pub fn resolve_algorithm_sizing<'a>(
+    overrides: &'a std::collections::HashMap<String, crate::app_config::AlgorithmSizing>,
+    pack_name: &str,
+    device_name: &str,
+) -> Option<&'a crate::app_config::AlgorithmSizing> {
+    let device_upper = device_name.to_uppercase();
+
+    overrides
+        .iter()
+        .filter(|(key, _)| !key.is_empty() && device_upper.starts_with(&key.to_uppercase()))
+        .max_by_key(|(key, _)| key.len())
+        .or_else(|| overrides.iter().find(|(key, _)| key.eq_ignore_ascii_case(pack_name)))
+        .map(|(_, sizing)| sizing)
+}
+
+/// 算法 blob（代码 + 数据 + BSS）的字节数
+pub fn algorithm_blob_size(algo: &flash_algo::FlashAlgorithm) -> u64 {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(&algo.instructions)
+        .map_or(0, |blob| blob.len() as u64)
+}
+
+/// 算法在 load_address 之后占用的 RAM
+#[derive(Debug, Clone, Copy)]
+pub struct AlgorithmFootprint {
+    pub blob_size: u64,
+    pub page_size: u64,
+    pub stack_size: u64,
+    /// 页数据缓冲数量（probe-rs 在 RAM 足够时使用双缓冲）
+    pub page_buffers: u64,
+}
+
+impl AlgorithmFootprint {
+    /// blob + 页缓冲 + 栈
+    pub fn total(&self) -> u64 {
+        self.blob_size + self.page_size * self.page_buffers + self.stack_size
+    }
+}
+
+/// 检查以 load_address 加载的算法是否完整落在 RAM 内
+pub fn check_algorithm_fits_ram(
+    load_address: u64,
+    footprint: &AlgorithmFootprint,
+    ram_start: u64,
+    ram_size: u64,
+) -> Result<(), String> {
+    let ram_end = ram_start + ram_size;
+    let end = load_address + footprint.total();
+
+    if load_address < ram_start || end > ram_end {
+        return Err(format!(
+            "Flash 算法需要 RAM 0x{:08X}-0x{:08X} (blob 0x{:X} + {} 个页缓冲 0x{:X} + 栈 0x{:X})，超出 RAM 0x{:08X}-0x{:08X}",
+            load_address,
+            end,
+            footprint.blob_size,
+            footprint.page_buffers,
+            footprint.page_size,
+            footprint.stack_size,
+            ram_start,
+            ram_end
+        ));
+    }
+    Ok(())
+}
+
+/// 生成目标定义时使用的用户覆盖，由调用方传入（测试可使用固定配置）
+#[derive(Debug, Clone, Default)]
+pub struct TargetGenOverrides {
+    /// header 预留大小覆盖（Pack 名称或设备名前缀 -> 字节数）
+    pub header_reserves: std::collections::HashMap<String, u64>,
+    /// 算法栈大小与页缓冲数量覆盖（Pack 名称或设备名前缀 -> 尺寸）
+    pub algorithm_sizing: std::collections::HashMap<String, crate::app_config::AlgorithmSizing>,
+}
+
+impl TargetGenOverrides {
+    /// 从应用配置读取覆盖
+    pub fn from_config(config: &crate::app_config::AppConfig) -> Self {
+        Self {
+            header_reserves: config.header_reserves.clone(),
+            algorithm_sizing: config.algorithm_sizing.clone(),
+        }
+    }
+}
 
 /// 设备定义（从 PDSC 解析）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +142,8 @@ pub struct DeviceDefinition {
     pub processor: ProcessorInfo,
     pub memory: MemoryInfo,
     pub flash_algorithm: Option<String>, // Flash 算法文件名
+    #[serde(default)]
+    pub ap_index: u8,                    // 主内核所在 AP（来自 <debug __ap>）
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,10 +207,12 @@ pub fn parse_devices_from_pdsc(
     let mut family_processor: Option<ProcessorInfo> = None;
     let mut family_memory: Option<MemoryInfo> = None;
     let mut family_algorithm: Option<String> = None;
+    let mut family_ap: Option<u8> = None;
 
     let mut subfamily_processor: Option<ProcessorInfo> = None;
     let mut subfamily_memory: Option<MemoryInfo> = None;
     let mut subfamily_algorithm: Option<String> = None;
+    let mut subfamily_ap: Option<u8> = None;
 
     let mut current_device: Option<DeviceDefinition> = None;
     let mut current_processor: Option<ProcessorInfo> = None;
@@ -123,6 +248,7 @@ pub fn parse_devices_from_pdsc(
                         family_processor = None;
                         family_memory = None;
                         family_algorithm = None;
+                        family_ap = None;
                     }
                     b"subFamily" if in_family => {
                         in_subfamily = true;
@@ -130,6 +256,7 @@ pub fn parse_devices_from_pdsc(
                         subfamily_processor = None;
                         subfamily_memory = None;
                         subfamily_algorithm = None;
+                        subfamily_ap = None;
 
                         // 提取 subFamily 名称用于日志
                         let mut subfamily_name = String::new();
@@ -183,11 +310,14 @@ pub fn parse_devices_from_pdsc(
                             let inherited_algorithm = subfamily_algorithm.clone()
                                 .or_else(|| family_algorithm.clone());
 
+                            let inherited_ap = subfamily_ap.or(family_ap).unwrap_or(0);
+
                             current_device = Some(DeviceDefinition {
                                 name,
                                 processor: inherited_processor,
                                 memory: inherited_memory,
                                 flash_algorithm: inherited_algorithm,
+                                ap_index: inherited_ap,
                             });
                         }
                     }
@@ -325,6 +455,20 @@ pub fn parse_devices_from_pdsc(
                             }
                         }
                     }
+                    b"debug" if in_devices => {
+                        if let Some(ap) = parse_debug_ap(e) {
+                            // 根据当前层级保存 AP 编号
+                            if in_device {
+                                if let Some(ref mut dev) = current_device {
+                                    dev.ap_index = ap;
+                                }
+                            } else if in_subfamily {
+                                subfamily_ap = Some(ap);
+                            } else if in_family {
+                                family_ap = Some(ap);
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -465,6 +609,20 @@ pub fn parse_devices_from_pdsc(
                             }
                         }
                     }
+                    b"debug" if in_devices => {
+                        if let Some(ap) = parse_debug_ap(e) {
+                            // 根据当前层级保存 AP 编号
+                            if in_device {
+                                if let Some(ref mut dev) = current_device {
+                                    dev.ap_index = ap;
+                                }
+                            } else if in_subfamily {
+                                subfamily_ap = Some(ap);
+                            } else if in_family {
+                                family_ap = Some(ap);
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -478,6 +636,7 @@ pub fn parse_devices_from_pdsc(
                         family_processor = None;
                         family_memory = None;
                         family_algorithm = None;
+                        family_ap = None;
                     }
                     b"subFamily" => {
                         log::info!("结束解析 subFamily，共解析 {} 个设备", subfamily_device_count);
@@ -485,6 +644,7 @@ pub fn parse_devices_from_pdsc(
                         subfamily_processor = None;
                         subfamily_memory = None;
                         subfamily_algorithm = None;
+                        subfamily_ap = None;
                         subfamily_device_count = 0; // 重置计数器
                     }
                     b"device" => {
@@ -547,21 +707,23 @@ pub fn parse_devices_from_pdsc(
     Ok(devices)
 }
 
-/// 解析十六进制或十进制数字
-fn parse_hex_or_dec(s: &str) -> Option<u64> {
-    let s = s.trim();
-
-    if s.starts_with("0x") || s.starts_with("0X") {
-        u64::from_str_radix(&s[2..], 16).ok()
-    } else {
-        s.parse::<u64>().ok()
+/// 解析 <debug> 元素的 __ap 属性
+fn parse_debug_ap(e: &BytesStart) -> Option<u8> {
+    for attr in e.attributes().flatten() {
+        if attr.key.as_ref() == b"__ap" {
+            let val = String::from_utf8_lossy(&attr.value);
+            return parse_hex_or_dec(&val).and_then(|v| u8::try_from(v).ok());
+        }
     }
+    None
 }
 
 /// Flash 算法信息（用于收集和去重）
 struct CollectedAlgo {
     algo: flash_algo::FlashAlgorithm,
     load_address: u64,
+    header_reserve: u64,
+    stack_size: u64,
 }
 
 /// 生成 probe-rs YAML 格式的目标定义（包含 Flash 算法）
@@ -569,7 +731,21 @@ pub fn generate_probe_rs_yaml_with_algo(
     devices: &[DeviceDefinition],
     family_name: &str,
     pack_dir: &Path,
+    overrides: &TargetGenOverrides,
+    progress_callback: Option<&ProgressCallback>,
+) -> AppResult<String> {
+    let generated_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    generate_probe_rs_yaml_with_algo_at(devices, family_name, pack_dir, overrides, progress_callback, &generated_at)
+}
+
+/// 同 generate_probe_rs_yaml_with_algo，但由调用方指定生成时间（用于可复现的输出）
+pub fn generate_probe_rs_yaml_with_algo_at(
+    devices: &[DeviceDefinition],
+    family_name: &str,
+    pack_dir: &Path,
+    overrides: &TargetGenOverrides,
     progress_callback: Option<&ProgressCallback>,
+    generated_at: &str,
 ) -> AppResult<String> {
     use std::collections::HashMap;
 
@@ -590,7 +766,6 @@ pub fn generate_probe_rs_yaml_with_algo(
     // 第一遍：收集所有唯一的 flash 算法，并记录设备与算法的映射
     let mut algo_map: HashMap<String, CollectedAlgo> = HashMap::new();
     let mut device_algo_map: HashMap<String, String> = HashMap::new(); // device_name -> algo_name
-
     let total_devices = devices.len();
     for (idx, device) in devices.iter().enumerate() {
         // 报告匹配进度（每5个设备报告一次）
@@ -618,16 +793,50 @@ pub fn generate_probe_rs_yaml_with_algo(
                     Ok(mut algo) => {
                         // 算法名称包含 Flash 大小，避免不同大小的设备共享错误的扇区配置
                         let flash_size_kb = device.memory.flash_size / 1024;
-                        let algo_key = format!("{}_{}", algo.name, flash_size_kb);
+                        let header_reserve = resolve_header_reserve(&overrides.header_reserves, family_name, &device.name);
+                        let page_size = algo.flash_properties.page_size;
+                        let sizing = resolve_algorithm_sizing(&overrides.algorithm_sizing, family_name, &device.name);
+                        let stack_override = sizing.and_then(|s| s.stack_size);
+                        let footprint = AlgorithmFootprint {
+                            blob_size: algorithm_blob_size(&algo),
+                            page_size,
+                            stack_size: stack_override.unwrap_or_else(|| default_stack_size(page_size)),
+                            page_buffers: sizing.and_then(|s| s.page_buffers).unwrap_or(1) as u64,
+                        };
+
+                        // 非默认预留大小或栈大小的设备使用独立的算法定义
+                        let mut algo_key = format!("{}_{}", algo.name, flash_size_kb);
+                        if header_reserve != FLASH_LOADER_HEADER_RESERVE {
+                            algo_key.push_str(&format!("_r{:x}", header_reserve));
+                        }
+                        if let Some(stack_size) = stack_override {
+                            algo_key.push_str(&format!("_s{:x}", stack_size));
+                        }
                         algo.name = algo_key.clone();
 
                         device_algo_map.insert(device.name.clone(), algo_key.clone());
 
+                        // 同名算法共享第一个设备的 load_address，按当前设备的 RAM 校验
+                        let load_address = algo_map
+                            .get(&algo_key)
+                            .map_or(device.memory.ram_start, |c| c.load_address)
+                            + header_reserve;
+                        if let Err(e) = check_algorithm_fits_ram(
+                            load_address,
+                            &footprint,
+                            device.memory.ram_start,
+                            device.memory.ram_size,
+                        ) {
+                            log::warn!("设备 {} 的 Flash 算法可能无法加载: {}", device.name, e);
+                        }
+
                         // 只保存第一个遇到的同名+同大小算法
                         if !algo_map.contains_key(&algo_key) {
                             algo_map.insert(algo_key, CollectedAlgo {
                                 algo,
                                 load_address: device.memory.ram_start,
+                                header_reserve,
+                                stack_size: footprint.stack_size,
                             });
                         }
                     }
@@ -656,7 +865,10 @@ pub fn generate_probe_rs_yaml_with_algo(
 
     // 版本标记（用于检测旧版本配置）
     yaml.push_str(&format!("# EK-OmniProbe Pack Scanner Version: {}\n", PACK_SCANNER_VERSION));
-    yaml.push_str(&format!("# Generated at: {}\n\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
+    if let Some(mtime) = pdsc_mtime(pack_dir) {
+        yaml.push_str(&format!("{} {}\n", PDSC_MTIME_MARKER, mtime));
+    }
+    yaml.push_str(&format!("# Generated at: {}\n\n", generated_at));
 
     // 家族定义
     yaml.push_str(&format!("name: {}\n", family_name));
@@ -670,53 +882,29 @@ pub fn generate_probe_rs_yaml_with_algo(
     if !algo_map.is_empty() {
         yaml.push_str("flash_algorithms:\n");
 
-        for collected in algo_map.values() {
-            let algo = &collected.algo;
-            yaml.push_str(&format!("  - name: {}\n", algo.name));
-            yaml.push_str(&format!("    description: {}\n", algo.description));
-            yaml.push_str("    default: true\n");
-            // load_address 需要预留空间给 flash loader header
-            // probe-rs 会在 load_address 之前分配 header 空间
-            // 预留 0x20 (32 字节) 给 header
-            let adjusted_load_address = collected.load_address + 0x20;
-            yaml.push_str(&format!("    load_address: 0x{:x}\n", adjusted_load_address));
-            yaml.push_str(&format!("    data_section_offset: 0x{:x}\n", algo.data_section_offset));
-            yaml.push_str("    transfer_encoding: raw\n");
-
-            // 函数指针
-            if let Some(pc_init) = algo.pc_init {
-                yaml.push_str(&format!("    pc_init: 0x{:x}\n", pc_init));
-            }
-            if let Some(pc_uninit) = algo.pc_uninit {
-                yaml.push_str(&format!("    pc_uninit: 0x{:x}\n", pc_uninit));
-            }
-            yaml.push_str(&format!("    pc_program_page: 0x{:x}\n", algo.pc_program_page));
-            yaml.push_str(&format!("    pc_erase_sector: 0x{:x}\n", algo.pc_erase_sector));
-            if let Some(pc_erase_all) = algo.pc_erase_all {
-                yaml.push_str(&format!("    pc_erase_all: 0x{:x}\n", pc_erase_all));
-            }
-
-            // Flash 属性
-            yaml.push_str("    flash_properties:\n");
-            yaml.push_str("      address_range:\n");
-            yaml.push_str(&format!("        start: 0x{:x}\n", algo.flash_properties.address_range.start));
-            yaml.push_str(&format!("        end: 0x{:x}\n", algo.flash_properties.address_range.end));
-            yaml.push_str(&format!("      page_size: {}\n", algo.flash_properties.page_size));
-            yaml.push_str(&format!("      erased_byte_value: 0x{:x}\n", algo.flash_properties.erased_byte_value));
-            yaml.push_str(&format!("      program_page_timeout: {}\n", algo.flash_properties.program_page_timeout));
-            yaml.push_str(&format!("      erase_sector_timeout: {}\n", algo.flash_properties.erase_sector_timeout));
-
-            // 扇区信息
-            yaml.push_str("      sectors:\n");
-            for sector in &algo.flash_properties.sectors {
-                yaml.push_str(&format!("        - size: {}\n", sector.size));
-                yaml.push_str(&format!("          address: 0x{:x}\n", sector.address));
+        // 按名称排序，保证输出稳定
+        let mut collected_algos: Vec<&mut CollectedAlgo> = algo_map.values_mut().collect();
+        collected_algos.sort_by(|a, b| a.algo.name.cmp(&b.algo.name));
+
+        // 地址范围重叠的算法只有第一个标记为默认；每个型号只引用一个算法，不受影响
+        let mut default_ranges: Vec<std::ops::Range<u64>> = Vec::new();
+        for collected in collected_algos {
+            let range = &collected.algo.flash_properties.address_range;
+            let range = range.start..range.end;
+            collected.algo.default = !default_ranges.iter().any(|r| r.start < range.end && range.start < r.end);
+            if collected.algo.default {
+                default_ranges.push(range);
             }
 
-            // Instructions (base64 编码)
-            yaml.push_str(&format!("    instructions: \"{}\"\n", algo.instructions));
-
-            log::info!("生成家族级 Flash 算法: {}", algo.name);
+            let algo = &collected.algo;
+            write_flash_algorithm_yaml(
+                &mut yaml,
+                algo,
+                collected.load_address + collected.header_reserve,
+                collected.stack_size,
+            );
+
+            log::info!("生成家族级 Flash 算法: {} (header 预留 0x{:x})", algo.name, collected.header_reserve);
         }
     }
 
@@ -760,7 +948,7 @@ pub fn generate_probe_rs_yaml_with_algo(
         yaml.push_str("      - name: main\n");
         yaml.push_str(&format!("        type: {}\n", map_core_type(&device.processor.core)));
         yaml.push_str("        core_access_options: !Arm\n");
-        yaml.push_str("          ap: !v1 0\n");
+        yaml.push_str(&format!("          ap: !v1 {}\n", device.ap_index));
 
         // Flash 算法引用（只输出算法名称）
         if let Some(algo_name) = device_algo_map.get(&device.name) {
@@ -784,6 +972,55 @@ pub fn generate_probe_rs_yaml_with_algo(
     Ok(yaml)
 }
 
+/// 输出单个 Flash 算法的 YAML 定义（作为 flash_algorithms 列表的一项）
+pub fn write_flash_algorithm_yaml(
+    yaml: &mut String,
+    algo: &flash_algo::FlashAlgorithm,
+    load_address: u64,
+    stack_size: u64,
+) {
+    yaml.push_str(&format!("  - name: {}\n", algo.name));
+    yaml.push_str(&format!("    description: {}\n", algo.description));
+    yaml.push_str(&format!("    default: {}\n", algo.default));
+    yaml.push_str(&format!("    load_address: 0x{:x}\n", load_address));
+    yaml.push_str(&format!("    data_section_offset: 0x{:x}\n", algo.data_section_offset));
+    yaml.push_str(&format!("    stack_size: {}\n", stack_size));
+    yaml.push_str("    transfer_encoding: raw\n");
+
+    // 函数指针
+    if let Some(pc_init) = algo.pc_init {
+        yaml.push_str(&format!("    pc_init: 0x{:x}\n", pc_init));
+    }
+    if let Some(pc_uninit) = algo.pc_uninit {
+        yaml.push_str(&format!("    pc_uninit: 0x{:x}\n", pc_uninit));
+    }
+    yaml.push_str(&format!("    pc_program_page: 0x{:x}\n", algo.pc_program_page));
+    yaml.push_str(&format!("    pc_erase_sector: 0x{:x}\n", algo.pc_erase_sector));
+    if let Some(pc_erase_all) = algo.pc_erase_all {
+        yaml.push_str(&format!("    pc_erase_all: 0x{:x}\n", pc_erase_all));
+    }
+
+    // Flash 属性
+    yaml.push_str("    flash_properties:\n");
+    yaml.push_str("      address_range:\n");
+    yaml.push_str(&format!("        start: 0x{:x}\n", algo.flash_properties.address_range.start));
+    yaml.push_str(&format!("        end: 0x{:x}\n", algo.flash_properties.address_range.end));
+    yaml.push_str(&format!("      page_size: {}\n", algo.flash_properties.page_size));
+    yaml.push_str(&format!("      erased_byte_value: 0x{:x}\n", algo.flash_properties.erased_byte_value));
+    yaml.push_str(&format!("      program_page_timeout: {}\n", algo.flash_properties.program_page_timeout));
+    yaml.push_str(&format!("      erase_sector_timeout: {}\n", algo.flash_properties.erase_sector_timeout));
+
+    // 扇区信息
+    yaml.push_str("      sectors:\n");
+    for sector in &algo.flash_properties.sectors {
+        yaml.push_str(&format!("        - size: {}\n", sector.size));
+        yaml.push_str(&format!("          address: 0x{:x}\n", sector.address));
+    }
+
+    // Instructions (base64 编码)
+    yaml.push_str(&format!("    instructions: \"{}\"\n", algo.instructions));
+}
+
 /// 映射处理器核心类型到 probe-rs 格式
 fn map_core_type(core: &str) -> &'static str {
     match core.to_uppercase().as_str() {
@@ -802,6 +1039,7 @@ pub fn generate_scan_report(
     devices: &[DeviceDefinition],
     pack_name: &str,
     pack_dir: &Path,
+    overrides: &TargetGenOverrides,
 ) -> AppResult<crate::pack::scan_report::PackScanReport> {
     use crate::pack::scan_report::{
         AlgorithmInfo, DeviceReport, DeviceStatus, PackScanReport,
@@ -811,7 +1049,6 @@ pub fn generate_scan_report(
 
     // 查找所有 FLM 文件
     let flm_files = flash_algo::find_flm_files(pack_dir)?;
-
     for device in devices {
         let mut device_report = DeviceReport {
             name: device.name.clone(),
@@ -837,6 +1074,24 @@ pub fn generate_scan_report(
                     device.memory.flash_size,
                 ) {
                     Ok(algo) => {
+                        let header_reserve = resolve_header_reserve(&overrides.header_reserves, pack_name, &device.name);
+                        let page_size = algo.flash_properties.page_size;
+                        let sizing = resolve_algorithm_sizing(&overrides.algorithm_sizing, pack_name, &device.name);
+                        let footprint = AlgorithmFootprint {
+                            blob_size: algorithm_blob_size(&algo),
+                            page_size,
+                            stack_size: sizing
+                                .and_then(|s| s.stack_size)
+                                .unwrap_or_else(|| default_stack_size(page_size)),
+                            page_buffers: sizing.and_then(|s| s.page_buffers).unwrap_or(1) as u64,
+                        };
+                        let ram_error = check_algorithm_fits_ram(
+                            device.memory.ram_start + header_reserve,
+                            &footprint,
+                            device.memory.ram_start,
+                            device.memory.ram_size,
+                        )
+                        .err();
                         device_report.algorithm = Some(AlgorithmInfo {
                             name: algo.name.clone(),
                             flm_file: flm_path
@@ -846,8 +1101,14 @@ pub fn generate_scan_report(
                                 .to_string(),
                             page_size: algo.flash_properties.page_size as u32,
                             sector_count: algo.flash_properties.sectors.len(),
+                            header_reserve: Some(header_reserve),
+                            ram_error: ram_error.clone(),
                         });
-                        device_report.status = DeviceStatus::Ok;
+                        device_report.status = if ram_error.is_some() {
+                            DeviceStatus::Warning
+                        } else {
+                            DeviceStatus::Ok
+                        };
                     }
                     Err(_) => {
                         device_report.status = DeviceStatus::Warning;
@@ -871,38 +1132,182 @@ pub fn generate_scan_report(
 }
 
 /// 保存扫描报告到文件
+/// compress 为 true 时保存为 scan_report.json.gz，并移除旧的未压缩文件（反之亦然）
 pub fn save_scan_report(
     report: &crate::pack::scan_report::PackScanReport,
     pack_dir: &Path,
+    compress: bool,
 ) -> AppResult<()> {
-    let report_path = pack_dir.join("scan_report.json");
+    let plain_path = pack_dir.join("scan_report.json");
+    let gz_path = crate::compression::gz_path(&plain_path);
+    let (report_path, stale_path) = if compress {
+        (gz_path, plain_path)
+    } else {
+        (plain_path, gz_path)
+    };
+
     let json = serde_json::to_string_pretty(report)
         .map_err(|e| AppError::PackError(format!("序列化报告失败: {}", e)))?;
 
-    std::fs::write(&report_path, json)
+    crate::compression::write_file(&report_path, json.as_bytes(), compress)
         .map_err(|e| AppError::FileError(format!("保存报告失败: {}", e)))?;
 
+    if stale_path.exists() {
+        let _ = std::fs::remove_file(&stale_path);
+    }
+
     log::info!("扫描报告已保存到: {:?}", report_path);
     Ok(())
 }
 
-/// 加载扫描报告
+/// 加载扫描报告（同时支持 scan_report.json 与 scan_report.json.gz）
 pub fn load_scan_report(pack_dir: &Path) -> AppResult<crate::pack::scan_report::PackScanReport> {
-    let report_path = pack_dir.join("scan_report.json");
+    let plain_path = pack_dir.join("scan_report.json");
+    let gz_path = crate::compression::gz_path(&plain_path);
 
-    if !report_path.exists() {
+    let report_path = if plain_path.exists() {
+        plain_path
+    } else if gz_path.exists() {
+        gz_path
+    } else {
         return Err(AppError::FileError("扫描报告不存在".to_string()));
-    }
+    };
 
-    let json = std::fs::read_to_string(&report_path)
+    let data = crate::compression::read_file(&report_path)
         .map_err(|e| AppError::FileError(format!("读取报告失败: {}", e)))?;
 
-    let report = serde_json::from_str(&json)
+    let report = serde_json::from_slice(&data)
         .map_err(|e| AppError::PackError(format!("解析报告失败: {}", e)))?;
 
     Ok(report)
 }
 
+/// targets.yaml 中记录 PDSC 修改时间的标记
+const PDSC_MTIME_MARKER: &str = "# PDSC Modified:";
+
+/// 去掉生成时写入的内部标记注释（扫描器版本、PDSC 修改时间、生成时间）
+pub fn strip_generator_markers(yaml: &str) -> String {
+    let mut stripped = yaml
+        .lines()
+        .filter(|line| {
+            !line.starts_with("# EK-OmniProbe Pack Scanner Version:")
+                && !line.starts_with(PDSC_MTIME_MARKER)
+                && !line.starts_with("# Generated at:")
+        })
+        .skip_while(|line| line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    stripped.push('\n');
+    stripped
+}
+
+/// 合并后的 probe-rs 目标定义
+#[derive(Debug, Clone, Default)]
+pub struct TargetBundle {
+    /// 每个家族一个 (家族名, YAML 文档)，与 families 顺序一致
+    pub documents: Vec<(String, String)>,
+    pub families: Vec<String>,
+    pub variants: usize,
+    /// 与已加入的家族同名而被跳过的家族
+    pub duplicates: Vec<String>,
+}
+
+/// 将多个 (来源, targets.yaml) 按家族合并，同名家族只保留第一个
+/// 无法解析或缺少家族名的文档按来源返回错误，其余文档照常合并
+pub fn bundle_target_families(documents: &[(String, String)]) -> (TargetBundle, Vec<(String, String)>) {
+    let mut bundle = TargetBundle::default();
+    let mut errors = Vec::new();
+
+    for (source, document) in documents {
+        let document = strip_generator_markers(document);
+        let value: serde_yaml::Value = match serde_yaml::from_str(&document) {
+            Ok(value) => value,
+            Err(e) => {
+                errors.push((source.clone(), format!("解析 targets.yaml 失败: {}", e)));
+                continue;
+            }
+        };
+        let Some(name) = value.get("name").and_then(|n| n.as_str()) else {
+            errors.push((source.clone(), "targets.yaml 缺少家族名称".to_string()));
+            continue;
+        };
+
+        if bundle.families.iter().any(|f| f.eq_ignore_ascii_case(name)) {
+            bundle.duplicates.push(name.to_string());
+            continue;
+        }
+
+        bundle.variants += value
+            .get("variants")
+            .and_then(|v| v.as_sequence())
+            .map_or(0, |v| v.len());
+        bundle.families.push(name.to_string());
+        bundle.documents.push((name.to_string(), document));
+    }
+
+    (bundle, errors)
+}
+
+/// 家族导出文件名（不含扩展名）：非法路径字符替换为 `_`
+fn family_file_stem(family: &str) -> String {
+    family
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// 将每个家族写入输出目录下的单独文件（probe-rs 每个文件只能加载一个家族），返回写入的文件路径
+pub fn write_target_bundle(bundle: &TargetBundle, output_dir: &Path) -> AppResult<Vec<PathBuf>> {
+    fs::create_dir_all(output_dir)?;
+
+    let mut files = Vec::with_capacity(bundle.documents.len());
+    for (family, document) in &bundle.documents {
+        let stem = family_file_stem(family);
+        let mut path = output_dir.join(format!("{}.yaml", stem));
+        // 清理后同名的家族追加序号，避免互相覆盖
+        let mut suffix = 2;
+        while files.contains(&path) {
+            path = output_dir.join(format!("{}_{}.yaml", stem, suffix));
+            suffix += 1;
+        }
+        fs::write(&path, document)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// 需要重新扫描的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RescanReason {
+    /// 扫描器版本不一致或无法检测
+    Version,
+    /// PDSC 文件在上次扫描后被修改
+    Modified,
+}
+
+/// PDSC 文件修改时间（Unix 秒）
+fn pdsc_mtime(pack_dir: &Path) -> Option<u64> {
+    let pdsc_path = find_pdsc_file(pack_dir).ok()?;
+    fs::metadata(pdsc_path)
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// 读取 targets.yaml 中记录的 PDSC 修改时间
+fn recorded_pdsc_mtime(pack_dir: &Path) -> Option<u64> {
+    let content = fs::read_to_string(pack_dir.join("targets.yaml")).ok()?;
+    content
+        .lines()
+        .take_while(|line| line.starts_with('#') || line.is_empty())
+        .find_map(|line| line.strip_prefix(PDSC_MTIME_MARKER))
+        .and_then(|v| v.trim().parse().ok())
+}
+
 /// 检测 Pack 的扫描器版本
 /// 返回 None 表示无法检测版本（可能是旧版本）
 pub fn detect_pack_scanner_version(pack_dir: &Path) -> Option<String> {
@@ -930,14 +1335,22 @@ pub fn detect_pack_scanner_version(pack_dir: &Path) -> Option<String> {
 
 /// 检查 Pack 是否需要重新扫描
 pub fn needs_rescan(pack_dir: &Path) -> bool {
+    rescan_reason(pack_dir).is_some()
+}
+
+/// 检查 Pack 需要重新扫描的原因，None 表示无需重新扫描
+pub fn rescan_reason(pack_dir: &Path) -> Option<RescanReason> {
     match detect_pack_scanner_version(pack_dir) {
-        Some(version) => {
-            // 比较版本号
-            version != PACK_SCANNER_VERSION
-        }
-        None => {
-            // 无法检测版本，可能是旧版本，需要重新扫描
-            true
-        }
+        // 比较版本号
+        Some(version) if version != PACK_SCANNER_VERSION => return Some(RescanReason::Version),
+        Some(_) => {}
+        // 无法检测版本，可能是旧版本，需要重新扫描
+        None => return Some(RescanReason::Version),
+    }
+
+    // PDSC 比记录的更新（用户原地替换了 Pack 文件）
+    match (pdsc_mtime(pack_dir), recorded_pdsc_mtime(pack_dir)) {
+        (Some(current), Some(recorded)) if current > recorded => Some(RescanReason::Modified),
+        _ => None,
     }
 }
@@ -0,0 +1,202 @@
+// 目标定义覆盖模块
+// 将用户提供的 YAML 片段合并到已注册的 probe-rs 目标定义上，并持久化以便启动时重新应用
+
+use crate::app_config;
+use crate::error::{AppError, AppResult};
+use crate::pack::flash_algo::FlashAlgorithm;
+use crate::pack::target_gen;
+use probe_rs::config::Registry;
+use serde_yaml::Value;
+
+/// 深度合并 YAML：映射逐键合并；元素均带 name 的序列按 name 合并；其余类型直接替换
+pub fn merge_yaml(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Mapping(base_map), Value::Mapping(patch_map)) => {
+            for (key, patch_value) in patch_map {
+                match base_map.get_mut(&key) {
+                    Some(base_value) => merge_yaml(base_value, patch_value),
+                    None => {
+                        base_map.insert(key, patch_value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base_seq), Value::Sequence(patch_seq))
+            if patch_seq.iter().all(|v| v.get("name").is_some()) =>
+        {
+            for patch_item in patch_seq {
+                let name = patch_item.get("name").cloned();
+                match base_seq.iter_mut().find(|item| item.get("name") == name.as_ref()) {
+                    Some(base_item) => merge_yaml(base_item, patch_item),
+                    None => base_seq.push(patch_item),
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// 将 YAML 片段合并到包含指定芯片/家族的目标家族上，并重新注册
+/// 名称匹配家族时合并到家族根节点，匹配芯片时合并到对应 variant
+/// 返回被修改的家族名称
+pub fn apply_override(registry: &mut Registry, name: &str, fragment: &str) -> AppResult<String> {
+    let patch: Value = serde_yaml::from_str(fragment)
+        .map_err(|e| AppError::ConfigError(format!("解析 YAML 片段失败: {}", e)))?;
+
+    let family = registry
+        .families()
+        .iter()
+        .find(|f| {
+            f.name.eq_ignore_ascii_case(name)
+                || f.variants().iter().any(|v| v.name.eq_ignore_ascii_case(name))
+        })
+        .cloned()
+        .ok_or_else(|| AppError::ConfigError(format!("未找到芯片或家族: {}", name)))?;
+
+    let mut family_value = serde_yaml::to_value(&family)
+        .map_err(|e| AppError::ConfigError(format!("序列化目标定义失败: {}", e)))?;
+
+    if family.name.eq_ignore_ascii_case(name) {
+        merge_yaml(&mut family_value, patch);
+    } else {
+        let variant = family_value
+            .get_mut("variants")
+            .and_then(|v| v.as_sequence_mut())
+            .and_then(|variants| {
+                variants.iter_mut().find(|v| {
+                    v.get("name")
+                        .and_then(|n| n.as_str())
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+                })
+            })
+            .ok_or_else(|| AppError::ConfigError(format!("未找到芯片定义: {}", name)))?;
+        merge_yaml(variant, patch);
+    }
+
+    let yaml = serde_yaml::to_string(&family_value)
+        .map_err(|e| AppError::ConfigError(format!("生成 YAML 失败: {}", e)))?;
+
+    registry
+        .add_target_family_from_yaml(&yaml)
+        .map_err(|e| AppError::ConfigError(format!("重新注册目标定义失败: {}", e)))?;
+
+    log::info!("已将覆盖应用到 {} (家族 {})", name, family.name);
+
+    Ok(family.name)
+}
+
+/// 重新应用所有已持久化的覆盖（启动或重新扫描 Pack 后调用）
+pub fn apply_saved_overrides(registry: &mut Registry) -> usize {
+    let config = app_config::load_config();
+    let mut applied = 0;
+
+    for (name, fragment) in &config.target_overrides {
+        match apply_override(registry, name, fragment) {
+            Ok(_) => applied += 1,
+            Err(e) => log::warn!("应用目标覆盖 {} 失败: {}", name, e),
+        }
+    }
+
+    applied
+}
+
+/// 外部 Flash 临时变体名称后缀
+pub const EXTERNAL_LOADER_SUFFIX: &str = "-ExtLoader";
+
+/// 基于已注册芯片生成带外部 Flash 区域和外部加载算法的临时变体并注册
+/// 返回临时变体名称
+pub fn register_external_loader_variant(
+    registry: &mut Registry,
+    chip_name: &str,
+    algo: &FlashAlgorithm,
+    load_address: u64,
+) -> AppResult<String> {
+    let family = registry
+        .families()
+        .iter()
+        .find(|f| f.variants().iter().any(|v| v.name.eq_ignore_ascii_case(chip_name)))
+        .cloned()
+        .ok_or_else(|| AppError::ConfigError(format!("未找到芯片: {}", chip_name)))?;
+
+    let mut family_value = serde_yaml::to_value(&family)
+        .map_err(|e| AppError::ConfigError(format!("序列化目标定义失败: {}", e)))?;
+
+    let variant_name = format!("{}{}", chip_name, EXTERNAL_LOADER_SUFFIX);
+
+    // 外部加载算法
+    let mut algo_yaml = String::new();
+    target_gen::write_flash_algorithm_yaml(
+        &mut algo_yaml,
+        algo,
+        load_address,
+        target_gen::default_stack_size(algo.flash_properties.page_size),
+    );
+    let algo_value: Value = serde_yaml::from_str(&algo_yaml)
+        .map_err(|e| AppError::ConfigError(format!("生成外部加载算法失败: {}", e)))?;
+
+    let algorithms = family_value
+        .as_mapping_mut()
+        .ok_or_else(|| AppError::ConfigError("目标定义格式错误".to_string()))?
+        .entry(Value::from("flash_algorithms"))
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    if let (Some(list), Value::Sequence(new_items)) = (algorithms.as_sequence_mut(), algo_value) {
+        list.retain(|a| a.get("name").and_then(|n| n.as_str()) != Some(algo.name.as_str()));
+        list.extend(new_items);
+    }
+
+    // 复制原芯片定义作为临时变体，追加外部 Flash 区域
+    let variants = family_value
+        .get_mut("variants")
+        .and_then(|v| v.as_sequence_mut())
+        .ok_or_else(|| AppError::ConfigError("目标定义缺少 variants".to_string()))?;
+    variants.retain(|v| v.get("name").and_then(|n| n.as_str()) != Some(variant_name.as_str()));
+
+    let mut variant = variants
+        .iter()
+        .find(|v| {
+            v.get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case(chip_name))
+        })
+        .cloned()
+        .ok_or_else(|| AppError::ConfigError(format!("未找到芯片定义: {}", chip_name)))?;
+
+    let core_name = variant
+        .get("cores")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or("main")
+        .to_string();
+
+    let region: Value = serde_yaml::from_str(&format!(
+        "!Nvm\nname: external\nrange:\n  start: 0x{:x}\n  end: 0x{:x}\ncores:\n  - {}\n",
+        algo.flash_properties.address_range.start,
+        algo.flash_properties.address_range.end,
+        core_name
+    ))
+    .map_err(|e| AppError::ConfigError(format!("生成外部 Flash 区域失败: {}", e)))?;
+
+    if let Some(map) = variant.as_mapping_mut() {
+        map.insert(Value::from("name"), Value::from(variant_name.clone()));
+        if let Some(memory_map) = map.get_mut("memory_map").and_then(|m| m.as_sequence_mut()) {
+            memory_map.push(region);
+        }
+        map.insert(
+            Value::from("flash_algorithms"),
+            Value::Sequence(vec![Value::from(algo.name.clone())]),
+        );
+    }
+    variants.push(variant);
+
+    let yaml = serde_yaml::to_string(&family_value)
+        .map_err(|e| AppError::ConfigError(format!("生成 YAML 失败: {}", e)))?;
+
+    registry
+        .add_target_family_from_yaml(&yaml)
+        .map_err(|e| AppError::ConfigError(format!("注册外部加载变体失败: {}", e)))?;
+
+    log::info!("已注册外部加载临时变体: {}", variant_name);
+
+    Ok(variant_name)
+}
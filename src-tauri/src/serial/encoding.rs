@@ -0,0 +1,133 @@
+use serde::Serialize;
+
+/// Detected text encoding
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextEncoding {
+    Ascii,
+    Utf8,
+    Gbk,
+    Unknown,
+}
+
+/// Encoding guess with confidence (0.0 - 1.0)
+#[derive(Debug, Clone, Serialize)]
+pub struct EncodingGuess {
+    pub encoding: TextEncoding,
+    pub confidence: f32,
+    pub sample_size: usize,
+}
+
+/// Heuristically detect the encoding of a byte sample.
+///
+/// Pure ASCII wins first, then strict UTF-8 validity (a sequence truncated at
+/// the end of the sample is tolerated), then GBK double-byte pair validity.
+pub fn detect_encoding(data: &[u8]) -> EncodingGuess {
+    let sample_size = data.len();
+    let guess = |encoding, confidence| EncodingGuess {
+        encoding,
+        confidence,
+        sample_size,
+    };
+
+    if data.is_empty() {
+        return guess(TextEncoding::Unknown, 0.0);
+    }
+
+    let high_bytes = data.iter().filter(|&&b| b >= 0x80).count();
+    if high_bytes == 0 {
+        // Mostly control characters is more likely binary than text
+        let printable = data
+            .iter()
+            .filter(|&&b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+            .count();
+        return guess(TextEncoding::Ascii, printable as f32 / sample_size as f32);
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(_) => return guess(TextEncoding::Utf8, utf8_confidence(high_bytes)),
+        Err(e) if e.error_len().is_none() => {
+            return guess(TextEncoding::Utf8, utf8_confidence(high_bytes) * 0.95)
+        }
+        Err(_) => {}
+    }
+
+    let (valid_pairs, invalid) = count_gbk_pairs(data);
+    if valid_pairs > 0 {
+        let ratio = valid_pairs as f32 / (valid_pairs + invalid) as f32;
+        return guess(TextEncoding::Gbk, ratio * 0.9);
+    }
+
+    guess(TextEncoding::Unknown, 0.0)
+}
+
+/// More multi-byte sequences make a UTF-8 match less likely to be coincidence
+fn utf8_confidence(high_bytes: usize) -> f32 {
+    if high_bytes >= 6 {
+        0.99
+    } else {
+        0.8 + high_bytes as f32 * 0.03
+    }
+}
+
+/// Count valid GBK double-byte characters and invalid high bytes
+fn count_gbk_pairs(data: &[u8]) -> (usize, usize) {
+    let mut valid = 0;
+    let mut invalid = 0;
+    let mut i = 0;
+
+    while i < data.len() {
+        let lead = data[i];
+        if lead < 0x80 {
+            i += 1;
+            continue;
+        }
+
+        match data.get(i + 1) {
+            Some(&trail) if (0x81..=0xFE).contains(&lead) && (0x40..=0xFE).contains(&trail) && trail != 0x7F => {
+                valid += 1;
+                i += 2;
+            }
+            // Lead byte cut off at the end of the sample
+            None if (0x81..=0xFE).contains(&lead) => i += 1,
+            _ => {
+                invalid += 1;
+                i += 1;
+            }
+        }
+    }
+
+    (valid, invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_text() {
+        let guess = detect_encoding(b"temp=25.3 C\r\n");
+        assert_eq!(guess.encoding, TextEncoding::Ascii);
+        assert!(guess.confidence > 0.99);
+    }
+
+    #[test]
+    fn test_utf8_chinese() {
+        let guess = detect_encoding("温度: 25 度\n".as_bytes());
+        assert_eq!(guess.encoding, TextEncoding::Utf8);
+        assert!(guess.confidence > 0.9);
+    }
+
+    #[test]
+    fn test_gbk_chinese() {
+        // "温度" in GBK
+        let guess = detect_encoding(&[0xCE, 0xC2, 0xB6, 0xC8, b':', b' ', b'2', b'5']);
+        assert_eq!(guess.encoding, TextEncoding::Gbk);
+        assert!(guess.confidence > 0.8);
+    }
+
+    #[test]
+    fn test_empty_sample() {
+        assert_eq!(detect_encoding(&[]).encoding, TextEncoding::Unknown);
+    }
+}
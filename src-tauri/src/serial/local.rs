@@ -1,3 +1,4 @@
+use super::{SerialErrorCategory, SerialOpenError};
 use crate::state::{DataSource, SerialStats};
 use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
 use std::io::{Read, Write};
@@ -53,8 +54,51 @@ impl LocalSerial {
     }
 }
 
-impl DataSource for LocalSerial {
-    fn connect(&mut self) -> Result<(), String> {
+/// Classify a serial port open error and attach a suggestion for the user
+pub fn classify_open_error(port_name: &str, error: &serialport::Error) -> SerialOpenError {
+    let description = error.to_string();
+    let lower = description.to_lowercase();
+
+    let category = match error.kind() {
+        serialport::ErrorKind::NoDevice => SerialErrorCategory::NotFound,
+        serialport::ErrorKind::Io(std::io::ErrorKind::NotFound) => SerialErrorCategory::NotFound,
+        // Windows reports a port held by another program as "Access is denied"
+        serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) if cfg!(windows) => {
+            SerialErrorCategory::Busy
+        }
+        serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => {
+            SerialErrorCategory::PermissionDenied
+        }
+        _ if lower.contains("busy") || lower.contains("in use") || lower.contains("locked") => {
+            SerialErrorCategory::Busy
+        }
+        _ if lower.contains("permission denied") => SerialErrorCategory::PermissionDenied,
+        _ if lower.contains("no such file") || lower.contains("cannot find") => {
+            SerialErrorCategory::NotFound
+        }
+        _ => SerialErrorCategory::Other,
+    };
+
+    let suggestion = match category {
+        SerialErrorCategory::PermissionDenied if cfg!(target_os = "linux") => Some(
+            "当前用户无权访问串口，请执行 sudo usermod -aG dialout $USER 后重新登录，或安装 udev 规则".to_string(),
+        ),
+        SerialErrorCategory::PermissionDenied => Some("当前用户无权访问串口，请检查设备权限".to_string()),
+        SerialErrorCategory::Busy => Some("串口已被其他程序占用，请关闭其他串口工具后重试".to_string()),
+        SerialErrorCategory::NotFound => Some("设备不存在，可能已拔出，请刷新串口列表".to_string()),
+        SerialErrorCategory::Other => None,
+    };
+
+    SerialOpenError {
+        category,
+        message: format!("Failed to open serial port {}: {}", port_name, description),
+        suggestion,
+    }
+}
+
+impl LocalSerial {
+    /// Open the port, returning a classified error on failure
+    pub fn open(&mut self) -> Result<(), SerialOpenError> {
         if self.port.is_some() {
             return Ok(());
         }
@@ -66,12 +110,18 @@ impl DataSource for LocalSerial {
             .flow_control(self.flow_control)
             .timeout(Duration::from_millis(1)) // 降低超时到 1ms，提高响应速度
             .open()
-            .map_err(|e| format!("Failed to open serial port: {}", e))?;
+            .map_err(|e| classify_open_error(&self.port_name, &e))?;
 
         self.port = Some(port);
         self.stats = SerialStats::default();
         Ok(())
     }
+}
+
+impl DataSource for LocalSerial {
+    fn connect(&mut self) -> Result<(), String> {
+        self.open().map_err(|e| e.to_string())
+    }
 
     fn disconnect(&mut self) -> Result<(), String> {
         self.port = None;
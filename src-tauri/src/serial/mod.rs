@@ -1,7 +1,8 @@
+pub mod encoding;
 pub mod local;
 pub mod tcp;
 
-pub use local::{list_serial_ports, LocalSerial, SerialPortInfo};
+pub use local::{classify_open_error, list_serial_ports, LocalSerial, SerialPortInfo};
 pub use tcp::TcpSerial;
 
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,46 @@ pub enum SerialConfig {
     },
 }
 
+/// Category of a serial port open failure
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialErrorCategory {
+    /// The current user lacks access rights to the device node
+    PermissionDenied,
+    /// Another program already holds the port
+    Busy,
+    /// The device does not exist (unplugged or wrong name)
+    NotFound,
+    Other,
+}
+
+/// Structured serial open error with an actionable hint
+#[derive(Debug, Clone, Serialize)]
+pub struct SerialOpenError {
+    pub category: SerialErrorCategory,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl SerialOpenError {
+    pub fn other(message: String) -> Self {
+        Self {
+            category: SerialErrorCategory::Other,
+            message,
+            suggestion: None,
+        }
+    }
+}
+
+impl std::fmt::Display for SerialOpenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.suggestion {
+            Some(suggestion) => write!(f, "{} ({})", self.message, suggestion),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 fn default_data_bits() -> u8 {
     8
 }
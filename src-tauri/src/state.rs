@@ -1,9 +1,14 @@
+use crate::commands::probe::ConnectOptions;
 use parking_lot::Mutex;
 use probe_rs::Session;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
+
+/// 通道统计快照：(采样时间, 各通道收/发字节数)
+pub type RttStatsSnapshot = (Instant, HashMap<usize, (u64, u64)>);
 
 /// RTT 运行时状态
 pub struct RttState {
@@ -19,6 +24,50 @@ pub struct RttState {
     pub channel_read_offsets: Mutex<HashMap<usize, u32>>,
     /// 各通道的缓冲区信息 (地址, 大小)
     pub channel_buffers: Mutex<HashMap<usize, (u64, u32)>>,
+    /// 各通道字节统计
+    pub channel_stats: Mutex<HashMap<usize, RttChannelStats>>,
+    /// 上次查询统计时的快照 (时间, 各通道收/发字节数)，用于计算速率
+    pub stats_snapshot: Mutex<RttStatsSnapshot>,
+    /// 各通道最近接收数据的历史记录 (停止 RTT 后仍保留，供回看)
+    pub history: Mutex<HashMap<usize, ByteHistory>>,
+    /// 后端预过滤规则（未配置的通道不过滤）
+    pub view_filters: Mutex<HashMap<usize, RttChannelFilter>>,
+    /// 批量发送超时 (毫秒)，轮询任务每次循环读取
+    pub batch_timeout_ms: Mutex<u64>,
+    /// 批量大小阈值 (事件数)，达到后立即发送
+    pub batch_size_threshold: Mutex<usize>,
+    /// 各下行通道最近发送的命令（最旧在前），供终端式上翻调用
+    pub write_history: Mutex<HashMap<usize, VecDeque<String>>>,
+}
+
+/// 编译后的 RTT 通道过滤规则
+#[derive(Debug, Clone)]
+pub struct RttChannelFilter {
+    /// 隐藏的通道不发送数据（仍计入统计与历史）
+    pub visible: bool,
+    /// 行过滤正则，匹配任意一条的行保留
+    pub patterns: Vec<regex::Regex>,
+}
+
+/// RTT 每个通道保留的历史字节数
+pub const RTT_HISTORY_CAPACITY: usize = 64 * 1024;
+
+/// RTT 每个下行通道保留的命令历史条数
+pub const RTT_WRITE_HISTORY_CAPACITY: usize = 50;
+
+/// RTT 默认批量发送超时 (毫秒)
+pub const RTT_BATCH_TIMEOUT_MS: u64 = 50;
+
+/// RTT 默认批量大小阈值 (事件数)
+pub const RTT_BATCH_SIZE_THRESHOLD: usize = 10;
+
+/// RTT 通道字节统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RttChannelStats {
+    /// 上行通道接收字节数
+    pub bytes_received: u64,
+    /// 下行通道发送字节数
+    pub bytes_sent: u64,
 }
 
 impl Default for RttState {
@@ -30,6 +79,13 @@ impl Default for RttState {
             line_buffers: Mutex::new(HashMap::new()),
             channel_read_offsets: Mutex::new(HashMap::new()),
             channel_buffers: Mutex::new(HashMap::new()),
+            channel_stats: Mutex::new(HashMap::new()),
+            stats_snapshot: Mutex::new((Instant::now(), HashMap::new())),
+            history: Mutex::new(HashMap::new()),
+            view_filters: Mutex::new(HashMap::new()),
+            batch_timeout_ms: Mutex::new(RTT_BATCH_TIMEOUT_MS),
+            batch_size_threshold: Mutex::new(RTT_BATCH_SIZE_THRESHOLD),
+            write_history: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -50,6 +106,296 @@ impl RttState {
         self.channel_read_offsets.lock().clear();
         self.channel_buffers.lock().clear();
     }
+
+    /// 记录通道接收数据到历史缓冲区
+    pub fn record_history(&self, channel: usize, data: &[u8]) {
+        self.history
+            .lock()
+            .entry(channel)
+            .or_insert_with(|| ByteHistory::new(RTT_HISTORY_CAPACITY))
+            .push(data);
+    }
+
+    /// 通道历史的累计写入位置
+    pub fn history_mark(&self, channel: usize) -> u64 {
+        self.history.lock().get(&channel).map_or(0, |h| h.total())
+    }
+
+    /// 读取通道历史中 mark 之后的新数据，返回数据和新的位置
+    pub fn history_since(&self, channel: usize, mark: u64) -> (Vec<u8>, u64) {
+        match self.history.lock().get(&channel) {
+            Some(history) => (history.since(mark), history.total()),
+            None => (Vec::new(), mark),
+        }
+    }
+
+    /// 记录写入下行通道的命令：去掉行尾换行，忽略空命令和与上一条相同的命令
+    pub fn record_write(&self, channel: usize, data: &[u8]) {
+        let command = String::from_utf8_lossy(data).trim_end_matches(['\r', '\n']).to_string();
+        if command.is_empty() {
+            return;
+        }
+
+        let mut history = self.write_history.lock();
+        let entries = history.entry(channel).or_default();
+        if entries.back() == Some(&command) {
+            return;
+        }
+        if entries.len() >= RTT_WRITE_HISTORY_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(command);
+    }
+
+    /// 下行通道的命令历史（最旧在前）
+    pub fn write_history(&self, channel: usize) -> Vec<String> {
+        self.write_history
+            .lock()
+            .get(&channel)
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 按预过滤规则处理通道数据，返回需要发送到前端的部分
+    /// 配置了正则的通道按完整行过滤，未完成的行暂存在 line_buffers 中
+    pub fn filter_for_view(&self, channel: usize, data: &[u8]) -> Option<Vec<u8>> {
+        let filters = self.view_filters.lock();
+        let Some(filter) = filters.get(&channel) else {
+            return Some(data.to_vec());
+        };
+        if !filter.visible {
+            return None;
+        }
+        if filter.patterns.is_empty() {
+            return Some(data.to_vec());
+        }
+
+        let mut line_buffers = self.line_buffers.lock();
+        let pending = line_buffers.entry(channel).or_default();
+        pending.extend_from_slice(data);
+
+        let mut output = Vec::new();
+        while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            let text = String::from_utf8_lossy(&line);
+            if filter.patterns.iter().any(|re| re.is_match(text.trim_end())) {
+                output.extend_from_slice(&line);
+            }
+        }
+
+        (!output.is_empty()).then_some(output)
+    }
+
+    pub fn add_received(&self, channel: usize, bytes: usize) {
+        self.channel_stats.lock().entry(channel).or_default().bytes_received += bytes as u64;
+    }
+
+    pub fn add_sent(&self, channel: usize, bytes: usize) {
+        self.channel_stats.lock().entry(channel).or_default().bytes_sent += bytes as u64;
+    }
+
+    pub fn reset_stats(&self) {
+        self.channel_stats.lock().clear();
+        *self.stats_snapshot.lock() = (Instant::now(), HashMap::new());
+    }
+}
+
+/// 固定容量的字节环形缓冲区，超出容量时丢弃最旧的数据
+#[derive(Debug, Clone)]
+pub struct ByteHistory {
+    data: VecDeque<u8>,
+    capacity: usize,
+    /// 累计写入的字节数（清空时不归零），用于增量读取
+    total: u64,
+}
+
+impl ByteHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            capacity,
+            total: 0,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        // 单次写入超过容量时只保留末尾部分
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.data.len() + bytes.len()).saturating_sub(self.capacity);
+        self.data.drain(..overflow);
+        self.data.extend(bytes);
+    }
+
+    /// 返回最近的至多 max_bytes 字节
+    pub fn tail(&self, max_bytes: usize) -> Vec<u8> {
+        let skip = self.data.len().saturating_sub(max_bytes);
+        self.data.iter().skip(skip).copied().collect()
+    }
+
+    /// 累计写入的字节数
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// 返回累计位置 mark 之后写入且仍在缓冲区中的字节
+    pub fn since(&self, mark: u64) -> Vec<u8> {
+        let new_bytes = self.total.saturating_sub(mark) as usize;
+        self.tail(new_bytes)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+/// 字访问宽度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AccessWidth {
+    U16,
+    U32,
+    U64,
+}
+
+impl AccessWidth {
+    /// 字节数
+    pub fn bytes(self) -> u64 {
+        match self {
+            AccessWidth::U16 => 2,
+            AccessWidth::U32 => 4,
+            AccessWidth::U64 => 8,
+        }
+    }
+
+    /// 该宽度能表示的最大值
+    pub fn max_value(self) -> u64 {
+        match self {
+            AccessWidth::U16 => u16::MAX as u64,
+            AccessWidth::U32 => u32::MAX as u64,
+            AccessWidth::U64 => u64::MAX,
+        }
+    }
+}
+
+/// 内存监视项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryWatch {
+    pub address: u64,
+    pub width: AccessWidth,
+    /// 显示名称（变量名/寄存器名）
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// 内存监视运行时状态
+pub struct MemoryWatchState {
+    /// 是否正在运行
+    pub running: AtomicBool,
+    /// 每次启动递增，旧的轮询任务发现代数变化后退出，避免重复任务
+    generation: AtomicU64,
+    /// 刷新间隔 (毫秒)
+    pub poll_interval_ms: Mutex<u64>,
+    /// 监视地址列表
+    pub watches: Mutex<Vec<MemoryWatch>>,
+}
+
+impl Default for MemoryWatchState {
+    fn default() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            poll_interval_ms: Mutex::new(200),
+            watches: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl MemoryWatchState {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::SeqCst);
+    }
+
+    /// 标记为运行并返回新的任务代数，之前启动的任务随之失效
+    pub fn start(&self) -> u64 {
+        self.running.store(true, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 代数为 generation 的任务是否应继续运行
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.is_running() && self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// 任务退出时调用：仅当仍是当前任务时清除运行标志
+    pub fn finish(&self, generation: u64) {
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.running.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// ITM 单个激励端口的数据统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItmPortStats {
+    pub packets: u64,
+    pub bytes: u64,
+}
+
+/// SWO/ITM 跟踪运行时状态
+pub struct SwoState {
+    /// 是否正在运行
+    pub running: AtomicBool,
+    /// 轮询间隔 (毫秒)
+    pub poll_interval_ms: Mutex<u64>,
+    /// 各激励端口收到的数据统计
+    pub port_stats: Mutex<std::collections::BTreeMap<u8, ItmPortStats>>,
+}
+
+impl Default for SwoState {
+    fn default() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            poll_interval_ms: Mutex::new(10),
+            port_stats: Mutex::new(std::collections::BTreeMap::new()),
+        }
+    }
+}
+
+impl SwoState {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::SeqCst);
+    }
+
+    /// 原子地标记为运行中，已在运行（或正在采样）时返回 false
+    pub fn try_start(&self) -> bool {
+        self.running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// 记录一个激励端口数据包
+    pub fn record_packet(&self, port: u8, len: usize) {
+        let mut stats = self.port_stats.lock();
+        let entry = stats.entry(port).or_default();
+        entry.packets += 1;
+        entry.bytes += len as u64;
+    }
 }
 
 // ============================================================================
@@ -100,8 +446,39 @@ pub struct SerialState {
     pub datasource: Mutex<Option<Box<dyn DataSource>>>,
     /// Line buffer for incomplete lines
     pub line_buffer: Mutex<Vec<u8>>,
+    /// 最近接收的数据（用于编码检测）
+    pub recent_rx: Mutex<Vec<u8>>,
+    /// 将写入的数据以 "tx" 事件回显到控制台
+    pub local_echo: AtomicBool,
+    /// 显示已暂停：轮询任务继续读取但暂存数据
+    pub paused: AtomicBool,
+    /// 暂停期间收到的数据，恢复时输出
+    pub paused_buffer: Mutex<Vec<u8>>,
+    /// 因暂停缓冲区已满而丢弃的字节数
+    pub paused_dropped: AtomicU64,
+    /// 接收数据的回看历史，独立于界面缓冲区保存
+    pub history: Mutex<ByteHistory>,
+    /// 缓冲数据超过该毫秒数即发送，每次轮询时读取
+    pub batch_timeout_ms: Mutex<u64>,
+    /// 缓冲数据达到该字节数时立即发送
+    pub batch_size_threshold: Mutex<usize>,
 }
 
+/// 为编码检测保留的最近接收字节数
+pub const SERIAL_RECENT_RX_CAPACITY: usize = 4096;
+
+/// 暂停期间最多暂存的字节数（超出时丢弃最旧的数据）
+pub const SERIAL_PAUSE_BUFFER_CAPACITY: usize = 4 * 1024 * 1024;
+
+/// 回看历史保留的接收字节数
+pub const SERIAL_HISTORY_CAPACITY: usize = 256 * 1024;
+
+/// 默认批量发送超时（毫秒）
+pub const SERIAL_BATCH_TIMEOUT_MS: u64 = 10;
+
+/// 默认批量发送大小阈值（字节）
+pub const SERIAL_BATCH_SIZE_THRESHOLD: usize = 4096;
+
 impl Default for SerialState {
     fn default() -> Self {
         Self {
@@ -109,6 +486,14 @@ impl Default for SerialState {
             poll_interval_ms: Mutex::new(10),
             datasource: Mutex::new(None),
             line_buffer: Mutex::new(Vec::new()),
+            recent_rx: Mutex::new(Vec::new()),
+            local_echo: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            paused_buffer: Mutex::new(Vec::new()),
+            paused_dropped: AtomicU64::new(0),
+            history: Mutex::new(ByteHistory::new(SERIAL_HISTORY_CAPACITY)),
+            batch_timeout_ms: Mutex::new(SERIAL_BATCH_TIMEOUT_MS),
+            batch_size_threshold: Mutex::new(SERIAL_BATCH_SIZE_THRESHOLD),
         }
     }
 }
@@ -130,6 +515,38 @@ impl SerialState {
             .unwrap_or(false)
     }
 
+    /// 将接收的数据追加到最近接收缓冲区，只保留最新部分
+    pub fn record_rx(&self, data: &[u8]) {
+        let mut recent = self.recent_rx.lock();
+        recent.extend_from_slice(data);
+        if recent.len() > SERIAL_RECENT_RX_CAPACITY {
+            let excess = recent.len() - SERIAL_RECENT_RX_CAPACITY;
+            recent.drain(..excess);
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// 暂停期间暂存收到的数据，超出上限时丢弃最旧的数据
+    pub fn buffer_paused(&self, data: &[u8]) {
+        let mut buffer = self.paused_buffer.lock();
+        buffer.extend_from_slice(data);
+        if buffer.len() > SERIAL_PAUSE_BUFFER_CAPACITY {
+            let excess = buffer.len() - SERIAL_PAUSE_BUFFER_CAPACITY;
+            buffer.drain(..excess);
+            self.paused_dropped.fetch_add(excess as u64, Ordering::SeqCst);
+        }
+    }
+
+    /// 取出暂停期间暂存的全部数据及丢弃的字节数
+    pub fn take_paused(&self) -> (Vec<u8>, u64) {
+        let data = std::mem::take(&mut *self.paused_buffer.lock());
+        let dropped = self.paused_dropped.swap(0, Ordering::SeqCst);
+        (data, dropped)
+    }
+
     pub fn get_stats(&self) -> SerialStats {
         self.datasource
             .lock()
@@ -142,6 +559,9 @@ impl SerialState {
         self.running.store(false, Ordering::SeqCst);
         *self.datasource.lock() = None;
         self.line_buffer.lock().clear();
+        self.recent_rx.lock().clear();
+        self.paused.store(false, Ordering::SeqCst);
+        self.take_paused();
     }
 }
 
@@ -149,6 +569,70 @@ impl SerialState {
 // Application State
 // ============================================================================
 
+thread_local! {
+    /// 当前线程通过 SessionGuard 持有的 session 锁数量
+    static SESSION_LOCKS_HELD: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+/// session 锁守卫：检查同一线程不会重复获取 session 锁（统一模式下会直接死锁），
+/// 烧录用途时在等待和持有期间计入 flash_in_progress
+pub struct SessionGuard<'a> {
+    guard: parking_lot::MutexGuard<'a, Option<Session>>,
+    flash_count: Option<&'a AtomicUsize>,
+}
+
+impl<'a> SessionGuard<'a> {
+    /// 获取任意 session 句柄（主连接或 RTT 连接）的锁
+    pub fn lock(session: &'a Mutex<Option<Session>>) -> Self {
+        Self::acquire(session, None)
+    }
+
+    fn acquire(session: &'a Mutex<Option<Session>>, flash_count: Option<&'a AtomicUsize>) -> Self {
+        SESSION_LOCKS_HELD.with(|held| {
+            debug_assert_eq!(held.get(), 0, "同一线程重复获取 session 锁，违反锁顺序约定");
+            held.set(held.get() + 1);
+        });
+        // 等待锁之前计数，让轮询任务尽早让出；多个烧录守卫重叠时由计数保证标志不被提前清除
+        if let Some(count) = flash_count {
+            count.fetch_add(1, Ordering::SeqCst);
+        }
+        Self {
+            guard: session.lock(),
+            flash_count,
+        }
+    }
+}
+
+impl std::ops::Deref for SessionGuard<'_> {
+    type Target = Option<Session>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl std::ops::DerefMut for SessionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(count) = self.flash_count {
+            count.fetch_sub(1, Ordering::SeqCst);
+        }
+        SESSION_LOCKS_HELD.with(|held| held.set(held.get().saturating_sub(1)));
+    }
+}
+
+/// 锁顺序约定（避免 RTT/烧录等并发操作死锁）：
+/// 1. session / rtt_session：同一线程同时只能持有其中一个（统一模式下二者是同一把锁）
+/// 2. connect_options、connection_info 等短时状态锁：可在持有 session 时获取，反之不可
+/// 3. TARGET_REGISTRY：最后获取
+///
+/// 除后台轮询任务外，session / rtt_session 一律通过 SessionGuard 获取；
+/// 后台轮询任务只能用 try_lock_for 获取 session，且在 flash_in_progress 非零时主动让出
 pub struct AppState {
     pub session: Arc<Mutex<Option<Session>>>,           // 主连接（用于烧录）
     pub rtt_session: Arc<Mutex<Option<Session>>>,       // RTT 独立连接
@@ -157,6 +641,24 @@ pub struct AppState {
     pub settings: Arc<Mutex<DeviceSettings>>,
     pub rtt_state: Arc<RttState>,
     pub serial_state: Arc<SerialState>,  // Serial port state
+    pub memory_watch_state: Arc<MemoryWatchState>, // 内存监视状态
+    pub swo_state: Arc<SwoState>,                   // SWO/ITM 跟踪状态
+    pub active_core: Arc<Mutex<usize>>,             // 多核芯片当前操作的内核
+    pub connect_options: Arc<Mutex<Option<ConnectOptions>>>, // 主连接使用的连接选项（用于重连）
+    pub unified_session: Arc<AtomicBool>,           // 统一模式：RTT 复用主连接
+    pub watch_poll_state: Arc<MemoryWatchState>,    // 变量监视列表轮询状态（内核运行时也读取）
+    pub flash_in_progress: Arc<AtomicUsize>,        // 进行中的烧录/擦除数，非零时轮询任务暂停访问 session
+    pub flash_read_running: Arc<AtomicBool>,        // 后台 Flash 读取任务运行中（置 false 取消）
+    pub control_server: Arc<Mutex<Option<ControlServerHandle>>>, // 本地控制服务（运行时为 Some）
+}
+
+/// 运行中的本地控制服务
+pub struct ControlServerHandle {
+    pub port: u16,
+    /// 通知监听任务退出
+    pub shutdown: Arc<tokio::sync::Notify>,
+    /// 监听任务，退出时释放端口
+    pub task: tauri::async_runtime::JoinHandle<()>,
 }
 
 impl AppState {
@@ -169,8 +671,61 @@ impl AppState {
             settings: Arc::new(Mutex::new(DeviceSettings::default())),
             rtt_state: Arc::new(RttState::default()),
             serial_state: Arc::new(SerialState::default()),
+            memory_watch_state: Arc::new(MemoryWatchState::default()),
+            swo_state: Arc::new(SwoState::default()),
+            active_core: Arc::new(Mutex::new(0)),
+            connect_options: Arc::new(Mutex::new(None)),
+            unified_session: Arc::new(AtomicBool::new(false)),
+            watch_poll_state: Arc::new(MemoryWatchState::default()),
+            flash_in_progress: Arc::new(AtomicUsize::new(0)),
+            flash_read_running: Arc::new(AtomicBool::new(false)),
+            control_server: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 创建状态并应用配置中保存的设置（内核操作超时等）
+    pub fn from_config(config: &crate::app_config::AppConfig) -> Self {
+        let state = Self::new();
+        state.settings.lock().core_operation_timeout_ms = config.core_operation_timeout_ms;
+        state
+    }
+
+    /// 获取主连接 session 锁
+    pub fn lock_session(&self) -> SessionGuard<'_> {
+        SessionGuard::acquire(&self.session, None)
+    }
+
+    /// 为烧录/擦除获取主连接 session 锁：等待前增加 flash_in_progress 让轮询任务让出，释放锁时减少
+    pub fn lock_session_for_flash(&self) -> SessionGuard<'_> {
+        SessionGuard::acquire(&self.session, Some(&self.flash_in_progress))
+    }
+
+    pub fn is_flash_in_progress(&self) -> bool {
+        self.flash_in_progress.load(Ordering::SeqCst) > 0
+    }
+
+    pub fn is_unified(&self) -> bool {
+        self.unified_session.load(Ordering::SeqCst)
+    }
+
+    /// RTT 使用的 session：统一模式下为主连接，否则为 RTT 独立连接
+    pub fn rtt_session_handle(&self) -> Arc<Mutex<Option<Session>>> {
+        if self.is_unified() {
+            Arc::clone(&self.session)
+        } else {
+            Arc::clone(&self.rtt_session)
         }
     }
+
+    /// 解析操作使用的内核编号：显式指定优先，否则使用当前活动内核
+    pub fn core_index(&self, requested: Option<usize>) -> usize {
+        requested.unwrap_or_else(|| *self.active_core.lock())
+    }
+
+    /// 指定内核操作当前使用的超时
+    pub fn core_timeout(&self, op: CoreOperation) -> std::time::Duration {
+        self.settings.lock().core_timeout(op)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -181,6 +736,10 @@ pub struct ConnectionInfo {
     pub core_type: String,
     pub chip_id: Option<u32>,          // 芯片DBGMCU_IDCODE
     pub target_idcode: Option<u32>,    // 新增：目标芯片的真实IDCODE（通过SWD读取）
+    #[serde(default)]
+    pub rtt_control_block: Option<u64>, // 已扫描到的 RTT 控制块地址（用于跳过重复扫描）
+    #[serde(default)]
+    pub reset_reason: Option<String>,   // 连接时读取的上次复位原因
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -190,6 +749,43 @@ pub struct DeviceSettings {
     pub connect_mode: ConnectMode,
     pub reset_mode: ResetMode,
     pub voltage: f32,
+    /// 暂停/复位等内核操作的统一超时覆盖 (毫秒)，未设置时各操作使用各自的默认值
+    #[serde(default)]
+    pub core_operation_timeout_ms: Option<u64>,
+}
+
+/// 内核操作超时允许范围 (毫秒)
+pub const CORE_OPERATION_TIMEOUT_RANGE_MS: std::ops::RangeInclusive<u64> = 10..=10_000;
+
+/// 使用超时的内核操作类别，未设置覆盖时各自保持原有的默认超时
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreOperation {
+    /// RTT 轮询时的短暂暂停
+    RttHalt,
+    /// 读内存/量产等流程中的暂停
+    Halt,
+    /// 复位并暂停、烧录失败后的恢复
+    Reset,
+}
+
+impl CoreOperation {
+    /// 未设置覆盖时的默认超时 (毫秒)
+    pub fn default_timeout_ms(self) -> u64 {
+        match self {
+            CoreOperation::RttHalt => 50,
+            CoreOperation::Halt => 100,
+            CoreOperation::Reset => 500,
+        }
+    }
+}
+
+impl DeviceSettings {
+    /// 指定操作使用的超时：有覆盖时统一使用覆盖值
+    pub fn core_timeout(&self, op: CoreOperation) -> std::time::Duration {
+        std::time::Duration::from_millis(
+            self.core_operation_timeout_ms.unwrap_or_else(|| op.default_timeout_ms()),
+        )
+    }
 }
 
 impl Default for DeviceSettings {
@@ -200,6 +796,7 @@ impl Default for DeviceSettings {
             connect_mode: ConnectMode::Normal,
             reset_mode: ResetMode::Software,
             voltage: 3.3,
+            core_operation_timeout_ms: None,
         }
     }
 }
@@ -221,3 +818,97 @@ pub enum ResetMode {
     Software,
     Hardware,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::AppConfig;
+    use std::time::Duration;
+
+    // 内核操作超时设置测试
+    #[test]
+    fn test_default_timeouts_per_operation() {
+        let settings = DeviceSettings::default();
+        assert_eq!(settings.core_operation_timeout_ms, None);
+        assert_eq!(settings.core_timeout(CoreOperation::RttHalt), Duration::from_millis(50));
+        assert_eq!(settings.core_timeout(CoreOperation::Halt), Duration::from_millis(100));
+        assert_eq!(settings.core_timeout(CoreOperation::Reset), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_override_applies_to_all_operations() {
+        let settings = DeviceSettings {
+            core_operation_timeout_ms: Some(2000),
+            ..DeviceSettings::default()
+        };
+        assert_eq!(settings.core_timeout(CoreOperation::RttHalt), Duration::from_millis(2000));
+        assert_eq!(settings.core_timeout(CoreOperation::Reset), Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_missing_field_uses_default() {
+        // 旧版本保存的设置没有该字段
+        let mut value = serde_json::to_value(DeviceSettings::default()).unwrap();
+        value.as_object_mut().unwrap().remove("core_operation_timeout_ms");
+        let settings: DeviceSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.core_operation_timeout_ms, None);
+    }
+
+    #[test]
+    fn test_state_applies_saved_timeout() {
+        let config = AppConfig {
+            core_operation_timeout_ms: Some(300),
+            ..AppConfig::default()
+        };
+        let state = AppState::from_config(&config);
+        assert_eq!(state.core_timeout(CoreOperation::Halt), Duration::from_millis(300));
+    }
+
+    // 内存监视任务代数测试
+    #[test]
+    fn test_restart_supersedes_previous_task() {
+        let state = MemoryWatchState::default();
+
+        let first = state.start();
+        assert!(state.is_current(first));
+
+        // 停止后立即重新启动：旧任务失效，退出时不影响新任务
+        state.set_running(false);
+        let second = state.start();
+        assert!(!state.is_current(first));
+        assert!(state.is_current(second));
+
+        state.finish(first);
+        assert!(state.is_running());
+
+        state.finish(second);
+        assert!(!state.is_running());
+    }
+
+    // RTT 下行命令历史测试
+    #[test]
+    fn test_line_endings_trimmed_and_repeats_skipped() {
+        let state = RttState::default();
+        state.record_write(0, b"help\r\n");
+        state.record_write(0, b"help\n");
+        state.record_write(0, b"\r\n");
+        state.record_write(0, b"status\n");
+        state.record_write(1, b"other\n");
+
+        assert_eq!(state.write_history(0), vec!["help", "status"]);
+        assert_eq!(state.write_history(1), vec!["other"]);
+        assert!(state.write_history(2).is_empty());
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let state = RttState::default();
+        for i in 0..RTT_WRITE_HISTORY_CAPACITY + 5 {
+            state.record_write(0, format!("cmd {}\n", i).as_bytes());
+        }
+
+        let history = state.write_history(0);
+        assert_eq!(history.len(), RTT_WRITE_HISTORY_CAPACITY);
+        assert_eq!(history[0], "cmd 5");
+    }
+}
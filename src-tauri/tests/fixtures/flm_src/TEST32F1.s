@@ -0,0 +1,65 @@
+@ Minimal CMSIS flash algorithm used by the pack scan golden test.
+@ The functions are stubs; only the symbol layout and FlashDevice matter.
+@
+@ Rebuild test_pack/Flash/TEST32F1.FLM with:
+@   llvm-mc --triple=thumbv7m-none-eabi -filetype=obj TEST32F1.s -o TEST32F1.o
+@   rust-lld -flavor gnu -T link.ld TEST32F1.o -o ../test_pack/Flash/TEST32F1.FLM -N -e 0 --build-id=none
+
+    .syntax unified
+    .thumb
+
+    .section PrgCode, "ax"
+    .global Init
+    .type Init, %function
+    .thumb_func
+Init:
+    movs r0, #0
+    bx lr
+    .global UnInit
+    .type UnInit, %function
+    .thumb_func
+UnInit:
+    movs r0, #0
+    bx lr
+    .global EraseChip
+    .type EraseChip, %function
+    .thumb_func
+EraseChip:
+    movs r0, #0
+    bx lr
+    .global EraseSector
+    .type EraseSector, %function
+    .thumb_func
+EraseSector:
+    movs r0, #0
+    bx lr
+    .global ProgramPage
+    .type ProgramPage, %function
+    .thumb_func
+ProgramPage:
+    movs r0, #0
+    bx lr
+
+    .section PrgData, "aw"
+    .word 0x12345678
+
+    .section DevDscr, "a"
+    .global FlashDevice
+    .type FlashDevice, %object
+FlashDevice:
+    .hword 0x0101
+    .ascii "TEST32F1 128kB Flash"
+    .space 128 - 20
+    .hword 1
+    .word 0x08000000
+    .word 0x00020000
+    .word 0x400
+    .word 0
+    .byte 0xFF
+    .space 3
+    .word 100
+    .word 3000
+    .word 0x1000, 0x00000000
+    .word 0x4000, 0x00008000
+    .word 0xFFFFFFFF, 0xFFFFFFFF
+    .size FlashDevice, . - FlashDevice
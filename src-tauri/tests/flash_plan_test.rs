@@ -0,0 +1,170 @@
+// 烧录前擦除范围检查测试
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::commands::flash::{evaluate_flash_plan, exclude_ram_ranges, FlashSector};
+
+    fn sectors() -> Vec<FlashSector> {
+        (0..64)
+            .map(|i| FlashSector { address: 0x0800_0000 + i * 0x400, size: 0x400 })
+            .collect()
+    }
+
+    #[test]
+    fn test_normal_image_needs_no_confirmation() {
+        let image = [0x0800_0000..0x0800_1000];
+
+        let report = evaluate_flash_plan(&image, &sectors(), None, false, false);
+
+        assert!(report.reasons.is_empty());
+        assert_eq!(report.erase_bytes, 0x1000);
+        assert_eq!(report.required_erase_bytes, 0x1000);
+    }
+
+    #[test]
+    fn test_oversized_custom_erase_range_requires_confirmation() {
+        let image = [0x0800_0000..0x0800_0800];
+
+        let report = evaluate_flash_plan(&image, &sectors(), Some(0x0800_0000..0x0801_0000), false, false);
+
+        assert_eq!(report.erase_bytes, 0x10000);
+        assert_eq!(report.reasons.len(), 1);
+    }
+
+    #[test]
+    fn test_image_outside_flash_is_reported() {
+        let image = [0x0800_F800..0x0801_0800];
+
+        let report = evaluate_flash_plan(&image, &sectors(), None, false, false);
+
+        assert_eq!(report.out_of_bounds_address, Some(0x0801_0000));
+        assert!(!report.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_chip_erase_of_small_image_requires_confirmation() {
+        let image = [0x0800_0000..0x0800_0100];
+
+        let report = evaluate_flash_plan(&image, &sectors(), None, true, false);
+
+        assert_eq!(report.erase_bytes, 0x10000);
+        assert_eq!(report.reasons.len(), 1);
+
+        // 固件接近整片大小时整片擦除不需要确认
+        let image = [0x0800_0000..0x0800_C000];
+        let report = evaluate_flash_plan(&image, &sectors(), None, true, false);
+        assert!(report.reasons.is_empty());
+    }
+
+    #[test]
+    fn test_ram_segments_are_not_checked() {
+        // ELF 中的 RAM 函数段不写入 Flash，不应被当作超出 Flash 范围
+        let image = [0x0800_0000..0x0800_0800, 0x2000_0000..0x2000_0100];
+        let ram = [0x2000_0000..0x2002_0000];
+
+        let flash_image = exclude_ram_ranges(&image, &ram);
+        assert_eq!(flash_image, vec![0x0800_0000..0x0800_0800]);
+
+        let report = evaluate_flash_plan(&flash_image, &sectors(), None, false, false);
+        assert!(report.reasons.is_empty());
+    }
+}
+
+// 经 flash_firmware_with 的完整烧录前检查流程（使用 probe-rs 模拟探针，不访问硬件）
+#[cfg(test)]
+mod flash_firmware_tests {
+    use ek_omniprobe_lib::commands::flash::{flash_firmware_with, EraseMode, EventSink, FlashOptions};
+    use ek_omniprobe_lib::error::AppError;
+    use ek_omniprobe_lib::state::AppState;
+    use probe_rs::config::{Registry, TargetSelector};
+    use probe_rs::probe::fake_probe::FakeProbe;
+    use probe_rs::Permissions;
+    use std::path::PathBuf;
+
+    #[derive(Clone)]
+    struct NoEvents;
+
+    impl EventSink for NoEvents {
+        fn emit_event<S: serde::Serialize + Clone>(&self, _event: &str, _payload: S) {}
+    }
+
+    fn connected_state() -> AppState {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack_targets.yaml");
+        let mut registry = Registry::from_builtin_families();
+        registry
+            .add_target_family_from_yaml(&std::fs::read_to_string(path).unwrap())
+            .unwrap();
+        let target = registry.get_target_by_name("TEST32F1CB").unwrap();
+        let session = FakeProbe::with_mocked_core()
+            .into_probe()
+            .attach(TargetSelector::Specified(target), Permissions::default())
+            .unwrap();
+
+        let state = AppState::new();
+        *state.session.lock() = Some(session);
+        state
+    }
+
+    fn small_firmware(name: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, [0x5Au8; 0x100]).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn options(file_path: String, erase_mode: EraseMode) -> FlashOptions {
+        FlashOptions {
+            file_path,
+            verify: false,
+            skip_erase: false,
+            reset_after: false,
+            erase_mode,
+            use_custom_address: None,
+            custom_flash_address: None,
+            custom_flash_size: None,
+            flash_algorithm: None,
+            preverify: false,
+            core_index: None,
+            algorithm_ram_region: None,
+            auto_algorithm_ram_region: false,
+            halt_after: false,
+            preserve_regions: Vec::new(),
+            verify_per_sector: false,
+            confirmed: false,
+            flash_retry_count: 0,
+            reset_before: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_chip_erase_of_small_image_is_gated() {
+        let state = connected_state();
+        let file = small_firmware("ek_flash_plan_chip_erase.bin");
+
+        let result = flash_firmware_with(options(file, EraseMode::ChipErase), &state, &NoEvents);
+
+        assert!(matches!(result, Err(AppError::RequiresConfirmation(_))));
+    }
+
+    #[test]
+    fn test_oversized_custom_range_is_gated() {
+        let state = connected_state();
+        let file = small_firmware("ek_flash_plan_custom_range.bin");
+        let options = FlashOptions {
+            use_custom_address: Some(true),
+            custom_flash_address: Some(0x0800_0000),
+            custom_flash_size: Some(0x10000),
+            ..options(file, EraseMode::SectorErase)
+        };
+
+        let result = flash_firmware_with(options, &state, &NoEvents);
+
+        let Err(AppError::RequiresConfirmation(details)) = result else {
+            panic!("自定义范围远大于固件时应要求确认");
+        };
+        let report: serde_json::Value = serde_json::from_str(&details).unwrap();
+        assert_eq!(report["erase_bytes"], 0x10000);
+    }
+}
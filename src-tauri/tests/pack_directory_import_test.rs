@@ -0,0 +1,30 @@
+// 目录批量导入 Pack 文件查找测试
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::commands::config::find_pack_files;
+    use std::fs;
+
+    #[test]
+    fn test_finds_pack_and_zip_files_sorted() {
+        let dir = std::env::temp_dir().join(format!("pack_dir_import_{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["b.pack", "A.PACK", "c.zip", "readme.txt", "nested/d.pack"] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let names: Vec<String> = find_pack_files(&dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["A.PACK", "b.pack", "c.zip"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_directory_is_error() {
+        assert!(find_pack_files(std::path::Path::new("/nonexistent/pack/dir")).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+// Pack 扫描端到端黄金文件测试
+// 固定的 PDSC + FLM 夹具生成的 YAML 应与 tests/fixtures/test_pack_targets.yaml 一致，
+// 防止扇区布局、load_address 调整和算法去重的回归
+// 生成逻辑有意变更时，使用 UPDATE_GOLDEN=1 cargo test 重新生成黄金文件
+// 生成时使用空的用户覆盖，结果不受本机应用配置影响
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::pack::target_gen;
+    use probe_rs::config::Registry;
+    use std::path::PathBuf;
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+    }
+
+    /// 去掉版本/时间戳等注释行
+    fn strip_comments(yaml: &str) -> String {
+        yaml.lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+
+    fn generate_fixture_yaml() -> String {
+        let pack_dir = fixtures_dir().join("test_pack");
+        let devices = target_gen::parse_devices_from_pack(&pack_dir, None).expect("解析夹具 PDSC 失败");
+
+        target_gen::generate_probe_rs_yaml_with_algo_at(
+            &devices,
+            "TEST32F1_DFP",
+            &pack_dir,
+            &target_gen::TargetGenOverrides::default(),
+            None,
+            "1970-01-01 00:00:00",
+        )
+        .expect("生成 YAML 失败")
+    }
+
+    #[test]
+    fn test_generated_yaml_matches_golden() {
+        let yaml = generate_fixture_yaml();
+        let golden_path = fixtures_dir().join("test_pack_targets.yaml");
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&golden_path, strip_comments(&yaml) + "\n").expect("写入黄金文件失败");
+        }
+
+        let golden = std::fs::read_to_string(&golden_path).expect("读取黄金文件失败");
+        assert_eq!(strip_comments(&yaml), strip_comments(&golden));
+    }
+
+    #[test]
+    fn test_generated_yaml_is_deterministic_and_registers() {
+        let first = generate_fixture_yaml();
+        assert_eq!(first, generate_fixture_yaml());
+
+        let mut registry = Registry::from_builtin_families();
+        registry.add_target_family_from_yaml(&first).expect("注册夹具目标失败");
+
+        let target = registry.get_target_by_name("TEST32F1CB").expect("未找到夹具芯片");
+        assert_eq!(target.flash_algorithms.len(), 1);
+        assert_eq!(target.flash_algorithms[0].name, "TEST32F1_128");
+    }
+
+    #[test]
+    fn test_generated_algorithms_are_consistent() {
+        let mut registry = Registry::from_builtin_families();
+        registry.add_target_family_from_yaml(&generate_fixture_yaml()).expect("注册夹具目标失败");
+
+        // 重叠的家族级算法只有一个默认
+        let family = registry
+            .families()
+            .iter()
+            .find(|f| f.name == "TEST32F1_DFP")
+            .expect("未找到夹具家族");
+        assert_eq!(family.flash_algorithms.iter().filter(|a| a.default).count(), 1);
+
+        // 小容量型号的扇区不超出其 Flash 范围
+        let target = registry.get_target_by_name("TEST32F1C8").expect("未找到夹具芯片");
+        let algo = &target.flash_algorithms[0];
+        let flash_size = algo.flash_properties.address_range.end - algo.flash_properties.address_range.start;
+        assert!(algo.flash_properties.sectors.iter().all(|s| s.address < flash_size));
+    }
+}
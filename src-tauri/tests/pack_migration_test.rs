@@ -0,0 +1,53 @@
+// 更换 Pack 目录时的迁移测试
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::pack::manager::{PackManager, PackMigrationMode};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn setup(tag: &str) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("pack_migrate_{}_{}", tag, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let from = root.join("old");
+        let to = root.join("new");
+        fs::create_dir_all(from.join("PackA/sub")).unwrap();
+        fs::write(from.join("PackA/sub/a.FLM"), b"flm").unwrap();
+        fs::create_dir_all(from.join("PackB")).unwrap();
+        fs::create_dir_all(to.join("PackB")).unwrap();
+        (from, to)
+    }
+
+    #[test]
+    fn test_copy_skips_existing_packs() {
+        let (from, to) = setup("copy");
+        let report = PackManager::migrate_packs(&from, &to, PackMigrationMode::Copy).unwrap();
+
+        assert_eq!(report.migrated, vec!["PackA"]);
+        assert_eq!(report.skipped, vec!["PackB"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(fs::read(to.join("PackA/sub/a.FLM")).unwrap(), b"flm");
+        assert!(from.join("PackA").exists());
+
+        fs::remove_dir_all(from.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_move_removes_source() {
+        let (from, to) = setup("move");
+        let report = PackManager::migrate_packs(&from, &to, PackMigrationMode::Move).unwrap();
+
+        assert_eq!(report.migrated, vec!["PackA"]);
+        assert!(!from.join("PackA").exists());
+        assert!(to.join("PackA/sub/a.FLM").exists());
+
+        fs::remove_dir_all(from.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_missing_source_is_empty_report() {
+        let root = std::env::temp_dir().join(format!("pack_migrate_none_{}", std::process::id()));
+        let report = PackManager::migrate_packs(&root.join("missing"), &root.join("new"), PackMigrationMode::Copy).unwrap();
+        assert!(report.migrated.is_empty() && report.skipped.is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+// 仅存在于 Pack 中的芯片测试
+// 防止界面/命令只认内置芯片列表，导致 Pack 导入的芯片无法搜索和连接
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::commands::config;
+    use ek_omniprobe_lib::pack::target_gen;
+    use probe_rs::config::Registry;
+
+    const PACK_ONLY_CHIP: &str = "PackOnlyM4Test";
+
+    fn registry_with_pack_only_chip() -> Registry {
+        let pdsc_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package>
+  <devices>
+    <family Dfamily="PackOnlyFamily">
+      <device Dname="PackOnlyM4Test">
+        <processor Dcore="Cortex-M4" Dfpu="1" Dmpu="0"/>
+        <memory id="IROM1" start="0x08000000" size="0x40000"/>
+        <memory id="IRAM1" start="0x20000000" size="0x10000"/>
+      </device>
+    </family>
+  </devices>
+</package>"#;
+
+        let devices = target_gen::parse_devices_from_pdsc(pdsc_content, None)
+            .expect("解析失败");
+
+        // 借用黄金测试夹具中的 FLM 文件，芯片本身只存在于上面的 PDSC 中
+        let pack_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack");
+
+        let overrides = target_gen::TargetGenOverrides::default();
+        let yaml = target_gen::generate_probe_rs_yaml_with_algo(&devices, "PackOnlyFamily", &pack_dir, &overrides, None)
+            .expect("生成 YAML 失败");
+
+        let mut registry = Registry::from_builtin_families();
+        config::register_pack_family(&mut registry, &yaml, "PackOnlyFamily").expect("注册 Pack 设备失败");
+        registry
+    }
+
+    #[test]
+    fn test_pack_only_chip_is_searchable() {
+        let registry = registry_with_pack_only_chip();
+
+        let results = config::search_chip_names(&registry, "packonly");
+        assert!(
+            results.iter().any(|c| c == PACK_ONLY_CHIP),
+            "搜索结果应包含 Pack 芯片，实际: {:?}",
+            results
+        );
+    }
+
+    #[test]
+    fn test_pack_only_chip_resolves_from_pack() {
+        let registry = registry_with_pack_only_chip();
+
+        let resolved = config::resolve_chip(&registry, PACK_ONLY_CHIP).expect("Pack 芯片应可解析");
+        assert_eq!(resolved.name, PACK_ONLY_CHIP);
+        assert_eq!(resolved.source, "pack");
+
+        assert!(config::resolve_chip(&Registry::from_builtin_families(), PACK_ONLY_CHIP).is_err());
+    }
+
+    #[test]
+    fn test_builtin_generated_family_is_not_pack() {
+        // probe-rs 内置目标多由 Pack 生成并带 generated_from_pack 标记，但不是用户导入的 Pack
+        let registry = Registry::from_builtin_families();
+        let chip = registry
+            .families()
+            .iter()
+            .filter(|family| family.generated_from_pack)
+            .find_map(|family| family.variants().first().map(|v| v.name.clone()));
+
+        if let Some(chip) = chip {
+            let resolved = config::resolve_chip(&registry, &chip).expect("内置芯片应可解析");
+            assert_ne!(resolved.source, "pack", "{} 不应被识别为 Pack 芯片", chip);
+            assert_eq!(config::pack_for_chip(&registry, &chip), None);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// session 锁守卫测试
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::state::AppState;
+
+    #[test]
+    fn test_flash_flag_is_set_while_guard_is_held() {
+        let state = AppState::new();
+
+        {
+            let guard = state.lock_session_for_flash();
+            assert!(guard.is_none());
+            assert!(state.is_flash_in_progress());
+        }
+
+        assert!(!state.is_flash_in_progress());
+    }
+
+    #[test]
+    fn test_flash_flag_survives_overlapping_guards() {
+        use std::sync::atomic::Ordering;
+
+        let state = AppState::new();
+        let first = state.lock_session_for_flash();
+
+        std::thread::scope(|scope| {
+            let waiter = scope.spawn(|| {
+                // 第一个守卫释放后，第二个守卫持有期间标志仍然有效
+                let _second = state.lock_session_for_flash();
+                assert!(state.is_flash_in_progress());
+            });
+            // 等待第二个守卫开始等待锁后再释放第一个
+            while state.flash_in_progress.load(Ordering::SeqCst) < 2 {
+                std::thread::yield_now();
+            }
+            drop(first);
+            waiter.join().unwrap();
+        });
+
+        assert!(!state.is_flash_in_progress());
+    }
+
+    #[test]
+    fn test_session_can_be_relocked_after_release() {
+        let state = AppState::new();
+
+        drop(state.lock_session());
+        let guard = state.lock_session();
+        assert!(guard.is_none());
+    }
+}
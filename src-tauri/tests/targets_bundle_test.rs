@@ -0,0 +1,51 @@
+// 目标定义合并导出测试
+
+#[cfg(test)]
+mod tests {
+    use ek_omniprobe_lib::pack::target_gen::{bundle_target_families, strip_generator_markers, write_target_bundle};
+    use probe_rs::config::Registry;
+    use std::path::PathBuf;
+
+    fn fixture_yaml() -> String {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("test_pack_targets.yaml");
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_markers_stripped() {
+        let yaml = "# EK-OmniProbe Pack Scanner Version: 2.0.0\n# PDSC Modified: 1700000000\n# Generated at: now\n\nname: Test\n";
+        assert_eq!(strip_generator_markers(yaml), "name: Test\n");
+    }
+
+    #[test]
+    fn test_duplicate_families_skipped_and_documents_valid() {
+        let yaml = fixture_yaml();
+        let documents = vec![
+            ("PackA".to_string(), yaml.clone()),
+            ("PackB".to_string(), yaml),
+            ("Broken".to_string(), "variants: [".to_string()),
+        ];
+
+        let (bundle, errors) = bundle_target_families(&documents);
+        assert_eq!(bundle.families.len(), 1);
+        assert_eq!(bundle.duplicates, bundle.families);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "Broken");
+
+        // 导出的每个文件原样可被 probe-rs 加载
+        let dir = std::env::temp_dir().join(format!("targets_bundle_{}", std::process::id()));
+        let files = write_target_bundle(&bundle, &dir).unwrap();
+        assert_eq!(files.len(), 1);
+
+        let mut registry = Registry::from_builtin_families();
+        for file in &files {
+            let content = std::fs::read_to_string(file).unwrap();
+            assert!(!content.contains("Pack Scanner Version"));
+            registry.add_target_family_from_yaml(&content).unwrap();
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
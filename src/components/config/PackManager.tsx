@@ -19,13 +19,14 @@ import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { listen } from "@tauri-apps/api/event";
 import {
   importPack,
+  importPacksFromDirectory,
   listImportedPacks,
   deletePack,
   getPackScanReport,
   getPacksDirectory,
   setCustomPacksDirectory,
 } from "@/lib/tauri";
-import type { PackInfo, PackScanReport, AlgorithmStat, DeviceScanResult } from "@/lib/types";
+import type { PackInfo, PackBatchProgress, PackImportProgress, PackScanReport, AlgorithmStat, DeviceScanResult } from "@/lib/types";
 import { useLogStore } from "@/stores/logStore";
 import { Package, Upload, Trash2, ChevronDown, ChevronRight, FileText, AlertCircle, CheckCircle, FolderOpen, Settings } from "lucide-react";
 import { cn } from "@/lib/utils";
@@ -54,6 +55,10 @@ function getPhaseLabel(phase: string): string {
   return labels[phase] || phase;
 }
 
+function formatMegabytes(bytes: number): string {
+  return (bytes / 1024 / 1024).toFixed(1);
+}
+
 export function PackManager() {
   const [packs, setPacks] = useState<PackInfo[]>([]);
   const [importing, setImporting] = useState(false);
@@ -61,6 +66,8 @@ export function PackManager() {
   const [isOpen, setIsOpen] = useState(false);
   const [isDragging, setIsDragging] = useState(false);
   const [scanProgress, setScanProgress] = useState<PackScanProgress | null>(null);
+  const [batchProgress, setBatchProgress] = useState<PackBatchProgress | null>(null);
+  const [extractProgress, setExtractProgress] = useState<PackImportProgress | null>(null);
   const [reportDialogOpen, setReportDialogOpen] = useState(false);
   const [selectedPackReport, setSelectedPackReport] = useState<PackScanReport | null>(null);
   const [packsDirectory, setPacksDirectory] = useState<string>("");
@@ -90,9 +97,20 @@ export function PackManager() {
       });
 
       if (selected) {
-        await setCustomPacksDirectory(selected as string);
+        const migrate = window.confirm("是否将现有Pack复制到新目录？")
+          ? "copy"
+          : undefined;
+        const change = await setCustomPacksDirectory(selected as string, migrate);
         await loadPacksDirectory();
-        addLog("success", `Pack目录已更改为: ${selected}`);
+        addLog("success", `Pack目录已更改为: ${change.new_dir}`);
+        if (change.migration) {
+          const { migrated, skipped, failed } = change.migration;
+          addLog(
+            failed.length > 0 ? "warn" : "info",
+            `Pack迁移: ${migrated.length} 个已复制，${skipped.length} 个已存在跳过，${failed.length} 个失败`
+          );
+          failed.forEach((f) => addLog("error", `迁移 ${f.pack} 失败: ${f.error}`));
+        }
         addLog("info", "请重启应用以使更改生效");
       }
     } catch (error) {
@@ -116,9 +134,22 @@ export function PackManager() {
     loadPacks();
   }, []);
 
+  // Listen for pack extraction progress (before the device scan starts)
+  useEffect(() => {
+    const unlisten = listen<PackImportProgress>("pack-import-progress", (event) => {
+      setExtractProgress(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for pack scan progress events
   useEffect(() => {
     const unlisten = listen<PackScanProgress>("pack-scan-progress", (event) => {
+      // 扫描开始即解压已结束
+      setExtractProgress(null);
       setScanProgress(event.payload);
 
       // 当扫描完成时，清除进度显示
@@ -134,6 +165,17 @@ export function PackManager() {
     };
   }, []);
 
+  // Listen for directory batch import progress
+  useEffect(() => {
+    const unlisten = listen<PackBatchProgress>("pack-import-batch-progress", (event) => {
+      setBatchProgress(event.payload.current < event.payload.total ? event.payload : null);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for file drag-drop events
   useEffect(() => {
     const webview = getCurrentWebviewWindow();
@@ -205,6 +247,7 @@ export function PackManager() {
     }
 
     setImporting(false);
+    setExtractProgress(null);
   }, [addLog]);
 
   // Import Pack file via dialog (supports multiple files)
@@ -224,6 +267,41 @@ export function PackManager() {
     }
   };
 
+  // Import all Pack files in a directory
+  const handleImportDirectory = async () => {
+    try {
+      const selected = await open({
+        directory: true,
+        multiple: false,
+        title: "选择包含Pack文件的目录",
+      });
+      if (!selected) return;
+
+      setImporting(true);
+      addLog("info", `开始从目录导入Pack: ${selected}`);
+      const results = await importPacksFromDirectory(selected as string);
+      for (const result of results) {
+        if (result.success) {
+          addLog("success", `成功导入: ${result.pack_name} (${result.device_count} 个设备)`);
+        } else {
+          addLog("error", `导入失败 ${result.file}: ${result.error}`);
+        }
+      }
+      const successCount = results.filter((r) => r.success).length;
+      addLog("info", `导入完成: ${successCount} 成功, ${results.length - successCount} 失败`);
+      // 注册设备失败的 Pack 也已导入，同样需要刷新列表
+      if (results.some((r) => r.pack_name)) {
+        await loadPacks();
+      }
+    } catch (error) {
+      addLog("error", `从目录导入Pack失败: ${error}`);
+    } finally {
+      setImporting(false);
+      setExtractProgress(null);
+      setBatchProgress(null);
+    }
+  };
+
   // Delete Pack
   const handleDelete = async (packName: string) => {
     console.log("handleDelete called with:", packName);
@@ -286,6 +364,19 @@ export function PackManager() {
                 >
                   <Settings className="h-3 w-3" />
                 </Button>
+                <Button
+                  size="sm"
+                  variant="ghost"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    handleImportDirectory();
+                  }}
+                  disabled={importing || loading}
+                  className="gap-1 h-7"
+                  title="从目录导入全部Pack"
+                >
+                  <FolderOpen className="h-3 w-3" />
+                </Button>
                 <Button
                   size="sm"
                   onClick={(e) => {
@@ -352,6 +443,44 @@ export function PackManager() {
               </div>
             )}
 
+            {/* Directory batch import progress */}
+            {batchProgress && (
+              <div className="border rounded-lg p-3 space-y-2 bg-muted/30">
+                <div className="flex items-center justify-between text-sm">
+                  <span className="font-medium">批量导入</span>
+                  <span className="text-xs text-muted-foreground">
+                    {batchProgress.current + 1}/{batchProgress.total}
+                  </span>
+                </div>
+                <Progress value={batchProgress.progress * 100} className="h-2" />
+                <div className="text-xs text-muted-foreground truncate">当前: {batchProgress.file}</div>
+              </div>
+            )}
+
+            {/* Pack extraction progress */}
+            {extractProgress && (
+              <div className="border rounded-lg p-3 space-y-2 bg-muted/30">
+                <div className="flex items-center justify-between text-sm">
+                  <span className="font-medium">解压 {extractProgress.pack_file}</span>
+                  <span className="text-xs text-muted-foreground">
+                    {Math.round(extractProgress.progress * 100)}%
+                  </span>
+                </div>
+                <Progress value={extractProgress.progress * 100} className="h-2" />
+                <div className="text-xs text-muted-foreground space-y-1">
+                  <div className="flex items-center justify-between">
+                    <span>
+                      {formatMegabytes(extractProgress.bytes_extracted)} / {formatMegabytes(extractProgress.total_bytes)} MB
+                    </span>
+                    <span>{extractProgress.files_extracted}/{extractProgress.total_files} 个文件</span>
+                  </div>
+                  {extractProgress.current_file && (
+                    <div className="truncate">当前: {extractProgress.current_file}</div>
+                  )}
+                </div>
+              </div>
+            )}
+
             {/* Pack scan progress */}
             {scanProgress && (
               <div className="border rounded-lg p-3 space-y-2 bg-muted/30">
@@ -12,6 +12,7 @@ import { Label } from "@/components/ui/label";
 import { Input } from "@/components/ui/input";
 import { RadioGroup, RadioGroupItem } from "@/components/ui/radio-group";
 import { AlertTriangle } from "lucide-react";
+import { parseHexAddress } from "@/lib/tauri";
 
 interface EraseDialogProps {
   open: boolean;
@@ -24,20 +25,20 @@ export function EraseDialog({ open, onOpenChange, onConfirm }: EraseDialogProps)
   const [address, setAddress] = useState("0x08000000");
   const [size, setSize] = useState("0x10000");
 
-  const handleConfirm = () => {
+  const handleConfirm = async () => {
     if (eraseMode === "full") {
       onConfirm("full");
     } else {
       try {
-        const addr = parseInt(address, 16);
-        const sz = parseInt(size, 16);
-        if (isNaN(addr) || isNaN(sz) || sz <= 0) {
-          alert("请输入有效的地址和大小（十六进制格式）");
+        const addr = await parseHexAddress(address);
+        const sz = await parseHexAddress(size);
+        if (sz <= 0) {
+          alert("擦除大小必须大于 0");
           return;
         }
         onConfirm("custom", addr, sz);
       } catch (error) {
-        alert("地址或大小格式错误");
+        alert(`地址或大小格式错误: ${error}`);
         return;
       }
     }
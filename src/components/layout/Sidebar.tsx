@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { RefreshCw, Plug, Unplug, ChevronDown, ChevronRight } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Card, CardHeader, CardTitle, CardContent } from "@/components/ui/card";
@@ -15,12 +16,14 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { Input } from "@/components/ui/input";
+import { Switch } from "@/components/ui/switch";
 import { useProbeStore } from "@/stores/probeStore";
 import { useChipStore } from "@/stores/chipStore";
 import { useLogStore } from "@/stores/logStore";
 import { listProbes, connectTarget, disconnect, searchChips, getChipInfo, getConnectionStatus } from "@/lib/tauri";
 import { PackManager } from "@/components/config/PackManager";
 import { cn } from "@/lib/utils";
+import type { ConnectionLostEvent } from "@/lib/types";
 
 export function Sidebar() {
   const {
@@ -102,6 +105,18 @@ export function Sidebar() {
     refreshProbes();
   }, [refreshProbes]);
 
+  // 临时重连（外部加载算法、切换算法、时钟扫描等）后无法恢复原连接
+  useEffect(() => {
+    const unlisten = listen<ConnectionLostEvent>("connection-lost", (event) => {
+      setConnected(false);
+      addLog("error", event.payload.message);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setConnected, addLog]);
+
   const handleConnect = async () => {
     if (!selectedProbe || !selectedChip) {
       addLog("error", "请先选择探针和目标芯片");
@@ -114,10 +129,14 @@ export function Sidebar() {
 
       const targetInfo = await connectTarget({
         probe_identifier: selectedProbe.identifier,
+        probe_index: selectedProbe.probe_index,
+        usb_location: selectedProbe.serial_conflict ? selectedProbe.usb_location : null,
         target: selectedChip,
         interface_type: settings.interfaceType === "SWD" ? "Swd" : "Jtag",
         clock_speed: settings.clockSpeed,
         connect_mode: settings.connectMode === "Normal" ? "Normal" : "UnderReset",
+        force_dap_version: settings.dapVersion === "auto" ? null : settings.dapVersion,
+        read_reset_reason: settings.readResetReason,
       });
 
       // 从后端获取完整的连接信息
@@ -236,6 +255,9 @@ export function Sidebar() {
                 <SelectItem key={probe.probe_id} value={probe.probe_id}>
                   <div className="flex items-center justify-between gap-2 w-full">
                     <span className="truncate">{probe.identifier}</span>
+                    {probe.serial_conflict && probe.usb_location && (
+                      <span className="text-xs text-muted-foreground shrink-0">USB {probe.usb_location}</span>
+                    )}
                     {probe.dap_version && (
                       <span className="text-xs px-1.5 py-0.5 rounded bg-primary/10 text-primary font-medium shrink-0">
                         {probe.dap_version}
@@ -246,6 +268,11 @@ export function Sidebar() {
               ))}
             </SelectContent>
           </Select>
+          {selectedProbe?.serial_conflict && (
+            <p className="text-xs text-yellow-600 dark:text-yellow-400 mt-2">
+              该探针与其他探针序列号重复，将按 USB 位置 {selectedProbe.usb_location ?? "未知"} 连接
+            </p>
+          )}
         </CardContent>
       </Card>
 
@@ -360,6 +387,28 @@ export function Sidebar() {
             </Select>
           </div>
 
+          {selectedProbe?.dap_version?.includes("v1+v2") && (
+            <div>
+              <label className="text-xs text-muted-foreground">DAP 传输方式</label>
+              <Select
+                value={settings.dapVersion}
+                onValueChange={(value: "auto" | "v1" | "v2") =>
+                  setSettings({ dapVersion: value })
+                }
+                disabled={connected}
+              >
+                <SelectTrigger>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="auto">自动</SelectItem>
+                  <SelectItem value="v1">DAPv1 (HID)</SelectItem>
+                  <SelectItem value="v2">DAPv2 (WinUSB)</SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+          )}
+
           <div>
             <label className="text-xs text-muted-foreground">复位方式</label>
             <Select
@@ -378,6 +427,18 @@ export function Sidebar() {
               </SelectContent>
             </Select>
           </div>
+
+          <div className="flex items-center justify-between">
+            <label className="text-xs text-muted-foreground">
+              连接时读取复位原因
+              {settings.connectMode === "UnderReset" && "（复位下连接时不读取）"}
+            </label>
+            <Switch
+              checked={settings.readResetReason}
+              onCheckedChange={(checked) => setSettings({ readResetReason: checked })}
+              disabled={connected}
+            />
+          </div>
         </CardContent>
           </CollapsibleContent>
         </Card>
@@ -516,6 +577,12 @@ export function Sidebar() {
                 </span>
               </div>
             )}
+            {connectionInfo.reset_reason && (
+              <div className="flex justify-between">
+                <span className="text-muted-foreground">复位原因:</span>
+                <span>{connectionInfo.reset_reason}</span>
+              </div>
+            )}
           </CardContent>
         </Card>
       )}
@@ -9,6 +9,7 @@ import { useProbeStore } from "@/stores/probeStore";
 import { useChipStore } from "@/stores/chipStore";
 import { useFlashStore } from "@/stores/flashStore";
 import { formatBytes, formatHex } from "@/lib/utils";
+import { parseHexAddress } from "@/lib/tauri";
 
 export function FlashContent() {
   const { connected, targetInfo } = useProbeStore();
@@ -249,11 +250,7 @@ export function FlashContent() {
                         type="text"
                         value={`0x${customFlashAddress.toString(16).toUpperCase().padStart(8, '0')}`}
                         onChange={(e) => {
-                          const value = e.target.value.replace(/^0x/i, '');
-                          const parsed = parseInt(value, 16);
-                          if (!isNaN(parsed)) {
-                            setCustomFlashAddress(parsed);
-                          }
+                          parseHexAddress(e.target.value).then(setCustomFlashAddress).catch(() => {});
                         }}
                         className="w-full px-2 py-1 text-xs font-mono bg-background border border-border rounded"
                         placeholder="0x08000000"
@@ -268,11 +265,7 @@ export function FlashContent() {
                         type="text"
                         value={`0x${customFlashSize.toString(16).toUpperCase()}`}
                         onChange={(e) => {
-                          const value = e.target.value.replace(/^0x/i, '');
-                          const parsed = parseInt(value, 16);
-                          if (!isNaN(parsed)) {
-                            setCustomFlashSize(parsed);
-                          }
+                          parseHexAddress(e.target.value).then(setCustomFlashSize).catch(() => {});
                         }}
                         className="w-full px-2 py-1 text-xs font-mono bg-background border border-border rounded"
                         placeholder="0x100000"
@@ -17,7 +17,7 @@ import { FlashToolbar } from "./FlashToolbar";
 import { FlashContent } from "./FlashContent";
 
 // Firmware file extensions
-const FIRMWARE_EXTENSIONS = [".hex", ".bin", ".elf", ".axf", ".out", ".ihex"];
+const FIRMWARE_EXTENSIONS = [".hex", ".bin", ".elf", ".axf", ".out", ".ihex", ".s19", ".srec", ".s37", ".mot"];
 
 // Check if file is a firmware file
 function isFirmwareFile(path: string): boolean {
@@ -77,7 +77,7 @@ export function FlashMode() {
           <div className="flex flex-col items-center gap-3 p-6 rounded-lg border-2 border-dashed border-primary bg-primary/5">
             <FileCode className="h-12 w-12 text-primary animate-bounce" />
             <p className="text-lg font-medium text-primary">释放以选择固件文件</p>
-            <p className="text-xs text-muted-foreground">支持 .hex, .bin, .elf, .axf 格式</p>
+            <p className="text-xs text-muted-foreground">支持 .hex, .bin, .elf, .axf, .s19 格式</p>
           </div>
         </div>
       )}
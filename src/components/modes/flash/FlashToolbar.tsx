@@ -29,17 +29,34 @@ import { useChipStore } from "@/stores/chipStore";
 import { useFlashStore } from "@/stores/flashStore";
 import { useLogStore } from "@/stores/logStore";
 import { open, save } from "@tauri-apps/plugin-dialog";
-import { flashFirmware, eraseChip, eraseSector, verifyFirmware, readFlash, getFirmwareInfo } from "@/lib/tauri";
+import { flashFirmware, eraseChip, eraseSector, verifyFirmware, readFlash, getFirmwareInfo, getDefaultFlashBase } from "@/lib/tauri";
 import { listen } from "@tauri-apps/api/event";
-import type { FlashProgressEvent, EraseMode } from "@/lib/types";
+import type { FlashProgressEvent, EraseMode, EraseResult, FlashResult, FlashOptions, FlashPlanReport } from "@/lib/types";
 import { EraseDialog } from "@/components/dialogs/EraseDialog";
 
 function ToolbarSeparator() {
   return <div className="w-px h-6 bg-border mx-1" />;
 }
 
+function formatEraseResult(result: EraseResult) {
+  const size = result.total_bytes >= 1024 ? `${(result.total_bytes / 1024).toFixed(0)} KB` : `${result.total_bytes} 字节`;
+  return `已擦除 ${size}，共 ${result.regions_erased.length} 个区域，耗时 ${(result.elapsed_ms / 1000).toFixed(1)} s`;
+}
+
+function formatFlashResult(result: FlashResult) {
+  const kb = (bytes: number) => `${(bytes / 1024).toFixed(1)} KB`;
+  let text = `编程 ${kb(result.bytes_programmed)}，擦除 ${kb(result.bytes_erased)}`;
+  if (result.sectors_skipped > 0) {
+    text += `，跳过 ${result.sectors_skipped} 个未变化扇区`;
+  }
+  if (result.verified) {
+    text += "，校验通过";
+  }
+  return `${text}，耗时 ${(result.elapsed_ms / 1000).toFixed(1)} s`;
+}
+
 export function FlashToolbar() {
-  const { connected } = useProbeStore();
+  const { connected, connectionInfo } = useProbeStore();
   const { selectedFlashAlgorithm } = useChipStore();
   const {
     firmwarePath,
@@ -72,7 +89,7 @@ export function FlashToolbar() {
     const file = await open({
       multiple: false,
       filters: [
-        { name: "固件文件", extensions: ["hex", "bin", "elf", "axf", "out", "ihex"] },
+        { name: "固件文件", extensions: ["hex", "bin", "elf", "axf", "out", "ihex", "s19", "srec", "s37", "mot"] },
         { name: "所有文件", extensions: ["*"] },
       ],
     });
@@ -135,16 +152,31 @@ export function FlashToolbar() {
       setProgress(0, "init", "开始烧录");
       addLog("info", `开始烧录: ${firmwarePath}`);
 
-      await flashFirmware({
+      const options: FlashOptions = {
         file_path: firmwarePath,
         verify: verifyAfterFlash,
         skip_erase: false,
         reset_after: resetAfterFlash,
         erase_mode: eraseMode,
         flash_algorithm: selectedFlashAlgorithm || undefined,
-      });
+      };
+      let result: FlashResult;
+      try {
+        result = await flashFirmware(options);
+      } catch (error) {
+        // 烧录前检查发现风险（擦除范围远大于固件、超出 Flash 范围）时由用户确认后重试
+        const message = String(error);
+        const prefix = "requires_confirmation: ";
+        if (!message.startsWith(prefix)) throw error;
+        const report: FlashPlanReport = JSON.parse(message.slice(prefix.length));
+        if (!confirm(`烧录前检查发现以下风险：\n\n${report.reasons.join("\n")}\n\n确定继续烧录吗？`)) {
+          addLog("info", "已取消烧录");
+          return;
+        }
+        result = await flashFirmware({ ...options, confirmed: true });
+      }
 
-      addLog("success", "烧录成功");
+      addLog("success", `烧录成功: ${formatFlashResult(result)}`);
     } catch (error) {
       addLog("error", `烧录失败: ${error}`);
     } finally {
@@ -166,12 +198,12 @@ export function FlashToolbar() {
 
       if (mode === "full") {
         addLog("info", "开始全片擦除");
-        await eraseChip();
-        addLog("success", "全片擦除完成");
+        const result = await eraseChip();
+        addLog("success", `全片擦除完成: ${formatEraseResult(result)}`);
       } else if (mode === "custom" && address !== undefined && size !== undefined) {
         addLog("info", `开始擦除 0x${address.toString(16).toUpperCase()} - 0x${(address + size).toString(16).toUpperCase()} (${size} 字节)`);
-        await eraseSector(address, size);
-        addLog("success", "自定义范围擦除完成");
+        const result = await eraseSector(address, size);
+        addLog("success", `自定义范围擦除完成: ${formatEraseResult(result)}`);
       }
     } catch (error) {
       addLog("error", `擦除失败: ${error}`);
@@ -221,7 +253,9 @@ export function FlashToolbar() {
       try {
         setFlashing(true);
         addLog("info", "开始读取Flash");
-        const data = await readFlash(0x08000000, 0x10000); // 64KB
+        // 按目标定义获取 Flash 起始地址（nRF 为 0x0，RP2040 为 0x10000000 等）
+        const flashBase = connectionInfo ? await getDefaultFlashBase(connectionInfo.target_name) : 0x08000000;
+        const data = await readFlash(flashBase, 0x10000); // 64KB
         addLog("success", `已读取 ${data.length} 字节到 ${path}`);
       } catch (error) {
         addLog("error", `读取失败: ${error}`);
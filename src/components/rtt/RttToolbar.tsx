@@ -93,10 +93,14 @@ export function RttToolbar() {
 
       await connectRtt({
         probe_identifier: selectedProbe.identifier,
+        probe_index: selectedProbe.probe_index,
+        usb_location: selectedProbe.serial_conflict ? selectedProbe.usb_location : null,
         target: chipName,
         interface_type: settings.interfaceType === "SWD" ? "Swd" : "Jtag",
         clock_speed: settings.clockSpeed,
         connect_mode: settings.connectMode === "Normal" ? "Normal" : "UnderReset",
+        force_dap_version: settings.dapVersion === "auto" ? null : settings.dapVersion,
+        read_reset_reason: settings.readResetReason,
       });
 
       setRttConnected(true);
@@ -21,7 +21,7 @@ import { useSerialStore } from "@/stores/serialStore";
 import { useSerialStats } from "@/hooks/useSerialEvents";
 import { useLogStore } from "@/stores/logStore";
 import { listSerialPorts, connectSerial, disconnectSerial, startSerial, stopSerial } from "@/lib/tauri";
-import { COMMON_BAUD_RATES, type SerialPortInfo, type DataSourceType } from "@/lib/serialTypes";
+import { COMMON_BAUD_RATES, formatSerialOpenError, type SerialPortInfo, type DataSourceType } from "@/lib/serialTypes";
 
 export function SerialSidebar() {
   const {
@@ -99,8 +99,9 @@ export function SerialSidebar() {
 
       addLog("success", `串口连接成功`);
     } catch (error) {
-      addLog("error", `连接失败: ${error}`);
-      setError(String(error));
+      const message = formatSerialOpenError(error);
+      addLog("error", `连接失败: ${message}`);
+      setError(message);
     } finally {
       setConnecting(false);
     }
@@ -41,6 +41,21 @@ export interface TcpSerialConfig {
  */
 export type SerialConfig = LocalSerialConfig | TcpSerialConfig;
 
+// Structured serial open error returned by connect_serial
+export interface SerialOpenError {
+  category: "permission_denied" | "busy" | "not_found" | "other";
+  message: string;
+  suggestion: string | null;
+}
+
+export function formatSerialOpenError(error: unknown): string {
+  if (error && typeof error === "object" && "message" in error) {
+    const e = error as SerialOpenError;
+    return e.suggestion ? `${e.message}（${e.suggestion}）` : e.message;
+  }
+  return String(error);
+}
+
 /**
  * Serial connection statistics
  */
@@ -59,6 +74,14 @@ export interface SerialStatus {
   stats: SerialStats;
 }
 
+/**
+ * Result of resuming a paused serial console
+ */
+export interface SerialResumeResult {
+  flushed: number;
+  dropped: number;
+}
+
 /**
  * Serial data event from backend
  */
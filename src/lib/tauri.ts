@@ -3,20 +3,53 @@ import type {
   ProbeInfo,
   ConnectOptions,
   TargetInfo,
+  ClockRecommendation,
+  ProbeBenchmarkOptions,
+  ProbeBenchmarkResult,
+  ProtectionStatus,
+  LowPowerDebugStatus,
+  LowPowerDebugOptions,
+  ConnectionCapabilities,
+  OnchipMemorySizes,
   ConnectionStatus,
   ChipInfo,
+  ChipInfoLookup,
+  Memory64Read,
   FlashOptions,
+  FlashResult,
+  EffectiveFlashConfig,
+  ProductionCheckOptions,
+  ProductionFlashResult,
+  FlashAlgorithmTestResult,
   FirmwareFileInfo,
   PackInfo,
+  PackImportResult,
+  ChipProvider,
+  ReloadTargetsResult,
+  ExportTargetsResult,
   ProjectConfig,
   RttConfig,
   RttStartOptions,
   RttStatusEvent,
+  RttViewConfig,
+  RttPatternMatch,
+  SwoStartOptions,
+  ItmPortActivity,
   RegisterValue,
   FlashAlgorithmInfo,
   EraseMode,
+  EraseResult,
   UsbPermissionStatus,
   UsbDeviceInfo,
+  PackMigrationMode,
+  PacksDirectoryChange,
+  ControlServerStatus,
+  CycleCounter,
+  FirmwareMetadata,
+  SymbolType,
+  SymbolValue,
+  BootTimeResult,
+  DebugRegisterDump,
 } from "./types";
 
 // 探针命令
@@ -28,14 +61,65 @@ export async function connectTarget(options: ConnectOptions): Promise<TargetInfo
   return await invoke<TargetInfo>("connect_target", { options });
 }
 
+export async function quickConnect(): Promise<TargetInfo> {
+  return await invoke<TargetInfo>("quick_connect");
+}
+
 export async function disconnect(): Promise<void> {
   return await invoke("disconnect");
 }
 
+export async function resetTarget(halt?: boolean, coreIndex?: number): Promise<void> {
+  return await invoke("reset_target", { halt: halt ?? null, coreIndex: coreIndex ?? null });
+}
+
+export async function readDebugRegisters(coreIndex?: number): Promise<DebugRegisterDump> {
+  return await invoke<DebugRegisterDump>("read_debug_registers", { coreIndex: coreIndex ?? null });
+}
+
+export async function getCoreOperationTimeout(): Promise<number | null> {
+  return await invoke<number | null>("get_core_operation_timeout");
+}
+
+export async function setCoreOperationTimeout(timeoutMs: number | null): Promise<void> {
+  return await invoke("set_core_operation_timeout", { timeoutMs });
+}
+
 export async function getConnectionStatus(): Promise<ConnectionStatus> {
   return await invoke<ConnectionStatus>("get_connection_status");
 }
 
+export async function getClockRecommendation(): Promise<ClockRecommendation | null> {
+  return await invoke<ClockRecommendation | null>("get_clock_recommendation");
+}
+
+export async function getProtectionStatus(): Promise<ProtectionStatus> {
+  return await invoke<ProtectionStatus>("get_protection_status");
+}
+
+export async function getDebugInLowPower(): Promise<LowPowerDebugStatus> {
+  return await invoke<LowPowerDebugStatus>("get_debug_in_low_power");
+}
+
+export async function setDebugInLowPower(options: LowPowerDebugOptions): Promise<LowPowerDebugStatus> {
+  return await invoke<LowPowerDebugStatus>("set_debug_in_low_power", { options });
+}
+
+export async function getCapabilities(): Promise<ConnectionCapabilities> {
+  return await invoke<ConnectionCapabilities>("get_capabilities");
+}
+
+export async function readOnchipMemorySizes(
+  flashSizeAddress?: number,
+  ramSizeAddress?: number
+): Promise<OnchipMemorySizes> {
+  return await invoke<OnchipMemorySizes>("read_onchip_memory_sizes", { flashSizeAddress, ramSizeAddress });
+}
+
+export async function benchmarkProbe(options: ProbeBenchmarkOptions): Promise<ProbeBenchmarkResult> {
+  return await invoke<ProbeBenchmarkResult>("benchmark_probe", { options });
+}
+
 // RTT 独立连接命令
 export async function connectRtt(options: ConnectOptions): Promise<TargetInfo> {
   return await invoke<TargetInfo>("connect_rtt", { options });
@@ -50,33 +134,130 @@ export async function getRttConnectionStatus(): Promise<ConnectionStatus> {
 }
 
 // Flash命令
-export async function flashFirmware(options: FlashOptions): Promise<void> {
-  return await invoke("flash_firmware", { options });
+export async function flashFirmware(options: FlashOptions): Promise<FlashResult> {
+  return await invoke<FlashResult>("flash_firmware", { options });
 }
 
-export async function eraseChip(eraseMode?: EraseMode): Promise<void> {
-  return await invoke("erase_chip", { options: eraseMode ? { erase_mode: eraseMode } : null });
+export async function getEffectiveFlashConfig(chipName?: string): Promise<EffectiveFlashConfig> {
+  return await invoke<EffectiveFlashConfig>("get_effective_flash_config", { chipName });
 }
 
-export async function eraseSector(address: number, size: number): Promise<void> {
-  return await invoke("erase_sector", { options: { address, size } });
+export async function productionFlash(
+  options: FlashOptions,
+  checks?: ProductionCheckOptions
+): Promise<ProductionFlashResult> {
+  return await invoke<ProductionFlashResult>("production_flash", { options, checks });
 }
 
-export async function verifyFirmware(filePath: string): Promise<boolean> {
-  return await invoke<boolean>("verify_firmware", { filePath });
+export async function measureBootTime(
+  channel?: number,
+  timeoutMs?: number,
+  coreIndex?: number
+): Promise<BootTimeResult> {
+  return await invoke<BootTimeResult>("measure_boot_time", {
+    channel: channel ?? null,
+    timeoutMs: timeoutMs ?? null,
+    coreIndex: coreIndex ?? null,
+  });
+}
+
+export async function eraseChip(eraseMode?: EraseMode): Promise<EraseResult> {
+  return await invoke<EraseResult>("erase_chip", { options: eraseMode ? { erase_mode: eraseMode } : null });
+}
+
+export async function eraseSector(address: number, size: number): Promise<EraseResult> {
+  return await invoke<EraseResult>("erase_sector", { options: { address, size } });
+}
+
+export async function verifyFirmware(filePath: string, coreIndex?: number): Promise<boolean> {
+  return await invoke<boolean>("verify_firmware", { filePath, coreIndex: coreIndex ?? null });
 }
 
 export async function readFlash(address: number, size: number): Promise<number[]> {
   return await invoke<number[]>("read_flash", { options: { address, size } });
 }
 
+export async function startFlashRead(
+  address: number,
+  size: number,
+  chunkSize?: number
+): Promise<void> {
+  return await invoke("start_flash_read", { options: { address, size, chunk_size: chunkSize } });
+}
+
+export async function cancelFlashRead(): Promise<void> {
+  return await invoke("cancel_flash_read");
+}
+
+export async function getDefaultFlashBase(chipName: string): Promise<number> {
+  return await invoke<number>("get_default_flash_base", { chipName });
+}
+
 export async function getFirmwareInfo(filePath: string): Promise<FirmwareFileInfo> {
   return await invoke<FirmwareFileInfo>("get_firmware_info", { filePath });
 }
 
+export async function analyzeFirmware(filePath: string, binBase?: number): Promise<FirmwareMetadata> {
+  return await invoke<FirmwareMetadata>("analyze_firmware", { filePath, binBase: binBase ?? null });
+}
+
+export async function testFlashAlgorithm(chipName: string, coreIndex?: number): Promise<FlashAlgorithmTestResult> {
+  return await invoke<FlashAlgorithmTestResult>("test_flash_algorithm", { chipName, coreIndex: coreIndex ?? null });
+}
+
 // 内存命令
-export async function readMemory(address: number, size: number): Promise<number[]> {
-  return await invoke<number[]>("read_memory", { options: { address, size } });
+// 校验并解析地址字符串（0x 十六进制 / 十进制，允许 _ 分隔），无效时抛出错误信息
+export async function parseAddress(input: string): Promise<number> {
+  return await invoke<number>("parse_address", { input });
+}
+
+// 十六进制输入框使用：没有 0x 前缀时仍按十六进制解析
+export async function parseHexAddress(input: string): Promise<number> {
+  const trimmed = input.trim();
+  return await parseAddress(/^0x/i.test(trimmed) ? trimmed : `0x${trimmed}`);
+}
+
+export async function readMemory(
+  address: number,
+  size: number,
+  haltDuringRead = false
+): Promise<number[]> {
+  return await invoke<number[]>("read_memory", {
+    options: { address, size, halt_during_read: haltDuringRead },
+  });
+}
+
+export async function readMemoryFast(
+  address: number,
+  size: number,
+  haltDuringRead = false
+): Promise<number[]> {
+  return await invoke<number[]>("read_memory_fast", {
+    options: { address, size, halt_during_read: haltDuringRead },
+  });
+}
+
+export async function readMemory64(address: number, count: number): Promise<Memory64Read> {
+  return await invoke<Memory64Read>("read_memory_64", { options: { address, count } });
+}
+
+export async function readCycleCounter(coreIndex?: number): Promise<CycleCounter> {
+  return await invoke<CycleCounter>("read_cycle_counter", { coreIndex: coreIndex ?? null });
+}
+
+export async function resetCycleCounter(coreIndex?: number): Promise<CycleCounter> {
+  return await invoke<CycleCounter>("reset_cycle_counter", { coreIndex: coreIndex ?? null });
+}
+
+export async function readSymbol(
+  elfPath: string,
+  symbol: string,
+  typeHint?: SymbolType,
+  haltDuringRead = false
+): Promise<SymbolValue> {
+  return await invoke<SymbolValue>("read_symbol", {
+    options: { elf_path: elfPath, symbol, type_hint: typeHint ?? null, halt_during_read: haltDuringRead },
+  });
 }
 
 export async function writeMemory(address: number, data: number[]): Promise<void> {
@@ -87,6 +268,13 @@ export async function readRegisters(): Promise<RegisterValue[]> {
   return await invoke<RegisterValue[]>("read_registers");
 }
 
+export async function dumpRegistersToFile(
+  path: string,
+  format: "json" | "gdb" = "json"
+): Promise<void> {
+  return await invoke("dump_registers_to_file", { path, format });
+}
+
 // RTT命令
 export async function startRtt(options: RttStartOptions): Promise<RttConfig> {
   return await invoke<RttConfig>("start_rtt", { options });
@@ -108,6 +296,53 @@ export async function clearRttBuffer(): Promise<void> {
   return await invoke("clear_rtt_buffer");
 }
 
+export async function saveRttView(config: RttViewConfig): Promise<void> {
+  return await invoke("save_rtt_view", { config });
+}
+
+export async function loadRttView(): Promise<RttViewConfig> {
+  return await invoke<RttViewConfig>("load_rtt_view");
+}
+
+export async function getRttHistory(channel: number, maxBytes?: number): Promise<number[]> {
+  return await invoke<number[]>("get_rtt_history", { channel, maxBytes });
+}
+
+export async function getRttWriteHistory(channel: number): Promise<string[]> {
+  return await invoke<string[]>("get_rtt_write_history", { channel });
+}
+
+export async function waitForRttPattern(
+  channel: number,
+  pattern: string,
+  timeoutMs: number,
+  contextLines?: number
+): Promise<RttPatternMatch> {
+  return await invoke<RttPatternMatch>("wait_for_rtt_pattern", { channel, pattern, timeoutMs, contextLines });
+}
+
+export async function setRttBatching(timeoutMs: number, sizeThreshold: number): Promise<void> {
+  return await invoke("set_rtt_batching", { timeoutMs, sizeThreshold });
+}
+
+// SWO/ITM 命令
+export async function startSwo(options: SwoStartOptions): Promise<void> {
+  return await invoke("start_swo", { options });
+}
+
+export async function stopSwo(): Promise<void> {
+  return await invoke("stop_swo");
+}
+
+export async function listItmPorts(
+  coreClockHz: number,
+  baudRate: number,
+  durationMs?: number,
+  coreIndex?: number
+): Promise<ItmPortActivity[]> {
+  return await invoke<ItmPortActivity[]>("list_itm_ports", { coreClockHz, baudRate, durationMs, coreIndex });
+}
+
 // 配置命令
 export async function getSupportedChips(): Promise<string[]> {
   return await invoke<string[]>("get_supported_chips");
@@ -121,14 +356,30 @@ export async function getChipInfo(chipName: string): Promise<ChipInfo> {
   return await invoke<ChipInfo>("get_chip_info", { chipName });
 }
 
+export async function getChipInfoBatch(names: string[]): Promise<ChipInfoLookup[]> {
+  return await invoke<ChipInfoLookup[]>("get_chip_info_batch", { names });
+}
+
 export async function initPacks(): Promise<number> {
   return await invoke<number>("init_packs");
 }
 
+export async function reloadTargets(): Promise<ReloadTargetsResult> {
+  return await invoke<ReloadTargetsResult>("reload_targets");
+}
+
+export async function exportAllTargetsYaml(outputDir: string): Promise<ExportTargetsResult> {
+  return await invoke<ExportTargetsResult>("export_all_targets_yaml", { outputDir });
+}
+
 export async function importPack(packPath: string): Promise<PackInfo> {
   return await invoke<PackInfo>("import_pack", { packPath });
 }
 
+export async function importPacksFromDirectory(directory: string): Promise<PackImportResult[]> {
+  return await invoke<PackImportResult[]>("import_packs_from_directory", { directory });
+}
+
 export async function listImportedPacks(): Promise<PackInfo[]> {
   return await invoke<PackInfo[]>("list_imported_packs");
 }
@@ -141,11 +392,15 @@ export async function getPackScanReport(packName: string): Promise<any> {
   return await invoke("get_pack_scan_report", { packName });
 }
 
+export async function findChipProvider(chipName: string): Promise<ChipProvider> {
+  return await invoke<ChipProvider>("find_chip_provider", { chipName });
+}
+
 export async function getDevicesWithoutAlgorithm(packName: string): Promise<string[]> {
   return await invoke("get_devices_without_algorithm", { packName });
 }
 
-export async function checkOutdatedPacks(): Promise<PackInfo[]> {
+export async function checkOutdatedPacks(): Promise<(PackInfo & { reason: "version" | "modified" })[]> {
   return await invoke("check_outdated_packs");
 }
 
@@ -174,6 +429,7 @@ import type {
   SerialPortInfo,
   SerialConfig,
   SerialStatus,
+  SerialResumeResult,
 } from "./serialTypes";
 
 export async function listSerialPorts(): Promise<SerialPortInfo[]> {
@@ -208,6 +464,18 @@ export async function stopSerial(): Promise<void> {
   return await invoke("stop_serial");
 }
 
+export async function setSerialBatching(timeoutMs: number, sizeThreshold: number): Promise<void> {
+  return await invoke("set_serial_batching", { timeoutMs, sizeThreshold });
+}
+
+export async function pauseSerial(): Promise<void> {
+  return await invoke("pause_serial");
+}
+
+export async function resumeSerial(): Promise<SerialResumeResult> {
+  return await invoke<SerialResumeResult>("resume_serial");
+}
+
 export async function getSerialStatus(): Promise<SerialStatus> {
   return await invoke<SerialStatus>("get_serial_status");
 }
@@ -216,6 +484,10 @@ export async function clearSerialBuffer(): Promise<void> {
   return await invoke("clear_serial_buffer");
 }
 
+export async function getSerialHistory(maxBytes?: number): Promise<number[]> {
+  return await invoke<number[]>("get_serial_history", { maxBytes });
+}
+
 // USB 权限检查命令
 export async function checkUsbPermissions(): Promise<UsbPermissionStatus> {
   return await invoke<UsbPermissionStatus>("check_usb_permissions");
@@ -238,6 +510,27 @@ export async function getPacksDirectory(): Promise<string> {
   return await invoke<string>("get_packs_directory");
 }
 
-export async function setCustomPacksDirectory(path: string | null): Promise<void> {
-  return await invoke("set_custom_packs_directory", { path });
+export async function setCustomPacksDirectory(
+  path: string | null,
+  migrate?: PackMigrationMode
+): Promise<PacksDirectoryChange> {
+  return await invoke("set_custom_packs_directory", { path, migrate: migrate ?? null });
+}
+
+// ============ 本地控制服务 ============
+
+export async function getControlServerStatus(): Promise<ControlServerStatus> {
+  return await invoke("get_control_server_status");
+}
+
+export async function setControlServerConfig(
+  enabled: boolean,
+  port?: number,
+  regenerateToken?: boolean
+): Promise<ControlServerStatus> {
+  return await invoke("set_control_server_config", {
+    enabled,
+    port: port ?? null,
+    regenerateToken: regenerateToken ?? null,
+  });
 }
@@ -8,15 +8,22 @@ export interface ProbeInfo {
   probe_type: string;
   dap_version: string | null;
   debug_info: string | null;
+  probe_index: number; // 同名探针中的序号（区分无序列号的相同探针）
+  serial_conflict: boolean; // 与其他探针序列号重复（克隆探针常见）
+  usb_location: string | null; // USB 物理位置 "总线-地址"
 }
 
 // 连接选项
 export interface ConnectOptions {
   probe_identifier: string;
+  probe_index?: number;
+  usb_location?: string | null;
   target: string;
   interface_type: "Swd" | "Jtag";
   clock_speed: number | null;
   connect_mode: "Normal" | "UnderReset";
+  force_dap_version?: "v1" | "v2" | null; // 同时支持 HID/WinUSB 的探针强制使用的传输方式
+  read_reset_reason?: boolean; // 连接时读取上次复位原因（复位下连接时不读取）
 }
 
 // 目标信息
@@ -26,6 +33,50 @@ export interface TargetInfo {
   memory_regions: MemoryRegion[];
   flash_algorithms: string[];
   chip_id: number | null;
+  clock_recommendation?: ClockRecommendation | null;
+  reset_reason?: string | null;  // 上次复位原因
+}
+
+// 调试时钟建议（clock-warning 事件）
+export interface ClockRecommendation {
+  requested_hz: number;
+  core_clock_hz: number;
+  recommended_hz: number;
+  message: string;
+}
+
+// 探针吞吐量测试
+export interface ProbeBenchmarkOptions {
+  address?: number;
+  size?: number;
+  iterations?: number;
+  clock_speeds?: number[];
+  core_index?: number;
+}
+
+export interface ProbeThroughput {
+  clock_hz: number | null;
+  bytes_per_sec: number;
+  effective_clock_hz: number;
+  best_ms: number;
+  average_ms: number;
+  error: string | null;
+}
+
+export interface ProbeBenchmarkResult {
+  address: number;
+  size: number;
+  iterations: number;
+  results: ProbeThroughput[];
+}
+
+// 吞吐量测试进度（probe-benchmark-progress 事件）
+export interface ProbeBenchmarkProgressEvent {
+  clock_hz: number | null;
+  step: number;
+  total_steps: number;
+  iteration: number;
+  iterations: number;
 }
 
 // 内存区域
@@ -49,6 +100,76 @@ export interface ConnectionInfo {
   core_type: string;
   chip_id: number | null;        // 芯片DBGMCU_IDCODE
   target_idcode: number | null;  // 目标芯片的真实IDCODE
+  rtt_control_block?: number | null;  // 已缓存的 RTT 控制块地址
+  reset_reason?: string | null;  // 连接时读取的上次复位原因
+}
+
+// 读保护/调试保护状态
+export type ProtectionState = "Unprotected" | "ReadProtected" | "DebugLocked" | "Unknown";
+
+export interface ProtectionStatus {
+  target: string;
+  state: ProtectionState;
+  register_address: number | null;
+  raw_value: number | null;
+  unprotect_method: "option_bytes" | "ctrl_ap_recover" | null; // 对应的解除保护流程
+}
+
+// 芯片自身记录的 Flash/RAM 容量
+export interface OnchipMemorySizes {
+  target_name: string;
+  flash_size_address: number | null;
+  flash_kb: number | null;
+  declared_flash_kb: number;
+  ram_size_address: number | null;
+  ram_kb: number | null;
+  declared_ram_kb: number;
+  matches: boolean;
+  warnings: string[];
+}
+
+// 低功耗模式调试设置 (STM32 DBGMCU_CR)
+export interface LowPowerDebugStatus {
+  target: string;
+  supported: boolean;
+  register_address: number | null;
+  raw_value: number | null;
+  sleep: boolean | null; // null 表示该系列 Sleep 模式始终可调试
+  stop: boolean;
+  standby: boolean;
+}
+
+export interface LowPowerDebugOptions {
+  sleep?: boolean;
+  stop?: boolean;
+  standby?: boolean;
+}
+
+// 当前探针与目标组合支持的功能
+export interface ProbeFeatures {
+  swd: boolean;
+  jtag: boolean;
+  swo: boolean;
+  target_power: boolean;
+}
+
+export interface ConnectionCapabilities {
+  probe_type: string | null;
+  probe: ProbeFeatures | null;  // null 表示未知探针类型
+  itm_present: boolean;
+  tpiu_present: boolean;
+  swo: boolean;
+  hardware_breakpoints: number | null;
+  rtt: boolean;
+  flash: boolean;
+  flash_algorithms: number;
+}
+
+// 64 位宽内存读取结果（超过 2^53 的值在 JS 中会丢失精度）
+export interface Memory64Read {
+  words: number[];
+  access_width: "U16" | "U32" | "U64";
+  fallback: boolean;  // 目标不支持 64 位访问，已用两次 32 位读取拼接
 }
 
 // 芯片信息
@@ -59,6 +180,19 @@ export interface ChipInfo {
   cores: CoreInfo[];
   memory_regions: MemoryRegionInfo[];
   flash_algorithms: FlashAlgorithmInfo[];
+  fallback?: FallbackMapping | null;
+}
+
+// 批量查询中单个芯片的结果
+export interface ChipInfoLookup {
+  name: string;
+  info: ChipInfo | null;
+  error: string | null;
+}
+
+export interface FallbackMapping {
+  prefix: string;
+  fallback: string;
 }
 
 export interface CoreInfo {
@@ -85,6 +219,69 @@ export interface FlashAlgorithmInfo {
 // 擦除模式
 export type EraseMode = "ChipErase" | "SectorErase";
 
+// 擦除结果
+export interface ErasedRegion {
+  address: number;
+  size: number;
+}
+
+export interface EraseResult {
+  regions_erased: ErasedRegion[];
+  total_bytes: number;
+  elapsed_ms: number;
+}
+
+// 烧录结果统计
+export interface FlashResult {
+  bytes_programmed: number;
+  bytes_erased: number;
+  verified: boolean;
+  elapsed_ms: number;
+  sectors_skipped: number;  // 内容未变化而跳过的扇区数
+  detected_algorithm_ram_region: [number, number] | null; // 本次自动探测使用的算法 RAM 区域（未保存）
+}
+
+// Flash 扇区布局
+export interface FlashSector {
+  address: number;
+  size: number;
+}
+
+export interface FlashRegionLayout {
+  name: string | null;
+  address: number;
+  size: number;
+  external: boolean;
+  algorithm: string | null;
+  sectors: FlashSector[];
+}
+
+// 烧录实际使用的 Flash 算法参数
+export interface EffectiveFlashAlgorithm {
+  name: string;
+  default: boolean;
+  load_address: number | null;
+  header_reserve: number | null;   // load_address 之前实际生效的 header 预留，内置目标为 null
+  header_address: number | null;
+  data_load_address: number | null;
+  data_section_offset: number;
+  stack_size: number | null;
+  flash_start: number;
+  flash_end: number;
+  page_size: number;
+  erased_byte_value: number;
+  sector_descriptions: [number, number][];  // (相对偏移, 扇区大小)
+}
+
+// 烧录使用的最终内存布局与算法配置
+export interface EffectiveFlashConfig {
+  target_name: string;
+  connected: boolean;
+  memory_regions: MemoryRegion[];
+  flash_regions: FlashRegionLayout[];
+  algorithms: EffectiveFlashAlgorithm[];
+}
+
 // Flash烧录选项
 export interface FlashOptions {
   file_path: string;
@@ -94,6 +291,59 @@ export interface FlashOptions {
   erase_mode: EraseMode;
   flash_algorithm?: string; // 可选：指定使用的Flash算法名称
   preverify?: boolean;      // 预校验：烧录前检查，跳过已正确的块（加速重复烧录）
+  halt_after?: boolean;     // 烧录后复位并暂停在复位向量
+  preserve_regions?: { address: number; size: number }[]; // 擦除后恢复的 Flash 区域
+  verify_per_sector?: boolean; // 逐扇区烧录并立即校验，首个坏扇区处停止
+  confirmed?: boolean;         // 已确认烧录前检查提示的风险
+  flash_retry_count?: number;  // 通信类暂时性错误时的重试次数
+  reset_before?: boolean;      // 烧录前复位并暂停（未指定时 UnderReset 连接默认开启）
+  auto_algorithm_ram_region?: boolean; // 默认算法 RAM 不可写时自动探测（会暂停内核写测试数据）
+}
+
+// 量产烧录启动检查选项
+export interface ProductionCheckOptions {
+  rtt_channel?: number;
+  boot_capture_ms?: number;
+  boot_pattern?: string;         // 启动输出中必须出现的正则
+  health_check_delay_ms?: number;
+}
+
+export interface ProductionStep {
+  name: "flash" | "verify" | "reset" | "boot_output" | "boot_health";
+  passed: boolean;
+  skipped: boolean;
+  message: string;
+  elapsed_ms: number;
+}
+
+// 量产烧录结果（同时以 production-flash-result 事件发送）
+export interface ProductionFlashResult {
+  passed: boolean;
+  steps: ProductionStep[];
+}
+
+// 烧录前检查详情（错误信息以 "requires_confirmation: " 开头，其后为 JSON）
+export interface FlashPlanReport {
+  image_bytes: number;
+  erase_bytes: number;
+  required_erase_bytes: number;
+  flash_bytes: number;
+  out_of_bounds_address: number | null;
+  reasons: string[];
+}
+
+// Flash 算法实机测试结果
+export interface FlashAlgorithmTestResult {
+  chip: string;
+  algorithm: string;
+  sector_address: number;
+  sector_size: number;
+  pattern_size: number;
+  success: boolean;
+  program_ms: number;
+  verify_ms: number;
+  restored: boolean; // 测试扇区是否已恢复
+  error: string | null;
 }
 
 // Flash进度事件
@@ -103,6 +353,26 @@ export interface FlashProgressEvent {
   message: string;
 }
 
+// 结构化烧录日志（flash-log 事件）
+export interface FlashLogEvent {
+  level: "error" | "warn" | "info" | "debug" | "trace";
+  message: string;
+  timestamp: number;
+}
+
+// 后台 Flash 读取数据块 (flash-read-chunk)
+export interface FlashReadChunkEvent {
+  offset: number; // 相对读取起始地址
+  data: number[];
+}
+
+// 后台 Flash 读取结束 (flash-read-complete)
+export interface FlashReadCompleteEvent {
+  bytes_read: number;
+  cancelled: boolean;
+  error: string | null;
+}
+
 // 固件文件信息
 export interface FirmwareFileInfo {
   path: string;
@@ -120,6 +390,41 @@ export interface PackInfo {
   device_count: number;
 }
 
+// 目录批量导入中单个文件的结果
+export interface PackImportResult {
+  file: string;
+  // Pack 已导入但设备注册失败时为 false，pack_name 仍有值
+  success: boolean;
+  pack_name: string | null;
+  device_count: number | null;
+  error: string | null;
+}
+
+// 目录批量导入的整体进度（pack-import-batch-progress 事件）
+export interface PackBatchProgress {
+  current: number;
+  total: number;
+  file: string;
+  progress: number;
+}
+
+// 重新加载目标定义结果
+export interface ReloadTargetsResult {
+  families: number;
+  variants: number;
+  failed: { pack: string; error: string }[];
+}
+
+// 导出目标定义的结果
+export interface ExportTargetsResult {
+  output_dir: string;
+  files: string[];  // 每个家族一个文件
+  families: string[];
+  variants: number;
+  duplicates: string[];  // 同名而被跳过的家族
+  failed: { pack: string; error: string }[];
+}
+
 // Pack 扫描报告
 export interface PackScanReport {
   pack_name: string;
@@ -143,6 +448,16 @@ export interface DeviceScanResult {
   status: "Success" | "Warning" | "Error";
 }
 
+// 芯片定义的提供者
+export interface ChipProvider {
+  chip_name: string;
+  source: "pack" | "builtin" | "probe_rs" | "none";
+  pack_name: string | null;
+  has_algorithm: boolean;
+  status: "Ok" | "Warning" | "Error" | null;  // Pack 扫描报告中的设备状态
+  status_reason: string | null;
+}
+
 // 项目配置
 export interface ProjectConfig {
   name: string;
@@ -165,13 +480,46 @@ export interface RttStartOptions {
   range_size?: number;
   poll_interval?: number;
   halt_on_read?: boolean; // 是否在读取时暂停目标 (默认 true)
+  scan_timeout_ms?: number; // 控制块扫描超时 (默认 5000ms)
+  elf_path?: string; // 固件 ELF 路径 (用于识别 defmt 通道)
 }
 
+// RTT 通道数据格式推测
+export type RttChannelFormat = "unknown" | "text" | "defmt" | "binary";
+
 // RTT 通道信息
 export interface RttChannel {
   index: number;
   name: string;
   buffer_size: number;
+  likely_format: RttChannelFormat;
+}
+
+// rtt-channels 事件
+export interface RttChannelsEvent {
+  up_channels: RttChannel[];
+}
+
+// RTT 查看器单通道设置
+export interface RttChannelView {
+  channel: number;
+  visible: boolean;
+  display_name?: string | null;
+  color?: string | null;
+  filters: string[]; // 行过滤正则
+}
+
+// RTT 查看器设置
+export interface RttViewConfig {
+  channels: RttChannelView[];
+  backend_filter: boolean; // 由后端预先过滤
+}
+
+// wait_for_rtt_pattern 匹配结果
+export interface RttPatternMatch {
+  line: string;
+  context_before: string[];
+  elapsed_ms: number;
 }
 
 // RTT 配置响应
@@ -186,6 +534,7 @@ export interface RttDataEvent {
   channel: number;
   data: number[];
   timestamp: number;
+  direction: "rx" | "tx";
 }
 
 // RTT 状态事件
@@ -204,6 +553,30 @@ export interface RttLine {
   rawData?: number[]; // 新增：原始字节数据
 }
 
+// SWO 启动选项
+export interface SwoStartOptions {
+  core_clock_hz: number;
+  baud_rate: number;
+  stimulus_ports?: number[]; // 启用的 ITM 激励端口，优先于 stimulus_mask
+  stimulus_mask?: number;
+  poll_interval?: number;
+  core_index?: number;
+}
+
+// SWO 数据事件 (swo-data)
+export interface SwoDataEvent {
+  port: number;
+  data: number[];
+  timestamp: number;
+}
+
+// ITM 激励端口活动统计
+export interface ItmPortActivity {
+  port: number;
+  packets: number;
+  bytes: number;
+}
+
 // 寄存器值
 export interface RegisterValue {
   name: string;
@@ -217,6 +590,9 @@ export interface DeviceSettings {
   connectMode: "Normal" | "UnderReset";
   resetMode: "Software" | "Hardware";
   voltage: number;
+  dapVersion: "auto" | "v1" | "v2"; // HID/WinUSB 双接口探针的传输方式
+  readResetReason: boolean; // 连接时读取上次复位原因
+  coreOperationTimeoutMs?: number | null; // 内核操作统一超时覆盖，为空时各操作使用各自的默认值
 }
 
 // 日志条目
@@ -255,3 +631,127 @@ export interface UsbPermissionStatus {
   detected_dap_devices: UsbDeviceInfo[];
   suggestions: string[];
 }
+
+// Pack 目录迁移
+export type PackMigrationMode = "copy" | "move";
+
+export interface PackMigrationFailure {
+  pack: string;
+  error: string;
+}
+
+export interface PackMigrationReport {
+  migrated: string[];
+  skipped: string[];
+  failed: PackMigrationFailure[];
+}
+
+export interface PacksDirectoryChange {
+  old_dir: string;
+  new_dir: string;
+  migration: PackMigrationReport | null;
+}
+
+// 本地控制服务（JSON-RPC over HTTP）
+export interface ControlServerStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  token: string | null;
+  url: string;
+  methods: string[];
+}
+
+// DWT 周期计数器
+export interface CycleCounter {
+  supported: boolean;
+  cycles: number | null;
+  enabled_now: boolean;
+  core_type: string;
+  reason: string | null;
+}
+
+// Pack 导入（解压）进度，对应 pack-import-progress 事件
+export interface PackImportProgress {
+  pack_file: string;
+  current_file: string;
+  bytes_extracted: number;
+  total_bytes: number;
+  files_extracted: number;
+  total_files: number;
+  progress: number;
+}
+
+// 固件元数据（无需连接目标）
+export interface FirmwareMetadata {
+  format: "elf" | "hex" | "bin" | "srec";
+  total_bytes: number;
+  segment_count: number;
+  entry_point: number | null;
+  address_range: [number, number];
+  crc32: number;
+}
+
+// 按符号名读取变量
+export type SymbolType =
+  | "u8" | "u16" | "u32" | "u64"
+  | "i8" | "i16" | "i32" | "i64"
+  | "f32" | "f64" | "array";
+
+export interface ElfSymbol {
+  name: string;
+  address: number;
+  size: number;
+  is_function: boolean;
+}
+
+export interface SymbolValue {
+  symbol: ElfSymbol;
+  size: number;
+  type_hint: SymbolType;
+  bytes: number[];
+  values: string[];
+}
+
+// 启动耗时测量（复位到首次 RTT 输出）
+export interface BootTimeResult {
+  boot_time_ms: number;
+  channel: number;
+  first_output: string;
+}
+
+// ADIv5 调试寄存器快照
+export interface DpCtrlStat {
+  csyspwrupack: boolean;
+  csyspwrupreq: boolean;
+  cdbgpwrupack: boolean;
+  cdbgpwrupreq: boolean;
+  cdbgrstack: boolean;
+  cdbgrstreq: boolean;
+  wdataerr: boolean;
+  readok: boolean;
+  stickyerr: boolean;
+  stickycmp: boolean;
+  stickyorun: boolean;
+  orundetect: boolean;
+  powered: boolean;
+  has_fault: boolean;
+}
+
+export interface DebugRegisterDump {
+  dpidr: number | null;
+  ctrl_stat: number | null;
+  ctrl_stat_decoded: DpCtrlStat | null;
+  ap_index: number | null;
+  ap_idr: number | null;
+  ap_csw: number | null;
+  ap_cfg: number | null;
+  ap_base: number | null;
+  rom_table_base: number | null;
+  errors: string[];
+}
+
+// 临时重连后无法恢复原连接
+export interface ConnectionLostEvent {
+  message: string;
+}
@@ -36,6 +36,8 @@ const defaultSettings: DeviceSettings = {
   connectMode: "Normal",
   resetMode: "Software",
   voltage: 3.3,
+  dapVersion: "auto",
+  readResetReason: false,
 };
 
 export const useProbeStore = create<ProbeState>((set) => ({